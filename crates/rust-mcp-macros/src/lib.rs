@@ -8,7 +8,11 @@ use syn::{
     parse::Parse, parse_macro_input, punctuated::Punctuated, Data, DeriveInput, Error, Expr,
     ExprLit, Fields, Lit, Meta, Token,
 };
-use utils::{is_option, renamed_field, type_to_json_schema};
+use utils::{
+    doc_comment, has_schema_default, has_serde_flag, inner_type, is_option, is_skipped,
+    map_value_type, rename_all_rule, schema_constraints, serde_default, serde_field_name,
+    type_to_json_schema, SchemaContext,
+};
 
 /// Represents the attributes for the `mcp_tool` procedural macro.
 ///
@@ -18,10 +22,18 @@ use utils::{is_option, renamed_field, type_to_json_schema};
 /// # Fields
 /// * `name` - An optional string representing the tool's name.
 /// * `description` - An optional string describing the tool.
+/// * `output` - An optional path to the struct describing the tool's structured output.
+/// * `schemars` - Whether the schemas are generated by `schemars::JsonSchema` implementations.
+/// * `export_schema` - An optional directory the schema snapshot of the tool is exported to.
+/// * `dry_run` - Whether the tool supports dry runs.
 ///
 struct McpToolMacroAttributes {
     name: Option<String>,
     description: Option<String>,
+    output: Option<syn::Path>,
+    export_schema: Option<String>,
+    dry_run: bool,
+    schemars: bool,
 }
 
 impl Parse for McpToolMacroAttributes {
@@ -37,20 +49,52 @@ impl Parse for McpToolMacroAttributes {
     fn parse(attributes: syn::parse::ParseStream) -> syn::Result<Self> {
         let mut name = None;
         let mut description = None;
+        let mut output = None;
+        let mut export_schema = None;
+        let mut dry_run = false;
+        let mut schemars = false;
         let meta_list: Punctuated<Meta, Token![,]> = Punctuated::parse_terminated(attributes)?;
         for meta in meta_list {
+            if let Meta::Path(path) = &meta {
+                if path.is_ident("export_schema") {
+                    return Err(Error::new_spanned(
+                        path,
+                        "The 'export_schema' attribute requires a directory, e.g. `export_schema = \"tool_schemas\"`.",
+                    ));
+                }
+                if path.is_ident("dry_run") {
+                    dry_run = true;
+                }
+                if path.is_ident("schemars") {
+                    if !cfg!(feature = "schemars") {
+                        return Err(Error::new_spanned(
+                            path,
+                            "The 'schemars' attribute requires the `schemars` feature of rust-mcp-macros.",
+                        ));
+                    }
+                    schemars = true;
+                }
+            }
             if let Meta::NameValue(meta_name_value) = meta {
                 let ident = meta_name_value.path.get_ident().unwrap();
-                if let Expr::Lit(ExprLit {
-                    lit: Lit::Str(lit_str),
-                    ..
-                }) = meta_name_value.value
-                {
-                    match ident.to_string().as_str() {
+                match meta_name_value.value {
+                    Expr::Path(expr_path) if ident == "output" => output = Some(expr_path.path),
+                    value if ident == "output" => {
+                        return Err(Error::new_spanned(
+                            value,
+                            "The 'output' attribute should be the path of a struct, e.g. `output = MyToolOutput`.",
+                        ));
+                    }
+                    Expr::Lit(ExprLit {
+                        lit: Lit::Str(lit_str),
+                        ..
+                    }) => match ident.to_string().as_str() {
                         "name" => name = Some(lit_str.value()),
                         "description" => description = Some(lit_str.value()),
+                        "export_schema" => export_schema = Some(lit_str.value()),
                         _ => {}
-                    }
+                    },
+                    _ => {}
                 }
             }
         }
@@ -88,7 +132,14 @@ impl Parse for McpToolMacroAttributes {
             }
         }
 
-        Ok(Self { name, description })
+        Ok(Self {
+            name,
+            description,
+            output,
+            export_schema,
+            dry_run,
+            schemars,
+        })
     }
 }
 
@@ -98,10 +149,35 @@ impl Parse for McpToolMacroAttributes {
 /// - A `tool_name()` method returning the tool's name as a string.
 /// - A `tool()` method returning a `rust_mcp_schema::Tool` instance with the tool's name,
 ///   description, and input schema derived from the struct's fields.
+/// - A `write_schema_snapshot()` method writing the tool, along with the schema of its structured
+///   output if any, as pretty-printed JSON to `<dir>/<tool name>.json`.
+///
+/// When an `output` struct is provided, it also includes:
+/// - An `output_schema()` method returning the JSON Schema of the tool's structured output.
+/// - An `into_call_tool_result()` method converting an output value into a `rust_mcp_schema::CallToolResult`.
+///
+/// The generated code refers to `rust_mcp_schema`, `serde_json` and `rust_mcp_sdk`, which the crate
+/// using the macro depends on.
 ///
 /// # Attributes
 /// * `name` - The name of the tool (required, non-empty string).
 /// * `description` - A description of the tool (required, non-empty string).
+/// * `output` - A struct describing the structured output of the tool (optional). It must derive
+///   `JsonSchema` and `serde::Serialize`.
+/// * `export_schema` - Exports the schema snapshot of the tool to the given directory, relative to
+///   the crate, whenever the tests of the crate run (optional), e.g. `export_schema = "tool_schemas"`,
+///   so that schema changes can be diffed in code review and published. The `MCP_TOOL_SCHEMA_DIR`
+///   environment variable overrides the directory. The snapshot is written by a hidden test
+///   generated next to the tool, so it is not exported when the tool is defined inside a function
+///   body; `rust_mcp_sdk::mcp_server::write_schema_snapshots` exports the snapshots of any tools,
+///   e.g. from an xtask or a binary, to a directory outside the source tree.
+/// * `dry_run` - Advertises that the tool supports dry runs (optional), by declaring the reserved
+///   `_meta` argument and its `dryRun` flag in the input schema of the tool. The tool implements
+///   the dry run with the `dry_run()` method of the `McpTool` or `McpAsyncTool` trait of rust-mcp-sdk.
+/// * `schemars` - Generates the input schema of the tool, and the schema of its `output`, from their
+///   implementation of `schemars::JsonSchema` (0.8) instead of the `JsonSchema` derive macro of this
+///   crate (optional). Requires the `schemars` feature, and a dependency on `schemars` in the crate
+///   using the macro.
 ///
 /// # Panics
 /// Panics if the macro is applied to anything other than a struct.
@@ -126,6 +202,32 @@ impl Parse for McpToolMacroAttributes {
 /// assert!(schema_properties.contains_key("field2"));
 ///
 /// ```
+///
+/// # Example with structured output
+/// ```rust
+/// #[derive(serde::Serialize, rust_mcp_macros::JsonSchema)]
+/// struct WeatherOutput {
+///     temperature: f64,
+///     conditions: String,
+/// }
+///
+/// #[rust_mcp_macros::mcp_tool(name = "get_weather", description = "Get the current weather", output = WeatherOutput)]
+/// #[derive(rust_mcp_macros::JsonSchema)]
+/// struct GetWeatherTool {
+///     city: String,
+/// }
+///
+/// let output_schema = GetWeatherTool::output_schema();
+/// assert!(output_schema.get("properties").unwrap().get("temperature").is_some());
+///
+/// let result = GetWeatherTool::into_call_tool_result(WeatherOutput {
+///     temperature: 22.5,
+///     conditions: "Sunny".to_string(),
+/// })
+/// .unwrap();
+/// let text = &result.content[0].as_text_content().unwrap().text;
+/// assert_eq!(text, r#"{"temperature":22.5,"conditions":"Sunny"}"#);
+/// ```
 #[proc_macro_attribute]
 pub fn mcp_tool(attributes: TokenStream, input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput); // Parse the input as a function
@@ -136,6 +238,91 @@ pub fn mcp_tool(attributes: TokenStream, input: TokenStream) -> TokenStream {
     let tool_name = macro_attributes.name.unwrap_or_default();
     let tool_description = macro_attributes.description.unwrap_or_default();
 
+    // the schema of the structured output is included in the schema snapshot
+    let snapshot_output_schema = macro_attributes.output.as_ref().map(|_| {
+        quote! {
+            if let serde_json::Value::Object(snapshot) = &mut snapshot {
+                snapshot.insert(
+                    "outputSchema".to_string(),
+                    serde_json::Value::Object(#input_ident::output_schema()),
+                );
+            }
+        }
+    });
+
+    let export_schema_test = macro_attributes.export_schema.map(|dir| {
+        let test_ident = quote::format_ident!("__export_schema_snapshot_{}", input_ident);
+        quote! {
+            #[cfg(test)]
+            #[test]
+            #[allow(non_snake_case)]
+            fn #test_ident() {
+                let dir = std::env::var("MCP_TOOL_SCHEMA_DIR").unwrap_or_else(|_| #dir.to_string());
+                #input_ident::write_schema_snapshot(
+                    std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join(dir),
+                )
+                .unwrap();
+            }
+        }
+    });
+
+    // the reserved `_meta` argument of tools supporting dry runs, see `rust_mcp_sdk::dry_run`
+    let dry_run_property = macro_attributes.dry_run.then(|| {
+        quote! {
+            let mut properties = properties;
+            if let serde_json::Value::Object(meta_schema) = serde_json::json!({
+                "type": "object",
+                "description": "Metadata of the tool call",
+                "properties": {
+                    "dryRun": {
+                        "type": "boolean",
+                        "description": "Validates the call and describes what it would do, without side effects"
+                    }
+                }
+            }) {
+                properties
+                    .get_or_insert_with(Default::default)
+                    .insert("_meta".to_string(), meta_schema);
+            }
+        }
+    });
+
+    let schemars = macro_attributes.schemars;
+    let input_schema = if schemars {
+        schemars_schema(&quote! { #input_ident })
+    } else {
+        quote! { #input_ident::json_schema() }
+    };
+
+    let output_methods = macro_attributes.output.map(|output_path| {
+        let output_schema = if schemars {
+            schemars_schema(&quote! { #output_path })
+        } else {
+            quote! { #output_path::json_schema() }
+        };
+        quote! {
+            /// Returns the JSON Schema of the tool's structured output.
+            pub fn output_schema() -> serde_json::Map<String, serde_json::Value> {
+                #output_schema
+            }
+
+            /// Converts the structured output of the tool into a `rust_mcp_schema::CallToolResult`.
+            ///
+            /// The output is serialized to JSON and returned as text content, which is how
+            /// structured results are represented in the current protocol revision.
+            pub fn into_call_tool_result(
+                output: #output_path,
+            ) -> std::result::Result<
+                rust_mcp_schema::CallToolResult,
+                rust_mcp_schema::schema_utils::CallToolError,
+            > {
+                let text = serde_json::to_string(&output)
+                    .map_err(rust_mcp_schema::schema_utils::CallToolError::new)?;
+                Ok(rust_mcp_schema::CallToolResult::text_content(text, None))
+            }
+        }
+    });
+
     let output = quote! {
         impl #input_ident {
             /// Returns the name of the tool as a string.
@@ -149,7 +336,7 @@ pub fn mcp_tool(attributes: TokenStream, input: TokenStream) -> TokenStream {
             /// the struct's attributes.
             pub fn tool()-> rust_mcp_schema::Tool
             {
-                let json_schema = &#input_ident::json_schema();
+                let json_schema = &#input_schema;
 
                 let required: Vec<_> = match json_schema.get("required").and_then(|r| r.as_array()) {
                     Some(arr) => arr
@@ -182,13 +369,417 @@ pub fn mcp_tool(attributes: TokenStream, input: TokenStream) -> TokenStream {
                             .collect()
                     });
 
+                // The input schema of a tool has no `$defs`: the definitions of recursive types are
+                // inlined where they are first referred to, and the other references point there.
+                let properties = match json_schema.get("$defs") {
+                    Some(serde_json::Value::Object(defs)) => properties.map(|properties| {
+                        rust_mcp_sdk::__macro_support::inline_definitions(properties, defs)
+                    }),
+                    _ => properties,
+                };
+                #dry_run_property
+
                 rust_mcp_schema::Tool {
                     name: #tool_name.to_string(),
                     description: Some(#tool_description.to_string()),
                     input_schema: rust_mcp_schema::ToolInputSchema::new(required, properties),
                 }
             }
+
+            /// Returns the schema snapshot of the tool: the tool as listed by a server, along with
+            /// the schema of its structured output if any.
+            pub fn schema_snapshot() -> serde_json::Value {
+                let mut snapshot = serde_json::to_value(#input_ident::tool()).unwrap_or_default();
+                #snapshot_output_schema
+                snapshot
+            }
+
+            /// Writes the schema snapshot of the tool to `<dir>/<tool name>.json`, see
+            /// `rust_mcp_sdk::mcp_server::write_schema_snapshots`.
+            ///
+            /// The file is only written if its content changed. Returns the path of the file.
+            pub fn write_schema_snapshot(
+                dir: impl AsRef<std::path::Path>,
+            ) -> std::io::Result<std::path::PathBuf> {
+                let mut paths = rust_mcp_sdk::mcp_server::write_schema_snapshots(
+                    dir,
+                    [#input_ident::schema_snapshot()],
+                )?;
+                Ok(paths.remove(0))
+            }
+
+            #output_methods
         }
+
+        #export_schema_test
+
+        // Retain the original item (struct definition)
+        #input
+    };
+
+    TokenStream::from(output)
+}
+
+/// Generates the expression converting the `schemars` root schema of a type into a schema map, as
+/// returned by the `json_schema()` method derived by `JsonSchema`: its `definitions` are moved to
+/// `$defs`, where the references to them point, and the `allOf` wrapping documented references is
+/// removed.
+fn schemars_schema(ty: &proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    quote! {
+        {
+            fn to_defs_references(value: &mut serde_json::Value) {
+                match value {
+                    serde_json::Value::Object(map) => {
+                        // documented references are wrapped in an `allOf` of a single schema
+                        if let Some(serde_json::Value::Array(all_of)) = map.get("allOf") {
+                            if let [serde_json::Value::Object(schema)] = all_of.as_slice() {
+                                let schema = schema.clone();
+                                map.remove("allOf");
+                                for (key, value) in schema {
+                                    map.entry(key).or_insert(value);
+                                }
+                            }
+                        }
+                        if let Some(serde_json::Value::String(reference)) = map.get_mut("$ref") {
+                            if let Some(name) = reference.strip_prefix("#/definitions/") {
+                                *reference = format!("#/$defs/{}", name);
+                            }
+                        }
+                        map.values_mut().for_each(to_defs_references);
+                    }
+                    serde_json::Value::Array(items) => items.iter_mut().for_each(to_defs_references),
+                    _ => {}
+                }
+            }
+
+            let mut root_schema =
+                serde_json::to_value(::schemars::schema_for!(#ty)).unwrap_or_default();
+            to_defs_references(&mut root_schema);
+            match root_schema {
+                serde_json::Value::Object(mut schema) => {
+                    if let Some(definitions) = schema.remove("definitions") {
+                        schema.insert("$defs".to_string(), definitions);
+                    }
+                    schema
+                }
+                _ => serde_json::Map::new(),
+            }
+        }
+    }
+}
+
+/// Returns the value of a required string attribute.
+///
+/// # Errors
+/// Returns a `syn::Error` if the attribute is missing or is an empty string.
+fn required_attribute(
+    value: Option<String>,
+    attribute: &str,
+    attributes: syn::parse::ParseStream,
+) -> syn::Result<String> {
+    match value {
+        Some(value) if value.trim().is_empty() => Err(Error::new(
+            attributes.span(),
+            format!("The '{attribute}' attribute should not be an empty string."),
+        )),
+        Some(value) => Ok(value),
+        None => Err(Error::new(
+            attributes.span(),
+            format!("The '{attribute}' attribute is required."),
+        )),
+    }
+}
+
+/// Represents the attributes for the `mcp_prompt` procedural macro.
+///
+/// Both the `name` and the `description` of the prompt are required and must not be empty strings.
+struct McpPromptMacroAttributes {
+    name: String,
+    description: String,
+}
+
+impl Parse for McpPromptMacroAttributes {
+    /// Parses the `name` and `description` string literals of the macro attributes.
+    ///
+    /// # Errors
+    /// Returns a `syn::Error` if the `name` or `description` attribute is missing or empty.
+    fn parse(attributes: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut name = None;
+        let mut description = None;
+        let meta_list: Punctuated<Meta, Token![,]> = Punctuated::parse_terminated(attributes)?;
+        for meta in meta_list {
+            if let Meta::NameValue(meta_name_value) = meta {
+                if let Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit_str),
+                    ..
+                }) = meta_name_value.value
+                {
+                    if meta_name_value.path.is_ident("name") {
+                        name = Some(lit_str.value());
+                    } else if meta_name_value.path.is_ident("description") {
+                        description = Some(lit_str.value());
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            name: required_attribute(name, "name", attributes)?,
+            description: required_attribute(description, "description", attributes)?,
+        })
+    }
+}
+
+/// A procedural macro attribute to generate rust_mcp_schema::Prompt related utility methods for a struct.
+///
+/// The `mcp_prompt` macro generates an implementation for the annotated struct that includes:
+/// - A `prompt_name()` method returning the prompt's name as a string.
+/// - A `prompt()` method returning a `rust_mcp_schema::Prompt` instance with the prompt's name,
+///   description, and arguments derived from the struct's fields: each field is an argument named
+///   as serde deserializes the field, honoring the `rename` and `rename_all` attributes, described
+///   by the doc comment of the field, and required unless it is an `Option`.
+///
+/// The struct usually also derives `JsonSchema` and `serde::Deserialize`, which the `prompt_box!`
+/// macro of rust-mcp-sdk requires to convert the arguments of `prompts/get` requests into the struct.
+///
+/// # Attributes
+/// * `name` - The name of the prompt (required, non-empty string).
+/// * `description` - A description of the prompt (required, non-empty string).
+///
+/// # Example
+/// ```rust
+/// #[rust_mcp_macros::mcp_prompt(name = "code_review", description = "Asks the model to review a piece of code")]
+/// #[derive(serde::Deserialize)]
+/// struct CodeReviewPrompt {
+///     /// The code to review
+///     code: String,
+///     /// The maximum number of comments
+///     #[serde(rename = "maxComments")]
+///     max_comments: Option<u32>,
+/// }
+///
+/// assert_eq!(CodeReviewPrompt::prompt_name(), "code_review");
+/// let prompt: rust_mcp_schema::Prompt = CodeReviewPrompt::prompt();
+/// assert_eq!(prompt.description.unwrap(), "Asks the model to review a piece of code");
+///
+/// assert_eq!(prompt.arguments.len(), 2);
+/// assert_eq!(prompt.arguments[0].name, "code");
+/// assert_eq!(prompt.arguments[0].description.as_deref(), Some("The code to review"));
+/// assert_eq!(prompt.arguments[0].required, Some(true));
+/// assert_eq!(prompt.arguments[1].name, "maxComments");
+/// assert_eq!(prompt.arguments[1].required, Some(false));
+/// ```
+#[proc_macro_attribute]
+pub fn mcp_prompt(attributes: TokenStream, input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let input_ident = &input.ident;
+
+    let macro_attributes = parse_macro_input!(attributes as McpPromptMacroAttributes);
+    let prompt_name = &macro_attributes.name;
+    let prompt_description = &macro_attributes.description;
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            Fields::Unit => &Punctuated::new(),
+            _ => {
+                return Error::new_spanned(
+                    &input,
+                    "The mcp_prompt macro only supports structs with named fields",
+                )
+                .to_compile_error()
+                .into()
+            }
+        },
+        _ => {
+            return Error::new_spanned(&input, "The mcp_prompt macro only supports structs")
+                .to_compile_error()
+                .into()
+        }
+    };
+
+    let rename_all = rename_all_rule(&input.attrs);
+    let container_default = serde_default(&input.attrs).is_some();
+    let arguments = fields
+        .iter()
+        .filter(|field| !is_skipped(&field.attrs))
+        .map(|field| {
+            let argument_name = serde_field_name(field, rename_all.as_deref());
+            let description = match doc_comment(&field.attrs) {
+                Some(description) => quote! { Some(#description.to_string()) },
+                None => quote! { None },
+            };
+            let has_default = container_default
+                || serde_default(&field.attrs).is_some()
+                || has_schema_default(&field.attrs);
+            let required = !is_option(&field.ty) && !has_default;
+            quote! {
+                rust_mcp_schema::PromptArgument {
+                    description: #description,
+                    name: #argument_name.to_string(),
+                    required: Some(#required),
+                }
+            }
+        });
+
+    let output = quote! {
+        impl #input_ident {
+            /// Returns the name of the prompt as a string.
+            pub fn prompt_name() -> String {
+                #prompt_name.to_string()
+            }
+
+            /// Constructs and returns a `rust_mcp_schema::Prompt` instance.
+            ///
+            /// The prompt includes the name, description, and arguments derived from
+            /// the struct's fields.
+            pub fn prompt() -> rust_mcp_schema::Prompt {
+                rust_mcp_schema::Prompt {
+                    arguments: vec![#(#arguments),*],
+                    description: Some(#prompt_description.to_string()),
+                    name: #prompt_name.to_string(),
+                }
+            }
+        }
+
+        // Retain the original item (struct definition)
+        #input
+    };
+
+    TokenStream::from(output)
+}
+
+/// Represents the attributes for the `mcp_resource` procedural macro.
+///
+/// The `uri` and the `name` of the resource are required and must not be empty strings, its
+/// `mime_type` and `description` are optional.
+struct McpResourceMacroAttributes {
+    uri: String,
+    name: String,
+    mime_type: Option<String>,
+    description: Option<String>,
+}
+
+impl Parse for McpResourceMacroAttributes {
+    /// Parses the `uri`, `name`, `mime_type` and `description` string literals of the macro attributes.
+    ///
+    /// # Errors
+    /// Returns a `syn::Error` if the `uri` or `name` attribute is missing or empty.
+    fn parse(attributes: syn::parse::ParseStream) -> syn::Result<Self> {
+        let mut uri = None;
+        let mut name = None;
+        let mut mime_type = None;
+        let mut description = None;
+        let meta_list: Punctuated<Meta, Token![,]> = Punctuated::parse_terminated(attributes)?;
+        for meta in meta_list {
+            if let Meta::NameValue(meta_name_value) = meta {
+                if let Expr::Lit(ExprLit {
+                    lit: Lit::Str(lit_str),
+                    ..
+                }) = meta_name_value.value
+                {
+                    let ident = meta_name_value.path.get_ident().map(ToString::to_string);
+                    match ident.as_deref() {
+                        Some("uri") => uri = Some(lit_str.value()),
+                        Some("name") => name = Some(lit_str.value()),
+                        Some("mime_type") => mime_type = Some(lit_str.value()),
+                        Some("description") => description = Some(lit_str.value()),
+                        _ => {}
+                    }
+                }
+            }
+        }
+        Ok(Self {
+            uri: required_attribute(uri, "uri", attributes)?,
+            name: required_attribute(name, "name", attributes)?,
+            mime_type,
+            description,
+        })
+    }
+}
+
+/// A procedural macro attribute to generate rust_mcp_schema::Resource related utility methods for a struct.
+///
+/// The `mcp_resource` macro generates an implementation for the annotated struct that includes:
+/// - A `resource_uri()` method returning the resource's uri as a string.
+/// - A `resource()` method returning a `rust_mcp_schema::Resource` instance with the resource's uri,
+///   name, mime type and description.
+/// - An `into_read_resource_result()` method converting the text content of the resource into a
+///   `rust_mcp_schema::ReadResourceResult`.
+///
+/// The struct implements the `McpResource` trait of rust-mcp-sdk to read the resource, and is then
+/// registered with `ResourceRegistry::register_handler()`.
+///
+/// # Attributes
+/// * `uri` - The uri of the resource (required, non-empty string).
+/// * `name` - The name of the resource (required, non-empty string).
+/// * `mime_type` - The mime type of the resource (optional).
+/// * `description` - A description of the resource (optional).
+///
+/// # Example
+/// ```rust
+/// #[rust_mcp_macros::mcp_resource(uri = "file:///readme", name = "README", mime_type = "text/markdown")]
+/// struct ReadmeResource;
+///
+/// assert_eq!(ReadmeResource::resource_uri(), "file:///readme");
+/// let resource: rust_mcp_schema::Resource = ReadmeResource::resource();
+/// assert_eq!(resource.name, "README");
+/// assert_eq!(resource.mime_type.unwrap(), "text/markdown");
+/// assert!(resource.description.is_none());
+///
+/// let result = ReadmeResource::into_read_resource_result("# Readme");
+/// assert_eq!(result.contents.len(), 1);
+/// ```
+#[proc_macro_attribute]
+pub fn mcp_resource(attributes: TokenStream, input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let input_ident = &input.ident;
+
+    let macro_attributes = parse_macro_input!(attributes as McpResourceMacroAttributes);
+    let uri = &macro_attributes.uri;
+    let name = &macro_attributes.name;
+    let optional = |value: &Option<String>| match value {
+        Some(value) => quote! { Some(#value.to_string()) },
+        None => quote! { None },
+    };
+    let mime_type = optional(&macro_attributes.mime_type);
+    let description = optional(&macro_attributes.description);
+
+    let output = quote! {
+        impl #input_ident {
+            /// Returns the uri of the resource as a string.
+            pub fn resource_uri() -> String {
+                #uri.to_string()
+            }
+
+            /// Constructs and returns a `rust_mcp_schema::Resource` instance.
+            pub fn resource() -> rust_mcp_schema::Resource {
+                rust_mcp_schema::Resource {
+                    annotations: None,
+                    description: #description,
+                    mime_type: #mime_type,
+                    name: #name.to_string(),
+                    size: None,
+                    uri: #uri.to_string(),
+                }
+            }
+
+            /// Converts the text content of the resource into a `rust_mcp_schema::ReadResourceResult`,
+            /// with the uri and the mime type of the resource.
+            pub fn into_read_resource_result(
+                text: impl Into<String>,
+            ) -> rust_mcp_schema::ReadResourceResult {
+                rust_mcp_schema::ReadResourceResult {
+                    contents: vec![rust_mcp_schema::TextResourceContents {
+                        mime_type: #mime_type,
+                        text: text.into(),
+                        uri: #uri.to_string(),
+                    }
+                    .into()],
+                    meta: None,
+                }
+            }
+        }
+
         // Retain the original item (struct definition)
         #input
     };
@@ -201,15 +792,45 @@ pub fn mcp_tool(attributes: TokenStream, input: TokenStream) -> TokenStream {
 /// This procedural macro generates a `json_schema()` method for the annotated struct, returning a
 /// `serde_json::Map<String, serde_json::Value>` that represents the struct as a JSON Schema object.
 /// The schema includes the struct's fields as properties, with support for basic types, `Option<T>`,
-/// `Vec<T>`, and nested structs that also derive `JsonSchema`.
+/// `Vec<T>`, `HashMap<String, T>`/`BTreeMap<String, T>`, and nested structs that also derive `JsonSchema`.
 ///
 /// # Features
-/// - **Basic Types:** Maps `String` to `"string"`, `i32` to `"integer"`, `bool` to `"boolean"`, etc.
+/// - **Basic Types:** Maps `String` to `"string"`, integer types such as `i32` to `"integer"`, `f32` and
+///   `f64` to `"number"`, `bool` to `"boolean"`.
 /// - **`Option<T>`:** Adds `"nullable": true` to the schema of the inner type, indicating the field is optional.
+///   With the `#[json_schema(strict_nullable)]` struct attribute, the type of the inner schema is combined
+///   with `"null"` instead, e.g. `"type": ["integer", "null"]`, or the inner schema with `{"type": "null"}`
+///   in an `"anyOf"` if it has no single type, for clients validating schemas strictly.
 /// - **`Vec<T>`:** Generates an `"array"` schema with an `"items"` field describing the inner type.
+/// - **`HashMap<String, T>` / `BTreeMap<String, T>`:** Generates an `"object"` schema with an
+///   `"additionalProperties"` field describing the value type.
 /// - **Nested Structs:** Recursively includes the schema of nested structs (assumed to derive `JsonSchema`),
 ///   embedding their `"properties"` and `"required"` fields.
-/// - **Required Fields:** Adds a top-level `"required"` array listing field names not wrapped in `Option`.
+/// - **Validation Constraints:** The `#[json_schema(...)]` field attribute adds validation keywords to
+///   the schema of a field: `minimum`, `maximum`, `exclusive_minimum`, `exclusive_maximum`, `multiple_of`,
+///   `min_length`, `max_length`, `min_items`, `max_items` (numeric literals), `pattern` and `format`
+///   (string literals).
+/// - **Encrypted Fields:** `#[json_schema(encrypted)]` marks a sensitive field with `"x-mcp-encrypted": true`,
+///   so that clients using the `encryption` feature of rust-mcp-sdk encrypt its value before sending it.
+/// - **Required Fields:** Adds a top-level `"required"` array listing field names not wrapped in `Option`
+///   and without a default value.
+/// - **Default Values:** Fields with `#[serde(default)]` or `#[serde(default = "path")]`, or in a struct
+///   with `#[serde(default)]`, are not required, and the schema declares their serialized default value,
+///   which requires their type to implement `serde::Serialize`. `#[json_schema(default = ...)]`
+///   declares the default value of a field explicitly, e.g. `#[json_schema(default = 10)]`.
+/// - **Skipped and Flattened Fields:** Fields with `#[serde(skip)]` or `#[serde(skip_deserializing)]` are
+///   omitted, and the properties of `#[serde(flatten)]` fields are merged into the properties of the
+///   struct, required unless the field is an `Option`. A flattened map allows additional properties.
+/// - **Renamed Fields:** Fields are named as serde deserializes them, honoring the `rename` attribute of
+///   fields and the `rename_all` attribute of the struct, e.g. `#[serde(rename_all = "camelCase")]`.
+/// - **Recursive Types:** Fields referring to the struct itself, e.g. `Vec<Node>` or `Option<Box<Node>>`
+///   in `Node`, are emitted as `{"$ref": "#/$defs/Node"}`, and the schema of the struct is defined in
+///   its `"$defs"`. The definitions of nested structs are moved to the `"$defs"` of the parent schema.
+///   As the input schema of tools has no `"$defs"`, `mcp_tool` inlines a definition where it is first
+///   referred to, and points the other references to it.
+/// - **Generic Structs:** A struct with generic type parameters gets a `json_schema_with()` method
+///   instead of `json_schema()`, taking the schemas of its type arguments in order. Fields of a
+///   generic type, e.g. `Versioned<String>`, are described with the schemas of their type arguments.
 ///
 /// # Notes
 /// It’s designed as a straightforward solution to meet the basic needs of this package, supporting
@@ -219,7 +840,8 @@ pub fn mcp_tool(attributes: TokenStream, input: TokenStream) -> TokenStream {
 ///
 /// # Limitations
 /// - Supports only structs with named fields (e.g., `struct S { field: Type }`).
-/// - Nested structs must also derive `JsonSchema`, or compilation will fail.
+/// - Nested structs must also derive `JsonSchema`, or compilation will fail. This includes the
+///   generic structs of fields, such as `Versioned<T>`.
 /// - Unknown types are mapped to `{"type": "unknown"}`.
 /// - Type paths must be in scope (e.g., fully qualified paths like `my_mod::InnerStruct` work if imported).
 ///
@@ -229,7 +851,7 @@ pub fn mcp_tool(attributes: TokenStream, input: TokenStream) -> TokenStream {
 /// # Dependencies
 /// Relies on `serde_json` for `Map` and `Value` types.
 ///
-#[proc_macro_derive(JsonSchema)]
+#[proc_macro_derive(JsonSchema, attributes(json_schema))]
 pub fn derive_json_schema(input: TokenStream) -> TokenStream {
     let input = parse_macro_input!(input as DeriveInput);
     let name = &input.ident;
@@ -242,46 +864,136 @@ pub fn derive_json_schema(input: TokenStream) -> TokenStream {
         _ => panic!("JsonSchema derive macro only supports structs"),
     };
 
-    let field_entries = fields.iter().map(|field| {
-        let field_attrs = &field.attrs;
-        let renamed_field = renamed_field(field_attrs);
-        let field_name = renamed_field.unwrap_or(field.ident.as_ref().unwrap().to_string());
-        let field_type = &field.ty;
+    let rename_all = rename_all_rule(&input.attrs);
+    let container_default = serde_default(&input.attrs).is_some();
+    let context = SchemaContext::new(&input);
+    let field_entries: Vec<_> = fields
+        .iter()
+        .filter(|field| !is_skipped(&field.attrs))
+        .map(|field| {
+            let field_attrs = &field.attrs;
+            let field_name = serde_field_name(field, rename_all.as_deref());
+            let field_type = &field.ty;
 
-        let schema = type_to_json_schema(field_type, field_attrs);
-        quote! {
-            properties.insert(
-                #field_name.to_string(),
-                serde_json::Value::Object(#schema)
-            );
-        }
-    });
+            // the properties of flattened fields are merged into the properties of the struct
+            if has_serde_flag(field_attrs, "flatten") {
+                let flattened_type = if is_option(field_type) {
+                    inner_type(field_type).unwrap_or(field_type)
+                } else {
+                    field_type
+                };
+                if let Some(value_type) = map_value_type(flattened_type) {
+                    let value_schema = type_to_json_schema(value_type, &[], &context);
+                    return quote! {
+                        schema.insert(
+                            "additionalProperties".to_string(),
+                            serde_json::Value::Object(#value_schema)
+                        );
+                    };
+                }
+                let has_default = container_default || serde_default(field_attrs).is_some();
+                let merge_required = (!is_option(field_type) && !has_default).then(|| {
+                    quote! {
+                        if let Some(serde_json::Value::Array(flattened_required)) = flattened.get("required") {
+                            required.extend(
+                                flattened_required
+                                    .iter()
+                                    .filter_map(|name| name.as_str().map(String::from)),
+                            );
+                        }
+                    }
+                });
+                let flattened_schema = type_to_json_schema(flattened_type, &[], &context);
+                return quote! {
+                    let flattened = #flattened_schema;
+                    if let Some(serde_json::Value::Object(flattened_properties)) = flattened.get("properties") {
+                        properties.extend(flattened_properties.clone());
+                    }
+                    #merge_required
+                };
+            }
+
+            let mut schema = type_to_json_schema(field_type, field_attrs, &context);
+            let constraints =
+                schema_constraints(field_attrs).unwrap_or_else(|err| err.to_compile_error());
+            if !constraints.is_empty() {
+                schema = quote! {
+                    {
+                        let mut map = #schema;
+                        #constraints
+                        map
+                    }
+                };
+            }
 
-    let required_fields = fields.iter().filter_map(|field| {
-        let renamed_field = renamed_field(&field.attrs);
-        let field_name = renamed_field.unwrap_or(field.ident.as_ref().unwrap().to_string());
+            // the default value of fields with a serde default, unless declared by `#[json_schema(default = ...)]`
+            let field_default = serde_default(field_attrs);
+            let schema_default = has_schema_default(field_attrs);
+            let default_value = match &field_default {
+                _ if schema_default || is_option(field_type) => None,
+                Some(Some(default_fn)) => Some(quote! { #default_fn() }),
+                Some(None) => Some(quote! { <#field_type as Default>::default() }),
+                None if container_default => {
+                    let field_ident = &field.ident;
+                    Some(quote! { <Self as Default>::default().#field_ident })
+                }
+                None => None,
+            };
+            if let Some(default_value) = default_value {
+                schema = quote! {
+                    {
+                        let mut map = #schema;
+                        if let Ok(default) = serde_json::to_value(#default_value) {
+                            map.insert("default".to_string(), default);
+                        }
+                        map
+                    }
+                };
+            }
 
-        let field_type = &field.ty;
-        if !is_option(field_type) {
-            Some(quote! {
-                required.push(#field_name.to_string());
-            })
-        } else {
-            None
+            let has_default = container_default || field_default.is_some() || schema_default;
+            let required_field = (!is_option(field_type) && !has_default).then(|| {
+                quote! {
+                    required.push(#field_name.to_string());
+                }
+            });
+            quote! {
+                properties.insert(
+                    #field_name.to_string(),
+                    serde_json::Value::Object(#schema)
+                );
+                #required_field
+            }
+        })
+        .collect();
+
+    // recursive structs are defined in `$defs`, referred to by their fields
+    let struct_name = name.to_string();
+    let definition = context.recursive.get().then(|| {
+        quote! {
+            defs.insert(#struct_name.to_string(), serde_json::Value::Object(schema.clone()));
         }
     });
 
+    // generic structs are described given the schemas of their type arguments
+    let signature = if context.generic_params.is_empty() {
+        quote! { json_schema() }
+    } else {
+        quote! { json_schema_with(generic_schemas: &[serde_json::Map<String, serde_json::Value>]) }
+    };
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
     let expanded = quote! {
-        impl #name {
-            pub fn json_schema() -> serde_json::Map<String, serde_json::Value> {
+        impl #impl_generics #name #ty_generics #where_clause {
+            pub fn #signature -> serde_json::Map<String, serde_json::Value> {
                 let mut schema = serde_json::Map::new();
                 let mut properties = serde_json::Map::new();
                 let mut required = Vec::new();
+                #[allow(unused_mut)]
+                let mut defs = serde_json::Map::new();
 
                 #(#field_entries)*
 
-                #(#required_fields)*
-
                 schema.insert("type".to_string(), serde_json::Value::String("object".to_string()));
                 schema.insert("properties".to_string(), serde_json::Value::Object(properties));
                 if !required.is_empty() {
@@ -289,6 +1001,10 @@ pub fn derive_json_schema(input: TokenStream) -> TokenStream {
                         required.into_iter().map(serde_json::Value::String).collect()
                     ));
                 }
+                #definition
+                if !defs.is_empty() {
+                    schema.insert("$defs".to_string(), serde_json::Value::Object(defs));
+                }
 
                 schema
             }
@@ -310,6 +1026,57 @@ mod tests {
         assert_eq!(parsed.description.unwrap(), "A test tool.");
     }
 
+    #[test]
+    fn test_output_attribute() {
+        let input =
+            r#"name = "test_tool", description = "A test tool.", output = my_mod::TestOutput"#;
+        let parsed: McpToolMacroAttributes = parse_str(input).unwrap();
+        let output = parsed.output.unwrap();
+        assert_eq!(quote!(#output).to_string(), "my_mod :: TestOutput");
+
+        let input = r#"name = "test_tool", description = "A test tool.""#;
+        let parsed: McpToolMacroAttributes = parse_str(input).unwrap();
+        assert!(parsed.output.is_none());
+    }
+
+    #[test]
+    fn test_export_schema_attribute() {
+        // the directory is explicit, snapshots are written to the source tree
+        let input = r#"name = "test_tool", description = "A test tool.", export_schema"#;
+        assert!(parse_str::<McpToolMacroAttributes>(input).is_err());
+
+        let input =
+            r#"name = "test_tool", description = "A test tool.", export_schema = "docs/schemas""#;
+        let parsed: McpToolMacroAttributes = parse_str(input).unwrap();
+        assert_eq!(parsed.export_schema.unwrap(), "docs/schemas");
+
+        let input = r#"name = "test_tool", description = "A test tool.""#;
+        let parsed: McpToolMacroAttributes = parse_str(input).unwrap();
+        assert!(parsed.export_schema.is_none());
+    }
+
+    #[test]
+    fn test_dry_run_attribute() {
+        let input = r#"name = "test_tool", description = "A test tool.", dry_run"#;
+        let parsed: McpToolMacroAttributes = parse_str(input).unwrap();
+        assert!(parsed.dry_run);
+
+        let input = r#"name = "test_tool", description = "A test tool.""#;
+        let parsed: McpToolMacroAttributes = parse_str(input).unwrap();
+        assert!(!parsed.dry_run);
+    }
+
+    #[test]
+    fn test_invalid_output_attribute() {
+        let input = r#"name = "test_tool", description = "A test tool.", output = "TestOutput""#;
+        let result: Result<McpToolMacroAttributes, Error> = parse_str(input);
+        assert!(result.is_err());
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "The 'output' attribute should be the path of a struct, e.g. `output = MyToolOutput`."
+        );
+    }
+
     #[test]
     fn test_missing_name() {
         let input = r#"description = "Only description""#;
@@ -342,6 +1109,52 @@ mod tests {
             "The 'name' attribute should not be an empty string."
         );
     }
+    #[test]
+    fn test_prompt_macro_attributes() {
+        let input = r#"name = "code_review", description = "Reviews code.""#;
+        let parsed: McpPromptMacroAttributes = parse_str(input).unwrap();
+        assert_eq!(parsed.name, "code_review");
+        assert_eq!(parsed.description, "Reviews code.");
+
+        let input = r#"description = "Reviews code.""#;
+        let result: Result<McpPromptMacroAttributes, Error> = parse_str(input);
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "The 'name' attribute is required."
+        );
+
+        let input = r#"name = "code_review", description = " ""#;
+        let result: Result<McpPromptMacroAttributes, Error> = parse_str(input);
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "The 'description' attribute should not be an empty string."
+        );
+    }
+
+    #[test]
+    fn test_resource_macro_attributes() {
+        let input = r#"uri = "file:///readme", name = "README", mime_type = "text/markdown""#;
+        let parsed: McpResourceMacroAttributes = parse_str(input).unwrap();
+        assert_eq!(parsed.uri, "file:///readme");
+        assert_eq!(parsed.name, "README");
+        assert_eq!(parsed.mime_type.unwrap(), "text/markdown");
+        assert!(parsed.description.is_none());
+
+        let input = r#"name = "README""#;
+        let result: Result<McpResourceMacroAttributes, Error> = parse_str(input);
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "The 'uri' attribute is required."
+        );
+
+        let input = r#"uri = "file:///readme", name = """#;
+        let result: Result<McpResourceMacroAttributes, Error> = parse_str(input);
+        assert_eq!(
+            result.err().unwrap().to_string(),
+            "The 'name' attribute should not be an empty string."
+        );
+    }
+
     #[test]
     fn test_empty_description_field() {
         let input = r#"name = "my-tool", description = """#;
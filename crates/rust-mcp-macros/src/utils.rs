@@ -1,3 +1,5 @@
+use std::cell::Cell;
+
 use quote::quote;
 use syn::{punctuated::Punctuated, token, Attribute, Path, PathArguments, Type};
 
@@ -46,7 +48,30 @@ pub fn inner_type(ty: &Type) -> Option<&Type> {
     None
 }
 
-fn doc_comment(attrs: &[Attribute]) -> Option<String> {
+// Extract the value type from HashMap<K, V> or BTreeMap<K, V>, including fully qualified paths
+// such as std::collections::HashMap<K, V>
+pub fn map_value_type(ty: &Type) -> Option<&Type> {
+    if let Type::Path(type_path) = ty {
+        if let Some(segment) = type_path.path.segments.last() {
+            if segment.ident != "HashMap" && segment.ident != "BTreeMap" {
+                return None;
+            }
+            if let PathArguments::AngleBracketed(args) = &segment.arguments {
+                let mut types = args.args.iter().filter_map(|arg| match arg {
+                    syn::GenericArgument::Type(ty) => Some(ty),
+                    _ => None,
+                });
+                // HashMap may have a third (hasher) type argument, the value type is always the second one
+                if let (Some(_), Some(value_ty)) = (types.next(), types.next()) {
+                    return Some(value_ty);
+                }
+            }
+        }
+    }
+    None
+}
+
+pub fn doc_comment(attrs: &[Attribute]) -> Option<String> {
     let mut docs = Vec::new();
     for attr in attrs {
         if attr.path().is_ident("doc") {
@@ -72,8 +97,8 @@ pub fn might_be_struct(ty: &Type) -> bool {
         if type_path.path.segments.len() == 1 {
             let ident = type_path.path.segments[0].ident.to_string();
             let common_types = vec![
-                "i8", "i16", "i32", "i64", "i128", "u8", "u16", "u32", "u64", "u128", "f32", "f64",
-                "bool", "char", "str", "String", "Vec", "Option",
+                "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128",
+                "usize", "f32", "f64", "bool", "char", "str", "String", "Vec", "Option",
             ];
             return !common_types.contains(&ident.as_str())
                 && type_path.path.segments[0].arguments.is_empty();
@@ -82,28 +107,192 @@ pub fn might_be_struct(ty: &Type) -> bool {
     false
 }
 
-pub fn type_to_json_schema(ty: &Type, attrs: &[Attribute]) -> proc_macro2::TokenStream {
-    let number_types = [
-        "i8", "i16", "i32", "i64", "i128", "u8", "u16", "u32", "u64", "u128", "f32", "f64",
+/// The struct a JSON schema is derived for, so that the types of its fields can refer to the struct
+/// itself, in recursive types, and to its generic type parameters.
+#[derive(Default)]
+pub struct SchemaContext {
+    pub struct_ident: Option<syn::Ident>,
+    pub generic_params: Vec<syn::Ident>,
+    // Set once a field refers to the struct itself
+    pub recursive: Cell<bool>,
+    // Set by `#[json_schema(strict_nullable)]`, describing `Option<T>` fields with a `"null"` type
+    pub strict_nullable: bool,
+}
+
+impl SchemaContext {
+    pub fn new(input: &syn::DeriveInput) -> Self {
+        Self {
+            struct_ident: Some(input.ident.clone()),
+            generic_params: input
+                .generics
+                .type_params()
+                .map(|param| param.ident.clone())
+                .collect(),
+            recursive: Cell::new(false),
+            strict_nullable: has_schema_attribute(&input.attrs, STRICT_NULLABLE_ATTRIBUTE),
+        }
+    }
+
+    // The name of the struct, if the type is the struct itself
+    fn self_reference(&self, type_path: &syn::TypePath) -> Option<String> {
+        let struct_ident = self.struct_ident.as_ref()?;
+        let [segment] = single_segment(type_path)? else {
+            return None;
+        };
+        (segment.ident == "Self" || segment.ident == *struct_ident)
+            .then(|| struct_ident.to_string())
+    }
+
+    // The index of the generic type parameter, if the type is one of them
+    fn generic_param(&self, type_path: &syn::TypePath) -> Option<usize> {
+        let [segment] = single_segment(type_path)? else {
+            return None;
+        };
+        if !segment.arguments.is_empty() {
+            return None;
+        }
+        self.generic_params
+            .iter()
+            .position(|param| segment.ident == *param)
+    }
+}
+
+fn single_segment(type_path: &syn::TypePath) -> Option<&[syn::PathSegment]> {
+    (type_path.qself.is_none() && type_path.path.segments.len() == 1)
+        .then(|| std::slice::from_ref(&type_path.path.segments[0]))
+}
+
+// The type arguments of a path segment, e.g. `String` and `u32` for `HashMap<String, u32>`
+fn type_arguments(segment: &syn::PathSegment) -> Vec<&Type> {
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args
+            .args
+            .iter()
+            .filter_map(|arg| match arg {
+                syn::GenericArgument::Type(ty) => Some(ty),
+                _ => None,
+            })
+            .collect(),
+        _ => vec![],
+    }
+}
+
+// Inlines the schema of a struct deriving `JsonSchema`, moving its `$defs` to the `defs` of the
+// schema being generated. Recursive structs are referred to by their definition instead.
+fn nested_schema(schema: proc_macro2::TokenStream, struct_name: &str) -> proc_macro2::TokenStream {
+    let reference = format!("#/$defs/{struct_name}");
+    quote! {
+        {
+            let mut inner_schema = #schema;
+            if let Some(serde_json::Value::Object(inner_defs)) = inner_schema.remove("$defs") {
+                if inner_defs.contains_key(#struct_name) {
+                    inner_schema = serde_json::Map::new();
+                    inner_schema.insert("$ref".to_string(), serde_json::Value::String(#reference.to_string()));
+                }
+                defs.extend(inner_defs);
+            }
+            inner_schema
+        }
+    }
+}
+
+/// Generates the expression building the JSON schema of a field type, in the `json_schema()`
+/// function derived for the struct described by `context`.
+pub fn type_to_json_schema(
+    ty: &Type,
+    attrs: &[Attribute],
+    context: &SchemaContext,
+) -> proc_macro2::TokenStream {
+    let integer_types = [
+        "i8", "i16", "i32", "i64", "i128", "isize", "u8", "u16", "u32", "u64", "u128", "usize",
     ];
+    let number_types = ["f32", "f64"];
     let doc_comment = doc_comment(attrs);
     let description = doc_comment.as_ref().map(|desc| {
         quote! {
             map.insert("description".to_string(), serde_json::Value::String(#desc.to_string()));
         }
     });
+    // Handle HashMap<String, T> and BTreeMap<String, T>
+    if let Some(value_ty) = map_value_type(ty) {
+        let value_schema = type_to_json_schema(value_ty, &[], context);
+        return quote! {
+            {
+                let mut map = serde_json::Map::new();
+                map.insert("type".to_string(), serde_json::Value::String("object".to_string()));
+                map.insert("additionalProperties".to_string(), serde_json::Value::Object(#value_schema));
+                #description
+                map
+            }
+        };
+    }
     match ty {
         Type::Path(type_path) => {
+            // Handle references to the struct itself, in recursive types
+            if let Some(struct_name) = context.self_reference(type_path) {
+                context.recursive.set(true);
+                let reference = format!("#/$defs/{struct_name}");
+                return quote! {
+                    {
+                        let mut map = serde_json::Map::new();
+                        map.insert("$ref".to_string(), serde_json::Value::String(#reference.to_string()));
+                        #description
+                        map
+                    }
+                };
+            }
+            // Handle generic type parameters, described by the schemas of the type arguments
+            if let Some(index) = context.generic_param(type_path) {
+                return quote! {
+                    {
+                        let mut map = generic_schemas.get(#index).cloned().unwrap_or_default();
+                        #description
+                        map
+                    }
+                };
+            }
             if type_path.path.segments.len() == 1 {
                 let segment = &type_path.path.segments[0];
                 let ident = &segment.ident;
 
+                // Handle Box<T>, Rc<T> and Arc<T>
+                if ident == "Box" || ident == "Rc" || ident == "Arc" {
+                    if let [inner_ty] = type_arguments(segment)[..] {
+                        return type_to_json_schema(inner_ty, attrs, context);
+                    }
+                }
+
                 // Handle Option<T>
                 if ident == "Option" {
                     if let PathArguments::AngleBracketed(args) = &segment.arguments {
                         if args.args.len() == 1 {
                             if let syn::GenericArgument::Type(inner_ty) = &args.args[0] {
-                                let inner_schema = type_to_json_schema(inner_ty, attrs);
+                                let inner_schema = type_to_json_schema(inner_ty, attrs, context);
+                                if context.strict_nullable {
+                                    return quote! {
+                                        {
+                                            let mut map = #inner_schema;
+                                            match map.remove("type") {
+                                                Some(serde_json::Value::String(inner_type)) => {
+                                                    map.insert("type".to_string(), serde_json::json!([inner_type, "null"]));
+                                                }
+                                                inner_type => {
+                                                    if let Some(inner_type) = inner_type {
+                                                        map.insert("type".to_string(), inner_type);
+                                                    }
+                                                    map.remove("description");
+                                                    let null_schema = serde_json::json!({"type": "null"});
+                                                    map = serde_json::Map::from_iter([(
+                                                        "anyOf".to_string(),
+                                                        serde_json::json!([map, null_schema]),
+                                                    )]);
+                                                }
+                                            }
+                                            #description
+                                            map
+                                        }
+                                    };
+                                }
                                 return quote! {
                                     {
                                         let mut map = serde_json::Map::new();
@@ -120,12 +309,15 @@ pub fn type_to_json_schema(ty: &Type, attrs: &[Attribute]) -> proc_macro2::Token
                         }
                     }
                 }
-                // Handle Vec<T>
-                else if ident == "Vec" {
+                // Handle Vec<T>, VecDeque<T>, HashSet<T> and BTreeSet<T>
+                else if ["Vec", "VecDeque", "HashSet", "BTreeSet"]
+                    .iter()
+                    .any(|collection| ident == collection)
+                {
                     if let PathArguments::AngleBracketed(args) = &segment.arguments {
                         if args.args.len() == 1 {
                             if let syn::GenericArgument::Type(inner_ty) = &args.args[0] {
-                                let inner_schema = type_to_json_schema(inner_ty, &[]);
+                                let inner_schema = type_to_json_schema(inner_ty, &[], context);
                                 return quote! {
                                     {
                                         let mut map = serde_json::Map::new();
@@ -142,12 +334,17 @@ pub fn type_to_json_schema(ty: &Type, attrs: &[Attribute]) -> proc_macro2::Token
                 // Handle nested structs
                 else if might_be_struct(ty) {
                     let path = &type_path.path;
-                    return quote! {
-                        {
-                            let inner_schema = #path::json_schema();
-                            inner_schema
-                        }
-                    };
+                    return nested_schema(quote! { #path::json_schema() }, &ident.to_string());
+                }
+                // Handle generic structs, given the schemas of their type arguments
+                else if !type_arguments(segment).is_empty() {
+                    let argument_schemas = type_arguments(segment)
+                        .into_iter()
+                        .map(|argument| type_to_json_schema(argument, &[], context));
+                    return nested_schema(
+                        quote! { <#ty>::json_schema_with(&[#(#argument_schemas),*]) },
+                        &ident.to_string(),
+                    );
                 }
                 // Handle basic types
                 else if ident == "String" {
@@ -159,6 +356,15 @@ pub fn type_to_json_schema(ty: &Type, attrs: &[Attribute]) -> proc_macro2::Token
                             map
                         }
                     };
+                } else if integer_types.iter().any(|t| ident == t) {
+                    return quote! {
+                        {
+                            let mut map = serde_json::Map::new();
+                            map.insert("type".to_string(), serde_json::Value::String("integer".to_string()));
+                            #description
+                            map
+                        }
+                    };
                 } else if number_types.iter().any(|t| ident == t) {
                     return quote! {
                         {
@@ -200,6 +406,112 @@ pub fn type_to_json_schema(ty: &Type, attrs: &[Attribute]) -> proc_macro2::Token
     }
 }
 
+/// Validation keywords supported by the `#[json_schema(...)]` field attribute, with the
+/// JSON Schema keyword each of them is emitted as.
+const NUMERIC_CONSTRAINTS: [(&str, &str); 9] = [
+    ("minimum", "minimum"),
+    ("maximum", "maximum"),
+    ("exclusive_minimum", "exclusiveMinimum"),
+    ("exclusive_maximum", "exclusiveMaximum"),
+    ("multiple_of", "multipleOf"),
+    ("min_length", "minLength"),
+    ("max_length", "maxLength"),
+    ("min_items", "minItems"),
+    ("max_items", "maxItems"),
+];
+const STRING_CONSTRAINTS: [(&str, &str); 2] = [("pattern", "pattern"), ("format", "format")];
+/// Flags supported by the `#[json_schema(...)]` field attribute, emitted as `true`.
+/// `encrypted` marks a sensitive field that clients should encrypt before sending it.
+const FLAG_ATTRIBUTES: [(&str, &str); 1] = [("encrypted", "x-mcp-encrypted")];
+
+// The `#[json_schema(default = ...)]` attribute, declaring the default value of a field
+const DEFAULT_ATTRIBUTE: &str = "default";
+// The `#[json_schema(strict_nullable)]` struct attribute, describing optional fields with a `"null"`
+// type instead of `"nullable": true`
+const STRICT_NULLABLE_ATTRIBUTE: &str = "strict_nullable";
+
+// Parses a numeric literal, optionally negated, such as `1`, `-5` or `0.5`
+fn parse_number(expr: &syn::Expr) -> Option<proc_macro2::TokenStream> {
+    match expr {
+        syn::Expr::Lit(syn::ExprLit { lit, .. }) => match lit {
+            syn::Lit::Int(lit_int) => lit_int.base10_parse::<i64>().ok().map(|v| quote! {#v}),
+            syn::Lit::Float(lit_float) => lit_float.base10_parse::<f64>().ok().map(|v| quote! {#v}),
+            _ => None,
+        },
+        syn::Expr::Unary(syn::ExprUnary {
+            op: syn::UnOp::Neg(_),
+            expr,
+            ..
+        }) => parse_number(expr).map(|v| quote! {-#v}),
+        _ => None,
+    }
+}
+
+/// Generates the statements inserting the validation keywords of `#[json_schema(...)]`
+/// field attributes into a schema `map`.
+///
+/// e.g. `#[json_schema(minimum = 1, maximum = 100, format = "uri")]`
+pub fn schema_constraints(attrs: &[Attribute]) -> syn::Result<proc_macro2::TokenStream> {
+    let mut constraints = vec![];
+
+    for attr in attrs {
+        if !attr.path().is_ident("json_schema") {
+            continue;
+        }
+        attr.parse_nested_meta(|meta| {
+            if let Some((_, keyword)) = NUMERIC_CONSTRAINTS
+                .iter()
+                .find(|(name, _)| meta.path.is_ident(name))
+            {
+                let expr: syn::Expr = meta.value()?.parse()?;
+                let value = parse_number(&expr)
+                    .ok_or_else(|| syn::Error::new_spanned(&expr, "expected a numeric literal"))?;
+                constraints.push(quote! {
+                    map.insert(#keyword.to_string(), serde_json::Value::from(#value));
+                });
+                Ok(())
+            } else if let Some((_, keyword)) = STRING_CONSTRAINTS
+                .iter()
+                .find(|(name, _)| meta.path.is_ident(name))
+            {
+                let value: syn::LitStr = meta.value()?.parse()?;
+                constraints.push(quote! {
+                    map.insert(#keyword.to_string(), serde_json::Value::String(#value.to_string()));
+                });
+                Ok(())
+            } else if let Some((_, keyword)) = FLAG_ATTRIBUTES
+                .iter()
+                .find(|(name, _)| meta.path.is_ident(name))
+            {
+                constraints.push(quote! {
+                    map.insert(#keyword.to_string(), serde_json::Value::Bool(true));
+                });
+                Ok(())
+            } else if meta.path.is_ident(DEFAULT_ATTRIBUTE) {
+                let value: syn::Expr = meta.value()?.parse()?;
+                constraints.push(quote! {
+                    map.insert("default".to_string(), serde_json::json!(#value));
+                });
+                Ok(())
+            } else {
+                let supported: Vec<_> = NUMERIC_CONSTRAINTS
+                    .iter()
+                    .chain(STRING_CONSTRAINTS.iter())
+                    .chain(FLAG_ATTRIBUTES.iter())
+                    .map(|(name, _)| *name)
+                    .chain([DEFAULT_ATTRIBUTE])
+                    .collect();
+                Err(meta.error(format!(
+                    "unsupported json_schema attribute, expected one of: {}",
+                    supported.join(", ")
+                )))
+            }
+        })?;
+    }
+
+    Ok(quote! { #(#constraints)* })
+}
+
 #[allow(unused)]
 pub fn has_derive(attrs: &[Attribute], trait_name: &str) -> bool {
     attrs.iter().any(|attr| {
@@ -237,6 +549,166 @@ pub fn renamed_field(attrs: &[Attribute]) -> Option<String> {
     renamed
 }
 
+/// Returns the `rename_all` rule of the `#[serde(...)]` attributes of a struct, if any.
+/// When different rules are given for serialization and deserialization, the deserialization rule
+/// is returned, as it names the arguments expected from clients.
+pub fn rename_all_rule(attrs: &[Attribute]) -> Option<String> {
+    let mut rule = None;
+
+    for attr in attrs {
+        if attr.path().is_ident("serde") {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    if meta.input.peek(token::Paren) {
+                        meta.parse_nested_meta(|meta| {
+                            let value: syn::LitStr = meta.value()?.parse()?;
+                            if meta.path.is_ident("deserialize") {
+                                rule = Some(value.value());
+                            }
+                            Ok(())
+                        })?;
+                    } else {
+                        let value: syn::LitStr = meta.value()?.parse()?;
+                        rule = Some(value.value());
+                    }
+                } else {
+                    skip_meta_value(&meta)?;
+                }
+                Ok(())
+            });
+        }
+    }
+
+    rule
+}
+
+/// Returns `true` if the `#[serde(...)]` attributes include the given flag, e.g. `skip` or `flatten`.
+pub fn has_serde_flag(attrs: &[Attribute], flag: &str) -> bool {
+    let mut found = false;
+
+    for attr in attrs {
+        if attr.path().is_ident("serde") {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident(flag) {
+                    found = true;
+                }
+                skip_meta_value(&meta)
+            });
+        }
+    }
+
+    found
+}
+
+/// Returns the `default` of the `#[serde(...)]` attributes, if any: `Some(None)` for `#[serde(default)]`,
+/// and `Some(Some(path))` for `#[serde(default = "path")]`.
+pub fn serde_default(attrs: &[Attribute]) -> Option<Option<Path>> {
+    let mut default = None;
+
+    for attr in attrs {
+        if attr.path().is_ident("serde") {
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("default") {
+                    if meta.input.peek(syn::Token![=]) {
+                        let value: syn::LitStr = meta.value()?.parse()?;
+                        default = Some(Some(value.parse()?));
+                    } else {
+                        default = Some(None);
+                    }
+                    Ok(())
+                } else {
+                    skip_meta_value(&meta)
+                }
+            });
+        }
+    }
+
+    default
+}
+
+/// Returns `true` if the `#[json_schema(...)]` attributes declare the default value of the field.
+pub fn has_schema_default(attrs: &[Attribute]) -> bool {
+    has_schema_attribute(attrs, DEFAULT_ATTRIBUTE)
+}
+
+// Returns `true` if a `#[json_schema(...)]` attribute contains `name`, with or without a value
+fn has_schema_attribute(attrs: &[Attribute], name: &str) -> bool {
+    attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("json_schema"))
+        .any(|attr| {
+            let mut found = false;
+            let _ = attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident(name) {
+                    found = true;
+                }
+                skip_meta_value(&meta)
+            });
+            found
+        })
+}
+
+/// Returns `true` if serde never deserializes the field, so that it is not part of the schema.
+pub fn is_skipped(attrs: &[Attribute]) -> bool {
+    has_serde_flag(attrs, "skip") || has_serde_flag(attrs, "skip_deserializing")
+}
+
+// Skips the value of a serde meta item, e.g. `default = "..."` or `rename(serialize = "...")`,
+// so that the following items are parsed
+fn skip_meta_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(syn::Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(token::Paren) {
+        let _content;
+        syn::parenthesized!(_content in meta.input);
+    }
+    Ok(())
+}
+
+/// Applies a serde `rename_all` rule to the snake_case name of a field.
+/// Unknown rules leave the name unchanged.
+pub fn apply_rename_all(field_name: &str, rule: &str) -> String {
+    let pascal_case = || {
+        field_name
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                match chars.next() {
+                    Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                    None => String::new(),
+                }
+            })
+            .collect::<String>()
+    };
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field_name.to_ascii_uppercase(),
+        "PascalCase" => pascal_case(),
+        "camelCase" => {
+            let pascal_case = pascal_case();
+            let mut chars = pascal_case.chars();
+            match chars.next() {
+                Some(first) => first.to_lowercase().chain(chars).collect(),
+                None => pascal_case,
+            }
+        }
+        "kebab-case" => field_name.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field_name.replace('_', "-").to_ascii_uppercase(),
+        _ => field_name.to_string(),
+    }
+}
+
+/// Returns the name of a field as deserialized by serde: the name given by its `rename` attribute,
+/// or its own name with the `rename_all` rule of the struct applied.
+pub fn serde_field_name(field: &syn::Field, rename_all: Option<&str>) -> String {
+    renamed_field(&field.attrs).unwrap_or_else(|| {
+        let field_name = field.ident.as_ref().unwrap().to_string();
+        match rename_all {
+            Some(rule) => apply_rename_all(&field_name, rule),
+            None => field_name,
+        }
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -296,7 +768,7 @@ mod tests {
     fn test_type_to_json_schema_string() {
         let ty: Type = parse_quote!(String);
         let attrs: Vec<Attribute> = vec![];
-        let tokens = type_to_json_schema(&ty, &attrs);
+        let tokens = type_to_json_schema(&ty, &attrs, &SchemaContext::default());
         let output = tokens.to_string();
         assert!(output.contains("\"string\""));
     }
@@ -305,7 +777,7 @@ mod tests {
     fn test_type_to_json_schema_option() {
         let ty: Type = parse_quote!(Option<i32>);
         let attrs: Vec<Attribute> = vec![];
-        let tokens = type_to_json_schema(&ty, &attrs);
+        let tokens = type_to_json_schema(&ty, &attrs, &SchemaContext::default());
         let output = tokens.to_string();
         assert!(output.contains("\"nullable\""));
     }
@@ -314,7 +786,7 @@ mod tests {
     fn test_type_to_json_schema_vec() {
         let ty: Type = parse_quote!(Vec<String>);
         let attrs: Vec<Attribute> = vec![];
-        let tokens = type_to_json_schema(&ty, &attrs);
+        let tokens = type_to_json_schema(&ty, &attrs, &SchemaContext::default());
         let output = tokens.to_string();
         assert!(output.contains("\"array\""));
     }
@@ -322,7 +794,7 @@ mod tests {
     #[test]
     fn test_has_derive() {
         let attr: Attribute = parse_quote!(#[derive(Clone, Debug)]);
-        assert!(has_derive(&[attr.clone()], "Debug"));
+        assert!(has_derive(std::slice::from_ref(&attr), "Debug"));
         assert!(!has_derive(&[attr], "Serialize"));
     }
 
@@ -487,7 +959,7 @@ mod tests {
     #[test]
     fn test_json_schema_string() {
         let ty: syn::Type = parse_quote!(String);
-        let tokens = type_to_json_schema(&ty, &[]);
+        let tokens = type_to_json_schema(&ty, &[], &SchemaContext::default());
         let output = render(tokens);
         assert!(output
             .contains("\"type\".to_string(),serde_json::Value::String(\"string\".to_string())"));
@@ -496,7 +968,13 @@ mod tests {
     #[test]
     fn test_json_schema_number() {
         let ty: syn::Type = parse_quote!(i32);
-        let tokens = type_to_json_schema(&ty, &[]);
+        let tokens = type_to_json_schema(&ty, &[], &SchemaContext::default());
+        let output = render(tokens);
+        assert!(output
+            .contains("\"type\".to_string(),serde_json::Value::String(\"integer\".to_string())"));
+
+        let ty: syn::Type = parse_quote!(f64);
+        let tokens = type_to_json_schema(&ty, &[], &SchemaContext::default());
         let output = render(tokens);
         assert!(output
             .contains("\"type\".to_string(),serde_json::Value::String(\"number\".to_string())"));
@@ -505,7 +983,7 @@ mod tests {
     #[test]
     fn test_json_schema_boolean() {
         let ty: syn::Type = parse_quote!(bool);
-        let tokens = type_to_json_schema(&ty, &[]);
+        let tokens = type_to_json_schema(&ty, &[], &SchemaContext::default());
         let output = render(tokens);
         assert!(output
             .contains("\"type\".to_string(),serde_json::Value::String(\"boolean\".to_string())"));
@@ -514,7 +992,7 @@ mod tests {
     #[test]
     fn test_json_schema_vec_of_string() {
         let ty: syn::Type = parse_quote!(Vec<String>);
-        let tokens = type_to_json_schema(&ty, &[]);
+        let tokens = type_to_json_schema(&ty, &[], &SchemaContext::default());
         let output = render(tokens);
         assert!(output
             .contains("\"type\".to_string(),serde_json::Value::String(\"array\".to_string())"));
@@ -524,17 +1002,35 @@ mod tests {
     #[test]
     fn test_json_schema_option_of_number() {
         let ty: syn::Type = parse_quote!(Option<u64>);
-        let tokens = type_to_json_schema(&ty, &[]);
+        let tokens = type_to_json_schema(&ty, &[], &SchemaContext::default());
         let output = render(tokens);
         assert!(output.contains("\"nullable\".to_string(),serde_json::Value::Bool(true)"));
         assert!(output
-            .contains("\"type\".to_string(),serde_json::Value::String(\"number\".to_string())"));
+            .contains("\"type\".to_string(),serde_json::Value::String(\"integer\".to_string())"));
+    }
+
+    #[test]
+    fn test_json_schema_strict_nullable() {
+        let input: syn::DeriveInput = parse_quote!(
+            #[json_schema(strict_nullable)]
+            struct Query {
+                limit: Option<u64>,
+            }
+        );
+        let context = SchemaContext::new(&input);
+        assert!(context.strict_nullable);
+
+        let ty: syn::Type = parse_quote!(Option<u64>);
+        let output = render(type_to_json_schema(&ty, &[], &context));
+        assert!(!output.contains("\"nullable\""));
+        assert!(output.contains("serde_json::json!([inner_type,\"null\"])"));
+        assert!(output.contains("\"anyOf\""));
     }
 
     #[test]
     fn test_json_schema_custom_struct() {
         let ty: syn::Type = parse_quote!(MyStruct);
-        let tokens = type_to_json_schema(&ty, &[]);
+        let tokens = type_to_json_schema(&ty, &[], &SchemaContext::default());
         let output = render(tokens);
         assert!(output.contains("MyStruct::json_schema()"));
     }
@@ -543,19 +1039,234 @@ mod tests {
     fn test_json_schema_with_doc_comment() {
         let ty: syn::Type = parse_quote!(String);
         let attrs: Vec<Attribute> = vec![parse_quote!(#[doc = "A user name."])];
-        let tokens = type_to_json_schema(&ty, &attrs);
+        let tokens = type_to_json_schema(&ty, &attrs, &SchemaContext::default());
         let output = render(tokens);
         assert!(output.contains(
             "\"description\".to_string(),serde_json::Value::String(\"Ausername.\".to_string())"
         ));
     }
 
+    #[test]
+    fn test_map_value_type() {
+        let ty: Type = parse_quote!(HashMap<String, i32>);
+        let value_ty = map_value_type(&ty).unwrap();
+        assert_eq!(quote!(#value_ty).to_string(), quote!(i32).to_string());
+
+        let ty: Type = parse_quote!(std::collections::BTreeMap<String, Vec<String>>);
+        let value_ty = map_value_type(&ty).unwrap();
+        assert_eq!(
+            quote!(#value_ty).to_string(),
+            quote!(Vec<String>).to_string()
+        );
+
+        let ty: Type = parse_quote!(Vec<String>);
+        assert!(map_value_type(&ty).is_none());
+    }
+
+    #[test]
+    fn test_json_schema_hashmap() {
+        let ty: syn::Type = parse_quote!(HashMap<String, bool>);
+        let tokens = type_to_json_schema(&ty, &[], &SchemaContext::default());
+        let output = render(tokens);
+        assert!(output
+            .contains("\"type\".to_string(),serde_json::Value::String(\"object\".to_string())"));
+        assert!(output.contains("\"additionalProperties\".to_string(),serde_json::Value::Object"));
+        assert!(output
+            .contains("\"type\".to_string(),serde_json::Value::String(\"boolean\".to_string())"));
+    }
+
+    #[test]
+    fn test_json_schema_btreemap_of_struct() {
+        let ty: syn::Type = parse_quote!(BTreeMap<String, MyStruct>);
+        let tokens = type_to_json_schema(&ty, &[], &SchemaContext::default());
+        let output = render(tokens);
+        assert!(output.contains("\"additionalProperties\".to_string(),serde_json::Value::Object"));
+        assert!(output.contains("MyStruct::json_schema()"));
+    }
+
+    #[test]
+    fn test_schema_constraints() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[json_schema(minimum = 1, maximum = 100)]),
+            parse_quote!(#[json_schema(exclusive_minimum = -0.5, pattern = "^[a-z]+$", format = "uri")]),
+        ];
+        let output = render(schema_constraints(&attrs).unwrap());
+        assert!(output.contains("\"minimum\".to_string(),serde_json::Value::from(1i64)"));
+        assert!(output.contains("\"maximum\".to_string(),serde_json::Value::from(100i64)"));
+        assert!(
+            output.contains("\"exclusiveMinimum\".to_string(),serde_json::Value::from(-0.5f64)")
+        );
+        assert!(output.contains(
+            "\"pattern\".to_string(),serde_json::Value::String(\"^[a-z]+$\".to_string())"
+        ));
+        assert!(output
+            .contains("\"format\".to_string(),serde_json::Value::String(\"uri\".to_string())"));
+    }
+
+    #[test]
+    fn test_schema_constraints_encrypted_flag() {
+        let attrs: Vec<Attribute> = vec![parse_quote!(#[json_schema(encrypted, min_length = 8)])];
+        let output = render(schema_constraints(&attrs).unwrap());
+        assert!(output.contains("\"x-mcp-encrypted\".to_string(),serde_json::Value::Bool(true)"));
+        assert!(output.contains("\"minLength\".to_string(),serde_json::Value::from(8i64)"));
+    }
+
+    #[test]
+    fn test_schema_constraints_ignores_other_attributes() {
+        let attrs: Vec<Attribute> = vec![
+            parse_quote!(#[serde(rename = "x")]),
+            parse_quote!(#[doc = " A field"]),
+        ];
+        assert!(schema_constraints(&attrs).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_schema_constraints_errors() {
+        let attr: Attribute = parse_quote!(#[json_schema(minimum = "one")]);
+        let err = schema_constraints(&[attr]).unwrap_err();
+        assert_eq!(err.to_string(), "expected a numeric literal");
+
+        let attr: Attribute = parse_quote!(#[json_schema(pattern = 1)]);
+        assert!(schema_constraints(&[attr]).is_err());
+
+        let attr: Attribute = parse_quote!(#[json_schema(maximun = 1)]);
+        let err = schema_constraints(&[attr]).unwrap_err();
+        assert!(err
+            .to_string()
+            .starts_with("unsupported json_schema attribute"));
+    }
+
     #[test]
     fn test_json_schema_fallback_unknown() {
         let ty: syn::Type = parse_quote!((i32, i32));
-        let tokens = type_to_json_schema(&ty, &[]);
+        let tokens = type_to_json_schema(&ty, &[], &SchemaContext::default());
         let output = render(tokens);
         assert!(output
             .contains("\"type\".to_string(),serde_json::Value::String(\"unknown\".to_string())"));
     }
+
+    #[test]
+    fn test_rename_all_rule() {
+        let attr: Attribute = parse_quote!(#[serde(rename_all = "camelCase")]);
+        assert_eq!(rename_all_rule(&[attr]), Some("camelCase".to_string()));
+
+        let attr: Attribute =
+            parse_quote!(#[serde(deny_unknown_fields, default, rename_all = "kebab-case")]);
+        assert_eq!(rename_all_rule(&[attr]), Some("kebab-case".to_string()));
+
+        let attr: Attribute = parse_quote!(
+            #[serde(rename_all(serialize = "UPPERCASE", deserialize = "PascalCase"))]
+        );
+        assert_eq!(rename_all_rule(&[attr]), Some("PascalCase".to_string()));
+
+        let attr: Attribute = parse_quote!(#[serde(rename = "other")]);
+        assert_eq!(rename_all_rule(&[attr]), None);
+    }
+
+    #[test]
+    fn test_apply_rename_all() {
+        assert_eq!(
+            apply_rename_all("max_line_length", "camelCase"),
+            "maxLineLength"
+        );
+        assert_eq!(
+            apply_rename_all("max_line_length", "PascalCase"),
+            "MaxLineLength"
+        );
+        assert_eq!(
+            apply_rename_all("max_line_length", "kebab-case"),
+            "max-line-length"
+        );
+        assert_eq!(
+            apply_rename_all("max_line_length", "SCREAMING_SNAKE_CASE"),
+            "MAX_LINE_LENGTH"
+        );
+        assert_eq!(
+            apply_rename_all("max_line_length", "SCREAMING-KEBAB-CASE"),
+            "MAX-LINE-LENGTH"
+        );
+        assert_eq!(
+            apply_rename_all("max_line_length", "UPPERCASE"),
+            "MAX_LINE_LENGTH"
+        );
+        assert_eq!(
+            apply_rename_all("max_line_length", "lowercase"),
+            "max_line_length"
+        );
+        assert_eq!(
+            apply_rename_all("max_line_length", "snake_case"),
+            "max_line_length"
+        );
+        assert_eq!(apply_rename_all("path", "camelCase"), "path");
+    }
+
+    #[test]
+    fn test_serde_field_name() {
+        let field: syn::Field = parse_quote!(max_length: u32);
+        assert_eq!(serde_field_name(&field, None), "max_length");
+        assert_eq!(serde_field_name(&field, Some("camelCase")), "maxLength");
+
+        let field: syn::Field = parse_quote!(#[serde(rename = "limit")] max_length: u32);
+        assert_eq!(serde_field_name(&field, Some("camelCase")), "limit");
+    }
+
+    #[test]
+    fn test_has_serde_flag() {
+        let attr: Attribute = parse_quote!(#[serde(default = "default_limit", flatten)]);
+        assert!(has_serde_flag(&[attr], "flatten"));
+
+        let attr: Attribute = parse_quote!(#[serde(rename(serialize = "a"), skip_deserializing)]);
+        assert!(is_skipped(&[attr]));
+
+        let attr: Attribute = parse_quote!(#[serde(skip)]);
+        assert!(is_skipped(&[attr]));
+
+        let attr: Attribute = parse_quote!(#[serde(skip_serializing_if = "Option::is_none")]);
+        assert!(!is_skipped(std::slice::from_ref(&attr)));
+        assert!(!has_serde_flag(&[attr], "flatten"));
+    }
+
+    #[test]
+    fn test_defaults() {
+        let attr: Attribute = parse_quote!(#[serde(default)]);
+        assert!(matches!(serde_default(&[attr]), Some(None)));
+
+        let attr: Attribute = parse_quote!(#[serde(rename = "a", default = "defaults::limit")]);
+        let path = serde_default(&[attr]).unwrap().unwrap();
+        assert_eq!(quote!(#path).to_string(), "defaults :: limit");
+
+        let attr: Attribute = parse_quote!(#[serde(rename = "a")]);
+        assert!(serde_default(&[attr]).is_none());
+
+        let attr: Attribute = parse_quote!(#[json_schema(minimum = 1, default = 10)]);
+        assert!(has_schema_default(std::slice::from_ref(&attr)));
+        let output = render(schema_constraints(&[attr]).unwrap());
+        assert!(output.contains("\"default\".to_string(),serde_json::json!(10)"));
+
+        let attr: Attribute = parse_quote!(#[json_schema(minimum = 1)]);
+        assert!(!has_schema_default(&[attr]));
+    }
+
+    #[test]
+    fn test_type_to_json_schema_in_context() {
+        let input: syn::DeriveInput = parse_quote!(
+            struct Tree<T> {
+                value: T,
+            }
+        );
+        let context = SchemaContext::new(&input);
+
+        let ty: Type = parse_quote!(Vec<Box<Tree<T>>>);
+        let output = render(type_to_json_schema(&ty, &[], &context));
+        assert!(output.contains("\"#/$defs/Tree\""));
+        assert!(context.recursive.get());
+
+        let ty: Type = parse_quote!(T);
+        let output = render(type_to_json_schema(&ty, &[], &context));
+        assert!(output.contains("generic_schemas.get(0usize)"));
+
+        let ty: Type = parse_quote!(Versioned<String>);
+        let output = render(type_to_json_schema(&ty, &[], &SchemaContext::default()));
+        assert!(output.contains("<Versioned<String>>::json_schema_with(&["));
+    }
 }
@@ -26,3 +26,194 @@ pub struct EditFileTool {
     )]
     pub dry_run: Option<bool>,
 }
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchFilesTool {
+    /// The directory to search in.
+    pub root_path: String,
+    /// Glob patterns of the files to skip.
+    pub exclude_patterns: Option<Vec<String>>,
+    /// The maximum number of results.
+    #[serde(rename = "limit")]
+    pub max_results: u32,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct Paging {
+    /// The number of items to skip.
+    pub offset: u32,
+    /// The maximum number of items to return.
+    pub limit: Option<u32>,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct Sorting {
+    /// The field to sort by.
+    pub sort_by: String,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct ListFilesTool {
+    /// The directory to list.
+    pub path: String,
+    #[serde(flatten)]
+    pub paging: Paging,
+    #[serde(flatten)]
+    pub sorting: Option<Sorting>,
+    #[serde(flatten)]
+    pub extra: std::collections::HashMap<String, bool>,
+    #[serde(skip)]
+    pub cache: Vec<String>,
+    #[serde(skip_deserializing, default)]
+    pub listed_at: String,
+}
+
+fn default_encoding() -> String {
+    "utf-8".to_string()
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct ReadFileTool {
+    /// The path of the file to read.
+    pub path: String,
+    /// The encoding of the file.
+    #[serde(default = "default_encoding")]
+    pub encoding: String,
+    /// Whether to follow symbolic links.
+    #[serde(default)]
+    pub follow_links: bool,
+    /// The maximum number of lines to read.
+    #[json_schema(minimum = 1, default = 1000)]
+    pub max_lines: u32,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct SetLabelsTool {
+    /// Labels to attach, keyed by label name.
+    pub labels: std::collections::HashMap<String, String>,
+    /// Edit operations grouped by file path.
+    pub edits: Option<std::collections::BTreeMap<String, Vec<EditOperation>>>,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct FetchPageTool {
+    /// The URL of the page to fetch.
+    #[json_schema(format = "uri", pattern = "^https?://")]
+    pub url: String,
+    /// Maximum number of characters to return.
+    #[json_schema(minimum = 1, maximum = 100000)]
+    pub max_length: Option<u32>,
+    /// Sampling temperature.
+    #[json_schema(exclusive_minimum = -1.5, multiple_of = 0.5)]
+    pub temperature: f32,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct ConnectDatabaseTool {
+    /// The user to connect as.
+    pub user: String,
+    /// The password of the user.
+    #[json_schema(encrypted)]
+    pub password: String,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct FileInfo {
+    /// Size of the file in bytes.
+    pub size: u64,
+    /// Whether the path is a directory.
+    #[serde(rename = "isDirectory")]
+    pub is_directory: bool,
+}
+
+#[rust_mcp_macros::mcp_tool(
+    name = "get_file_info",
+    description = "Retrieve metadata about a file.",
+    output = FileInfo
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct GetFileInfoTool {
+    /// The path of the file.
+    pub path: String,
+}
+
+#[rust_mcp_macros::mcp_tool(name = "delete_file", description = "Delete a file.", dry_run)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct DeleteFileTool {
+    /// The path of the file.
+    pub path: String,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct ConfigNode {
+    /// The name of the setting.
+    pub name: String,
+    /// The nested settings.
+    pub children: Vec<ConfigNode>,
+    /// The setting this one overrides.
+    pub overrides: Option<Box<ConfigNode>>,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct Versioned<T> {
+    /// The version of the value.
+    pub version: u32,
+    /// The value.
+    pub value: T,
+    /// The previous values.
+    pub history: Vec<T>,
+}
+
+#[rust_mcp_macros::mcp_tool(name = "apply_config", description = "Applies a configuration tree.")]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+pub struct ApplyConfigTool {
+    /// The root of the configuration tree.
+    pub root: ConfigNode,
+    /// The labels of the configuration.
+    pub labels: Versioned<String>,
+}
+
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, JsonSchema)]
+#[json_schema(strict_nullable)]
+pub struct LogFilter {
+    /// The minimum level of the entries.
+    pub level: Option<String>,
+    /// The maximum number of entries.
+    pub limit: Option<u32>,
+    /// The fraction of the entries to sample.
+    pub sample_rate: f64,
+    /// The filter this one refines.
+    pub parent: Option<Box<LogFilter>>,
+}
+
+#[cfg(feature = "schemars")]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, schemars::JsonSchema)]
+pub struct RenameOptions {
+    /// Whether to replace an existing file.
+    pub overwrite: Option<bool>,
+}
+
+#[cfg(feature = "schemars")]
+#[derive(::serde::Serialize, schemars::JsonSchema)]
+pub struct RenameFileOutput {
+    /// The new path of the file.
+    pub path: String,
+}
+
+#[cfg(feature = "schemars")]
+#[rust_mcp_macros::mcp_tool(
+    name = "rename_file",
+    description = "Renames a file.",
+    output = RenameFileOutput,
+    schemars
+)]
+#[derive(::serde::Deserialize, ::serde::Serialize, Clone, Debug, schemars::JsonSchema)]
+pub struct RenameFileTool {
+    /// The path of the file.
+    pub from: String,
+    /// The new path of the file.
+    pub to: String,
+    /// The options of the renaming.
+    pub options: RenameOptions,
+}
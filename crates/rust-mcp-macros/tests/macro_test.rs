@@ -1,4 +1,8 @@
-use common::EditOperation;
+use common::{
+    ApplyConfigTool, ConfigNode, ConnectDatabaseTool, DeleteFileTool, EditOperation, FetchPageTool,
+    FileInfo, GetFileInfoTool, ListFilesTool, LogFilter, ReadFileTool, SearchFilesTool,
+    SetLabelsTool, Versioned,
+};
 
 #[path = "common/common.rs"]
 pub mod common;
@@ -31,3 +35,361 @@ fn test_rename() {
     let properties = schema.get("properties").unwrap().as_object().unwrap();
     assert_eq!(properties.len(), 2);
 }
+
+#[test]
+fn test_rename_all() {
+    let schema = SearchFilesTool::json_schema();
+
+    let properties = schema.get("properties").unwrap().as_object().unwrap();
+    let mut names: Vec<_> = properties.keys().map(String::as_str).collect();
+    names.sort();
+    assert_eq!(names, vec!["excludePatterns", "limit", "rootPath"]);
+
+    let required: Vec<_> = schema
+        .get("required")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|v| v.as_str())
+        .collect();
+    assert_eq!(required, vec!["rootPath", "limit"]);
+}
+
+#[test]
+fn test_skipped_and_flattened_fields() {
+    let schema = ListFilesTool::json_schema();
+
+    let properties = schema.get("properties").unwrap().as_object().unwrap();
+    let mut names: Vec<_> = properties.keys().map(String::as_str).collect();
+    names.sort();
+    assert_eq!(names, vec!["limit", "offset", "path", "sort_by"]);
+    assert_eq!(
+        properties
+            .get("offset")
+            .unwrap()
+            .get("description")
+            .unwrap(),
+        "The number of items to skip."
+    );
+
+    let required: Vec<_> = schema
+        .get("required")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|v| v.as_str())
+        .collect();
+    assert_eq!(required, vec!["path", "offset"]);
+
+    let additional_properties = schema.get("additionalProperties").unwrap();
+    assert_eq!(additional_properties.get("type").unwrap(), "boolean");
+}
+
+#[test]
+fn test_default_values() {
+    let schema = ReadFileTool::json_schema();
+    let properties = schema.get("properties").unwrap().as_object().unwrap();
+
+    assert_eq!(
+        properties.get("encoding").unwrap().get("default").unwrap(),
+        "utf-8"
+    );
+    assert_eq!(
+        properties
+            .get("follow_links")
+            .unwrap()
+            .get("default")
+            .unwrap(),
+        false
+    );
+    let max_lines = properties.get("max_lines").unwrap();
+    assert_eq!(max_lines.get("default").unwrap(), 1000);
+    assert_eq!(max_lines.get("minimum").unwrap(), 1);
+    assert!(properties.get("path").unwrap().get("default").is_none());
+
+    let required: Vec<_> = schema
+        .get("required")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|v| v.as_str())
+        .collect();
+    assert_eq!(required, vec!["path"]);
+}
+
+#[test]
+fn test_recursive_types() {
+    let schema = ConfigNode::json_schema();
+    let properties = schema.get("properties").unwrap().as_object().unwrap();
+
+    let children = properties.get("children").unwrap();
+    assert_eq!(children.get("type").unwrap(), "array");
+    assert_eq!(
+        children.get("items").unwrap().get("$ref").unwrap(),
+        "#/$defs/ConfigNode"
+    );
+    let overrides = properties.get("overrides").unwrap();
+    assert_eq!(overrides.get("$ref").unwrap(), "#/$defs/ConfigNode");
+    assert_eq!(overrides.get("nullable").unwrap(), true);
+
+    let definition = schema
+        .get("$defs")
+        .unwrap()
+        .get("ConfigNode")
+        .unwrap()
+        .as_object()
+        .unwrap();
+    assert_eq!(definition.get("properties"), schema.get("properties"));
+    assert!(definition.get("$defs").is_none());
+}
+
+#[test]
+fn test_generic_types() {
+    let mut string_schema = serde_json::Map::new();
+    string_schema.insert("type".to_string(), "string".into());
+    let schema = Versioned::<String>::json_schema_with(&[string_schema]);
+    let properties = schema.get("properties").unwrap().as_object().unwrap();
+
+    let value = properties.get("value").unwrap();
+    assert_eq!(value.get("type").unwrap(), "string");
+    assert_eq!(value.get("description").unwrap(), "The value.");
+    let history = properties.get("history").unwrap();
+    assert_eq!(history.get("items").unwrap().get("type").unwrap(), "string");
+}
+
+#[test]
+fn test_tool_with_recursive_input() {
+    let schema = ApplyConfigTool::json_schema();
+    let defs = schema.get("$defs").unwrap().as_object().unwrap();
+    assert!(defs.contains_key("ConfigNode"));
+    let root = schema.get("properties").unwrap().get("root").unwrap();
+    assert_eq!(root.get("$ref").unwrap(), "#/$defs/ConfigNode");
+    let labels = schema.get("properties").unwrap().get("labels").unwrap();
+    assert_eq!(
+        labels
+            .get("properties")
+            .unwrap()
+            .get("value")
+            .unwrap()
+            .get("type")
+            .unwrap(),
+        "string"
+    );
+
+    // the input schema of the tool has no `$defs`, the definition is inlined at its first reference
+    let tool = ApplyConfigTool::tool();
+    let properties = tool.input_schema.properties.unwrap();
+    let root = properties.get("root").unwrap();
+    assert!(root.get("$ref").is_none());
+    let root_properties = root.get("properties").unwrap();
+    assert_eq!(
+        root_properties
+            .get("children")
+            .unwrap()
+            .get("items")
+            .unwrap()
+            .get("$ref")
+            .unwrap(),
+        "#/properties/root"
+    );
+    assert_eq!(
+        root_properties
+            .get("overrides")
+            .unwrap()
+            .get("$ref")
+            .unwrap(),
+        "#/properties/root"
+    );
+}
+
+#[test]
+fn test_map_fields() {
+    let schema = SetLabelsTool::json_schema();
+    let properties = schema.get("properties").unwrap().as_object().unwrap();
+
+    let labels = properties.get("labels").unwrap().as_object().unwrap();
+    assert_eq!(labels.get("type").unwrap(), "object");
+    assert_eq!(
+        labels.get("description").unwrap(),
+        "Labels to attach, keyed by label name."
+    );
+    let value_schema = labels.get("additionalProperties").unwrap();
+    assert_eq!(value_schema.get("type").unwrap(), "string");
+
+    let edits = properties.get("edits").unwrap().as_object().unwrap();
+    assert_eq!(edits.get("type").unwrap(), "object");
+    assert_eq!(edits.get("nullable").unwrap(), true);
+    let value_schema = edits.get("additionalProperties").unwrap();
+    assert_eq!(value_schema.get("type").unwrap(), "array");
+    assert!(value_schema
+        .get("items")
+        .unwrap()
+        .get("properties")
+        .is_some());
+
+    let required: Vec<_> = schema
+        .get("required")
+        .unwrap()
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|v| v.as_str())
+        .collect();
+    assert_eq!(required, vec!["labels"]);
+}
+
+#[test]
+fn test_schema_constraints() {
+    let schema = FetchPageTool::json_schema();
+    let properties = schema.get("properties").unwrap().as_object().unwrap();
+
+    let url = properties.get("url").unwrap();
+    assert_eq!(url.get("type").unwrap(), "string");
+    assert_eq!(url.get("format").unwrap(), "uri");
+    assert_eq!(url.get("pattern").unwrap(), "^https?://");
+
+    let max_length = properties.get("max_length").unwrap();
+    assert_eq!(max_length.get("nullable").unwrap(), true);
+    assert_eq!(max_length.get("minimum").unwrap(), 1);
+    assert_eq!(max_length.get("maximum").unwrap(), 100000);
+
+    let temperature = properties.get("temperature").unwrap();
+    assert_eq!(temperature.get("exclusiveMinimum").unwrap(), -1.5);
+    assert_eq!(temperature.get("multipleOf").unwrap(), 0.5);
+}
+
+#[test]
+fn test_encrypted_fields() {
+    let schema = ConnectDatabaseTool::json_schema();
+    let properties = schema.get("properties").unwrap().as_object().unwrap();
+
+    let password = properties.get("password").unwrap();
+    assert_eq!(password.get("type").unwrap(), "string");
+    assert_eq!(password.get("x-mcp-encrypted").unwrap(), true);
+
+    let user = properties.get("user").unwrap();
+    assert!(user.get("x-mcp-encrypted").is_none());
+}
+
+#[test]
+fn test_tool_output() {
+    let output_schema = GetFileInfoTool::output_schema();
+    assert_eq!(output_schema, FileInfo::json_schema());
+    let properties = output_schema
+        .get("properties")
+        .unwrap()
+        .as_object()
+        .unwrap();
+    assert!(properties.contains_key("size"));
+    assert!(properties.contains_key("isDirectory"));
+
+    let result = GetFileInfoTool::into_call_tool_result(FileInfo {
+        size: 42,
+        is_directory: false,
+    })
+    .unwrap();
+    assert_eq!(result.content.len(), 1);
+    assert!(result.is_error.is_none());
+
+    let text = &result.content[0].as_text_content().unwrap().text;
+    let value: serde_json::Value = serde_json::from_str(text).unwrap();
+    assert_eq!(value, serde_json::json!({"size": 42, "isDirectory": false}));
+}
+
+#[test]
+fn test_dry_run() {
+    let properties = DeleteFileTool::tool().input_schema.properties.unwrap();
+    let meta = properties.get("_meta").unwrap();
+    assert_eq!(meta["properties"]["dryRun"]["type"], "boolean");
+    // the reserved argument is optional
+    assert_eq!(
+        DeleteFileTool::tool().input_schema.required,
+        vec!["path".to_string()]
+    );
+
+    let properties = GetFileInfoTool::tool().input_schema.properties.unwrap();
+    assert!(!properties.contains_key("_meta"));
+}
+
+#[test]
+fn test_schema_snapshot() {
+    let dir = std::path::Path::new(env!("CARGO_TARGET_TMPDIR")).join("tool_schemas");
+    let path = GetFileInfoTool::write_schema_snapshot(&dir).unwrap();
+    assert_eq!(path, dir.join("get_file_info.json"));
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    let snapshot: serde_json::Value = serde_json::from_str(&content).unwrap();
+    assert_eq!(snapshot["name"], "get_file_info");
+    assert_eq!(snapshot["description"], "Retrieve metadata about a file.");
+    assert_eq!(snapshot["inputSchema"]["required"][0], "path");
+    assert_eq!(
+        snapshot["outputSchema"]["properties"]["isDirectory"]["type"],
+        "boolean"
+    );
+
+    // the snapshot is stable, so that it only changes along with the tool
+    GetFileInfoTool::write_schema_snapshot(&dir).unwrap();
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), content);
+}
+
+#[test]
+fn test_integer_and_number_types() {
+    let schema = ReadFileTool::json_schema();
+    let properties = schema.get("properties").unwrap();
+    assert_eq!(properties["max_lines"]["type"], "integer");
+
+    let schema = LogFilter::json_schema();
+    let properties = schema.get("properties").unwrap();
+    assert_eq!(properties["sample_rate"]["type"], "number");
+}
+
+#[test]
+fn test_strict_nullable() {
+    let schema = LogFilter::json_schema();
+    let properties = schema.get("properties").unwrap();
+
+    let limit = properties["limit"].as_object().unwrap();
+    assert_eq!(limit["type"], serde_json::json!(["integer", "null"]));
+    assert!(!limit.contains_key("nullable"));
+    assert_eq!(limit["description"], "The maximum number of entries.");
+    assert_eq!(
+        properties["level"]["type"],
+        serde_json::json!(["string", "null"])
+    );
+
+    // schemas without a single type are combined with the null type
+    let parent = properties["parent"].as_object().unwrap();
+    assert_eq!(
+        parent["anyOf"],
+        serde_json::json!([{"$ref": "#/$defs/LogFilter"}, {"type": "null"}])
+    );
+    assert_eq!(parent["description"], "The filter this one refines.");
+    assert_eq!(schema["required"], serde_json::json!(["sample_rate"]));
+}
+
+#[cfg(feature = "schemars")]
+#[test]
+fn test_schemars_tool() {
+    use common::RenameFileTool;
+
+    let tool = RenameFileTool::tool();
+    assert_eq!(tool.name, "rename_file");
+    assert_eq!(tool.input_schema.required, vec!["from", "options", "to"]);
+
+    let properties = tool.input_schema.properties.unwrap();
+    assert_eq!(properties["from"]["type"], "string");
+    assert_eq!(properties["from"]["description"], "The path of the file.");
+    // the definitions of nested types are inlined
+    let options = &properties["options"];
+    assert_eq!(
+        options["properties"]["overwrite"]["type"],
+        serde_json::json!(["boolean", "null"])
+    );
+    assert!(!options.contains_key("$ref"));
+
+    let output_schema = RenameFileTool::output_schema();
+    assert_eq!(output_schema["properties"]["path"]["type"], "string");
+}
@@ -0,0 +1,168 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rust_mcp_schema::{
+    GetPromptRequestParams, LoggingLevel, ReadResourceRequestParams, SubscribeRequestParams,
+    UnsubscribeRequestParams,
+};
+use rust_mcp_sdk::diagnostics;
+use rust_mcp_sdk::McpClient;
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+pub const HELP: &str = "Commands:
+  info                       Show the server details and capabilities
+  inspect                    Inspect the server, listing everything it provides
+  tools                      List the tools
+  prompts                    List the prompts
+  resources                  List the resources
+  templates                  List the resource templates
+  call <tool> [json]         Call a tool, with its arguments as a JSON object
+  prompt <name> [json]       Get a prompt, with its arguments as a JSON object
+  read <uri>                 Read a resource
+  subscribe <uri>            Subscribe to the updates of a resource
+  unsubscribe <uri>          Unsubscribe from the updates of a resource
+  log <level>                Set the logging level of the server (debug, info, warning, error...)
+  ping [count]               Ping the server
+  help                       Show this help
+  quit                       Shut down the server and exit
+
+Notifications received from the server are printed as they arrive.";
+
+/// What the REPL does once a command ran.
+pub enum Flow {
+    Continue,
+    Quit,
+}
+
+type CommandResult = Result<Flow, Box<dyn std::error::Error>>;
+
+/// Runs a command line entered in the REPL, printing its outcome.
+pub async fn run_command(client: &dyn McpClient, line: &str) -> Flow {
+    let (command, rest) = split_first_word(line);
+    execute(client, command, rest)
+        .await
+        .unwrap_or_else(|error| {
+            eprintln!("Error: {error}");
+            Flow::Continue
+        })
+}
+
+async fn execute(client: &dyn McpClient, command: &str, rest: &str) -> CommandResult {
+    match command {
+        "" => {}
+        "help" | "?" => println!("{HELP}"),
+        "quit" | "exit" => return Ok(Flow::Quit),
+        "info" => print_json(&client.server_info()),
+        "inspect" => print_json(&diagnostics::inspect_server(client).await),
+        "tools" => print_json(&client.list_all_tools().await?),
+        "prompts" => print_json(&client.list_all_prompts().await?),
+        "resources" => print_json(&client.list_all_resources().await?),
+        "templates" => print_json(&client.list_all_resource_templates().await?),
+        "call" => {
+            let (tool_name, arguments) = parse_name_and_arguments(rest)?;
+            print_json(&diagnostics::call_tool(client, tool_name, arguments).await);
+        }
+        "prompt" => {
+            let (name, arguments) = parse_name_and_arguments(rest)?;
+            let params = GetPromptRequestParams {
+                name: name.to_string(),
+                arguments: arguments.map(string_arguments),
+            };
+            print_json(&client.prompt(params).await?);
+        }
+        "read" | "subscribe" | "unsubscribe" if rest.is_empty() => {
+            return Err(format!("Usage: {command} <uri>").into());
+        }
+        "read" => {
+            let params = ReadResourceRequestParams {
+                uri: rest.to_string(),
+            };
+            print_json(&client.read_resource(params).await?);
+        }
+        "subscribe" => {
+            let params = SubscribeRequestParams {
+                uri: rest.to_string(),
+            };
+            client.subscribe_resource(params).await?;
+            println!("Subscribed to {rest}");
+        }
+        "unsubscribe" => {
+            let params = UnsubscribeRequestParams {
+                uri: rest.to_string(),
+            };
+            client.unsubscribe_resource(params).await?;
+            println!("Unsubscribed from {rest}");
+        }
+        "log" => {
+            let level: LoggingLevel = serde_json::from_value(Value::String(rest.to_string()))
+                .map_err(|_| format!("Unknown logging level: '{rest}'"))?;
+            client.set_logging_level(level).await?;
+            println!("Logging level set to {rest}");
+        }
+        "ping" => {
+            let count = parse_count(rest)?;
+            print_json(&diagnostics::ping_server(client, count, Duration::from_millis(500)).await);
+        }
+        _ => {
+            return Err(format!(
+                "Unknown command: '{command}', type 'help' for the list of commands"
+            )
+            .into());
+        }
+    }
+    Ok(Flow::Continue)
+}
+
+fn print_json<T: Serialize>(value: &T) {
+    match serde_json::to_string_pretty(value) {
+        Ok(json) => println!("{json}"),
+        Err(error) => eprintln!("Error: {error}"),
+    }
+}
+
+fn split_first_word(line: &str) -> (&str, &str) {
+    let line = line.trim();
+    match line.split_once(char::is_whitespace) {
+        Some((first, rest)) => (first, rest.trim()),
+        None => (line, ""),
+    }
+}
+
+type Arguments = Option<Map<String, Value>>;
+
+// parses "<name> [json object]"
+fn parse_name_and_arguments(input: &str) -> Result<(&str, Arguments), String> {
+    let (name, arguments) = split_first_word(input);
+    if name.is_empty() {
+        return Err("A name is required".to_string());
+    }
+    if arguments.is_empty() {
+        return Ok((name, None));
+    }
+    match serde_json::from_str::<Value>(arguments) {
+        Ok(Value::Object(arguments)) => Ok((name, Some(arguments))),
+        Ok(_) => Err("Arguments must be a JSON object".to_string()),
+        Err(error) => Err(format!("Invalid JSON arguments: {error}")),
+    }
+}
+
+// prompt arguments are strings, other JSON values are passed as their JSON representation
+fn string_arguments(arguments: Map<String, Value>) -> HashMap<String, String> {
+    arguments
+        .into_iter()
+        .map(|(name, value)| match value {
+            Value::String(value) => (name, value),
+            value => (name, value.to_string()),
+        })
+        .collect()
+}
+
+fn parse_count(input: &str) -> Result<usize, String> {
+    if input.is_empty() {
+        return Ok(1);
+    }
+    input
+        .parse()
+        .map_err(|_| format!("Invalid count: '{input}'"))
+}
@@ -0,0 +1,113 @@
+use async_trait::async_trait;
+use rust_mcp_schema::{
+    CancelledNotification, LoggingMessageNotification, ProgressNotification,
+    PromptListChangedNotification, ResourceListChangedNotification, ResourceUpdatedNotification,
+    RpcError, ToolListChangedNotification,
+};
+use rust_mcp_sdk::mcp_client::ClientHandler;
+use rust_mcp_sdk::McpClient;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Prints the notifications received from the server as they arrive.
+pub struct InspectorHandler;
+
+fn print_notification<T: Serialize>(notification: &T) {
+    let notification = serde_json::to_string(notification).unwrap_or_default();
+    println!("\n<< notification: {notification}");
+}
+
+#[async_trait]
+impl ClientHandler for InspectorHandler {
+    async fn handle_cancelled_notification(
+        &self,
+        notification: CancelledNotification,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        print_notification(&notification);
+        Ok(())
+    }
+
+    async fn handle_progress_notification(
+        &self,
+        notification: ProgressNotification,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        print_notification(&notification);
+        Ok(())
+    }
+
+    async fn handle_resource_list_changed_notification(
+        &self,
+        notification: ResourceListChangedNotification,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        print_notification(&notification);
+        Ok(())
+    }
+
+    async fn handle_resource_updated_notification(
+        &self,
+        notification: ResourceUpdatedNotification,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        print_notification(&notification);
+        Ok(())
+    }
+
+    async fn handle_prompt_list_changed_notification(
+        &self,
+        notification: PromptListChangedNotification,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        print_notification(&notification);
+        Ok(())
+    }
+
+    async fn handle_tool_list_changed_notification(
+        &self,
+        notification: ToolListChangedNotification,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        print_notification(&notification);
+        Ok(())
+    }
+
+    async fn handle_logging_message_notification(
+        &self,
+        notification: LoggingMessageNotification,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        print_notification(&notification);
+        Ok(())
+    }
+
+    async fn handle_custom_notification(
+        &self,
+        notification: Value,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        print_notification(&notification);
+        Ok(())
+    }
+
+    async fn handle_error(
+        &self,
+        error: RpcError,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        eprintln!("\n<< error: {error}");
+        Ok(())
+    }
+
+    async fn handle_process_error(
+        &self,
+        error_message: String,
+        runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        if !runtime.is_shut_down().await {
+            eprintln!("<< stderr: {error_message}");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+//! An interactive inspector for MCP servers, built on the client runtime.
+//!
+//! Launches an MCP server, lists what it provides, and lets you call its tools, read its resources
+//! and get its prompts while printing the notifications it sends.
+//!
+//! ```text
+//! cargo run -p rust-mcp-sdk --features inspector --bin mcp-inspector -- npx -y @modelcontextprotocol/server-everything
+//! ```
+mod commands;
+mod handler;
+
+use std::io::Write;
+
+use commands::{run_command, Flow, HELP};
+use handler::InspectorHandler;
+use rust_mcp_sdk::error::SdkResult;
+use rust_mcp_sdk::mcp_client::{client_runtime, ClientRuntime};
+use rust_mcp_sdk::McpClient;
+use rust_mcp_transport::StdioTransport;
+use tokio::io::{AsyncBufReadExt, BufReader};
+
+const USAGE: &str = "Usage: mcp-inspector <command> [args...]
+
+Launches the MCP server with the given command and arguments, and starts an interactive session.";
+
+#[tokio::main]
+async fn main() -> SdkResult<()> {
+    let mut args = std::env::args().skip(1);
+    let command = match args.next() {
+        Some(command) if command != "-h" && command != "--help" => command,
+        _ => {
+            println!("{USAGE}\n\n{HELP}");
+            return Ok(());
+        }
+    };
+
+    let client_details = ClientRuntime::info("mcp-inspector", env!("CARGO_PKG_VERSION")).build();
+    let transport = StdioTransport::builder(command).args(args).build()?;
+    let client = client_runtime::create_client(client_details, transport, InspectorHandler);
+    client.clone().start().await?;
+
+    if let Some(server_version) = client.server_version() {
+        println!(
+            "Connected to {} v{}, type 'help' for the list of commands",
+            server_version.name, server_version.version
+        );
+    }
+
+    let mut lines = BufReader::new(tokio::io::stdin()).lines();
+    loop {
+        print!("mcp> ");
+        std::io::stdout().flush().ok();
+
+        // end of input is handled like `quit`
+        let Some(line) = lines.next_line().await? else {
+            break;
+        };
+        if let Flow::Quit = run_command(client.as_ref(), &line).await {
+            break;
+        }
+        if client.is_shut_down().await {
+            eprintln!("The server disconnected");
+            break;
+        }
+    }
+
+    client.shut_down().await?;
+    Ok(())
+}
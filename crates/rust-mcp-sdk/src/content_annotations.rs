@@ -0,0 +1,214 @@
+use rust_mcp_schema::{
+    CallToolResultContentItem, CreateMessageResultContent, EmbeddedResource,
+    EmbeddedResourceAnnotations, ImageContent, ImageContentAnnotations, PromptMessageContent,
+    Resource, ResourceAnnotations, Role, SamplingMessageContent, TextContent,
+    TextContentAnnotations,
+};
+
+/// The intended audience of a content item.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Audience {
+    /// The content is meant to be shown to the user, e.g. a summary of the operation
+    User,
+    /// The content is meant to be used by the model, e.g. raw data to reason about
+    Assistant,
+    /// The content is meant for both the user and the model
+    Both,
+}
+
+impl Audience {
+    /// Returns the roles of the audience, as sent in annotations.
+    pub fn roles(&self) -> Vec<Role> {
+        match self {
+            Audience::User => vec![Role::User],
+            Audience::Assistant => vec![Role::Assistant],
+            Audience::Both => vec![Role::User, Role::Assistant],
+        }
+    }
+}
+
+/// The annotations of a content item, informing the client how to use or display it.
+///
+/// The schema defines a distinct annotations type for each type of content, `ContentAnnotations`
+/// converts into and from all of them. The current protocol revision supports an audience and a
+/// priority, a last modification time is not supported yet.
+///
+/// # Example
+/// ```ignore
+/// let result = CallToolResult::text_content(summary, Some(
+///     ContentAnnotations::for_audience(Audience::User).with_priority(0.9).into(),
+/// ));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ContentAnnotations {
+    /// The intended audience of the content, empty if it is meant for everyone
+    pub audience: Vec<Role>,
+    /// The importance of the content, from 0 (entirely optional) to 1 (effectively required)
+    pub priority: Option<f64>,
+}
+
+impl ContentAnnotations {
+    /// Creates empty annotations.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates annotations marking the content for the given audience.
+    pub fn for_audience(audience: Audience) -> Self {
+        Self::new().with_audience(audience)
+    }
+
+    /// Sets the intended audience of the content.
+    pub fn with_audience(mut self, audience: Audience) -> Self {
+        self.audience = audience.roles();
+        self
+    }
+
+    /// Sets the priority of the content, clamped between 0 and 1. A NaN priority is ignored.
+    pub fn with_priority(mut self, priority: f64) -> Self {
+        self.priority = (!priority.is_nan()).then(|| priority.clamp(0.0, 1.0));
+        self
+    }
+
+    /// Returns `true` if the content is meant for `role`, which is the case when no audience is set.
+    pub fn is_intended_for(&self, role: Role) -> bool {
+        self.audience.is_empty() || self.audience.contains(&role)
+    }
+}
+
+macro_rules! impl_annotations_conversions {
+    ($($annotations:ty),*) => {
+        $(
+            impl From<ContentAnnotations> for $annotations {
+                fn from(annotations: ContentAnnotations) -> Self {
+                    Self {
+                        audience: annotations.audience,
+                        priority: annotations.priority,
+                    }
+                }
+            }
+
+            impl From<&$annotations> for ContentAnnotations {
+                fn from(annotations: &$annotations) -> Self {
+                    Self {
+                        audience: annotations.audience.clone(),
+                        priority: annotations.priority,
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_annotations_conversions!(
+    TextContentAnnotations,
+    ImageContentAnnotations,
+    EmbeddedResourceAnnotations,
+    ResourceAnnotations
+);
+
+/// Builder and accessor methods for the annotations of content items, implemented for the content
+/// of tool results, prompt messages and sampling messages, on both the server and the client side.
+///
+/// # Example
+/// ```ignore
+/// // server side, a summary for the user and the raw data for the model
+/// let result = CallToolResult {
+///     content: vec![
+///         CallToolResultContentItem::text_content(summary, None).for_audience(Audience::User),
+///         CallToolResultContentItem::text_content(data, None)
+///             .for_audience(Audience::Assistant)
+///             .with_priority(0.2),
+///     ],
+///     is_error: None,
+///     meta: None,
+/// };
+///
+/// // client side, only the content meant for the user is displayed
+/// for content in result.content.iter().filter(|content| content.is_intended_for(Role::User)) {
+///     display(content);
+/// }
+/// ```
+pub trait AnnotatedContent: Sized {
+    /// Returns the annotations of the content, if any.
+    fn annotations(&self) -> Option<ContentAnnotations>;
+
+    /// Replaces the annotations of the content.
+    fn with_annotations(self, annotations: ContentAnnotations) -> Self;
+
+    /// Sets the intended audience of the content, keeping its priority.
+    fn for_audience(self, audience: Audience) -> Self {
+        let annotations = self.annotations().unwrap_or_default();
+        self.with_annotations(annotations.with_audience(audience))
+    }
+
+    /// Sets the priority of the content, keeping its audience.
+    fn with_priority(self, priority: f64) -> Self {
+        let annotations = self.annotations().unwrap_or_default();
+        self.with_annotations(annotations.with_priority(priority))
+    }
+
+    /// Returns `true` if the content is meant for `role`, which is the case when no audience is set.
+    fn is_intended_for(&self, role: Role) -> bool {
+        self.annotations()
+            .is_none_or(|annotations| annotations.is_intended_for(role))
+    }
+}
+
+macro_rules! impl_annotated_content {
+    ($($content:ty),*) => {
+        $(
+            impl AnnotatedContent for $content {
+                fn annotations(&self) -> Option<ContentAnnotations> {
+                    self.annotations.as_ref().map(ContentAnnotations::from)
+                }
+
+                fn with_annotations(mut self, annotations: ContentAnnotations) -> Self {
+                    self.annotations = Some(annotations.into());
+                    self
+                }
+            }
+        )*
+    };
+}
+
+impl_annotated_content!(TextContent, ImageContent, EmbeddedResource, Resource);
+
+macro_rules! impl_annotated_content_enum {
+    ($content:ident { $($variant:ident),* }) => {
+        impl AnnotatedContent for $content {
+            fn annotations(&self) -> Option<ContentAnnotations> {
+                match self {
+                    $($content::$variant(content) => content.annotations(),)*
+                }
+            }
+
+            fn with_annotations(self, annotations: ContentAnnotations) -> Self {
+                match self {
+                    $($content::$variant(content) => {
+                        $content::$variant(content.with_annotations(annotations))
+                    })*
+                }
+            }
+        }
+    };
+}
+
+impl_annotated_content_enum!(CallToolResultContentItem {
+    TextContent,
+    ImageContent,
+    EmbeddedResource
+});
+impl_annotated_content_enum!(PromptMessageContent {
+    TextContent,
+    ImageContent,
+    EmbeddedResource
+});
+impl_annotated_content_enum!(SamplingMessageContent {
+    TextContent,
+    ImageContent
+});
+impl_annotated_content_enum!(CreateMessageResultContent {
+    TextContent,
+    ImageContent
+});
@@ -1,9 +1,22 @@
-use rust_mcp_schema::RpcError;
+use rust_mcp_schema::schema_utils::{SdkError, SdkErrorCodes};
+use rust_mcp_schema::{RequestId, RpcError};
 use rust_mcp_transport::error::TransportError;
 use thiserror::Error;
 
+use crate::tool_input_validation::InvalidToolArguments;
+
 pub type SdkResult<T> = core::result::Result<T, McpSdkError>;
 
+/// Code of the retryable error answering requests shed by a server under load, see `LoadSheddingPolicy`.
+pub const SERVER_BUSY_ERROR_CODE: i64 = -32003;
+
+/// Code of the error answering requests exceeding the rate limit of a server, see `RateLimiter`.
+pub const RATE_LIMITED_ERROR_CODE: i64 = -32004;
+
+/// Code of the error reporting a result or notification whose signature is missing or invalid,
+/// see `signing::SignatureVerifier`.
+pub const INVALID_SIGNATURE_ERROR_CODE: i64 = -32005;
+
 #[derive(Debug, Error)]
 pub enum McpSdkError {
     #[error("{0}")]
@@ -11,11 +24,116 @@ pub enum McpSdkError {
     #[error("{0}")]
     IoError(#[from] std::io::Error),
     #[error("{0}")]
-    TransportError(#[from] TransportError),
+    TransportError(TransportError),
     #[error("{0}")]
-    AnyErrorStatic(Box<(dyn std::error::Error + Send + Sync + 'static)>),
+    AnyErrorStatic(Box<dyn std::error::Error + Send + Sync + 'static>),
     #[error("{0}")]
-    AnyError(Box<(dyn std::error::Error + Send + Sync)>),
+    AnyError(Box<dyn std::error::Error + Send + Sync>),
     #[error("{0}")]
-    SdkError(#[from] rust_mcp_schema::schema_utils::SdkError),
+    SdkError(SdkError),
+    /// The client or the server lacks the capability required by a request or notification.
+    #[error("{entity} does not support {capability} (required for {method})")]
+    CapabilityNotSupported {
+        /// `"Client"` or `"Server"`
+        entity: String,
+        capability: String,
+        method: String,
+    },
+    /// The initialization with the other party has not completed yet.
+    #[error("The initialization has not completed")]
+    NotInitialized,
+    /// The connection with the other party is closed, or was never established.
+    #[error("Connection closed")]
+    ConnectionClosed,
+    /// No response was received for a request within the timeout of the transport.
+    #[error("Request timeout after {msec}ms")]
+    RequestTimeout {
+        /// The id of the request, if known
+        id: Option<RequestId>,
+        msec: u64,
+    },
+    /// A sampling request, or a tool call made while fulfilling one, is nested deeper than
+    /// the sampling depth limit, see `sampling`.
+    #[error("Maximum sampling depth exceeded: {depth} nested sampling requests, the limit is {max_depth}")]
+    SamplingDepthExceeded { depth: u32, max_depth: u32 },
+    /// The arguments of a tool call do not match the input schema of the tool, checked before
+    /// sending the call, see `ClientRuntime::set_tool_argument_validation()`.
+    #[error("{0}")]
+    InvalidToolArguments(#[from] InvalidToolArguments),
+    /// A tool called with `McpClientExt::call_tool_typed()` returned a result with `is_error`.
+    #[error("Tool '{name}' failed: {message}")]
+    ToolCallFailed { name: String, message: String },
+    /// The result of a tool called with `McpClientExt::call_tool_typed()` cannot be deserialized
+    /// into the expected type.
+    #[error("Unexpected result of tool '{name}': {message}")]
+    UnexpectedToolResult { name: String, message: String },
+    /// The other party missed too many consecutive pings, the connection was closed, see `KeepAlive`.
+    #[error("The connection was closed after {missed_pings} missed pings")]
+    KeepAliveTimeout { missed_pings: u32 },
+    /// The initialization with the server failed, e.g. because the launched process is not an
+    /// MCP server, with the last lines the server process wrote to its stderr.
+    #[error("{}", display_initialize_failure(.source, .stderr))]
+    InitializeFailed {
+        source: Box<McpSdkError>,
+        /// The last lines written to stderr by the server process, if they were captured
+        stderr: Vec<String>,
+    },
+}
+
+fn display_initialize_failure(source: &McpSdkError, stderr: &[String]) -> String {
+    let mut message = format!("The initialization with the server failed: {}", source);
+    if !stderr.is_empty() {
+        message.push_str("\nLast lines written to stderr by the server:");
+        for line in stderr {
+            message.push_str("\n  ");
+            message.push_str(line);
+        }
+    }
+    message
+}
+
+impl McpSdkError {
+    pub(crate) fn capability_not_supported(entity: &str, capability: &str, method: &str) -> Self {
+        Self::CapabilityNotSupported {
+            entity: entity.to_string(),
+            capability: capability.to_string(),
+            method: method.to_string(),
+        }
+    }
+}
+
+impl From<SdkError> for McpSdkError {
+    fn from(error: SdkError) -> Self {
+        if error.code == i64::from(SdkErrorCodes::CONNECTION_CLOSED) {
+            return Self::ConnectionClosed;
+        }
+        if error.code == i64::from(SdkErrorCodes::REQUEST_TIMEOUT) {
+            let data = error.data.unwrap_or_default();
+            return Self::RequestTimeout {
+                id: serde_json::from_value(data["requestId"].clone()).ok(),
+                msec: data["timeout"].as_u64().unwrap_or_default(),
+            };
+        }
+        Self::SdkError(error)
+    }
+}
+
+impl From<TransportError> for McpSdkError {
+    fn from(error: TransportError) -> Self {
+        match error {
+            TransportError::SdkError(error) => error.into(),
+            error => Self::TransportError(error),
+        }
+    }
+}
+
+/// Errors sent to the other party are JSON-RPC errors, other kinds of errors are
+/// reported as internal errors with the message of the error.
+impl From<McpSdkError> for RpcError {
+    fn from(error: McpSdkError) -> Self {
+        match error {
+            McpSdkError::RpcError(error) => error,
+            error => RpcError::internal_error().with_message(error.to_string()),
+        }
+    }
 }
@@ -0,0 +1,30 @@
+//! Codes of the JSON-RPC errors sent and received by MCP servers and clients.
+//!
+//! The errors of the JSON-RPC 2.0 specification come first, followed by the codes defined by the MCP
+//! specification and the SDK, in the range reserved for implementation-defined server errors.
+//! The errors with these codes are built with the constructors of `RpcError` and `RpcErrorExt`,
+//! e.g. `RpcError::invalid_params()` or `RpcError::resource_not_found(uri)`.
+
+use rust_mcp_schema::schema_utils::SdkErrorCodes;
+
+pub use rust_mcp_schema::{
+    INTERNAL_ERROR, INVALID_PARAMS, INVALID_REQUEST, METHOD_NOT_FOUND, PARSE_ERROR,
+};
+
+/// The connection with the other party is closed
+pub const CONNECTION_CLOSED: i64 = SdkErrorCodes::CONNECTION_CLOSED as i64;
+
+/// No response was received for a request within the timeout of the transport
+pub const REQUEST_TIMEOUT: i64 = SdkErrorCodes::REQUEST_TIMEOUT as i64;
+
+/// The resource requested by `resources/read` does not exist, as defined by the MCP specification
+pub const RESOURCE_NOT_FOUND: i64 = -32002;
+
+/// The server is overloaded and shed the request, which can be retried, see `LoadSheddingPolicy`
+pub const SERVER_BUSY: i64 = crate::error::SERVER_BUSY_ERROR_CODE;
+
+/// The request exceeds the rate limit of the server, see `RateLimiter`
+pub const RATE_LIMITED: i64 = crate::error::RATE_LIMITED_ERROR_CODE;
+
+/// The signature of a result or notification received from a server is missing or invalid
+pub const INVALID_SIGNATURE: i64 = crate::error::INVALID_SIGNATURE_ERROR_CODE;
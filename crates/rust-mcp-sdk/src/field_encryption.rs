@@ -0,0 +1,185 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rust_mcp_schema::{RpcError, Tool};
+use serde_json::{Map, Value};
+
+/// Schema keyword marking a tool argument as sensitive, emitted by `#[json_schema(encrypted)]`
+pub const ENCRYPTED_FIELD_KEYWORD: &str = "x-mcp-encrypted";
+
+/// Key of the object replacing the value of an encrypted argument on the wire:
+/// `{"$encrypted": "<base64 encoded ciphertext>"}`
+pub const ENCRYPTED_VALUE_KEY: &str = "$encrypted";
+
+/// Encrypts and decrypts the values of sensitive tool arguments.
+///
+/// The SDK does not ship any cipher: implement this trait on top of the crypto library of your
+/// choice (e.g. an AEAD such as AES-GCM or ChaCha20-Poly1305), using a key shared by the client
+/// and the server. Any nonce or authentication tag must be part of the returned ciphertext.
+pub trait KeyProvider: Send + Sync {
+    /// Encrypts the JSON serialized value of an argument
+    fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, RpcError>;
+    /// Decrypts a ciphertext returned by `encrypt()`
+    fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, RpcError>;
+}
+
+/// Returns the names of the arguments of `tool` marked as encrypted in its input schema.
+pub fn encrypted_fields(tool: &Tool) -> Vec<String> {
+    let mut fields: Vec<String> = tool
+        .input_schema
+        .properties
+        .iter()
+        .flatten()
+        .filter(|(_, schema)| schema.get(ENCRYPTED_FIELD_KEYWORD) == Some(&Value::Bool(true)))
+        .map(|(name, _)| name.to_string())
+        .collect();
+    fields.sort();
+    fields
+}
+
+/// Replaces the values of the arguments marked as encrypted in the input schema of `tool`
+/// with their encrypted form. Arguments that are missing or already encrypted are left untouched.
+pub fn encrypt_arguments(
+    tool: &Tool,
+    arguments: &mut Map<String, Value>,
+    key_provider: &dyn KeyProvider,
+) -> Result<(), RpcError> {
+    for field in encrypted_fields(tool) {
+        let Some(value) = arguments.get_mut(&field) else {
+            continue;
+        };
+        if is_encrypted_value(value) {
+            continue;
+        }
+        let plaintext = serde_json::to_vec(value).map_err(|err| {
+            RpcError::internal_error().with_message(format!(
+                "Failed to serialize the '{}' argument: {}",
+                field, err
+            ))
+        })?;
+        let ciphertext = key_provider.encrypt(&plaintext)?;
+
+        let mut encrypted = Map::new();
+        encrypted.insert(
+            ENCRYPTED_VALUE_KEY.to_string(),
+            Value::String(STANDARD.encode(ciphertext)),
+        );
+        *value = Value::Object(encrypted);
+    }
+    Ok(())
+}
+
+/// Replaces the values of the arguments marked as encrypted in the input schema of `tool` with
+/// their decrypted value. Arguments that are not marked are left untouched, even if their value
+/// looks encrypted, and so are missing arguments.
+///
+/// # Errors
+/// Returns an `invalid_params` error if a marked argument is received in plaintext, or can not be decrypted.
+pub fn decrypt_arguments(
+    tool: &Tool,
+    arguments: &mut Map<String, Value>,
+    key_provider: &dyn KeyProvider,
+) -> Result<(), RpcError> {
+    for field in encrypted_fields(tool) {
+        let Some(value) = arguments.get_mut(&field) else {
+            continue;
+        };
+        let invalid_argument = |reason: String| {
+            RpcError::invalid_params().with_message(format!(
+                "Failed to decrypt the '{}' argument: {}",
+                field, reason
+            ))
+        };
+        // a sensitive argument sent in plaintext may have been seen by relays and logs already
+        let encoded = encrypted_value(value)
+            .ok_or_else(|| invalid_argument("the argument is not encrypted".to_string()))?;
+        let ciphertext = STANDARD
+            .decode(encoded)
+            .map_err(|err| invalid_argument(err.to_string()))?;
+        let plaintext = key_provider.decrypt(&ciphertext)?;
+        *value =
+            serde_json::from_slice(&plaintext).map_err(|err| invalid_argument(err.to_string()))?;
+    }
+    Ok(())
+}
+
+// Returns the base64 encoded ciphertext if `value` is an encrypted argument value
+fn encrypted_value(value: &Value) -> Option<&str> {
+    match value {
+        Value::Object(map) if map.len() == 1 => map.get(ENCRYPTED_VALUE_KEY)?.as_str(),
+        _ => None,
+    }
+}
+
+pub(crate) fn is_encrypted_value(value: &Value) -> bool {
+    encrypted_value(value).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_mcp_schema::ToolInputSchema;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    // A toy cipher: the bytes of the plaintext, reversed
+    struct TestKey;
+
+    impl KeyProvider for TestKey {
+        fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>, RpcError> {
+            Ok(plaintext.iter().rev().copied().collect())
+        }
+
+        fn decrypt(&self, ciphertext: &[u8]) -> Result<Vec<u8>, RpcError> {
+            self.encrypt(ciphertext)
+        }
+    }
+
+    fn tool() -> Tool {
+        let properties: HashMap<String, Map<String, Value>> = serde_json::from_value(json!({
+            "user": {"type": "string"},
+            "password": {"type": "string", ENCRYPTED_FIELD_KEYWORD: true}
+        }))
+        .unwrap();
+        Tool {
+            description: None,
+            input_schema: ToolInputSchema::new(vec![], Some(properties)),
+            name: "login".to_string(),
+        }
+    }
+
+    fn arguments(value: Value) -> Map<String, Value> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn test_decrypts_the_marked_arguments() {
+        let mut encrypted = arguments(json!({"user": "alice", "password": "secret"}));
+        encrypt_arguments(&tool(), &mut encrypted, &TestKey).unwrap();
+        assert!(is_encrypted_value(&encrypted["password"]));
+
+        decrypt_arguments(&tool(), &mut encrypted, &TestKey).unwrap();
+        assert_eq!(
+            encrypted,
+            arguments(json!({"user": "alice", "password": "secret"}))
+        );
+    }
+
+    #[test]
+    fn test_leaves_the_other_arguments_untouched() {
+        let ciphertext = STANDARD.encode(TestKey.encrypt(b"\"alice\"").unwrap());
+        let mut received = arguments(json!({"user": {ENCRYPTED_VALUE_KEY: ciphertext}}));
+        let expected = received.clone();
+        decrypt_arguments(&tool(), &mut received, &TestKey).unwrap();
+        assert_eq!(received, expected);
+    }
+
+    #[test]
+    fn test_rejects_marked_arguments_in_plaintext() {
+        let mut received = arguments(json!({"user": "alice", "password": "secret"}));
+        let error = decrypt_arguments(&tool(), &mut received, &TestKey).unwrap_err();
+        assert_eq!(error.code, RpcError::invalid_params().code);
+        assert_eq!(
+            error.message,
+            "Failed to decrypt the 'password' argument: the argument is not encrypted"
+        );
+    }
+}
@@ -0,0 +1,277 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rust_mcp_schema::ImageContent;
+use thiserror::Error;
+
+/// Number of base64 characters decoded to detect the format of an image, a multiple of 4.
+/// The 48 decoded bytes hold the magic bytes of all formats, and the dimensions of all but JPEG.
+const HEADER_BASE64_SIZE: usize = 64;
+
+/// The formats of images recognized by their magic bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ImageFormat {
+    Png,
+    Jpeg,
+    Gif,
+    Webp,
+    Bmp,
+}
+
+impl ImageFormat {
+    /// Returns the format identified by a mime type, ignoring its case and parameters,
+    /// or `None` if the mime type is not the one of a recognized format.
+    pub fn from_mime_type(mime_type: &str) -> Option<Self> {
+        let essence = mime_type.split(';').next().unwrap_or_default().trim();
+        match essence.to_ascii_lowercase().as_str() {
+            "image/png" => Some(Self::Png),
+            "image/jpeg" | "image/jpg" | "image/pjpeg" => Some(Self::Jpeg),
+            "image/gif" => Some(Self::Gif),
+            "image/webp" => Some(Self::Webp),
+            "image/bmp" | "image/x-bmp" | "image/x-ms-bmp" => Some(Self::Bmp),
+            _ => None,
+        }
+    }
+
+    /// Returns the format of an image from its first bytes, or `None` if it is not recognized.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some(Self::Png),
+            [0xFF, 0xD8, 0xFF, ..] => Some(Self::Jpeg),
+            [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some(Self::Gif),
+            [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some(Self::Webp),
+            [b'B', b'M', ..] => Some(Self::Bmp),
+            _ => None,
+        }
+    }
+
+    /// Returns the canonical mime type of the format.
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            Self::Png => "image/png",
+            Self::Jpeg => "image/jpeg",
+            Self::Gif => "image/gif",
+            Self::Webp => "image/webp",
+            Self::Bmp => "image/bmp",
+        }
+    }
+}
+
+/// The width and height of an image, in pixels.
+#[cfg(feature = "image")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ImageDimensions {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// Errors returned when decoding or verifying image content.
+#[derive(Debug, Error)]
+pub enum ImageContentError {
+    /// The data of the image is not valid base64.
+    #[error("Invalid base64 image data: {0}")]
+    InvalidBase64(#[from] base64::DecodeError),
+    /// The magic bytes of the image do not match any recognized format.
+    #[error("Unrecognized image format (declared as {declared})")]
+    UnrecognizedFormat { declared: String },
+    /// The image is in a recognized format, other than the one of its declared mime type.
+    #[error("Image declared as {declared} is {}", detected.mime_type())]
+    MimeTypeMismatch {
+        declared: String,
+        detected: ImageFormat,
+    },
+    /// The image is truncated or its header is malformed.
+    #[cfg(feature = "image")]
+    #[error("Malformed {} image header", format.mime_type())]
+    MalformedHeader { format: ImageFormat },
+}
+
+/// An image decoded from base64 and verified against its declared mime type,
+/// returned by `ImageContentExt::decode()`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodedImage {
+    format: ImageFormat,
+    bytes: Vec<u8>,
+}
+
+impl DecodedImage {
+    /// Returns the format of the image
+    pub fn format(&self) -> ImageFormat {
+        self.format
+    }
+
+    /// Returns the bytes of the image
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the bytes of the image, consuming it
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    /// Returns the width and height of the image, read from its header.
+    #[cfg(feature = "image")]
+    pub fn dimensions(&self) -> Result<ImageDimensions, ImageContentError> {
+        read_dimensions(self.format, &self.bytes)
+    }
+}
+
+/// Helpers to decode the image content received from a server, and to verify that its data
+/// matches its declared mime type before displaying it.
+///
+/// The base64 data is decoded lazily: verifying the format only decodes the first bytes of the
+/// image, and so does reading the dimensions of all formats but JPEG. Dimensions are read from the
+/// headers of the images, without decoding their pixels, and require the `image` feature.
+///
+/// # Example
+/// ```ignore
+/// if let CallToolResultContentItem::ImageContent(image_content) = content {
+///     // rejects images whose data is not the one of their mime type
+///     let format = image_content.verify()?;
+///     let dimensions = image_content.dimensions()?;
+///     if dimensions.width <= MAX_WIDTH {
+///         display(format, image_content.decode()?.bytes());
+///     }
+/// }
+/// ```
+pub trait ImageContentExt {
+    /// Returns the format of the image, after verifying that it matches the declared mime type.
+    /// Only the first bytes of the image are decoded.
+    fn verify(&self) -> Result<ImageFormat, ImageContentError>;
+
+    /// Decodes the whole image, after verifying that its format matches the declared mime type.
+    fn decode(&self) -> Result<DecodedImage, ImageContentError>;
+
+    /// Returns the width and height of the image, after verifying that its format matches the
+    /// declared mime type. Only the header of the image is decoded, except for JPEG images whose
+    /// dimensions may appear anywhere before their pixels.
+    #[cfg(feature = "image")]
+    fn dimensions(&self) -> Result<ImageDimensions, ImageContentError>;
+}
+
+impl ImageContentExt for ImageContent {
+    fn verify(&self) -> Result<ImageFormat, ImageContentError> {
+        let header = decode_header(&self.data)?;
+        verify_format(&self.mime_type, &header)
+    }
+
+    fn decode(&self) -> Result<DecodedImage, ImageContentError> {
+        let bytes = STANDARD.decode(&self.data)?;
+        let format = verify_format(&self.mime_type, &bytes)?;
+        Ok(DecodedImage { format, bytes })
+    }
+
+    #[cfg(feature = "image")]
+    fn dimensions(&self) -> Result<ImageDimensions, ImageContentError> {
+        let header = decode_header(&self.data)?;
+        match verify_format(&self.mime_type, &header)? {
+            ImageFormat::Jpeg => self.decode()?.dimensions(),
+            format => read_dimensions(format, &header),
+        }
+    }
+}
+
+// Decodes the first bytes of base64 `data`
+fn decode_header(data: &str) -> Result<Vec<u8>, ImageContentError> {
+    match data.get(..HEADER_BASE64_SIZE) {
+        Some(header) if data.len() > HEADER_BASE64_SIZE => Ok(STANDARD.decode(header)?),
+        _ => Ok(STANDARD.decode(data)?),
+    }
+}
+
+// Returns the format detected from the first bytes of an image, if it matches `mime_type`
+fn verify_format(mime_type: &str, bytes: &[u8]) -> Result<ImageFormat, ImageContentError> {
+    let detected =
+        ImageFormat::detect(bytes).ok_or_else(|| ImageContentError::UnrecognizedFormat {
+            declared: mime_type.to_string(),
+        })?;
+    if ImageFormat::from_mime_type(mime_type) != Some(detected) {
+        return Err(ImageContentError::MimeTypeMismatch {
+            declared: mime_type.to_string(),
+            detected,
+        });
+    }
+    Ok(detected)
+}
+
+// Reads the dimensions of an image of the given format from its header
+#[cfg(feature = "image")]
+fn read_dimensions(
+    format: ImageFormat,
+    bytes: &[u8],
+) -> Result<ImageDimensions, ImageContentError> {
+    let u16_le = |offset: usize| -> Option<u32> {
+        Some(u16::from_le_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?) as u32)
+    };
+    let u16_be = |offset: usize| -> Option<u32> {
+        Some(u16::from_be_bytes(bytes.get(offset..offset + 2)?.try_into().ok()?) as u32)
+    };
+    let u24_le = |offset: usize| -> Option<u32> {
+        let bytes = bytes.get(offset..offset + 3)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]))
+    };
+    let u32_le = |offset: usize| -> Option<u32> {
+        Some(u32::from_le_bytes(
+            bytes.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+    let u32_be = |offset: usize| -> Option<u32> {
+        Some(u32::from_be_bytes(
+            bytes.get(offset..offset + 4)?.try_into().ok()?,
+        ))
+    };
+
+    let dimensions = match format {
+        // the IHDR chunk follows the signature
+        ImageFormat::Png => (bytes.get(12..16) == Some(b"IHDR"))
+            .then(|| Some((u32_be(16)?, u32_be(20)?)))
+            .flatten(),
+        ImageFormat::Gif => u16_le(6).zip(u16_le(8)),
+        // the height is negative for top-down bitmaps
+        ImageFormat::Bmp => u32_le(18).zip(u32_le(22).map(|height| (height as i32).unsigned_abs())),
+        ImageFormat::Webp => match bytes.get(12..16) {
+            Some(b"VP8 ") => u16_le(26)
+                .zip(u16_le(28))
+                .map(|(width, height)| (width & 0x3FFF, height & 0x3FFF)),
+            Some(b"VP8L") => {
+                u32_le(21).map(|bits| (1 + (bits & 0x3FFF), 1 + ((bits >> 14) & 0x3FFF)))
+            }
+            Some(b"VP8X") => u24_le(24)
+                .zip(u24_le(27))
+                .map(|(width, height)| (width + 1, height + 1)),
+            _ => None,
+        },
+        ImageFormat::Jpeg => read_jpeg_dimensions(bytes, u16_be),
+    };
+
+    dimensions
+        .map(|(width, height)| ImageDimensions { width, height })
+        .ok_or(ImageContentError::MalformedHeader { format })
+}
+
+// Reads the dimensions of a JPEG image from its start of frame segment
+#[cfg(feature = "image")]
+fn read_jpeg_dimensions(bytes: &[u8], u16_be: impl Fn(usize) -> Option<u32>) -> Option<(u32, u32)> {
+    // skip the start of image marker
+    let mut offset = 2;
+    loop {
+        // markers may be preceded by fill bytes
+        while *bytes.get(offset)? == 0xFF && *bytes.get(offset + 1)? == 0xFF {
+            offset += 1;
+        }
+        if *bytes.get(offset)? != 0xFF {
+            return None;
+        }
+        let marker = *bytes.get(offset + 1)?;
+        match marker {
+            // start of frame markers, except DHT, JPG and DAC
+            0xC0..=0xCF if !matches!(marker, 0xC4 | 0xC8 | 0xCC) => {
+                return Some((u16_be(offset + 7)?, u16_be(offset + 5)?));
+            }
+            // standalone markers without length
+            0x01 | 0xD0..=0xD7 => offset += 2,
+            // start of scan or end of image before any frame
+            0xDA | 0xD9 => return None,
+            _ => offset += 2 + u16_be(offset + 2)? as usize,
+        }
+    }
+}
@@ -0,0 +1,118 @@
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::error::{McpSdkError, SdkResult};
+
+// Invoked with the number of missed pings when the connection is considered dead
+type DisconnectCallback = Arc<dyn Fn(u32) + Send + Sync>;
+
+/// Keep-alive of a connection: the runtime periodically pings the other party, and closes the
+/// connection once it missed too many consecutive pings, instead of letting a dead connection
+/// linger silently.
+///
+/// A ping is missed when it fails, or is not answered within `timeout`. Once `max_missed_pings`
+/// consecutive pings are missed, the disconnect callback is invoked, if any, and the transport is
+/// shut down. Servers only ping initialized clients.
+///
+/// Enabled with `ServerRuntime::with_keep_alive()` and `ClientRuntime::set_keep_alive()`.
+///
+/// # Example
+/// ```ignore
+/// client.set_keep_alive(
+///     KeepAlive::new(Duration::from_secs(15))
+///         .with_max_missed_pings(2)
+///         .on_disconnect(|missed_pings| eprintln!("server unresponsive after {} pings", missed_pings)),
+/// );
+/// ```
+#[derive(Clone)]
+pub struct KeepAlive {
+    /// The delay between two pings
+    pub interval: Duration,
+    /// How long to wait for the response to a ping
+    pub timeout: Duration,
+    /// The number of consecutive missed pings closing the connection
+    pub max_missed_pings: u32,
+    on_disconnect: Option<DisconnectCallback>,
+}
+
+impl Default for KeepAlive {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(30),
+            timeout: Duration::from_secs(10),
+            max_missed_pings: 3,
+            on_disconnect: None,
+        }
+    }
+}
+
+impl KeepAlive {
+    /// Creates a keep-alive pinging the other party every `interval`.
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            ..Default::default()
+        }
+    }
+
+    /// Sets how long to wait for the response to a ping, 10 seconds by default.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the number of consecutive missed pings closing the connection, 3 by default.
+    pub fn with_max_missed_pings(mut self, max_missed_pings: u32) -> Self {
+        self.max_missed_pings = max_missed_pings;
+        self
+    }
+
+    /// Sets the function invoked with the number of missed pings before the connection is closed.
+    pub fn on_disconnect(mut self, on_disconnect: impl Fn(u32) + Send + Sync + 'static) -> Self {
+        self.on_disconnect = Some(Arc::new(on_disconnect));
+        self
+    }
+
+    /// Pings the other party with `ping` every interval, while `is_active` returns `true`.
+    ///
+    /// Returns `Ok(())` once `is_active` returns `false`, or an error once too many consecutive pings
+    /// are missed, after invoking the disconnect callback.
+    pub(crate) async fn run<P, PF, A, AF>(&self, ping: P, is_active: A) -> SdkResult<()>
+    where
+        P: Fn() -> PF,
+        PF: Future<Output = SdkResult<rust_mcp_schema::Result>>,
+        A: Fn() -> AF,
+        AF: Future<Output = bool>,
+    {
+        let mut missed_pings = 0;
+        loop {
+            tokio::time::sleep(self.interval).await;
+            if !is_active().await {
+                return Ok(());
+            }
+            match tokio::time::timeout(self.timeout, ping()).await {
+                Ok(Ok(_)) => missed_pings = 0,
+                Ok(Err(_)) | Err(_) => missed_pings += 1,
+            }
+            if missed_pings >= self.max_missed_pings.max(1) {
+                if let Some(on_disconnect) = &self.on_disconnect {
+                    on_disconnect(missed_pings);
+                }
+                return Err(McpSdkError::KeepAliveTimeout { missed_pings });
+            }
+        }
+    }
+}
+
+impl fmt::Debug for KeepAlive {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeepAlive")
+            .field("interval", &self.interval)
+            .field("timeout", &self.timeout)
+            .field("max_missed_pings", &self.max_missed_pings)
+            .field("on_disconnect", &self.on_disconnect.is_some())
+            .finish()
+    }
+}
@@ -1,9 +1,48 @@
+mod cancellation_token;
+mod content_annotations;
 pub mod error;
+pub mod error_codes;
+#[cfg(feature = "encryption")]
+mod field_encryption;
+mod image_content;
+mod keep_alive;
+#[cfg(any(feature = "log", feature = "tracing"))]
+mod log_bridge;
 mod mcp_handlers;
 mod mcp_macros;
 mod mcp_runtimes;
 mod mcp_traits;
-mod utils;
+mod paginator;
+mod progress;
+mod request_meta;
+mod resource_dispatcher;
+mod resource_reader;
+#[cfg(feature = "signing")]
+mod result_signing;
+mod runtime_metrics;
+mod sampling_depth;
+pub mod sampling {
+    //! Protection against sampling loops, where a tool requests a sampling, and the LLM of the client
+    //! calls the same tool again while fulfilling it.
+    //!
+    //! The nesting depth is propagated in `_meta.samplingDepth`: `McpServer::create_message()` sends
+    //! the depth of the tool call being executed plus one, and `McpClient::call_tool()` sends the depth
+    //! of the sampling request being fulfilled. The server and client runtimes reject the requests
+    //! nested deeper than their limit, `DEFAULT_MAX_SAMPLING_DEPTH` unless configured otherwise.
+    pub use super::sampling_depth::{
+        current_sampling_depth, sampling_request_depth, set_sampling_request_depth,
+        set_tool_call_depth, tool_call_depth, DEFAULT_MAX_SAMPLING_DEPTH, SAMPLING_DEPTH_KEY,
+    };
+}
+
+mod schema_definitions;
+mod schema_snapshots;
+#[cfg(feature = "diagnostics")]
+mod server_diagnostics;
+mod tool_dry_run;
+mod tool_input_validation;
+mod tool_schema_validation;
+mod uri_template;
 
 pub mod mcp_client {
     //! Includes the runtimes and traits required to create a type-safe MCP client.
@@ -28,11 +67,34 @@ pub mod mcp_client {
     //!   handle each message based on its type and parameters.
     //!
     //! Refer to [examples/simple-mcp-client-core](https://github.com/rust-mcp-stack/rust-mcp-sdk/tree/main/examples/simple-mcp-client-core) for an example.
+    pub use super::content_annotations::{AnnotatedContent, Audience, ContentAnnotations};
+    #[cfg(feature = "image")]
+    pub use super::image_content::ImageDimensions;
+    pub use super::image_content::{DecodedImage, ImageContentError, ImageContentExt, ImageFormat};
+    pub use super::keep_alive::KeepAlive;
     pub use super::mcp_handlers::mcp_client_handler::ClientHandler;
     pub use super::mcp_handlers::mcp_client_handler_core::ClientHandlerCore;
+    pub use super::mcp_handlers::sampling_handler::{sampling_fn, SamplingFn, SamplingHandler};
     pub use super::mcp_runtimes::client_runtime::mcp_client_runtime as client_runtime;
     pub use super::mcp_runtimes::client_runtime::mcp_client_runtime_core as client_runtime_core;
+    pub use super::mcp_runtimes::client_runtime::ClientInfoBuilder;
     pub use super::mcp_runtimes::client_runtime::ClientRuntime;
+    pub use super::mcp_runtimes::client_runtime::RetryPolicy;
+    pub use super::mcp_runtimes::client_runtime::ServerProfile;
+    pub use super::mcp_runtimes::client_runtime::{
+        ClientMiddleware, Next, NotificationNext, ScopedClient,
+    };
+    pub use super::mcp_runtimes::client_runtime::{ListChanged, NOTIFICATION_STREAM_CAPACITY};
+    pub use super::mcp_runtimes::client_runtime::{
+        MetadataChanges, ServerMetadata, ServerMetadataCache,
+    };
+    pub use super::mcp_traits::call_tool_params_ext::CallToolRequestParamsExt;
+    pub use super::mcp_traits::call_tool_result_ext::CallToolResultExt;
+    pub use super::mcp_traits::rpc_error_ext::RpcErrorExt;
+    pub use super::progress::Progress;
+    pub use super::resource_dispatcher::{ResourceContents, ResourceData, ResourceDispatcher};
+    pub use super::resource_reader::ResourceReader;
+    pub use super::tool_input_validation::{ArgumentError, InvalidToolArguments};
 }
 
 pub mod mcp_server {
@@ -60,17 +122,140 @@ pub mod mcp_server {
     //! Refer to [examples/hello-world-mcp-server-core](https://github.com/rust-mcp-stack/rust-mcp-sdk/tree/main/examples/hello-world-mcp-server-core) for an example.
     pub use super::mcp_handlers::mcp_server_handler::ServerHandler;
     pub use super::mcp_handlers::mcp_server_handler_core::ServerHandlerCore;
+    pub use super::mcp_handlers::server_handler_adapters::{
+        AsServerHandler, AsServerHandlerCore, ToolList,
+    };
+    pub use super::mcp_traits::rpc_error_ext::RpcErrorExt;
+    pub use super::mcp_traits::tool_input_schema_ext::ToolInputSchemaExt;
 
+    pub use super::content_annotations::{AnnotatedContent, Audience, ContentAnnotations};
+    pub use super::keep_alive::KeepAlive;
+    #[cfg(any(feature = "log", feature = "tracing"))]
+    pub use super::log_bridge::{LogBridge, LogBridgeOptions};
     pub use super::mcp_runtimes::server_runtime::mcp_server_runtime as server_runtime;
     pub use super::mcp_runtimes::server_runtime::mcp_server_runtime_core as server_runtime_core;
+    pub use super::mcp_runtimes::server_runtime::LoadSheddingPolicy;
+    pub use super::mcp_runtimes::server_runtime::ResourceRegistry;
+    pub use super::mcp_runtimes::server_runtime::ServerInfoBuilder;
+    pub use super::mcp_runtimes::server_runtime::ServerLogger;
     pub use super::mcp_runtimes::server_runtime::ServerRuntime;
+    pub use super::mcp_runtimes::server_runtime::ToolErrorPolicy;
+    pub use super::mcp_runtimes::server_runtime::ToolResultLimits;
+    pub use super::mcp_runtimes::server_runtime::{validate_prompt_arguments, PromptRegistry};
+    pub use super::mcp_runtimes::server_runtime::{
+        CompletionProvider, CompletionRegistry, MAX_COMPLETION_VALUES,
+    };
+    pub use super::mcp_runtimes::server_runtime::{RateLimit, RateLimiter};
+    pub use super::mcp_runtimes::server_runtime::{RequestNext, ServerMiddleware};
+    pub use super::mcp_runtimes::server_runtime::{SessionServer, SessionStore};
+    pub use super::mcp_runtimes::server_runtime::{SubscriptionManager, SubscriptionValidator};
+    pub use super::mcp_runtimes::server_runtime::{ToolCallNext, ToolHook};
+    pub use super::paginator::{ListItem, ListOrder, Page, Paginator};
+    pub use super::progress::Progress;
+    pub use super::schema_snapshots::write_schema_snapshots;
+    pub use super::tool_input_validation::{
+        validate_tool_arguments, ArgumentError, InvalidToolArguments,
+    };
+    pub use super::tool_schema_validation::{
+        validate_json_schema, validate_tool_schema, validate_tool_schemas, InvalidToolSchema,
+        InvalidToolSchemas, SchemaError,
+    };
+    pub use super::uri_template::{UriParams, UriTemplate};
 }
 
 pub use mcp_traits::mcp_client::*;
+pub use mcp_traits::mcp_resource::*;
 pub use mcp_traits::mcp_server::*;
+pub use mcp_traits::mcp_tool::*;
 
 pub use rust_mcp_transport::*;
 
+#[cfg(feature = "encryption")]
+pub mod encryption {
+    //! Encryption of sensitive tool arguments, for deployments where relays or logs must not see secrets.
+    //!
+    //! Arguments marked with `#[json_schema(encrypted)]` are encrypted by the client with
+    //! `McpClient::call_tool_encrypted()` and decrypted by a `ServerRuntime` configured with
+    //! `with_key_provider()`, before the tool call reaches the server handler.
+    pub use super::field_encryption::*;
+}
+
+#[cfg(feature = "signing")]
+pub mod signing {
+    //! Signing of the results and notifications of a server, for deployments that need to prove that
+    //! responses relayed through untrusted parties come from a specific trusted server.
+    //!
+    //! A `ServerRuntime` configured with `with_signer()` adds the signature of its results and
+    //! notifications to their `_meta`, and a `ClientRuntime` configured with `set_signature_verifier()`
+    //! rejects the results and notifications whose signature is missing or invalid.
+    pub use super::result_signing::*;
+}
+
+pub mod request_context {
+    //! The context of the requests served by handlers, and the propagation of the `_meta` of
+    //! requests across the client and server boundary, e.g. to carry the trace ids of distributed
+    //! tracing.
+    //!
+    //! Handlers read the JSON-RPC id, `_meta`, progress token and cancellation token of the request
+    //! they serve with `RequestContext::current()`. Clients attach `_meta` entries to tool calls with
+    //! `McpClient::call_tool_with_options()`, and servers to sampling requests with
+    //! `McpServer::create_message_with_options()`.
+    pub use super::cancellation_token::CancellationToken;
+    pub use super::request_meta::{RequestContext, RequestOptions, PROGRESS_TOKEN_KEY};
+}
+
+pub mod dry_run {
+    //! Dry runs of tool calls, validating a call and describing what it would do without side effects,
+    //! e.g. for the pre-flight checks of agents.
+    //!
+    //! Tools declared with `#[mcp_tool(dry_run)]` advertise the `_meta` argument in their input schema,
+    //! and implement `McpTool::dry_run()`. Clients request a dry run with `McpClient::call_tool_dry_run()`,
+    //! setting `_meta.dryRun` in the arguments of the call. A `ServerRuntime` rejects the dry runs of
+    //! tools that do not advertise them, and marks the results of dry runs with `_meta.dryRun`.
+    pub use super::tool_dry_run::*;
+}
+
+#[cfg(feature = "diagnostics")]
+pub mod diagnostics {
+    //! Standardized inspections of MCP servers, for hosts and CLIs checking the servers they connect to.
+    //!
+    //! The reports are plain serde structs, so they can be printed for humans or serialized as JSON:
+    //!
+    //! ```ignore
+    //! let inspection = diagnostics::inspect_server(client.as_ref()).await;
+    //! println!("{}", serde_json::to_string_pretty(&inspection)?);
+    //!
+    //! let pings = diagnostics::ping_server(client.as_ref(), 3, Duration::from_secs(1)).await;
+    //! println!("{} out of 3 pings succeeded", pings.successes());
+    //! ```
+    pub use super::server_diagnostics::*;
+}
+
+pub mod metrics {
+    //! Metrics of the server and client runtimes: request counts, errors and latencies,
+    //! requests in flight and notifications, by method.
+    //!
+    //! Runtimes record their activity with `DefaultMetrics` unless configured otherwise,
+    //! snapshots are returned by their `metrics()` method:
+    //!
+    //! ```ignore
+    //! let snapshot = server.metrics();
+    //! println!("{}", serde_json::to_string_pretty(&snapshot)?);
+    //!
+    //! // or in the Prometheus text exposition format
+    //! println!("{}", snapshot.to_prometheus("mcp_server"));
+    //! ```
+    pub use super::runtime_metrics::{
+        DefaultMetrics, LatencyHistogram, MethodMetrics, Metrics, MetricsSnapshot, OTHER_METHODS,
+    };
+}
+
+#[doc(hidden)]
+pub mod __macro_support {
+    //! Runtime support of the code generated by the macros of rust-mcp-macros, not a public API.
+    pub use super::schema_definitions::inline_definitions;
+}
+
 #[cfg(feature = "macros")]
 pub mod macros {
     pub use rust_mcp_macros::*;
@@ -0,0 +1,302 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rust_mcp_schema::{LoggingLevel, LoggingMessageNotificationParams};
+use serde_json::Value;
+use tokio::sync::mpsc::{self, error::TrySendError};
+use tokio::task::JoinHandle;
+
+use crate::error::McpSdkError;
+use crate::mcp_runtimes::server_runtime::severity;
+use crate::mcp_traits::mcp_server::McpServer;
+
+/// Name of the logger of the messages reporting dropped records
+const LOG_BRIDGE_LOGGER_NAME: &str = "rust_mcp_sdk";
+
+/// How often the forwarding task checks whether the client is initialized
+const INITIALIZATION_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Options of a `LogBridge`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LogBridgeOptions {
+    /// The least severe level of the records forwarded to the client
+    pub level: LoggingLevel,
+    /// The average number of records forwarded per second, records exceeding it are dropped
+    pub max_records_per_second: f64,
+    /// The number of records that can be forwarded at once above the average rate
+    pub burst: u32,
+    /// The number of records queued until the client is initialized, or while they are being sent
+    pub queue_capacity: usize,
+}
+
+impl Default for LogBridgeOptions {
+    fn default() -> Self {
+        Self {
+            level: LoggingLevel::Info,
+            max_records_per_second: 20.0,
+            burst: 100,
+            queue_capacity: 1024,
+        }
+    }
+}
+
+/// Forwards the log records of the application to the client as `notifications/message`,
+/// so that the existing `log::info!()` calls or `tracing` events of a server appear in the
+/// MCP log of the client.
+///
+/// With the `log` feature, the bridge is installed as the logger of the `log` crate with `install()`.
+/// With the `tracing` feature, it is a `tracing_subscriber::Layer` added to a subscriber.
+///
+/// Records are queued, then sent once the client is initialized by the task started with
+/// `forward_to()`. The levels of the records are mapped to MCP logging levels, and records less
+/// severe than the level set by the client are dropped if the server has a `ServerLogger`.
+/// Records exceeding the rate limit of the bridge, or the capacity of its queue, are dropped and
+/// reported by a warning sent with the next forwarded record.
+///
+/// # Example
+/// ```ignore
+/// let log_bridge = LogBridge::new(LogBridgeOptions::default());
+/// log_bridge.install()?;
+///
+/// let server = Arc::new(
+///     server_runtime::create_server(server_details, transport, handler)
+///         .with_logger(ServerLogger::default()),
+/// );
+/// log_bridge.forward_to(Arc::clone(&server));
+/// server.start().await?;
+/// ```
+#[derive(Clone)]
+pub struct LogBridge {
+    inner: Arc<LogBridgeInner>,
+}
+
+struct LogBridgeInner {
+    options: LogBridgeOptions,
+    sender: mpsc::Sender<LoggingMessageNotificationParams>,
+    // Taken by the forwarding task
+    receiver: Mutex<Option<mpsc::Receiver<LoggingMessageNotificationParams>>>,
+    rate_limiter: Mutex<RateLimiter>,
+}
+
+// A token bucket limiting the rate of the forwarded records
+struct RateLimiter {
+    tokens: f64,
+    last_refill: Instant,
+    // Number of records dropped since the last forwarded one
+    dropped: u64,
+}
+
+impl RateLimiter {
+    // Takes a token if one is available, returning the number of records dropped before it
+    fn acquire(&mut self, options: &LogBridgeOptions) -> Option<u64> {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens =
+            (self.tokens + elapsed * options.max_records_per_second).min(options.burst as f64);
+        self.last_refill = now;
+
+        if self.tokens < 1.0 {
+            self.dropped += 1;
+            return None;
+        }
+        self.tokens -= 1.0;
+        Some(std::mem::take(&mut self.dropped))
+    }
+}
+
+impl LogBridge {
+    pub fn new(options: LogBridgeOptions) -> Self {
+        let (sender, receiver) = mpsc::channel(options.queue_capacity.max(1));
+        Self {
+            inner: Arc::new(LogBridgeInner {
+                options,
+                sender,
+                receiver: Mutex::new(Some(receiver)),
+                rate_limiter: Mutex::new(RateLimiter {
+                    tokens: options.burst as f64,
+                    last_refill: Instant::now(),
+                    dropped: 0,
+                }),
+            }),
+        }
+    }
+
+    /// Returns the options of the bridge
+    pub fn options(&self) -> &LogBridgeOptions {
+        &self.inner.options
+    }
+
+    /// Spawns the task sending the queued records to the client of `server`, once it is initialized.
+    /// The task ends when the connection with the client is closed.
+    ///
+    /// Returns `None` if the records are already forwarded to a server.
+    pub fn forward_to<S: McpServer + 'static>(&self, server: Arc<S>) -> Option<JoinHandle<()>> {
+        let mut receiver = self.inner.receiver.lock().ok()?.take()?;
+        Some(tokio::spawn(async move {
+            while !server.is_initialized() {
+                tokio::time::sleep(INITIALIZATION_POLL_INTERVAL).await;
+            }
+            while let Some(params) = receiver.recv().await {
+                if let Err(McpSdkError::ConnectionClosed | McpSdkError::TransportError(_)) =
+                    server.send_logging_message(params).await
+                {
+                    break;
+                }
+            }
+        }))
+    }
+
+    /// Returns `true` if records of the given level are forwarded
+    pub fn is_enabled(&self, level: LoggingLevel) -> bool {
+        severity(level) >= severity(self.inner.options.level)
+    }
+
+    // Queues a record, unless it exceeds the rate limit or the capacity of the queue
+    fn forward(&self, level: LoggingLevel, logger_name: &str, data: Value) {
+        let Ok(mut rate_limiter) = self.inner.rate_limiter.lock() else {
+            return;
+        };
+        let Some(dropped) = rate_limiter.acquire(&self.inner.options) else {
+            return;
+        };
+
+        if dropped > 0 {
+            let params = LoggingMessageNotificationParams {
+                data: Value::String(format!("{} log records were dropped", dropped)),
+                level: LoggingLevel::Warning,
+                logger: Some(LOG_BRIDGE_LOGGER_NAME.to_string()),
+            };
+            if let Err(TrySendError::Full(_)) = self.inner.sender.try_send(params) {
+                rate_limiter.dropped += dropped;
+            }
+        }
+
+        let params = LoggingMessageNotificationParams {
+            data,
+            level,
+            logger: Some(logger_name.to_string()),
+        };
+        if let Err(TrySendError::Full(_)) = self.inner.sender.try_send(params) {
+            rate_limiter.dropped += 1;
+        }
+    }
+}
+
+#[cfg(feature = "log")]
+impl LogBridge {
+    /// Installs the bridge as the logger of the `log` crate, and sets the maximum level of the
+    /// `log` crate to the level of the bridge.
+    pub fn install(&self) -> Result<(), log::SetLoggerError> {
+        log::set_boxed_logger(Box::new(self.clone()))?;
+        log::set_max_level(match self.inner.options.level {
+            LoggingLevel::Debug => log::LevelFilter::Debug,
+            LoggingLevel::Info | LoggingLevel::Notice => log::LevelFilter::Info,
+            LoggingLevel::Warning => log::LevelFilter::Warn,
+            _ => log::LevelFilter::Error,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(feature = "log")]
+fn from_log_level(level: log::Level) -> LoggingLevel {
+    match level {
+        log::Level::Error => LoggingLevel::Error,
+        log::Level::Warn => LoggingLevel::Warning,
+        log::Level::Info => LoggingLevel::Info,
+        log::Level::Debug | log::Level::Trace => LoggingLevel::Debug,
+    }
+}
+
+/// Forwards the records as messages of the logger named after their target, holding the formatted record.
+#[cfg(feature = "log")]
+impl log::Log for LogBridge {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        self.is_enabled(from_log_level(metadata.level()))
+    }
+
+    fn log(&self, record: &log::Record) {
+        let level = from_log_level(record.level());
+        if self.is_enabled(level) {
+            self.forward(
+                level,
+                record.target(),
+                Value::String(record.args().to_string()),
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+#[cfg(feature = "tracing")]
+fn from_tracing_level(level: tracing::Level) -> LoggingLevel {
+    match level {
+        tracing::Level::ERROR => LoggingLevel::Error,
+        tracing::Level::WARN => LoggingLevel::Warning,
+        tracing::Level::INFO => LoggingLevel::Info,
+        _ => LoggingLevel::Debug,
+    }
+}
+
+// Collects the fields of an event into a JSON object
+#[cfg(feature = "tracing")]
+struct FieldVisitor(serde_json::Map<String, Value>);
+
+#[cfg(feature = "tracing")]
+impl tracing::field::Visit for FieldVisitor {
+    fn record_f64(&mut self, field: &tracing::field::Field, value: f64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_i64(&mut self, field: &tracing::field::Field, value: i64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_u64(&mut self, field: &tracing::field::Field, value: u64) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_bool(&mut self, field: &tracing::field::Field, value: bool) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+        self.0.insert(field.name().to_string(), value.into());
+    }
+
+    fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+        self.0
+            .insert(field.name().to_string(), format!("{:?}", value).into());
+    }
+}
+
+/// Forwards the events as messages of the logger named after their target, holding their message,
+/// or an object of their fields if they have fields other than the message.
+#[cfg(feature = "tracing")]
+impl<S: tracing::Subscriber> tracing_subscriber::Layer<S> for LogBridge {
+    fn on_event(
+        &self,
+        event: &tracing::Event<'_>,
+        _ctx: tracing_subscriber::layer::Context<'_, S>,
+    ) {
+        let metadata = event.metadata();
+        let level = from_tracing_level(*metadata.level());
+        if !self.is_enabled(level) {
+            return;
+        }
+
+        let mut visitor = FieldVisitor(serde_json::Map::new());
+        event.record(&mut visitor);
+        let mut fields = visitor.0;
+        let data = match fields.remove("message") {
+            Some(message) if fields.is_empty() => message,
+            Some(message) => {
+                fields.insert("message".to_string(), message);
+                Value::Object(fields)
+            }
+            None => Value::Object(fields),
+        };
+        self.forward(level, metadata.target(), data);
+    }
+}
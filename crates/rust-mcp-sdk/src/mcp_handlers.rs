@@ -2,3 +2,5 @@ pub mod mcp_client_handler;
 pub mod mcp_client_handler_core;
 pub mod mcp_server_handler;
 pub mod mcp_server_handler_core;
+pub mod sampling_handler;
+pub mod server_handler_adapters;
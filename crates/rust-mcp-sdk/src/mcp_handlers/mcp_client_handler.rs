@@ -1,3 +1,5 @@
+use std::process::ExitStatus;
+
 use async_trait::async_trait;
 use rust_mcp_schema::{
     CancelledNotification, CreateMessageRequest, CreateMessageResult, ListRootsRequest,
@@ -32,6 +34,11 @@ pub trait ClientHandler: Send + Sync + 'static {
         runtime: &dyn McpClient,
     ) -> std::result::Result<CreateMessageResult, RpcError> {
         runtime.assert_client_request_capabilities(request.method())?;
+        if let Some(sampling_handler) = runtime.sampling_handler() {
+            return sampling_handler
+                .create_message(request.params, runtime)
+                .await;
+        }
         Err(RpcError::method_not_found().with_message(format!(
             "No handler is implemented for '{}'.",
             request.method(),
@@ -44,10 +51,10 @@ pub trait ClientHandler: Send + Sync + 'static {
         runtime: &dyn McpClient,
     ) -> std::result::Result<ListRootsResult, RpcError> {
         runtime.assert_client_request_capabilities(request.method())?;
-        Err(RpcError::method_not_found().with_message(format!(
-            "No handler is implemented for '{}'.",
-            request.method(),
-        )))
+        Ok(ListRootsResult {
+            meta: None,
+            roots: runtime.roots(),
+        })
     }
 
     async fn handle_custom_request(
@@ -148,4 +155,14 @@ pub trait ClientHandler: Send + Sync + 'static {
         }
         Ok(())
     }
+
+    /// Invoked when the MCP server process launched by the transport exits unexpectedly,
+    /// with its exit status. It is not invoked when the client is shut down.
+    ///
+    /// The default implementation reports unsuccessful exits to stderr.
+    async fn on_server_process_exit(&self, status: ExitStatus, runtime: &dyn McpClient) {
+        if !status.success() {
+            eprintln!("Server process exited with {}", status);
+        }
+    }
 }
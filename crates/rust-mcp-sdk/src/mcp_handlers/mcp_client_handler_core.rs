@@ -1,3 +1,5 @@
+use std::process::ExitStatus;
+
 use async_trait::async_trait;
 use rust_mcp_schema::schema_utils::*;
 use rust_mcp_schema::*;
@@ -52,4 +54,14 @@ pub trait ClientHandlerCore: Send + Sync + 'static {
         }
         Ok(())
     }
+
+    /// Invoked when the MCP server process launched by the transport exits unexpectedly,
+    /// with its exit status. It is not invoked when the client is shut down.
+    ///
+    /// The default implementation reports unsuccessful exits to stderr.
+    async fn on_server_process_exit(&self, status: ExitStatus, _runtime: &dyn McpClient) {
+        if !status.success() {
+            eprintln!("Server process exited with {}", status);
+        }
+    }
 }
@@ -3,6 +3,7 @@ use rust_mcp_schema::{schema_utils::CallToolError, *};
 use serde_json::Value;
 
 use crate::mcp_traits::mcp_server::McpServer;
+use crate::paginator::Paginator;
 
 /// Defines the `ServerHandler` trait for handling Model Context Protocol (MCP) operations on a server.
 /// This trait provides default implementations for request and notification handlers in an MCP server,
@@ -24,18 +25,25 @@ pub trait ServerHandler: Send + Sync + 'static {
     /// * `runtime` - Reference to the MCP server runtime
     ///
     /// # Returns
-    /// Returns the server info as InitializeResult on success or a JSON-RPC error on failure
+    /// Returns the server info as InitializeResult on success or a JSON-RPC error on failure,
+    /// such as the version mismatch error if the protocol version of the client is not supported.
     /// Do not override this unless the standard initialization process doesn't work for you or you need to modify it.
     async fn handle_initialize_request(
         &self,
         initialize_request: InitializeRequest,
         runtime: &dyn McpServer,
     ) -> std::result::Result<InitializeResult, RpcError> {
+        let protocol_version = &initialize_request.params.protocol_version;
+        runtime.assert_protocol_version_supported(protocol_version)?;
+
         runtime
             .set_client_details(initialize_request.params.clone())
             .map_err(|err| RpcError::internal_error().with_message(format!("{}", err)))?;
 
-        Ok(runtime.server_info().to_owned())
+        // respond with the version requested by the client, as it is supported
+        let mut server_info = runtime.server_info().to_owned();
+        server_info.protocol_version = protocol_version.to_owned();
+        Ok(server_info)
     }
 
     /// Handles ping requests from clients.
@@ -53,7 +61,8 @@ pub trait ServerHandler: Send + Sync + 'static {
 
     /// Handles requests to list available resources.
     ///
-    /// Default implementation returns method not found error.
+    /// Default implementation lists the resources of the `ResourceRegistry` of the server, if enabled,
+    /// otherwise returns method not found error.
     /// Customize this function in your specific handler to implement behavior tailored to your MCP server's capabilities and requirements.
     async fn handle_list_resources_request(
         &self,
@@ -61,15 +70,26 @@ pub trait ServerHandler: Send + Sync + 'static {
         runtime: &dyn McpServer,
     ) -> std::result::Result<ListResourcesResult, RpcError> {
         runtime.assert_server_request_capabilities(request.method())?;
-        Err(RpcError::method_not_found().with_message(format!(
-            "No handler is implemented for '{}'.",
-            request.method(),
-        )))
+        let Some(resource_registry) = runtime.resource_registry() else {
+            return Err(RpcError::method_not_found().with_message(format!(
+                "No handler is implemented for '{}'.",
+                request.method(),
+            )));
+        };
+        let cursor = request.params.and_then(|params| params.cursor);
+        let page =
+            Paginator::default().paginate(resource_registry.resources(), cursor.as_deref())?;
+        Ok(ListResourcesResult {
+            meta: None,
+            next_cursor: page.next_cursor,
+            resources: page.items,
+        })
     }
 
     /// Handles requests to list resource templates.
     ///
-    /// Default implementation returns method not found error.
+    /// Default implementation lists the resource templates of the `ResourceRegistry` of the server,
+    /// if enabled, otherwise returns method not found error.
     /// Customize this function in your specific handler to implement behavior tailored to your MCP server's capabilities and requirements.
     async fn handle_list_resource_templates_request(
         &self,
@@ -77,15 +97,26 @@ pub trait ServerHandler: Send + Sync + 'static {
         runtime: &dyn McpServer,
     ) -> std::result::Result<ListResourceTemplatesResult, RpcError> {
         runtime.assert_server_request_capabilities(request.method())?;
-        Err(RpcError::method_not_found().with_message(format!(
-            "No handler is implemented for '{}'.",
-            request.method(),
-        )))
+        let Some(resource_registry) = runtime.resource_registry() else {
+            return Err(RpcError::method_not_found().with_message(format!(
+                "No handler is implemented for '{}'.",
+                request.method(),
+            )));
+        };
+        let cursor = request.params.and_then(|params| params.cursor);
+        let page = Paginator::default()
+            .paginate(resource_registry.resource_templates(), cursor.as_deref())?;
+        Ok(ListResourceTemplatesResult {
+            meta: None,
+            next_cursor: page.next_cursor,
+            resource_templates: page.items,
+        })
     }
 
     /// Handles requests to read a specific resource.
     ///
-    /// Default implementation returns method not found error.
+    /// Default implementation reads the resource from the `ResourceRegistry` of the server, if enabled,
+    /// otherwise returns method not found error.
     /// Customize this function in your specific handler to implement behavior tailored to your MCP server's capabilities and requirements.
     async fn handle_read_resource_request(
         &self,
@@ -93,10 +124,13 @@ pub trait ServerHandler: Send + Sync + 'static {
         runtime: &dyn McpServer,
     ) -> std::result::Result<ReadResourceResult, RpcError> {
         runtime.assert_server_request_capabilities(request.method())?;
-        Err(RpcError::method_not_found().with_message(format!(
-            "No handler is implemented for '{}'.",
-            request.method(),
-        )))
+        match runtime.resource_registry() {
+            Some(resource_registry) => resource_registry.read(&request.params.uri).await,
+            None => Err(RpcError::method_not_found().with_message(format!(
+                "No handler is implemented for '{}'.",
+                request.method(),
+            ))),
+        }
     }
 
     /// Handles subscription requests from clients.
@@ -133,7 +167,8 @@ pub trait ServerHandler: Send + Sync + 'static {
 
     /// Handles requests to list available prompts.
     ///
-    /// Default implementation returns method not found error.
+    /// Default implementation lists the prompts of the `PromptRegistry` of the server, if enabled,
+    /// otherwise returns method not found error.
     /// Customize this function in your specific handler to implement behavior tailored to your MCP server's capabilities and requirements.
     async fn handle_list_prompts_request(
         &self,
@@ -141,15 +176,25 @@ pub trait ServerHandler: Send + Sync + 'static {
         runtime: &dyn McpServer,
     ) -> std::result::Result<ListPromptsResult, RpcError> {
         runtime.assert_server_request_capabilities(request.method())?;
-        Err(RpcError::method_not_found().with_message(format!(
-            "No handler is implemented for '{}'.",
-            request.method(),
-        )))
+        let Some(prompt_registry) = runtime.prompt_registry() else {
+            return Err(RpcError::method_not_found().with_message(format!(
+                "No handler is implemented for '{}'.",
+                request.method(),
+            )));
+        };
+        let cursor = request.params.and_then(|params| params.cursor);
+        let page = Paginator::default().paginate(prompt_registry.prompts(), cursor.as_deref())?;
+        Ok(ListPromptsResult {
+            meta: None,
+            next_cursor: page.next_cursor,
+            prompts: page.items,
+        })
     }
 
     /// Handles requests to get a specific prompt.
     ///
-    /// Default implementation returns method not found error.
+    /// Default implementation validates the arguments and renders the prompt with the `PromptRegistry`
+    /// of the server, if enabled, otherwise returns method not found error.
     /// Customize this function in your specific handler to implement behavior tailored to your MCP server's capabilities and requirements.
     async fn handle_get_prompt_request(
         &self,
@@ -157,10 +202,13 @@ pub trait ServerHandler: Send + Sync + 'static {
         runtime: &dyn McpServer,
     ) -> std::result::Result<GetPromptResult, RpcError> {
         runtime.assert_server_request_capabilities(request.method())?;
-        Err(RpcError::method_not_found().with_message(format!(
-            "No handler is implemented for '{}'.",
-            request.method(),
-        )))
+        match runtime.prompt_registry() {
+            Some(prompt_registry) => prompt_registry.get(request.params).await,
+            None => Err(RpcError::method_not_found().with_message(format!(
+                "No handler is implemented for '{}'.",
+                request.method(),
+            ))),
+        }
     }
 
     /// Handles requests to list available tools.
@@ -191,7 +239,7 @@ pub trait ServerHandler: Send + Sync + 'static {
         runtime
             .assert_server_request_capabilities(request.method())
             .map_err(CallToolError::new)?;
-        Ok(CallToolError::unknown_tool(format!("Unknown tool: {}", request.params.name)).into())
+        Err(CallToolError::unknown_tool(request.params.name))
     }
 
     /// Handles requests to enable or adjust logging level.
@@ -212,7 +260,8 @@ pub trait ServerHandler: Send + Sync + 'static {
 
     /// Handles completion requests from clients.
     ///
-    /// Default implementation returns method not found error.
+    /// Default implementation completes the argument with the `CompletionRegistry` of the server,
+    /// if enabled, otherwise returns method not found error.
     /// Customize this function in your specific handler to implement behavior tailored to your MCP server's capabilities and requirements.
     async fn handle_complete_request(
         &self,
@@ -220,10 +269,13 @@ pub trait ServerHandler: Send + Sync + 'static {
         runtime: &dyn McpServer,
     ) -> std::result::Result<CompleteResult, RpcError> {
         runtime.assert_server_request_capabilities(request.method())?;
-        Err(RpcError::method_not_found().with_message(format!(
-            "No handler is implemented for '{}'.",
-            request.method(),
-        )))
+        match runtime.completion_registry() {
+            Some(completion_registry) => completion_registry.complete(&request.params).await,
+            None => Err(RpcError::method_not_found().with_message(format!(
+                "No handler is implemented for '{}'.",
+                request.method(),
+            ))),
+        }
     }
 
     /// Handles custom requests not defined in the standard protocol.
@@ -0,0 +1,72 @@
+use std::future::Future;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use rust_mcp_schema::{CreateMessageRequestParams, CreateMessageResult, RpcError};
+
+use crate::mcp_traits::mcp_client::McpClient;
+
+/// Fulfills `sampling/createMessage` requests sent by servers, typically by forwarding them to an LLM.
+///
+/// A sampling handler is registered on the client runtime with `ClientRuntime::set_sampling_handler()`,
+/// separately from the `ClientHandler`. The default implementation of
+/// `ClientHandler::handle_create_message_request` delegates to it, as long as it is not overridden.
+///
+/// Note that the client must advertise the `sampling` capability for servers to send sampling requests.
+#[async_trait]
+pub trait SamplingHandler: Send + Sync + 'static {
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParams,
+        runtime: &dyn McpClient,
+    ) -> std::result::Result<CreateMessageResult, RpcError>;
+}
+
+#[async_trait]
+impl<T: SamplingHandler> SamplingHandler for Arc<T> {
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParams,
+        runtime: &dyn McpClient,
+    ) -> std::result::Result<CreateMessageResult, RpcError> {
+        self.as_ref().create_message(params, runtime).await
+    }
+}
+
+/// A `SamplingHandler` backed by an async closure, see `sampling_fn()`.
+pub struct SamplingFn<F> {
+    callback: F,
+}
+
+/// Creates a `SamplingHandler` from an async closure mapping the parameters of a sampling request
+/// to its result.
+///
+/// # Example
+/// ```ignore
+/// client.set_sampling_handler(sampling_fn(move |params| {
+///     let llm = Arc::clone(&llm);
+///     async move { llm.complete(params).await }
+/// }));
+/// ```
+pub fn sampling_fn<F, Fut>(callback: F) -> SamplingFn<F>
+where
+    F: Fn(CreateMessageRequestParams) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = std::result::Result<CreateMessageResult, RpcError>> + Send,
+{
+    SamplingFn { callback }
+}
+
+#[async_trait]
+impl<F, Fut> SamplingHandler for SamplingFn<F>
+where
+    F: Fn(CreateMessageRequestParams) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = std::result::Result<CreateMessageResult, RpcError>> + Send,
+{
+    async fn create_message(
+        &self,
+        params: CreateMessageRequestParams,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<CreateMessageResult, RpcError> {
+        (self.callback)(params).await
+    }
+}
@@ -0,0 +1,397 @@
+use async_trait::async_trait;
+use rust_mcp_schema::schema_utils::{
+    CallToolError, NotificationFromClient, RequestFromClient, ResultFromServer,
+};
+use rust_mcp_schema::*;
+use serde_json::Value;
+
+use crate::mcp_handlers::mcp_server_handler::ServerHandler;
+use crate::mcp_handlers::mcp_server_handler_core::ServerHandlerCore;
+use crate::mcp_runtimes::server_runtime::mcp_server_runtime::{
+    dispatch_notification, dispatch_request,
+};
+use crate::mcp_traits::mcp_server::McpServer;
+use crate::paginator::Paginator;
+
+/// Turns a `ServerHandlerCore` into a `ServerHandler`, to run an existing core handler with
+/// `server_runtime::create_server()` while moving its methods one by one to the `ServerHandler`
+/// methods, or to the default ones backed by the registries of the runtime.
+///
+/// Every typed request and notification is passed back to `handle_request()` and
+/// `handle_notification()` of the core handler, and its results are converted to the type
+/// expected for the request: a result of another type is an internal error. The protocol version
+/// is checked and the client details are stored before the initialize request is passed on, as
+/// done by `server_runtime_core`.
+///
+/// Custom notifications are not passed on, as `ServerHandler::handle_custom_notification()` has no
+/// access to the runtime.
+///
+/// # Example
+/// ```ignore
+/// let server = server_runtime::create_server(server_details, transport, AsServerHandler(MyCoreHandler {}))
+///     .with_resource_registry(registry);
+/// ```
+pub struct AsServerHandler<H>(pub H);
+
+impl<H: ServerHandlerCore> AsServerHandler<H> {
+    // Passes a request to the core handler, converting its result to the type expected for the request
+    async fn forward<T>(
+        &self,
+        request: impl Into<RequestFromClient>,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<T, RpcError>
+    where
+        T: TryFrom<ResultFromServer, Error = RpcError>,
+    {
+        self.0
+            .handle_request(request.into(), runtime)
+            .await
+            .and_then(T::try_from)
+    }
+}
+
+#[async_trait]
+impl<H: ServerHandlerCore> ServerHandler for AsServerHandler<H> {
+    async fn on_initialized(&self, runtime: &dyn McpServer) {
+        self.0.on_initialized(runtime).await
+    }
+
+    async fn handle_initialize_request(
+        &self,
+        initialize_request: InitializeRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<InitializeResult, RpcError> {
+        runtime.assert_protocol_version_supported(&initialize_request.params.protocol_version)?;
+        runtime
+            .set_client_details(initialize_request.params.clone())
+            .map_err(|err| RpcError::internal_error().with_message(format!("{}", err)))?;
+        self.forward(initialize_request, runtime).await
+    }
+
+    async fn handle_ping_request(
+        &self,
+        request: PingRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<Result, RpcError> {
+        self.forward(request, runtime).await
+    }
+
+    async fn handle_list_resources_request(
+        &self,
+        request: ListResourcesRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<ListResourcesResult, RpcError> {
+        self.forward(request, runtime).await
+    }
+
+    async fn handle_list_resource_templates_request(
+        &self,
+        request: ListResourceTemplatesRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<ListResourceTemplatesResult, RpcError> {
+        self.forward(request, runtime).await
+    }
+
+    async fn handle_read_resource_request(
+        &self,
+        request: ReadResourceRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<ReadResourceResult, RpcError> {
+        self.forward(request, runtime).await
+    }
+
+    async fn handle_subscribe_request(
+        &self,
+        request: SubscribeRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<Result, RpcError> {
+        self.forward(request, runtime).await
+    }
+
+    async fn handle_unsubscribe_request(
+        &self,
+        request: UnsubscribeRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<Result, RpcError> {
+        self.forward(request, runtime).await
+    }
+
+    async fn handle_list_prompts_request(
+        &self,
+        request: ListPromptsRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<ListPromptsResult, RpcError> {
+        self.forward(request, runtime).await
+    }
+
+    async fn handle_get_prompt_request(
+        &self,
+        request: GetPromptRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<GetPromptResult, RpcError> {
+        self.forward(request, runtime).await
+    }
+
+    async fn handle_list_tools_request(
+        &self,
+        request: ListToolsRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<ListToolsResult, RpcError> {
+        self.forward(request, runtime).await
+    }
+
+    async fn handle_call_tool_request(
+        &self,
+        request: CallToolRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        // the errors of the core handler are failures of the call, see `ToolErrorPolicy`
+        self.forward(request, runtime)
+            .await
+            .map_err(CallToolError::new)
+    }
+
+    async fn handle_set_level_request(
+        &self,
+        request: SetLevelRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<Result, RpcError> {
+        self.forward(request, runtime).await
+    }
+
+    async fn handle_complete_request(
+        &self,
+        request: CompleteRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<CompleteResult, RpcError> {
+        self.forward(request, runtime).await
+    }
+
+    async fn handle_custom_request(
+        &self,
+        request: Value,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<Value, RpcError> {
+        let result = self
+            .0
+            .handle_request(RequestFromClient::CustomRequest(request), runtime)
+            .await?;
+        match result {
+            ResultFromServer::CustomResult(value) => Ok(value),
+            ResultFromServer::ServerResult(server_result) => serde_json::to_value(server_result)
+                .map_err(|err| RpcError::internal_error().with_message(err.to_string())),
+        }
+    }
+
+    async fn handle_initialized_notification(
+        &self,
+        notification: InitializedNotification,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<(), RpcError> {
+        self.0
+            .handle_notification(notification.into(), runtime)
+            .await
+    }
+
+    async fn handle_cancelled_notification(
+        &self,
+        notification: CancelledNotification,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<(), RpcError> {
+        self.0
+            .handle_notification(notification.into(), runtime)
+            .await
+    }
+
+    async fn handle_progress_notification(
+        &self,
+        notification: ProgressNotification,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<(), RpcError> {
+        self.0
+            .handle_notification(notification.into(), runtime)
+            .await
+    }
+
+    async fn handle_roots_list_changed_notification(
+        &self,
+        notification: RootsListChangedNotification,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<(), RpcError> {
+        self.0
+            .handle_notification(notification.into(), runtime)
+            .await
+    }
+
+    async fn handle_error(
+        &self,
+        error: RpcError,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<(), RpcError> {
+        self.0.handle_error(error, runtime).await
+    }
+
+    async fn on_server_started(&self, runtime: &dyn McpServer) {
+        self.0.on_server_started(runtime).await
+    }
+}
+
+/// Turns a `ServerHandler` into a `ServerHandlerCore`, to run an existing handler with
+/// `server_runtime_core::create_server()`, e.g. to wrap it in a core handler intercepting some
+/// messages before passing the others to it.
+///
+/// Requests and notifications are passed to the methods of the handler handling their type,
+/// as done by `server_runtime`.
+///
+/// # Example
+/// ```ignore
+/// let server = server_runtime_core::create_server(server_details, transport, AsServerHandlerCore(MyServerHandler {}));
+/// ```
+pub struct AsServerHandlerCore<H>(pub H);
+
+#[async_trait]
+impl<H: ServerHandler> ServerHandlerCore for AsServerHandlerCore<H> {
+    // `on_initialized()` of the handler is called when the initialized notification is dispatched
+
+    async fn handle_request(
+        &self,
+        request: RequestFromClient,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<ResultFromServer, RpcError> {
+        dispatch_request(&self.0, request, runtime).await
+    }
+
+    async fn handle_notification(
+        &self,
+        notification: NotificationFromClient,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<(), RpcError> {
+        dispatch_notification(&self.0, notification, runtime).await
+    }
+
+    async fn handle_error(
+        &self,
+        error: RpcError,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<(), RpcError> {
+        self.0.handle_error(error, runtime).await
+    }
+
+    async fn on_server_started(&self, runtime: &dyn McpServer) {
+        self.0.on_server_started(runtime).await
+    }
+}
+
+/// An existing list of tools, e.g. the tools of a `tool_box!`, served as is by
+/// `handle_list_tools_request()`, paginated like the lists of the registries.
+///
+/// Tools have no registry, as their calls are dispatched by the handler: the list only answers
+/// the list requests and looks tools up by name, e.g. to route the calls of a migrated handler.
+///
+/// # Example
+/// ```ignore
+/// let tools = ToolList::from(MyTools::tools());
+///
+/// async fn handle_list_tools_request(
+///     &self,
+///     request: ListToolsRequest,
+///     runtime: &dyn McpServer,
+/// ) -> std::result::Result<ListToolsResult, RpcError> {
+///     self.tools.list(request)
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ToolList {
+    tools: Vec<Tool>,
+    paginator: Paginator,
+}
+
+impl ToolList {
+    /// Sets the paginator splitting the list into pages, `Paginator::default()` by default.
+    pub fn with_paginator(mut self, paginator: Paginator) -> Self {
+        self.paginator = paginator;
+        self
+    }
+
+    /// Returns the tools of the list
+    pub fn tools(&self) -> &[Tool] {
+        &self.tools
+    }
+
+    /// Returns the tool with the given name, if any
+    pub fn get(&self, name: &str) -> Option<&Tool> {
+        self.tools.iter().find(|tool| tool.name == name)
+    }
+
+    /// Returns the page of tools requested by a list request.
+    ///
+    /// # Errors
+    /// Returns an `invalid_params` error if the cursor of the request was not created by a `Paginator`.
+    pub fn list(
+        &self,
+        request: ListToolsRequest,
+    ) -> std::result::Result<ListToolsResult, RpcError> {
+        let cursor = request.params.and_then(|params| params.cursor);
+        let page = self
+            .paginator
+            .paginate(self.tools.clone(), cursor.as_deref())?;
+        Ok(ListToolsResult {
+            meta: None,
+            next_cursor: page.next_cursor,
+            tools: page.items,
+        })
+    }
+}
+
+impl From<Vec<Tool>> for ToolList {
+    fn from(tools: Vec<Tool>) -> Self {
+        Self {
+            tools,
+            paginator: Paginator::default(),
+        }
+    }
+}
+
+impl FromIterator<Tool> for ToolList {
+    fn from_iter<I: IntoIterator<Item = Tool>>(tools: I) -> Self {
+        Self::from(tools.into_iter().collect::<Vec<_>>())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tool(name: &str) -> Tool {
+        Tool {
+            description: None,
+            input_schema: ToolInputSchema::new(vec![], None),
+            name: name.to_string(),
+        }
+    }
+
+    fn names(result: &ListToolsResult) -> Vec<&str> {
+        result.tools.iter().map(|tool| tool.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_lists_existing_tools_page_by_page() {
+        let tools: ToolList = ["first", "second", "third"].into_iter().map(tool).collect();
+        let tools = tools.with_paginator(Paginator::new(2));
+        assert_eq!(
+            tools.get("second").map(|tool| tool.name.as_str()),
+            Some("second")
+        );
+        assert!(tools.get("unknown").is_none());
+
+        let first_page = tools.list(ListToolsRequest::new(None)).unwrap();
+        assert_eq!(names(&first_page), ["first", "second"]);
+        let next_page = tools
+            .list(ListToolsRequest::new(Some(ListToolsRequestParams {
+                cursor: first_page.next_cursor,
+            })))
+            .unwrap();
+        assert_eq!(names(&next_page), ["third"]);
+        assert!(next_page.next_cursor.is_none());
+    }
+}
@@ -1 +1,2 @@
+pub mod prompt_box;
 pub mod tool_box;
@@ -0,0 +1,139 @@
+#[macro_export]
+/// Generates an enum representing a set of mcp prompts with associated functionality, the way
+/// `tool_box!` does for tools.
+///
+/// **Note:** The macro assumes that prompt types provided are annotated with the mcp_prompt() macro,
+/// and derive `JsonSchema` and `serde::Deserialize`.
+///
+/// This macro creates:
+/// - An enum with the specified name containing variants for each mcp prompt
+/// - A `prompts()` function returning a vector of supported prompts
+/// - A `prompt_name()` method returning the name of the prompt of a variant
+/// - A `TryFrom<GetPromptRequestParams>` implementation for converting `prompts/get` requests to
+///   prompt instances, validating their arguments, see `validate_prompt_arguments()`. Unknown
+///   prompts and invalid arguments are rejected with an `invalid_params` error.
+///
+/// # Arguments
+/// * `$enum_name` - The name to give the generated enum
+/// * `[$($prompt),*]` - A comma-separated list of prompt types to include in the enum. Like the tools
+///   of `tool_box!`, prompts may be given with the path of their module, up to three modules deep,
+///   and preceded by `#[cfg(...)]` attributes.
+///
+/// # Example
+/// ```ignore
+/// prompt_box!(ReviewPrompts, [CodeReviewPrompt, SummarizePrompt]);
+/// // Creates:
+/// // pub enum ReviewPrompts {
+/// //     CodeReviewPrompt(CodeReviewPrompt),
+/// //     SummarizePrompt(SummarizePrompt),
+/// // }
+///
+/// async fn handle_list_prompts_request(&self, request: ListPromptsRequest, runtime: &dyn McpServer)
+///     -> Result<ListPromptsResult, RpcError> {
+///     Ok(ListPromptsResult { prompts: ReviewPrompts::prompts(), meta: None, next_cursor: None })
+/// }
+///
+/// async fn handle_get_prompt_request(&self, request: GetPromptRequest, runtime: &dyn McpServer)
+///     -> Result<GetPromptResult, RpcError> {
+///     match ReviewPrompts::try_from(request.params)? {
+///         ReviewPrompts::CodeReviewPrompt(prompt) => prompt.render(),
+///         ReviewPrompts::SummarizePrompt(prompt) => prompt.render(),
+///     }
+/// }
+/// ```
+macro_rules! prompt_box {
+    // Prompts are normalized into `{ [cfg predicates] [type path] variant }` entries, see `tool_box!`
+    (@parse $enum_name:ident [$($parsed:tt)*] []) => {
+        $crate::prompt_box!(@generate $enum_name [$($parsed)*]);
+    };
+    (@parse $enum_name:ident [$($parsed:tt)*]
+        [$(#[cfg $cfg:tt])* $prompt:ident $(, $($rest:tt)*)?]) => {
+        $crate::prompt_box!(@parse $enum_name [$($parsed)* { [$($cfg)*] [$prompt] $prompt }]
+            [$($($rest)*)?]);
+    };
+    (@parse $enum_name:ident [$($parsed:tt)*]
+        [$(#[cfg $cfg:tt])* $m1:ident :: $prompt:ident $(, $($rest:tt)*)?]) => {
+        $crate::prompt_box!(@parse $enum_name [$($parsed)* { [$($cfg)*] [$m1::$prompt] $prompt }]
+            [$($($rest)*)?]);
+    };
+    (@parse $enum_name:ident [$($parsed:tt)*]
+        [$(#[cfg $cfg:tt])* $m1:ident :: $m2:ident :: $prompt:ident $(, $($rest:tt)*)?]) => {
+        $crate::prompt_box!(@parse $enum_name [$($parsed)* { [$($cfg)*] [$m1::$m2::$prompt] $prompt }]
+            [$($($rest)*)?]);
+    };
+    (@parse $enum_name:ident [$($parsed:tt)*]
+        [$(#[cfg $cfg:tt])* $m1:ident :: $m2:ident :: $m3:ident :: $prompt:ident $(, $($rest:tt)*)?]) => {
+        $crate::prompt_box!(@parse $enum_name [$($parsed)* { [$($cfg)*] [$m1::$m2::$m3::$prompt] $prompt }]
+            [$($($rest)*)?]);
+    };
+
+    (@generate $enum_name:ident [$({ [$($cfg:tt)*] [$($path:tt)*] $prompt:ident })*]) => {
+        #[derive(Debug)]
+        #[allow(clippy::enum_variant_names)]
+        pub enum $enum_name {
+            $(
+                $(#[cfg $cfg])*
+                $prompt($($path)*),
+            )*
+        }
+
+        impl $enum_name {
+            /// Returns the name of the prompt as a String
+            pub fn prompt_name(&self) -> String {
+                match self {
+                    $(
+                        $(#[cfg $cfg])*
+                        $enum_name::$prompt(_) => $($path)*::prompt_name(),
+                    )*
+                }
+            }
+
+            /// Returns a vector containing instances of all supported prompts
+            #[allow(unused_mut)]
+            pub fn prompts() -> Vec<rust_mcp_schema::Prompt> {
+                let mut prompts = vec![];
+                $(
+                    $(#[cfg $cfg])*
+                    prompts.push($($path)*::prompt());
+                )*
+                prompts
+            }
+        }
+
+        impl TryFrom<rust_mcp_schema::GetPromptRequestParams> for $enum_name {
+            type Error = rust_mcp_schema::RpcError;
+
+            /// Attempts to convert a prompt request into the appropriate prompt variant.
+            /// Missing required arguments, unknown arguments and values that cannot be converted to
+            /// the type of their field are rejected with an `invalid_params` error.
+            fn try_from(value: rust_mcp_schema::GetPromptRequestParams) -> Result<Self, Self::Error> {
+                let arguments = value.arguments.unwrap_or_default();
+                match value.name {
+                    $(
+                        $(#[cfg $cfg])*
+                        name if name == $($path)*::prompt_name().as_str() => {
+                            let arguments = $crate::mcp_server::validate_prompt_arguments(
+                                &$($path)*::prompt(),
+                                &$($path)*::json_schema(),
+                                arguments,
+                            )?;
+                            Ok(Self::$prompt(serde_json::from_value(serde_json::Value::Object(arguments)).map_err(
+                                |error| rust_mcp_schema::RpcError::invalid_params()
+                                    .with_message(format!("Invalid prompt arguments: {}", error)),
+                            )?))
+                        }
+                    )*
+                    name => Err(<rust_mcp_schema::RpcError as $crate::mcp_server::RpcErrorExt>::prompt_not_found(name)),
+                }
+            }
+        }
+    };
+
+    // plain prompt names are generated directly, without parsing them one by one
+    ($enum_name:ident, [$($prompt:ident),* $(,)?]) => {
+        $crate::prompt_box!(@generate $enum_name [$({ [] [$prompt] $prompt })*]);
+    };
+    ($enum_name:ident, [$($prompts:tt)*]) => {
+        $crate::prompt_box!(@parse $enum_name [] [$($prompts)*]);
+    };
+}
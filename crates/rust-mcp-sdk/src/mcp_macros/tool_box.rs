@@ -6,11 +6,17 @@
 /// This macro creates:
 /// - An enum with the specified name containing variants for each mcp tool
 /// - A `tools()` function returning a vector of supported tools
-/// - A `TryFrom<CallToolRequestParams>` implementation for converting requests to tool instances
+/// - A `TryFrom<CallToolRequestParams>` implementation for converting requests to tool instances,
+///   validating their arguments against the input schema of the tool, see `validate_tool_arguments()`
+/// - Optionally, `call()` and `dry_run()` methods executing the tool, when the list of tools is followed
+///   by `call` (tools implement `McpTool`) or `async call` (tools implement `McpAsyncTool`)
 ///
 /// # Arguments
 /// * `$enum_name` - The name to give the generated enum
-/// * `[$($tool:ident),*]` - A comma-separated list of tool types to include in the enum
+/// * `[$($tool),*]` - A comma-separated list of tool types to include in the enum. Tools may be
+///   given with the path of their module, up to three modules deep, e.g. `net::FetchTool`, and
+///   preceded by `#[cfg(...)]` attributes, gating their variant and their dispatch. The variant of
+///   a tool is named after its type, without the path of its module.
 ///
 ///
 /// # Example
@@ -29,13 +35,141 @@
 /// // impl TryFrom<CallToolRequestParams> for FileSystemTools {
 /// //  //.......
 /// // }
+/// ```
+///
+/// # Example with feature-gated tools
+/// ```ignore
+/// tool_box!(ServerTools, [
+///     ReadFileTool,
+///     #[cfg(feature = "net")]
+///     net::FetchTool,
+///     #[cfg(all(feature = "net", unix))]
+///     net::unix::SocketTool,
+/// ]);
+/// // Creates `ServerTools::FetchTool(net::FetchTool)` and `ServerTools::SocketTool(net::unix::SocketTool)`
+/// // variants, and lists and dispatches the tools, only when the features are enabled.
+/// ```
+///
+/// # Example with dispatch
+/// ```ignore
+/// impl McpTool for SayHelloTool {
+///     fn call_tool(&self, runtime: &dyn McpServer) -> Result<CallToolResult, CallToolError> {
+///         Ok(CallToolResult::text_content(format!("Hello, {}!", self.name), None))
+///     }
+/// }
+/// // ...
+///
+/// tool_box!(GreetingTools, [SayHelloTool, SayGoodbyeTool], call);
+///
+/// // The handler then collapses to:
+/// GreetingTools::try_from(request.params)?.call(runtime)
+///
+/// // Tools supporting dry runs implement `dry_run()`, called instead of `call()` for dry runs:
+/// let dry_run = rust_mcp_sdk::dry_run::is_dry_run(&request.params);
+/// let tool = GreetingTools::try_from(request.params)?;
+/// if dry_run { tool.dry_run(runtime) } else { tool.call(runtime) }
+///
+/// // With tools implementing McpAsyncTool instead:
+/// tool_box!(GreetingTools, [SayHelloTool, SayGoodbyeTool], async call);
+/// GreetingTools::try_from(request.params)?.call(runtime).await
+/// ```
 macro_rules! tool_box {
-    ($enum_name:ident, [$($tool:ident),*]) => {
+    // Tools are normalized into `{ [cfg predicates] [type path] variant }` entries, so that the
+    // generating rules handle plain tool names, module paths and cfg attributes alike.
+    (@parse $enum_name:ident [$($parsed:tt)*] [] $($mode:tt)*) => {
+        $crate::tool_box!(@generate $enum_name [$($parsed)*] $($mode)*);
+    };
+    (@parse $enum_name:ident [$($parsed:tt)*]
+        [$(#[cfg $cfg:tt])* $tool:ident $(, $($rest:tt)*)?] $($mode:tt)*) => {
+        $crate::tool_box!(@parse $enum_name [$($parsed)* { [$($cfg)*] [$tool] $tool }]
+            [$($($rest)*)?] $($mode)*);
+    };
+    (@parse $enum_name:ident [$($parsed:tt)*]
+        [$(#[cfg $cfg:tt])* $m1:ident :: $tool:ident $(, $($rest:tt)*)?] $($mode:tt)*) => {
+        $crate::tool_box!(@parse $enum_name [$($parsed)* { [$($cfg)*] [$m1::$tool] $tool }]
+            [$($($rest)*)?] $($mode)*);
+    };
+    (@parse $enum_name:ident [$($parsed:tt)*]
+        [$(#[cfg $cfg:tt])* $m1:ident :: $m2:ident :: $tool:ident $(, $($rest:tt)*)?] $($mode:tt)*) => {
+        $crate::tool_box!(@parse $enum_name [$($parsed)* { [$($cfg)*] [$m1::$m2::$tool] $tool }]
+            [$($($rest)*)?] $($mode)*);
+    };
+    (@parse $enum_name:ident [$($parsed:tt)*]
+        [$(#[cfg $cfg:tt])* $m1:ident :: $m2:ident :: $m3:ident :: $tool:ident $(, $($rest:tt)*)?] $($mode:tt)*) => {
+        $crate::tool_box!(@parse $enum_name [$($parsed)* { [$($cfg)*] [$m1::$m2::$m3::$tool] $tool }]
+            [$($($rest)*)?] $($mode)*);
+    };
+
+    (@generate $enum_name:ident [$({ [$($cfg:tt)*] [$($path:tt)*] $tool:ident })*] call) => {
+        $crate::tool_box!(@generate $enum_name [$({ [$($cfg)*] [$($path)*] $tool })*]);
+
+        impl $enum_name {
+            /// Executes the tool, dispatching the call to the matching tool variant
+            pub fn call(
+                &self,
+                runtime: &dyn $crate::McpServer,
+            ) -> Result<rust_mcp_schema::CallToolResult, rust_mcp_schema::schema_utils::CallToolError> {
+                match self {
+                    $(
+                        $(#[cfg $cfg])*
+                        $enum_name::$tool(tool) => $crate::McpTool::call_tool(tool, runtime),
+                    )*
+                }
+            }
+
+            /// Executes a dry run of the tool, dispatching it to the matching tool variant
+            pub fn dry_run(
+                &self,
+                runtime: &dyn $crate::McpServer,
+            ) -> Result<rust_mcp_schema::CallToolResult, rust_mcp_schema::schema_utils::CallToolError> {
+                match self {
+                    $(
+                        $(#[cfg $cfg])*
+                        $enum_name::$tool(tool) => $crate::McpTool::dry_run(tool, runtime),
+                    )*
+                }
+            }
+        }
+    };
+    (@generate $enum_name:ident [$({ [$($cfg:tt)*] [$($path:tt)*] $tool:ident })*] async call) => {
+        $crate::tool_box!(@generate $enum_name [$({ [$($cfg)*] [$($path)*] $tool })*]);
+
+        impl $enum_name {
+            /// Executes the tool asynchronously, dispatching the call to the matching tool variant
+            pub async fn call(
+                &self,
+                runtime: &dyn $crate::McpServer,
+            ) -> Result<rust_mcp_schema::CallToolResult, rust_mcp_schema::schema_utils::CallToolError> {
+                match self {
+                    $(
+                        $(#[cfg $cfg])*
+                        $enum_name::$tool(tool) => $crate::McpAsyncTool::call_tool(tool, runtime).await,
+                    )*
+                }
+            }
+
+            /// Executes a dry run of the tool asynchronously, dispatching it to the matching tool variant
+            pub async fn dry_run(
+                &self,
+                runtime: &dyn $crate::McpServer,
+            ) -> Result<rust_mcp_schema::CallToolResult, rust_mcp_schema::schema_utils::CallToolError> {
+                match self {
+                    $(
+                        $(#[cfg $cfg])*
+                        $enum_name::$tool(tool) => $crate::McpAsyncTool::dry_run(tool, runtime).await,
+                    )*
+                }
+            }
+        }
+    };
+    (@generate $enum_name:ident [$({ [$($cfg:tt)*] [$($path:tt)*] $tool:ident })*]) => {
         #[derive(Debug)]
+        #[allow(clippy::enum_variant_names)]
         pub enum $enum_name {
             $(
                 // Just create enum variants for each tool
-                $tool($tool),
+                $(#[cfg $cfg])*
+                $tool($($path)*),
             )*
         }
 
@@ -44,18 +178,21 @@ macro_rules! tool_box {
             pub fn tool_name(&self) -> String {
                 match self {
                     $(
-                        $enum_name::$tool(_) => $tool::tool_name(),
+                        $(#[cfg $cfg])*
+                        $enum_name::$tool(_) => $($path)*::tool_name(),
                     )*
                 }
             }
 
             /// Returns a vector containing instances of all supported tools
+            #[allow(unused_mut)]
             pub fn tools() -> Vec<rust_mcp_schema::Tool> {
-                vec![
-                    $(
-                        $tool::tool(),
-                    )*
-                ]
+                let mut tools = vec![];
+                $(
+                    $(#[cfg $cfg])*
+                    tools.push($($path)*::tool());
+                )*
+                tools
             }
         }
 
@@ -65,14 +202,20 @@ macro_rules! tool_box {
         impl TryFrom<rust_mcp_schema::CallToolRequestParams> for $enum_name {
             type Error = rust_mcp_schema::schema_utils::CallToolError;
 
-            /// Attempts to convert a tool request into the appropriate tool variant
+            /// Attempts to convert a tool request into the appropriate tool variant,
+            /// ignoring the reserved `_meta` argument.
+            /// Arguments not matching the input schema of the tool are rejected with an
+            /// `InvalidToolArguments` error, reported to the model with the path of each invalid argument.
             fn try_from(value: rust_mcp_schema::CallToolRequestParams) -> Result<Self, Self::Error> {
-                let v = serde_json::to_value(value.arguments.unwrap())
-                .map_err(rust_mcp_schema::schema_utils::CallToolError::new)?;
+                let mut arguments = value.arguments.unwrap_or_default();
+                arguments.remove($crate::dry_run::META_ARGUMENT);
                     match value.name {
                         $(
-                            name if name == $tool::tool_name().as_str() => {
-                                Ok(Self::$tool(serde_json::from_value(v).map_err(rust_mcp_schema::schema_utils::CallToolError::new)?))
+                            $(#[cfg $cfg])*
+                            name if name == $($path)*::tool_name().as_str() => {
+                                $crate::mcp_server::validate_tool_arguments(&$($path)*::tool(), &arguments)
+                                    .map_err(rust_mcp_schema::schema_utils::CallToolError::new)?;
+                                Ok(Self::$tool(serde_json::from_value(serde_json::Value::Object(arguments)).map_err(rust_mcp_schema::schema_utils::CallToolError::new)?))
                             }
                         )*
                         _ => {
@@ -84,5 +227,13 @@ macro_rules! tool_box {
 
             }
         }
-    }
+    };
+
+    // plain tool names are generated directly, without parsing them one by one
+    ($enum_name:ident, [$($tool:ident),* $(,)?] $(, $($mode:tt)+)?) => {
+        $crate::tool_box!(@generate $enum_name [$({ [] [$tool] $tool })*] $($($mode)+)?);
+    };
+    ($enum_name:ident, [$($tools:tt)*] $(, $($mode:tt)+)?) => {
+        $crate::tool_box!(@parse $enum_name [] [$($tools)*] $($($mode)+)?);
+    };
 }
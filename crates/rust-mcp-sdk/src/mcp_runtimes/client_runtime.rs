@@ -1,22 +1,83 @@
+mod client_info_builder;
+mod client_middleware;
 pub mod mcp_client_runtime;
 pub mod mcp_client_runtime_core;
+mod metadata_cache;
+mod notification_streams;
+mod pending_notifications;
+mod retry_policy;
+mod scoped_client;
+mod server_profile;
 
 use async_trait::async_trait;
 use futures::future::join_all;
+use futures::stream::BoxStream;
 use futures::StreamExt;
-use rust_mcp_schema::schema_utils::{self, MessageFromClient, ServerMessage};
+use rust_mcp_schema::schema_utils::{
+    MessageFromClient, NotificationFromClient, NotificationFromServer, RequestFromClient,
+    RequestFromServer, ResultFromServer, ServerMessage,
+};
 use rust_mcp_schema::{
-    InitializeRequest, InitializeRequestParams, InitializeResult, InitializedNotification,
-    RpcError, ServerResult,
+    CallToolRequestParams, ClientRequest, InitializeRequest, InitializeRequestParams,
+    InitializeResult, InitializedNotification, LoggingMessageNotificationParams, ProgressToken,
+    ResourceUpdatedNotificationParams, Root, RpcError, ServerNotification, ServerRequest,
+    ServerResult, Tool,
 };
-use rust_mcp_transport::{IoStream, McpDispatch, MessageDispatcher, Transport};
-use std::sync::{Arc, RwLock};
+use rust_mcp_transport::{IoStream, McpDispatch, MessageDispatcher, ProcessStatus, Transport};
+use std::collections::{HashMap, VecDeque};
+use std::process::ExitStatus;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::{Arc, RwLock, Weak};
+use std::time::Duration;
 use tokio::io::{AsyncBufReadExt, BufReader};
-use tokio::sync::Mutex;
+use tokio::sync::{watch, Mutex};
 
+use crate::cancellation_token::CancellationToken;
 use crate::error::{McpSdkError, SdkResult};
+use crate::keep_alive::KeepAlive;
+use crate::mcp_handlers::sampling_handler::SamplingHandler;
 use crate::mcp_traits::mcp_client::McpClient;
 use crate::mcp_traits::mcp_handler::McpClientHandler;
+use crate::progress::Progress;
+use crate::request_meta::{take_sampling_request_meta, with_request_context, RequestContext};
+use crate::runtime_metrics::{DefaultMetrics, Metrics, MetricsSnapshot};
+use crate::sampling_depth::{
+    check_sampling_depth, take_sampling_request_depth, with_sampling_depth,
+    DEFAULT_MAX_SAMPLING_DEPTH,
+};
+use notification_streams::{same_progress_token, server_notification, NotificationStreams};
+use pending_notifications::PendingNotifications;
+
+pub use client_info_builder::ClientInfoBuilder;
+pub use client_middleware::{ClientMiddleware, Next, NotificationNext};
+pub use metadata_cache::{MetadataChanges, ServerMetadata, ServerMetadataCache};
+pub use notification_streams::{ListChanged, NOTIFICATION_STREAM_CAPACITY};
+pub use pending_notifications::MAX_PENDING_NOTIFICATIONS;
+pub use retry_policy::RetryPolicy;
+pub use scoped_client::ScopedClient;
+pub use server_profile::ServerProfile;
+
+/// How long to wait for the server process to exit once it closed its output
+const SERVER_PROCESS_EXIT_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// Number of the last lines written to stderr by the server reported when the initialization fails
+const STDERR_TAIL_LINES: usize = 20;
+
+/// How long to wait for the stderr of the server to be read once the initialization failed
+const STDERR_SETTLE_TIMEOUT: Duration = Duration::from_millis(200);
+
+// The state of the initialization with the server, see `ready()`
+#[derive(Clone, Default)]
+enum Initialization {
+    #[default]
+    Pending,
+    Completed,
+    // the error is not cloneable, its message is kept for all the callers of `ready()`
+    Failed {
+        message: String,
+        stderr: Vec<String>,
+    },
+}
 
 pub struct ClientRuntime {
     // The transport interface for handling messages between client and server
@@ -27,11 +88,56 @@ pub struct ClientRuntime {
     client_details: InitializeRequestParams,
     // Details about the connected server
     server_details: Arc<RwLock<Option<InitializeResult>>>,
+    // Roots exposed to the server, used to answer ListRootsRequests
+    roots: RwLock<Vec<Root>>,
+    // Fulfills sampling requests sent by the server
+    sampling_handler: RwLock<Option<Arc<dyn SamplingHandler>>>,
+    // Check outgoing messages against the capabilities of both parties
+    enforce_strict_capabilities: AtomicBool,
+    // Maximum nesting depth of the sampling requests accepted from the server
+    max_sampling_depth: AtomicU32,
+    // Policy used to retry failed requests
+    retry_policy: RwLock<RetryPolicy>,
+    // Timeout of the initialize request, the timeout of the transport if not set
+    initialize_timeout: RwLock<Option<Duration>>,
+    // The last lines written to stderr by the server, and whether its stderr was read to the end
+    stderr_tail: std::sync::Mutex<VecDeque<String>>,
+    stderr_closed: watch::Sender<bool>,
+    // Pings the server periodically, closing the connection once it stops answering, if enabled
+    keep_alive: RwLock<Option<KeepAlive>>,
+    // Records the sent requests and received notifications
+    metrics: RwLock<Arc<dyn Metrics>>,
+    // Middlewares wrapping the sent requests and notifications
+    middlewares: RwLock<Vec<Arc<dyn ClientMiddleware>>>,
+    // Cache of the metadata of the server, and the key of the server in the cache
+    metadata_cache: RwLock<Option<(Arc<ServerMetadataCache>, String)>>,
+    // Check the arguments of tool calls against the input schema of the tool before sending them
+    validate_tool_arguments: AtomicBool,
+    // The tools received from `tools/list` responses, by name, until the tools of the server change
+    tool_schemas: RwLock<HashMap<String, Tool>>,
+    // Verifies the signatures of the results and notifications received from the server
+    #[cfg(feature = "signing")]
+    signature_verifier: RwLock<Option<Arc<dyn crate::signing::SignatureVerifier>>>,
+    // Broadcasts the notifications of the server to the streams subscribed to them
+    notification_streams: NotificationStreams,
     message_sender: tokio::sync::RwLock<Option<MessageDispatcher<ServerMessage>>>,
     handlers: Mutex<Vec<tokio::task::JoinHandle<Result<(), McpSdkError>>>>,
+    // The task pinging the server, stopped when the client is shut down
+    keep_alive_task: Mutex<Option<tokio::task::JoinHandle<Result<(), McpSdkError>>>>,
+    // The error of the keep-alive that closed the connection, if any
+    keep_alive_error: std::sync::Mutex<Option<McpSdkError>>,
+    // The state of the initialization, see `ready()`
+    initialization: watch::Sender<Initialization>,
+    // Set once the tasks of the session ended, see `wait_for_exit()`
+    exited: watch::Sender<bool>,
 }
 
 impl ClientRuntime {
+    /// Returns a `ClientInfoBuilder` to create the client details passed to `create_client()`.
+    pub fn info<N: Into<String>, V: Into<String>>(name: N, version: V) -> ClientInfoBuilder {
+        ClientInfoBuilder::new(name, version)
+    }
+
     pub(crate) async fn set_message_sender(&self, sender: MessageDispatcher<ServerMessage>) {
         let mut lock = self.message_sender.write().await;
         *lock = Some(sender);
@@ -47,14 +153,344 @@ impl ClientRuntime {
             handler,
             client_details,
             server_details: Arc::new(RwLock::new(None)),
+            roots: RwLock::new(vec![]),
+            sampling_handler: RwLock::new(None),
+            enforce_strict_capabilities: AtomicBool::new(false),
+            max_sampling_depth: AtomicU32::new(DEFAULT_MAX_SAMPLING_DEPTH),
+            retry_policy: RwLock::new(RetryPolicy::default()),
+            initialize_timeout: RwLock::new(None),
+            stderr_tail: std::sync::Mutex::new(VecDeque::new()),
+            stderr_closed: watch::Sender::new(false),
+            keep_alive: RwLock::new(None),
+            metrics: RwLock::new(Arc::new(DefaultMetrics::new())),
+            middlewares: RwLock::new(vec![]),
+            metadata_cache: RwLock::new(None),
+            validate_tool_arguments: AtomicBool::new(false),
+            tool_schemas: RwLock::new(HashMap::new()),
+            #[cfg(feature = "signing")]
+            signature_verifier: RwLock::new(None),
+            notification_streams: NotificationStreams::new(),
             message_sender: tokio::sync::RwLock::new(None),
             handlers: Mutex::new(vec![]),
+            keep_alive_task: Mutex::new(None),
+            keep_alive_error: std::sync::Mutex::new(None),
+            initialization: watch::Sender::new(Initialization::Pending),
+            exited: watch::Sender::new(false),
+        }
+    }
+
+    /// Registers the handler fulfilling `sampling/createMessage` requests sent by the server, see `SamplingHandler`.
+    ///
+    /// It should be registered before the client is started, as servers may send sampling requests right after initialization.
+    pub fn set_sampling_handler(&self, sampling_handler: impl SamplingHandler) {
+        if let Ok(mut lock) = self.sampling_handler.write() {
+            *lock = Some(Arc::new(sampling_handler));
+        }
+    }
+
+    /// Enables or disables the strict enforcement of capabilities, disabled by default.
+    ///
+    /// When enabled, requests and notifications sent to the server fail without being sent
+    /// if the client or the server lacks the capability they require.
+    pub fn set_strict_capabilities(&self, enforce_strict_capabilities: bool) {
+        self.enforce_strict_capabilities
+            .store(enforce_strict_capabilities, Ordering::Relaxed);
+    }
+
+    /// Enables or disables the validation of the arguments of tool calls before they are sent,
+    /// disabled by default.
+    ///
+    /// When enabled, the arguments of `tools/call` requests are checked against the input schema
+    /// of the tool, as received by the last `list_tools()` or `list_all_tools()`, or from the
+    /// metadata cache of the client, and invalid calls fail with `McpSdkError::InvalidToolArguments`
+    /// without being sent. Calls of tools whose schema is unknown are sent as is. The schemas are
+    /// forgotten when the server notifies that its tools changed.
+    pub fn set_tool_argument_validation(&self, validate_tool_arguments: bool) {
+        self.validate_tool_arguments
+            .store(validate_tool_arguments, Ordering::Relaxed);
+    }
+
+    /// Sets the maximum nesting depth of the sampling requests accepted from the server,
+    /// `DEFAULT_MAX_SAMPLING_DEPTH` by default, see `sampling`.
+    ///
+    /// Deeper sampling requests are rejected without reaching the handler, breaking the loops where
+    /// the LLM keeps calling the tool requesting the sampling.
+    pub fn set_max_sampling_depth(&self, max_sampling_depth: u32) {
+        self.max_sampling_depth
+            .store(max_sampling_depth, Ordering::Relaxed);
+    }
+
+    /// Sets the verifier of the signatures of the results and notifications received from the server,
+    /// see `signing`.
+    ///
+    /// Requests then fail if their result is not signed by the server or its signature is invalid,
+    /// and the notifications that fail verification are reported to `handle_error()` of the handler
+    /// instead of being handled.
+    #[cfg(feature = "signing")]
+    pub fn set_signature_verifier(
+        &self,
+        signature_verifier: impl crate::signing::SignatureVerifier + 'static,
+    ) {
+        if let Ok(mut lock) = self.signature_verifier.write() {
+            *lock = Some(Arc::new(signature_verifier));
+        }
+    }
+
+    /// Waits for the server process to exit after the end of its output, returning its exit status.
+    ///
+    /// Returns `None` if no process was launched, or if it is still running after `SERVER_PROCESS_EXIT_TIMEOUT`.
+    async fn wait_for_server_process_exit(&self) -> Option<ExitStatus> {
+        tokio::time::timeout(SERVER_PROCESS_EXIT_TIMEOUT, async {
+            loop {
+                match self.transport.process_status().await? {
+                    ProcessStatus::Exited(status) => return Some(status),
+                    ProcessStatus::Running => tokio::time::sleep(Duration::from_millis(10)).await,
+                }
+            }
+        })
+        .await
+        .ok()
+        .flatten()
+    }
+
+    /// Returns a handle sharing the connection of the client, with its own timeout and middlewares, see `ScopedClient`.
+    pub fn scoped(self: &Arc<Self>) -> ScopedClient {
+        ScopedClient::new(Arc::clone(self))
+    }
+
+    /// Sets the timeout of the initialize request sent by `start()`, the timeout of the transport by default.
+    ///
+    /// A launched process that is not an MCP server never answers the initialize request, a shorter
+    /// timeout reports it sooner. The error returned by `start()` then includes the last lines the
+    /// process wrote to its stderr, see `McpSdkError::InitializeFailed`.
+    pub fn set_initialize_timeout(&self, initialize_timeout: Duration) {
+        if let Ok(mut lock) = self.initialize_timeout.write() {
+            *lock = Some(initialize_timeout);
+        }
+    }
+
+    /// Sets the policy used to retry failed requests, see `RetryPolicy`. Requests are not retried by default.
+    pub fn set_retry_policy(&self, retry_policy: RetryPolicy) {
+        if let Ok(mut lock) = self.retry_policy.write() {
+            *lock = retry_policy;
+        }
+    }
+
+    /// Enables the keep-alive of the connection, pinging the server periodically and closing the
+    /// connection once it stops answering, see `KeepAlive`. Disabled by default.
+    ///
+    /// It must be enabled before the client is started, the server is pinged once initialized.
+    pub fn set_keep_alive(&self, keep_alive: KeepAlive) {
+        if let Ok(mut lock) = self.keep_alive.write() {
+            *lock = Some(keep_alive);
+        }
+    }
+
+    // Pings the server while the connection is open, shutting down the transport once it stops answering.
+    // The client is referenced weakly, so that the task does not keep it alive.
+    fn spawn_keep_alive(
+        client: Weak<Self>,
+        keep_alive: KeepAlive,
+    ) -> tokio::task::JoinHandle<SdkResult<()>> {
+        tokio::spawn(async move {
+            let result = keep_alive
+                .run(
+                    || async {
+                        match client.upgrade() {
+                            Some(client) => client.ping().await,
+                            None => Err(McpSdkError::ConnectionClosed),
+                        }
+                    },
+                    || async {
+                        match client.upgrade() {
+                            Some(client) => !client.transport.is_shut_down().await,
+                            None => false,
+                        }
+                    },
+                )
+                .await;
+            if let Err(error) = result {
+                if let Some(client) = client.upgrade() {
+                    // recorded before the connection is closed, so that it is reported by `wait_for_exit()`
+                    if let Ok(mut keep_alive_error) = client.keep_alive_error.lock() {
+                        *keep_alive_error = Some(error);
+                    }
+                    client.transport.shut_down().await?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    /// Sets the metrics recording the requests sent by the client and the notifications it receives,
+    /// `DefaultMetrics` by default.
+    pub fn set_metrics(&self, metrics: impl Metrics) {
+        if let Ok(mut lock) = self.metrics.write() {
+            *lock = Arc::new(metrics);
+        }
+    }
+
+    /// Returns a snapshot of the metrics of the client, see `Metrics::snapshot()`.
+    pub fn metrics(&self) -> MetricsSnapshot {
+        self.metrics_recorder()
+            .map(|metrics| metrics.snapshot())
+            .unwrap_or_default()
+    }
+
+    /// Adds a middleware wrapping the requests and notifications sent by the client, see `ClientMiddleware`.
+    /// Middlewares run in the order they are added.
+    ///
+    /// Middlewares should be added before the client is started, to also wrap the initialization.
+    pub fn add_middleware(&self, middleware: impl ClientMiddleware) {
+        if let Ok(mut lock) = self.middlewares.write() {
+            lock.push(Arc::new(middleware));
         }
     }
 
+    // Returns the middlewares of the client, in the order they run
+    pub(crate) fn middlewares(&self) -> Vec<Arc<dyn ClientMiddleware>> {
+        self.middlewares
+            .read()
+            .map(|middlewares| middlewares.clone())
+            .unwrap_or_default()
+    }
+
+    /// Sets the cache storing the metadata of the server across runs under `key`, an identity
+    /// of the server chosen by the host such as the command launching it, see `ServerMetadataCache`.
+    pub fn set_metadata_cache<K: Into<String>>(&self, cache: Arc<ServerMetadataCache>, key: K) {
+        if let Ok(mut lock) = self.metadata_cache.write() {
+            *lock = Some((cache, key.into()));
+        }
+    }
+
+    /// Returns the metadata of the server stored in the metadata cache of the client, if any.
+    ///
+    /// It is available before the client is started, and is the last known metadata of the server
+    /// until it is reconciled with `reconcile_metadata_cache()`.
+    pub fn cached_server_metadata(&self) -> Option<ServerMetadata> {
+        let (cache, key) = self.metadata_cache.read().ok()?.clone()?;
+        cache.get(&key)
+    }
+
+    /// Updates the metadata cache of the client with the live metadata of the server and saves it,
+    /// returning what changed since it was cached. The tools of the server are listed if it provides tools.
+    ///
+    /// It should be called once the client is started, and again whenever the server notifies that its tools changed.
+    /// Nothing is done if no metadata cache is set.
+    pub async fn reconcile_metadata_cache(&self) -> SdkResult<MetadataChanges> {
+        let Some((cache, key)) = self
+            .metadata_cache
+            .read()
+            .ok()
+            .and_then(|lock| lock.clone())
+        else {
+            return Ok(MetadataChanges::default());
+        };
+        let server_details = self.server_info().ok_or(McpSdkError::NotInitialized)?;
+        let tools = match server_details.capabilities.tools {
+            Some(_) => Some(self.list_all_tools().await?),
+            None => None,
+        };
+
+        let metadata = ServerMetadata::new(server_details, tools);
+        let changes = metadata.changes_from(cache.get(&key).as_ref());
+        cache.insert(key, metadata);
+        cache.save().await?;
+        Ok(changes)
+    }
+
+    /// Returns a stream of all the notifications received from the server, including custom ones.
+    ///
+    /// Notification streams let applications consume the notifications of the server without
+    /// implementing them in the client handler, which still receives them. A stream receives the
+    /// notifications that arrive after it is created, and ends when the connection is closed.
+    /// Streams lagging more than `NOTIFICATION_STREAM_CAPACITY` notifications behind miss the oldest ones.
+    pub fn subscribe_notifications(&self) -> BoxStream<'static, NotificationFromServer> {
+        self.notification_streams.subscribe(Some)
+    }
+
+    /// Returns a stream of the log messages sent by the server, see `subscribe_notifications()`.
+    pub fn subscribe_logging(&self) -> BoxStream<'static, LoggingMessageNotificationParams> {
+        self.notification_streams.subscribe(|notification| {
+            match server_notification(notification)? {
+                ServerNotification::LoggingMessageNotification(notification) => {
+                    Some(notification.params)
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Returns a stream of the progress of the request associated with `progress_token`,
+    /// see `subscribe_notifications()`.
+    pub fn subscribe_progress(
+        &self,
+        progress_token: ProgressToken,
+    ) -> BoxStream<'static, Progress> {
+        self.notification_streams.subscribe(move |notification| {
+            match server_notification(notification)? {
+                ServerNotification::ProgressNotification(notification)
+                    if same_progress_token(
+                        &notification.params.progress_token,
+                        &progress_token,
+                    ) =>
+                {
+                    Some(Progress::from(notification.params))
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Returns a stream of the updates of the resources the client subscribed to,
+    /// see `subscribe_notifications()`.
+    pub fn subscribe_resource_updated(
+        &self,
+    ) -> BoxStream<'static, ResourceUpdatedNotificationParams> {
+        self.notification_streams.subscribe(|notification| {
+            match server_notification(notification)? {
+                ServerNotification::ResourceUpdatedNotification(notification) => {
+                    Some(notification.params)
+                }
+                _ => None,
+            }
+        })
+    }
+
+    /// Returns a stream of the lists of the server that changed, see `subscribe_notifications()`.
+    pub fn subscribe_list_changed(&self) -> BoxStream<'static, ListChanged> {
+        self.notification_streams.subscribe(|notification| {
+            match server_notification(notification)? {
+                ServerNotification::ToolListChangedNotification(_) => Some(ListChanged::Tools),
+                ServerNotification::PromptListChangedNotification(_) => Some(ListChanged::Prompts),
+                ServerNotification::ResourceListChangedNotification(_) => {
+                    Some(ListChanged::Resources)
+                }
+                _ => None,
+            }
+        })
+    }
+
     async fn initialize_request(&self) -> SdkResult<()> {
         let request = InitializeRequest::new(self.client_details.clone());
-        let result: ServerResult = self.request(request.into()).await?.try_into()?;
+        let initialize_timeout = self.initialize_timeout.read().ok().and_then(|lock| *lock);
+        let result = match initialize_timeout {
+            Some(initialize_timeout) => {
+                tokio::time::timeout(initialize_timeout, self.request(request.into()))
+                    .await
+                    .unwrap_or_else(|_| {
+                        Err(McpSdkError::RequestTimeout {
+                            id: None,
+                            msec: initialize_timeout.as_millis() as u64,
+                        })
+                    })
+            }
+            None => self.request(request.into()).await,
+        };
+        let result: ServerResult = match result {
+            Ok(result) => result.try_into()?,
+            Err(error) => return Err(self.initialize_failure(error).await),
+        };
 
         if let ServerResult::InitializeResult(initialize_result) = result {
             // store server details
@@ -69,10 +505,183 @@ impl ClientRuntime {
         }
         Ok(())
     }
+
+    // Reports a failed initialization along with the last lines written to stderr by the server,
+    // once they are read, as the server process may have just exited
+    async fn initialize_failure(&self, error: McpSdkError) -> McpSdkError {
+        let _ = tokio::time::timeout(
+            STDERR_SETTLE_TIMEOUT,
+            self.stderr_closed.subscribe().wait_for(|closed| *closed),
+        )
+        .await;
+        let stderr = self
+            .stderr_tail
+            .lock()
+            .map(|stderr_tail| stderr_tail.iter().cloned().collect())
+            .unwrap_or_default();
+        McpSdkError::InitializeFailed {
+            source: Box::new(error),
+            stderr,
+        }
+    }
+
+    // Records the failure of the initialization, reported by `ready()`
+    fn set_initialization_failed(&self, error: &McpSdkError) {
+        let (message, stderr) = match error {
+            McpSdkError::InitializeFailed { source, stderr } => {
+                (source.to_string(), stderr.clone())
+            }
+            error => (error.to_string(), vec![]),
+        };
+        self.initialization
+            .send_replace(Initialization::Failed { message, stderr });
+    }
+
+    // Records a line written to stderr by the server, keeping the last `STDERR_TAIL_LINES`
+    fn record_stderr_line(&self, line: &str) {
+        if let Ok(mut stderr_tail) = self.stderr_tail.lock() {
+            if stderr_tail.len() == STDERR_TAIL_LINES {
+                stderr_tail.pop_front();
+            }
+            stderr_tail.push_back(line.to_string());
+        }
+    }
+
+    // Applies `update` to the roots store, returning the value produced by it
+    fn update_roots<T>(&self, update: impl FnOnce(&mut Vec<Root>) -> T) -> SdkResult<T> {
+        match self.roots.write() {
+            Ok(mut roots) => Ok(update(&mut roots)),
+            // Failed to acquire write lock, likely due to PoisonError from a thread panic.
+            Err(_) => Err(RpcError::internal_error()
+                .with_message("Internal Error: Failed to acquire write lock.".to_string())
+                .into()),
+        }
+    }
+
+    // Sends a request through a middleware chain, checking the arguments of tool calls before,
+    // and recording the tools listed by the server after
+    pub(crate) async fn request_through(
+        &self,
+        request: RequestFromClient,
+        next: Next<'_>,
+    ) -> SdkResult<ResultFromServer> {
+        if let RequestFromClient::ClientRequest(ClientRequest::CallToolRequest(request)) = &request
+        {
+            self.check_tool_arguments(&request.params)?;
+        }
+        let result = next.run(request).await;
+        if let Ok(ResultFromServer::ServerResult(ServerResult::ListToolsResult(result))) = &result {
+            if let Ok(mut tool_schemas) = self.tool_schemas.write() {
+                for tool in &result.tools {
+                    tool_schemas.insert(tool.name.clone(), tool.clone());
+                }
+            }
+        }
+        result
+    }
+
+    // Checks the arguments of a tool call against the input schema of the tool, if enabled and known
+    fn check_tool_arguments(&self, params: &CallToolRequestParams) -> SdkResult<()> {
+        if !self.validate_tool_arguments.load(Ordering::Relaxed) {
+            return Ok(());
+        }
+        let tool = self
+            .tool_schemas
+            .read()
+            .ok()
+            .and_then(|tool_schemas| tool_schemas.get(&params.name).cloned())
+            .or_else(|| {
+                self.cached_server_metadata()?
+                    .tools?
+                    .into_iter()
+                    .find(|tool| tool.name == params.name)
+            });
+        #[cfg_attr(not(feature = "encryption"), allow(unused_mut))]
+        let Some(mut tool) = tool
+        else {
+            return Ok(());
+        };
+        let arguments = params.arguments.clone().unwrap_or_default();
+
+        // encrypted values, see `encryption`, can only be checked by the server once decrypted
+        #[cfg(feature = "encryption")]
+        if let Some(properties) = tool.input_schema.properties.as_mut() {
+            properties.retain(|name, _| {
+                !arguments
+                    .get(name)
+                    .is_some_and(crate::field_encryption::is_encrypted_value)
+            });
+        }
+
+        crate::tool_input_validation::validate_tool_arguments(&tool, &arguments)?;
+        Ok(())
+    }
+
+    // Notifies the server about a change in roots, if the client is initialized and supports it
+    async fn notify_roots_changed(&self) -> SdkResult<()> {
+        if self.is_initialized() && self.client_supports_roots_list_changed() {
+            self.send_roots_list_changed(None).await?;
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl McpClient for ClientRuntime {
+    fn retry_policy(&self) -> RetryPolicy {
+        self.retry_policy
+            .read()
+            .map(|retry_policy| *retry_policy)
+            .unwrap_or_default()
+    }
+
+    fn enforce_strict_capabilities(&self) -> bool {
+        self.enforce_strict_capabilities.load(Ordering::Relaxed)
+    }
+
+    fn max_sampling_depth(&self) -> u32 {
+        self.max_sampling_depth.load(Ordering::Relaxed)
+    }
+
+    #[cfg(feature = "signing")]
+    fn signature_verifier(&self) -> Option<Arc<dyn crate::signing::SignatureVerifier>> {
+        self.signature_verifier
+            .read()
+            .ok()
+            .and_then(|lock| lock.clone())
+    }
+
+    fn metrics_recorder(&self) -> Option<Arc<dyn Metrics>> {
+        self.metrics.read().ok().map(|metrics| Arc::clone(&metrics))
+    }
+
+    /// Sends a request through the middlewares of the client, without retrying it if it fails.
+    async fn request_once(&self, request: RequestFromClient) -> SdkResult<ResultFromServer> {
+        let middlewares = self.middlewares();
+        self.request_through(request, Next::new(self, &middlewares, None))
+            .await
+    }
+
+    /// Sends a request through the middlewares of the client like `request_once()`, deduplicating
+    /// its sending at the end of the chain, see `McpClient::request_deduplicated()`.
+    async fn request_deduplicated(
+        &self,
+        request: RequestFromClient,
+        idempotency_token: &str,
+    ) -> SdkResult<ResultFromServer> {
+        let middlewares = self.middlewares();
+        let next = Next::new(self, &middlewares, None).with_idempotency_token(idempotency_token);
+        self.request_through(request, next).await
+    }
+
+    /// Sends a notification through the middlewares of the client.
+    async fn send_notification(&self, notification: NotificationFromClient) -> SdkResult<()> {
+        let middlewares = self.middlewares();
+        NotificationNext::new(self, &middlewares)
+            .run(notification)
+            .await
+    }
+
     async fn sender(&self) -> &tokio::sync::RwLock<Option<MessageDispatcher<ServerMessage>>>
     where
         MessageDispatcher<ServerMessage>: McpDispatch<ServerMessage, MessageFromClient>,
@@ -81,52 +690,163 @@ impl McpClient for ClientRuntime {
     }
 
     async fn start(self: Arc<Self>) -> SdkResult<()> {
-        let (mut stream, sender, error_io) = self.transport.start().await?;
+        let (mut stream, sender, error_io) = match self.transport.start().await {
+            Ok(started) => started,
+            Err(error) => {
+                let error = McpSdkError::from(error);
+                self.set_initialization_failed(&error);
+                return Err(error);
+            }
+        };
         self.set_message_sender(sender).await;
 
         let self_clone = Arc::clone(&self);
-        self_clone.initialize_request().await?;
-
         let self_clone_err = Arc::clone(&self);
 
+        // Messages are processed while the initialization is in progress, so that the stream
+        // does not fill up with messages the server sends right away, such as log messages.
+        // Notifications are buffered until the initialization completes, then delivered to the handler,
+        // up to `MAX_PENDING_NOTIFICATIONS` of them.
+        let (initialized_tx, mut initialized_rx) = watch::channel(false);
+
         let main_task = tokio::spawn(async move {
             let sender = self_clone.sender().await.read().await;
-            let sender = sender.as_ref().ok_or(crate::error::McpSdkError::SdkError(
-                schema_utils::SdkError::connection_closed(),
-            ))?;
-
-            while let Some(mcp_message) = stream.next().await {
-                let self_ref = &*self_clone;
-
-                match mcp_message {
-                    ServerMessage::Request(jsonrpc_request) => {
-                        let result = self_ref
-                            .handler
-                            .handle_request(jsonrpc_request.request, self_ref)
-                            .await;
-
-                        // create a response to send back to the server
-                        let response: MessageFromClient = match result {
-                            Ok(success_value) => success_value.into(),
-                            Err(error_value) => MessageFromClient::Error(error_value),
-                        };
-                        // send the response back with corresponding request id
-                        sender.send(response, Some(jsonrpc_request.id)).await?;
-                    }
-                    ServerMessage::Notification(jsonrpc_notification) => {
-                        self_ref
-                            .handler
-                            .handle_notification(jsonrpc_notification.notification, self_ref)
-                            .await?;
+            let sender = sender.as_ref().ok_or(McpSdkError::ConnectionClosed)?;
+            let self_ref = &*self_clone;
+
+            // `None` once the initialization completed
+            let mut pending_notifications =
+                Some(PendingNotifications::new(MAX_PENDING_NOTIFICATIONS));
+
+            loop {
+                tokio::select! {
+                    // deliver buffered notifications before processing any new message
+                    biased;
+
+                    changed = initialized_rx.changed(), if pending_notifications.is_some() => {
+                        let notifications = pending_notifications.take();
+                        // the initialization failed if the sender was dropped without a change
+                        if let (Ok(()), Some(notifications)) = (changed, notifications) {
+                            for notification in notifications.into_notifications() {
+                                self_ref.handler.handle_notification(notification, self_ref).await?;
+                            }
+                        }
                     }
-                    ServerMessage::Error(jsonrpc_error) => {
-                        self_ref
-                            .handler
-                            .handle_error(jsonrpc_error.error, self_ref)
-                            .await?;
+
+                    mcp_message = stream.next() => {
+                        let Some(mcp_message) = mcp_message else {
+                            break;
+                        };
+
+                        match mcp_message {
+                            ServerMessage::Request(mut jsonrpc_request) => {
+                                // sampling requests nested too deep are rejected, the tools called
+                                // while fulfilling the others carry their depth,
+                                // the id, `_meta` and progress token of requests are available to the handler
+                                let (sampling_depth, request_meta) = match &mut jsonrpc_request.request {
+                                    RequestFromServer::ServerRequest(
+                                        ServerRequest::CreateMessageRequest(create_message_request),
+                                    ) => (
+                                        take_sampling_request_depth(&mut create_message_request.params),
+                                        take_sampling_request_meta(&mut create_message_request.params),
+                                    ),
+                                    _ => (0, Default::default()),
+                                };
+                                let result = match check_sampling_depth(
+                                    sampling_depth,
+                                    self_ref.max_sampling_depth(),
+                                ) {
+                                    Ok(()) => {
+                                        let context = RequestContext::new(
+                                            jsonrpc_request.id.clone(),
+                                            request_meta,
+                                            CancellationToken::new(),
+                                            self_ref.server_info().map(|server_info| server_info.server_info),
+                                        );
+                                        with_request_context(
+                                            context,
+                                            with_sampling_depth(
+                                                sampling_depth,
+                                                self_ref
+                                                    .handler
+                                                    .handle_request(jsonrpc_request.request, self_ref),
+                                            ),
+                                        )
+                                        .await
+                                    }
+                                    Err(error) => Err(RpcError::invalid_request()
+                                        .with_message(error.to_string())),
+                                };
+
+                                // create a response to send back to the server
+                                let response: MessageFromClient = match result {
+                                    Ok(success_value) => success_value.into(),
+                                    Err(error_value) => MessageFromClient::Error(error_value),
+                                };
+                                // send the response back with corresponding request id
+                                sender.send(response, Some(jsonrpc_request.id)).await?;
+                            }
+                            ServerMessage::Notification(jsonrpc_notification) => {
+                                if let Some(metrics) = self_ref.metrics_recorder() {
+                                    metrics.notification_received(&jsonrpc_notification.method);
+                                }
+                                // notifications that fail verification are reported instead of being handled
+                                #[cfg(feature = "signing")]
+                                if let Some(verifier) = self_ref.signature_verifier() {
+                                    if let Err(error) = crate::signing::verify_notification(
+                                        &jsonrpc_notification.notification,
+                                        verifier.as_ref(),
+                                    ) {
+                                        self_ref.handler.handle_error(error, self_ref).await?;
+                                        continue;
+                                    }
+                                }
+                                self_ref
+                                    .notification_streams
+                                    .publish(&jsonrpc_notification.notification);
+                                // the tool schemas are stale once the tools of the server changed
+                                if matches!(
+                                    &jsonrpc_notification.notification,
+                                    NotificationFromServer::ServerNotification(
+                                        ServerNotification::ToolListChangedNotification(_)
+                                    )
+                                ) {
+                                    if let Ok(mut tool_schemas) = self_ref.tool_schemas.write() {
+                                        tool_schemas.clear();
+                                    }
+                                }
+                                match pending_notifications.as_mut() {
+                                    Some(pending) => pending.push(jsonrpc_notification.notification),
+                                    None => {
+                                        self_ref
+                                            .handler
+                                            .handle_notification(jsonrpc_notification.notification, self_ref)
+                                            .await?;
+                                    }
+                                }
+                            }
+                            ServerMessage::Error(jsonrpc_error) => {
+                                self_ref
+                                    .handler
+                                    .handle_error(jsonrpc_error.error, self_ref)
+                                    .await?;
+                            }
+                            // The response is the result of a request, it is processed at the transport level.
+                            ServerMessage::Response(_) => {}
+                        }
                     }
-                    // The response is the result of a request, it is processed at the transport level.
-                    ServerMessage::Response(_) => {}
+                }
+            }
+
+            self_ref.notification_streams.close();
+
+            // the server closed the connection, report the exit of its process unless the client was shut down
+            if !self_ref.transport.is_shut_down().await {
+                if let Some(status) = self_ref.wait_for_server_process_exit().await {
+                    self_ref
+                        .handler
+                        .on_server_process_exit(status, self_ref)
+                        .await;
                 }
             }
             Ok::<(), McpSdkError>(())
@@ -147,6 +867,7 @@ impl McpClient for ClientRuntime {
                         line = reader.next_line() =>{
                             match line {
                                 Ok(Some(error_message)) => {
+                                    self_ref.record_stderr_line(&error_message);
                                     self_ref
                                         .handler
                                         .handle_process_error(error_message, self_ref)
@@ -165,12 +886,28 @@ impl McpClient for ClientRuntime {
                     }
                 }
             }
+            self_ref.stderr_closed.send_replace(true);
             Ok::<(), McpSdkError>(())
         });
 
         let mut lock = self.handlers.lock().await;
         lock.push(main_task);
         lock.push(err_task);
+        drop(lock);
+
+        if let Err(error) = self.initialize_request().await {
+            self.set_initialization_failed(&error);
+            return Err(error);
+        }
+        // the handler is ready, deliver the notifications received so far
+        let _ = initialized_tx.send(true);
+        self.initialization.send_replace(Initialization::Completed);
+
+        let keep_alive = self.keep_alive.read().ok().and_then(|lock| lock.clone());
+        if let Some(keep_alive) = keep_alive {
+            let task = Self::spawn_keep_alive(Arc::downgrade(&self), keep_alive);
+            *self.keep_alive_task.lock().await = Some(task);
+        }
 
         Ok(())
     }
@@ -199,17 +936,124 @@ impl McpClient for ClientRuntime {
         }
     }
 
+    fn sampling_handler(&self) -> Option<Arc<dyn SamplingHandler>> {
+        if let Ok(sampling_handler) = self.sampling_handler.read() {
+            sampling_handler.clone()
+        } else {
+            // Failed to acquire read lock, likely due to PoisonError from a thread panic. Returning None.
+            None
+        }
+    }
+
+    fn roots(&self) -> Vec<Root> {
+        if let Ok(roots) = self.roots.read() {
+            roots.clone()
+        } else {
+            // Failed to acquire read lock, likely due to PoisonError from a thread panic. Returning an empty list.
+            vec![]
+        }
+    }
+
+    async fn set_roots(&self, roots: Vec<Root>) -> SdkResult<()> {
+        self.update_roots(|current| *current = roots)?;
+        self.notify_roots_changed().await
+    }
+
+    async fn add_root(&self, root: Root) -> SdkResult<()> {
+        self.update_roots(|roots| {
+            match roots.iter_mut().find(|existing| existing.uri == root.uri) {
+                Some(existing) => *existing = root,
+                None => roots.push(root),
+            }
+        })?;
+        self.notify_roots_changed().await
+    }
+
+    async fn remove_root(&self, uri: &str) -> SdkResult<bool> {
+        let removed = self.update_roots(|roots| {
+            let len = roots.len();
+            roots.retain(|root| root.uri != uri);
+            roots.len() != len
+        })?;
+        if removed {
+            self.notify_roots_changed().await?;
+        }
+        Ok(removed)
+    }
+
     async fn is_shut_down(&self) -> bool {
         self.transport.is_shut_down().await
     }
+
+    async fn server_process_status(&self) -> Option<ProcessStatus> {
+        self.transport.process_status().await
+    }
     async fn shut_down(&self) -> SdkResult<()> {
+        if let Some(keep_alive_task) = self.keep_alive_task.lock().await.take() {
+            keep_alive_task.abort();
+        }
         self.transport.shut_down().await?;
 
         // wait for tasks
         let mut tasks_lock = self.handlers.lock().await;
         let join_handlers: Vec<_> = tasks_lock.drain(..).collect();
-        join_all(join_handlers).await;
+        drop(tasks_lock);
+        if !join_handlers.is_empty() {
+            join_all(join_handlers).await;
+            self.exited.send_replace(true);
+        }
 
         Ok(())
     }
+
+    async fn ready(&self) -> SdkResult<()> {
+        let mut initialization = self.initialization.subscribe();
+        let initialization = initialization
+            .wait_for(|initialization| !matches!(initialization, Initialization::Pending))
+            .await
+            .map(|initialization| initialization.clone());
+        match initialization {
+            Ok(Initialization::Failed { message, stderr }) => Err(McpSdkError::InitializeFailed {
+                source: Box::new(McpSdkError::AnyError(message.into())),
+                stderr,
+            }),
+            Ok(_) => Ok(()),
+            Err(_) => Err(McpSdkError::ConnectionClosed),
+        }
+    }
+
+    async fn wait_for_exit(&self) -> SdkResult<()> {
+        let join_handlers: Vec<_> = self.handlers.lock().await.drain(..).collect();
+        if join_handlers.is_empty() {
+            // the tasks are awaited by another caller, or the client was not started
+            if self.message_sender.read().await.is_some() {
+                let _ = self.exited.subscribe().wait_for(|exited| *exited).await;
+            }
+            return Ok(());
+        }
+
+        let results = join_all(join_handlers).await;
+        // the connection is closed, the keep-alive is no longer needed
+        if let Some(keep_alive_task) = self.keep_alive_task.lock().await.take() {
+            keep_alive_task.abort();
+        }
+        self.exited.send_replace(true);
+
+        for result in results {
+            match result {
+                Ok(result) => result?,
+                // a task panicked
+                Err(join_error) if !join_error.is_cancelled() => {
+                    return Err(McpSdkError::AnyError(Box::new(join_error)))
+                }
+                Err(_) => {}
+            }
+        }
+        let keep_alive_error = self
+            .keep_alive_error
+            .lock()
+            .ok()
+            .and_then(|mut keep_alive_error| keep_alive_error.take());
+        keep_alive_error.map_or(Ok(()), Err)
+    }
 }
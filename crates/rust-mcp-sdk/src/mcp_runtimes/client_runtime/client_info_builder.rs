@@ -0,0 +1,92 @@
+use std::collections::HashMap;
+
+use rust_mcp_schema::{
+    ClientCapabilities, ClientCapabilitiesRoots, Implementation, InitializeRequestParams,
+    LATEST_PROTOCOL_VERSION,
+};
+use serde_json::{Map, Value};
+
+/// Builds the `InitializeRequestParams` describing a client, with the latest protocol version filled in.
+///
+/// # Example
+/// ```ignore
+/// let client_details = ClientRuntime::info("simple-rust-mcp-client", "0.1.0")
+///     .with_sampling()
+///     .with_roots(true)
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ClientInfoBuilder {
+    client_info: Implementation,
+    capabilities: ClientCapabilities,
+    protocol_version: String,
+}
+
+impl ClientInfoBuilder {
+    /// Creates a builder for a client with the given name and version, and no capabilities.
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, version: V) -> Self {
+        Self {
+            client_info: Implementation {
+                name: name.into(),
+                version: version.into(),
+            },
+            capabilities: ClientCapabilities::default(),
+            protocol_version: LATEST_PROTOCOL_VERSION.to_string(),
+        }
+    }
+
+    /// Advertises support for sampling, allowing servers to send `sampling/createMessage` requests.
+    pub fn with_sampling(mut self) -> Self {
+        self.capabilities.sampling = Some(Map::new());
+        self
+    }
+
+    /// Advertises support for roots. `list_changed` indicates whether the client sends
+    /// notifications when the list of roots changes.
+    pub fn with_roots(mut self, list_changed: bool) -> Self {
+        self.capabilities.roots = Some(ClientCapabilitiesRoots {
+            list_changed: Some(list_changed),
+        });
+        self
+    }
+
+    /// Advertises a non-standard, experimental capability.
+    pub fn with_experimental<N: Into<String>>(
+        mut self,
+        name: N,
+        value: Map<String, Value>,
+    ) -> Self {
+        self.capabilities
+            .experimental
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), value);
+        self
+    }
+
+    /// Replaces all capabilities of the client.
+    pub fn with_capabilities(mut self, capabilities: ClientCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Overrides the protocol version, `LATEST_PROTOCOL_VERSION` by default.
+    pub fn with_protocol_version<T: Into<String>>(mut self, protocol_version: T) -> Self {
+        self.protocol_version = protocol_version.into();
+        self
+    }
+
+    /// Returns the `InitializeRequestParams` to pass to `create_client()`.
+    pub fn build(self) -> InitializeRequestParams {
+        InitializeRequestParams {
+            capabilities: self.capabilities,
+            client_info: self.client_info,
+            protocol_version: self.protocol_version,
+        }
+    }
+}
+
+impl From<ClientInfoBuilder> for InitializeRequestParams {
+    fn from(builder: ClientInfoBuilder) -> Self {
+        builder.build()
+    }
+}
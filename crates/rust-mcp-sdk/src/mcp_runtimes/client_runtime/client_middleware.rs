@@ -0,0 +1,137 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use rust_mcp_schema::schema_utils::{NotificationFromClient, RequestFromClient, ResultFromServer};
+
+use crate::error::SdkResult;
+use crate::mcp_traits::mcp_client::{send_notification, send_request};
+
+use super::ClientRuntime;
+
+/// A middleware wrapping the requests and notifications sent by a `ClientRuntime` or a `ScopedClient`,
+/// e.g. to add metadata to the `_meta` of requests, to log them or to enforce per-method policies.
+///
+/// A middleware can inspect or modify the request, pass it on with `next.run(request)` and inspect
+/// or modify the result, or return a result without passing the request on. Notifications are
+/// passed on unchanged by default, override `handle_notification()` to intercept them.
+///
+/// Middlewares wrap each attempt of a request retried according to the `RetryPolicy` of the client.
+/// Requests sent with `request_deduplicated()` go through them too, only their sending at the end
+/// of the chain is deduplicated.
+///
+/// # Example
+/// ```ignore
+/// struct LogRequests;
+///
+/// #[async_trait]
+/// impl ClientMiddleware for LogRequests {
+///     async fn handle(&self, request: RequestFromClient, next: Next<'_>) -> SdkResult<ResultFromServer> {
+///         let method = request.method().to_string();
+///         let started = Instant::now();
+///         let result = next.run(request).await;
+///         println!("{} completed in {:?}", method, started.elapsed());
+///         result
+///     }
+/// }
+///
+/// client.add_middleware(LogRequests);
+/// ```
+#[async_trait]
+pub trait ClientMiddleware: Send + Sync + 'static {
+    async fn handle(
+        &self,
+        request: RequestFromClient,
+        next: Next<'_>,
+    ) -> SdkResult<ResultFromServer>;
+
+    /// Intercepts a notification: passes it on with `next.run(notification)`, or drops it by
+    /// returning without doing so.
+    async fn handle_notification(
+        &self,
+        notification: NotificationFromClient,
+        next: NotificationNext<'_>,
+    ) -> SdkResult<()> {
+        next.run(notification).await
+    }
+}
+
+/// The rest of the middleware chain of a request, ending with sending it to the server.
+pub struct Next<'a> {
+    client: &'a ClientRuntime,
+    middlewares: &'a [Arc<dyn ClientMiddleware>],
+    timeout: Option<Duration>,
+    // The token deduplicating the request, see `McpClient::request_deduplicated()`
+    idempotency_token: Option<&'a str>,
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(
+        client: &'a ClientRuntime,
+        middlewares: &'a [Arc<dyn ClientMiddleware>],
+        timeout: Option<Duration>,
+    ) -> Self {
+        Self {
+            client,
+            middlewares,
+            timeout,
+            idempotency_token: None,
+        }
+    }
+
+    // Deduplicates the request once it is sent, at the end of the chain
+    pub(crate) fn with_idempotency_token(mut self, idempotency_token: &'a str) -> Self {
+        self.idempotency_token = Some(idempotency_token);
+        self
+    }
+
+    /// Passes the request to the next middleware, or sends it to the server after the last one.
+    pub fn run(self, request: RequestFromClient) -> BoxFuture<'a, SdkResult<ResultFromServer>> {
+        Box::pin(async move {
+            match self.middlewares.split_first() {
+                Some((middleware, middlewares)) => {
+                    let next = Next {
+                        middlewares,
+                        ..self
+                    };
+                    middleware.handle(request, next).await
+                }
+                None => {
+                    send_request(self.client, request, self.timeout, self.idempotency_token).await
+                }
+            }
+        })
+    }
+}
+
+/// The rest of the middleware chain of a notification, ending with sending it to the server.
+pub struct NotificationNext<'a> {
+    client: &'a ClientRuntime,
+    middlewares: &'a [Arc<dyn ClientMiddleware>],
+}
+
+impl<'a> NotificationNext<'a> {
+    pub(crate) fn new(
+        client: &'a ClientRuntime,
+        middlewares: &'a [Arc<dyn ClientMiddleware>],
+    ) -> Self {
+        Self {
+            client,
+            middlewares,
+        }
+    }
+
+    /// Passes the notification to the next middleware, or sends it to the server after the last one.
+    pub fn run(self, notification: NotificationFromClient) -> BoxFuture<'a, SdkResult<()>> {
+        Box::pin(async move {
+            match self.middlewares.split_first() {
+                Some((middleware, middlewares)) => {
+                    let next = NotificationNext::new(self.client, middlewares);
+                    middleware.handle_notification(notification, next).await
+                }
+                None => send_notification(self.client, notification).await,
+            }
+        })
+    }
+}
@@ -1,3 +1,4 @@
+use std::process::ExitStatus;
 use std::sync::Arc;
 
 use async_trait::async_trait;
@@ -104,4 +105,8 @@ impl McpClientHandler for ClientCoreInternalHandler<Box<dyn ClientHandlerCore>>
             .await
             .map_err(|err| err.into())
     }
+
+    async fn on_server_process_exit(&self, status: ExitStatus, runtime: &dyn McpClient) {
+        self.handler.on_server_process_exit(status, runtime).await
+    }
 }
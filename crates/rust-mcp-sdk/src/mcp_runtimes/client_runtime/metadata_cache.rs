@@ -0,0 +1,206 @@
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use rust_mcp_schema::{Implementation, InitializeResult, ServerCapabilities, Tool};
+use serde::{Deserialize, Serialize};
+
+use crate::error::{McpSdkError, SdkResult};
+
+/// Version of the format of cache files, caches written in another format are discarded.
+const CACHE_FORMAT_VERSION: u32 = 1;
+
+/// The last known metadata of a server, as stored in a `ServerMetadataCache`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerMetadata {
+    pub server_info: Implementation,
+    pub protocol_version: String,
+    pub capabilities: ServerCapabilities,
+    pub instructions: Option<String>,
+    /// The tools of the server, `None` if it does not provide tools.
+    pub tools: Option<Vec<Tool>>,
+    /// When the metadata was received from the server, in seconds since the Unix epoch.
+    pub updated_at: u64,
+}
+
+impl ServerMetadata {
+    /// Creates the metadata of a server from its initialization result and its tools.
+    pub fn new(server_details: InitializeResult, tools: Option<Vec<Tool>>) -> Self {
+        Self {
+            server_info: server_details.server_info,
+            protocol_version: server_details.protocol_version,
+            capabilities: server_details.capabilities,
+            instructions: server_details.instructions,
+            tools,
+            updated_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default(),
+        }
+    }
+
+    /// Returns what changed from the `previous` metadata of the server, everything if there is none.
+    pub fn changes_from(&self, previous: Option<&ServerMetadata>) -> MetadataChanges {
+        let Some(previous) = previous else {
+            return MetadataChanges {
+                server_info: true,
+                protocol_version: true,
+                capabilities: true,
+                instructions: true,
+                tools: true,
+            };
+        };
+        MetadataChanges {
+            server_info: differs(&self.server_info, &previous.server_info),
+            protocol_version: self.protocol_version != previous.protocol_version,
+            capabilities: differs(&self.capabilities, &previous.capabilities),
+            instructions: self.instructions != previous.instructions,
+            tools: differs(&self.tools, &previous.tools),
+        }
+    }
+}
+
+// Schema types do not implement `PartialEq`, they are compared by their serialized values
+fn differs<T: Serialize>(value: &T, other: &T) -> bool {
+    serde_json::to_value(value).ok() != serde_json::to_value(other).ok()
+}
+
+/// The parts of the metadata of a server that changed since it was cached,
+/// returned by `ClientRuntime::reconcile_metadata_cache()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MetadataChanges {
+    pub server_info: bool,
+    pub protocol_version: bool,
+    pub capabilities: bool,
+    pub instructions: bool,
+    pub tools: bool,
+}
+
+impl MetadataChanges {
+    /// Returns `true` if the cached metadata was up to date.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheFile {
+    version: u32,
+    servers: HashMap<String, ServerMetadata>,
+}
+
+/// An on-disk cache of the last known metadata of servers: their details, capabilities,
+/// instructions and tools, keyed by an identity chosen by the host, such as the command
+/// launching the server.
+///
+/// Hosts can render the servers from the cache right away on startup, before their initialization
+/// completes, then reconcile the cache with the live metadata once the clients are started.
+///
+/// # Example
+/// ```ignore
+/// let cache = Arc::new(ServerMetadataCache::open(cache_dir.join("servers.json")).await?);
+///
+/// client.set_metadata_cache(cache, "npx @modelcontextprotocol/server-everything");
+/// if let Some(metadata) = client.cached_server_metadata() {
+///     render_tools(metadata.tools.unwrap_or_default());
+/// }
+///
+/// client.clone().start().await?;
+/// let changes = client.reconcile_metadata_cache().await?;
+/// if changes.tools {
+///     render_tools(client.list_all_tools().await?);
+/// }
+/// ```
+#[derive(Debug)]
+pub struct ServerMetadataCache {
+    path: PathBuf,
+    servers: RwLock<HashMap<String, ServerMetadata>>,
+}
+
+impl ServerMetadataCache {
+    /// Opens the cache stored in the file at `path`.
+    ///
+    /// The cache is empty if the file does not exist, is corrupted, or was written in another format.
+    pub async fn open<P: Into<PathBuf>>(path: P) -> SdkResult<Self> {
+        let path = path.into();
+        let servers = match tokio::fs::read(&path).await {
+            Ok(content) => serde_json::from_slice::<CacheFile>(&content)
+                .ok()
+                .filter(|cache_file| cache_file.version == CACHE_FORMAT_VERSION)
+                .map(|cache_file| cache_file.servers)
+                .unwrap_or_default(),
+            Err(error) if error.kind() == ErrorKind::NotFound => HashMap::new(),
+            Err(error) => return Err(error.into()),
+        };
+        Ok(Self {
+            path,
+            servers: RwLock::new(servers),
+        })
+    }
+
+    /// Returns the path of the file storing the cache.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Returns the cached metadata of a server.
+    pub fn get(&self, key: &str) -> Option<ServerMetadata> {
+        self.servers
+            .read()
+            .ok()
+            .and_then(|servers| servers.get(key).cloned())
+    }
+
+    /// Stores the metadata of a server, returning its previously cached metadata.
+    /// The cache is written to disk by `save()`.
+    pub fn insert<K: Into<String>>(
+        &self,
+        key: K,
+        metadata: ServerMetadata,
+    ) -> Option<ServerMetadata> {
+        self.servers
+            .write()
+            .ok()
+            .and_then(|mut servers| servers.insert(key.into(), metadata))
+    }
+
+    /// Removes the metadata of a server, returning it. The cache is written to disk by `save()`.
+    pub fn remove(&self, key: &str) -> Option<ServerMetadata> {
+        self.servers
+            .write()
+            .ok()
+            .and_then(|mut servers| servers.remove(key))
+    }
+
+    /// Writes the cache to its file, creating its parent directories if needed.
+    ///
+    /// The cache is written to a temporary file first, so that the file is never left half written.
+    pub async fn save(&self) -> SdkResult<()> {
+        let cache_file = CacheFile {
+            version: CACHE_FORMAT_VERSION,
+            servers: self
+                .servers
+                .read()
+                .map(|servers| servers.clone())
+                .unwrap_or_default(),
+        };
+        let content = serde_json::to_vec_pretty(&cache_file)
+            .map_err(|error| McpSdkError::AnyError(Box::new(error)))?;
+
+        if let Some(parent) = self
+            .path
+            .parent()
+            .filter(|parent| !parent.as_os_str().is_empty())
+        {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        let mut temporary_path = self.path.clone().into_os_string();
+        temporary_path.push(".tmp");
+        tokio::fs::write(&temporary_path, content).await?;
+        tokio::fs::rename(&temporary_path, &self.path).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use std::sync::RwLock;
+
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use rust_mcp_schema::schema_utils::NotificationFromServer;
+use rust_mcp_schema::{ProgressToken, ServerNotification};
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+/// Number of notifications kept for the subscribers of the notification streams of a client.
+/// Subscribers lagging further behind miss the oldest notifications.
+pub const NOTIFICATION_STREAM_CAPACITY: usize = 256;
+
+/// The lists of a server that changed, as notified by list changed notifications
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ListChanged {
+    Tools,
+    Prompts,
+    Resources,
+}
+
+// Broadcasts the notifications received from the server to the streams subscribed to them
+pub(crate) struct NotificationStreams {
+    // `None` once the connection is closed, ending the streams
+    sender: RwLock<Option<broadcast::Sender<NotificationFromServer>>>,
+}
+
+impl NotificationStreams {
+    pub(crate) fn new() -> Self {
+        Self {
+            sender: RwLock::new(Some(broadcast::channel(NOTIFICATION_STREAM_CAPACITY).0)),
+        }
+    }
+
+    // Sends the notification to the subscribed streams, if any
+    pub(crate) fn publish(&self, notification: &NotificationFromServer) {
+        if let Ok(sender) = self.sender.read() {
+            if let Some(sender) = sender.as_ref().filter(|sender| sender.receiver_count() > 0) {
+                let _ = sender.send(notification.clone());
+            }
+        }
+    }
+
+    // Ends the subscribed streams, and the ones subscribed afterwards
+    pub(crate) fn close(&self) {
+        if let Ok(mut sender) = self.sender.write() {
+            *sender = None;
+        }
+    }
+
+    // Returns a stream of the notifications selected and converted by `filter`
+    pub(crate) fn subscribe<T, F>(&self, filter: F) -> BoxStream<'static, T>
+    where
+        T: Send + 'static,
+        F: Fn(NotificationFromServer) -> Option<T> + Send + 'static,
+    {
+        let receiver = self
+            .sender
+            .read()
+            .ok()
+            .and_then(|sender| sender.as_ref().map(broadcast::Sender::subscribe));
+
+        futures::stream::unfold((receiver, filter), |(mut receiver, filter)| async move {
+            loop {
+                match receiver.as_mut()?.recv().await {
+                    Ok(notification) => {
+                        if let Some(item) = filter(notification) {
+                            return Some((item, (receiver, filter)));
+                        }
+                    }
+                    // notifications missed by a slow subscriber are skipped
+                    Err(RecvError::Lagged(_)) => {}
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+        .boxed()
+    }
+}
+
+// Returns the server notification, if it is not a custom notification
+pub(crate) fn server_notification(
+    notification: NotificationFromServer,
+) -> Option<ServerNotification> {
+    match notification {
+        NotificationFromServer::ServerNotification(notification) => Some(notification),
+        NotificationFromServer::CustomNotification(_) => None,
+    }
+}
+
+pub(crate) fn same_progress_token(token: &ProgressToken, other: &ProgressToken) -> bool {
+    match (token, other) {
+        (ProgressToken::String(token), ProgressToken::String(other)) => token == other,
+        (ProgressToken::Integer(token), ProgressToken::Integer(other)) => token == other,
+        _ => false,
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::VecDeque;
+
+use rust_mcp_schema::schema_utils::NotificationFromServer;
+
+/// Number of notifications received during the initialization that are buffered until it completes.
+/// Once it is reached, the oldest buffered notifications are dropped.
+pub const MAX_PENDING_NOTIFICATIONS: usize = 1024;
+
+// Buffers the notifications received while the initialization is in progress, so that they are
+// delivered to the handler once it completes, dropping the oldest ones once `capacity` are buffered
+pub(crate) struct PendingNotifications {
+    notifications: VecDeque<NotificationFromServer>,
+    capacity: usize,
+    dropped: usize,
+}
+
+impl PendingNotifications {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            notifications: VecDeque::new(),
+            capacity: capacity.max(1),
+            dropped: 0,
+        }
+    }
+
+    // Buffers a notification, dropping the oldest one if the buffer is full
+    pub(crate) fn push(&mut self, notification: NotificationFromServer) {
+        if self.notifications.len() == self.capacity {
+            self.notifications.pop_front();
+            self.dropped += 1;
+        }
+        self.notifications.push_back(notification);
+    }
+
+    // Returns the buffered notifications, reporting the ones that were dropped, if any
+    pub(crate) fn into_notifications(self) -> VecDeque<NotificationFromServer> {
+        if self.dropped > 0 {
+            eprintln!(
+                "Error: {} notifications received during the initialization were dropped, only the last {} are delivered.",
+                self.dropped,
+                self.notifications.len()
+            );
+        }
+        self.notifications
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_mcp_schema::{
+        LoggingLevel, LoggingMessageNotification, LoggingMessageNotificationParams,
+    };
+
+    fn log_message(index: usize) -> NotificationFromServer {
+        LoggingMessageNotification::new(LoggingMessageNotificationParams {
+            data: index.into(),
+            level: LoggingLevel::Info,
+            logger: None,
+        })
+        .into()
+    }
+
+    fn index(notification: &NotificationFromServer) -> u64 {
+        serde_json::to_value(notification).unwrap()["params"]["data"]
+            .as_u64()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_keeps_the_newest_notifications_of_a_flood() {
+        let mut pending = PendingNotifications::new(8);
+        for index in 0..100 {
+            pending.push(log_message(index));
+        }
+        assert_eq!(pending.dropped, 92);
+        let indices: Vec<_> = pending.into_notifications().iter().map(index).collect();
+        assert_eq!(indices, (92..100).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_keeps_every_notification_below_the_capacity() {
+        let mut pending = PendingNotifications::new(MAX_PENDING_NOTIFICATIONS);
+        for index in 0..MAX_PENDING_NOTIFICATIONS {
+            pending.push(log_message(index));
+        }
+        assert_eq!(pending.dropped, 0);
+        assert_eq!(
+            pending.into_notifications().len(),
+            MAX_PENDING_NOTIFICATIONS
+        );
+    }
+}
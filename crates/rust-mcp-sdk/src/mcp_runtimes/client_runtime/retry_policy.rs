@@ -0,0 +1,81 @@
+use std::time::Duration;
+
+use crate::error::{McpSdkError, SERVER_BUSY_ERROR_CODE};
+
+/// Retry policy applied to the requests sent by a client with `McpClient::request()`.
+///
+/// Failed requests are sent again after an exponentially increasing backoff, if the error is of a
+/// retryable kind. Retried requests may be processed more than once by the server, as a request
+/// that timed out may still be processed. Use `McpClient::request_once()` for requests that must
+/// not be retried.
+///
+/// The default policy makes a single attempt, so requests are not retried.
+///
+/// # Example
+/// ```ignore
+/// client.set_retry_policy(RetryPolicy {
+///     // the first attempt and up to 2 retries
+///     max_attempts: 3,
+///     initial_backoff: Duration::from_millis(200),
+///     ..Default::default()
+/// });
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// The maximum number of attempts, including the first one. `1` disables retries.
+    pub max_attempts: u32,
+    /// The delay before the first retry
+    pub initial_backoff: Duration,
+    /// The maximum delay between two attempts
+    pub max_backoff: Duration,
+    /// The factor applied to the delay after each retry
+    pub multiplier: f64,
+    /// Retry requests that did not receive a response within the timeout of the transport
+    pub retry_on_timeout: bool,
+    /// Retry requests that failed because the connection is closed
+    pub retry_on_connection_closed: bool,
+    /// Retry requests shed by a server under load
+    pub retry_on_server_busy: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(5),
+            multiplier: 2.0,
+            retry_on_timeout: true,
+            retry_on_connection_closed: true,
+            retry_on_server_busy: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Returns `true` if a request failing with `error` on the given attempt, starting at 1, should be retried.
+    pub fn should_retry(&self, attempt: u32, error: &McpSdkError) -> bool {
+        if attempt >= self.max_attempts {
+            return false;
+        }
+        match error {
+            McpSdkError::RequestTimeout { .. } => self.retry_on_timeout,
+            McpSdkError::ConnectionClosed => self.retry_on_connection_closed,
+            McpSdkError::RpcError(error) if error.code == SERVER_BUSY_ERROR_CODE => {
+                self.retry_on_server_busy
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the delay before retrying a request that failed on the given attempt, starting at 1.
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = self
+            .multiplier
+            .max(1.0)
+            .powi(attempt.saturating_sub(1) as i32);
+        self.initial_backoff
+            .mul_f64(factor.min(u32::MAX as f64))
+            .min(self.max_backoff)
+    }
+}
@@ -0,0 +1,197 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use rust_mcp_schema::schema_utils::{
+    MessageFromClient, NotificationFromClient, RequestFromClient, ResultFromServer, ServerMessage,
+};
+use rust_mcp_schema::{InitializeRequestParams, InitializeResult, Root, RpcError};
+use rust_mcp_transport::{McpDispatch, MessageDispatcher, ProcessStatus};
+
+use crate::error::SdkResult;
+use crate::mcp_handlers::sampling_handler::SamplingHandler;
+use crate::mcp_traits::mcp_client::McpClient;
+use crate::runtime_metrics::Metrics;
+
+use super::{ClientMiddleware, ClientRuntime, Next, NotificationNext, RetryPolicy};
+
+/// A lightweight handle sharing the connection of a `ClientRuntime`, applying its own timeout
+/// and middlewares to the requests sent through it, before the middlewares of the client.
+///
+/// This allows a component of a host to use aggressive timeouts, or to instrument its requests,
+/// without affecting the other users of the connection. Everything else, such as the roots or the
+/// retry policy, is shared with the client.
+///
+/// # Example
+/// ```ignore
+/// let fast_client = client
+///     .scoped()
+///     .with_timeout(Duration::from_millis(500))
+///     .with_middleware(LogRequests);
+///
+/// let tools = fast_client.list_tools(None).await?;
+/// ```
+#[derive(Clone)]
+pub struct ScopedClient {
+    client: Arc<ClientRuntime>,
+    timeout: Option<Duration>,
+    middlewares: Vec<Arc<dyn ClientMiddleware>>,
+}
+
+impl ScopedClient {
+    pub(crate) fn new(client: Arc<ClientRuntime>) -> Self {
+        Self {
+            client,
+            timeout: None,
+            middlewares: vec![],
+        }
+    }
+
+    /// Sets the time to wait for the response of a request, instead of the timeout of the transport.
+    /// The responses of requests sent with `request_deduplicated()` are awaited for the timeout of
+    /// the transport.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Adds a middleware wrapping the requests and notifications, middlewares run in the order
+    /// they are added.
+    pub fn with_middleware(mut self, middleware: impl ClientMiddleware) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    // Returns the middlewares of the scoped client followed by those of the client
+    fn middlewares(&self) -> Vec<Arc<dyn ClientMiddleware>> {
+        [self.middlewares.clone(), self.client.middlewares()].concat()
+    }
+
+    /// Returns the client sharing its connection with this handle.
+    pub fn client(&self) -> &Arc<ClientRuntime> {
+        &self.client
+    }
+}
+
+#[async_trait]
+impl McpClient for ScopedClient {
+    /// Scoped clients can not be started, start the `ClientRuntime` they were created from instead.
+    async fn start(self: Arc<Self>) -> SdkResult<()> {
+        Err(RpcError::internal_error()
+            .with_message(
+                "A scoped client shares the connection of its client, start the client instead."
+                    .to_string(),
+            )
+            .into())
+    }
+
+    fn set_server_details(&self, server_details: InitializeResult) -> SdkResult<()> {
+        self.client.set_server_details(server_details)
+    }
+
+    /// Shuts down the connection shared with the client and all of its scoped clients.
+    async fn shut_down(&self) -> SdkResult<()> {
+        self.client.shut_down().await
+    }
+
+    async fn is_shut_down(&self) -> bool {
+        self.client.is_shut_down().await
+    }
+
+    async fn ready(&self) -> SdkResult<()> {
+        self.client.ready().await
+    }
+
+    async fn wait_for_exit(&self) -> SdkResult<()> {
+        self.client.wait_for_exit().await
+    }
+
+    async fn server_process_status(&self) -> Option<ProcessStatus> {
+        self.client.server_process_status().await
+    }
+
+    async fn sender(&self) -> &tokio::sync::RwLock<Option<MessageDispatcher<ServerMessage>>>
+    where
+        MessageDispatcher<ServerMessage>: McpDispatch<ServerMessage, MessageFromClient>,
+    {
+        self.client.sender().await
+    }
+
+    fn client_info(&self) -> &InitializeRequestParams {
+        self.client.client_info()
+    }
+
+    fn server_info(&self) -> Option<InitializeResult> {
+        self.client.server_info()
+    }
+
+    fn sampling_handler(&self) -> Option<Arc<dyn SamplingHandler>> {
+        self.client.sampling_handler()
+    }
+
+    fn roots(&self) -> Vec<Root> {
+        self.client.roots()
+    }
+
+    async fn set_roots(&self, roots: Vec<Root>) -> SdkResult<()> {
+        self.client.set_roots(roots).await
+    }
+
+    async fn add_root(&self, root: Root) -> SdkResult<()> {
+        self.client.add_root(root).await
+    }
+
+    async fn remove_root(&self, uri: &str) -> SdkResult<bool> {
+        self.client.remove_root(uri).await
+    }
+
+    fn retry_policy(&self) -> RetryPolicy {
+        self.client.retry_policy()
+    }
+
+    fn max_sampling_depth(&self) -> u32 {
+        self.client.max_sampling_depth()
+    }
+
+    #[cfg(feature = "signing")]
+    fn signature_verifier(&self) -> Option<Arc<dyn crate::signing::SignatureVerifier>> {
+        self.client.signature_verifier()
+    }
+
+    fn enforce_strict_capabilities(&self) -> bool {
+        self.client.enforce_strict_capabilities()
+    }
+
+    fn metrics_recorder(&self) -> Option<Arc<dyn Metrics>> {
+        self.client.metrics_recorder()
+    }
+
+    /// Sends a request through the middlewares of the scoped client, then through those of the client,
+    /// without retrying it if it fails.
+    async fn request_once(&self, request: RequestFromClient) -> SdkResult<ResultFromServer> {
+        let middlewares = self.middlewares();
+        let next = Next::new(&self.client, &middlewares, self.timeout);
+        self.client.request_through(request, next).await
+    }
+
+    /// Sends a request through the middlewares of the scoped client, then through those of the client,
+    /// deduplicating its sending at the end of the chain, see `McpClient::request_deduplicated()`.
+    async fn request_deduplicated(
+        &self,
+        request: RequestFromClient,
+        idempotency_token: &str,
+    ) -> SdkResult<ResultFromServer> {
+        let middlewares = self.middlewares();
+        let next = Next::new(&self.client, &middlewares, self.timeout)
+            .with_idempotency_token(idempotency_token);
+        self.client.request_through(request, next).await
+    }
+
+    /// Sends a notification through the middlewares of the scoped client, then through those of the client.
+    async fn send_notification(&self, notification: NotificationFromClient) -> SdkResult<()> {
+        let middlewares = self.middlewares();
+        NotificationNext::new(&self.client, &middlewares)
+            .run(notification)
+            .await
+    }
+}
@@ -0,0 +1,118 @@
+use rust_mcp_schema::{Implementation, InitializeResult, ServerCapabilities};
+
+/// The details of the server negotiated during the initialization, returned by
+/// `McpClient::server_profile()`.
+///
+/// The accessors answer whether the server advertised a capability or one of its features, without
+/// going through the options of `ServerCapabilities`. A feature not advertised is not supported.
+///
+/// # Example
+/// ```ignore
+/// let profile = client.server_profile().ok_or(McpSdkError::NotInitialized)?;
+/// if profile.supports_resource_subscribe() {
+///     client.subscribe_resource(SubscribeRequestParams { uri }).await?;
+/// }
+/// if let Some(instructions) = profile.instructions() {
+///     system_prompt.push_str(instructions);
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServerProfile {
+    initialize_result: InitializeResult,
+}
+
+impl ServerProfile {
+    pub fn new(initialize_result: InitializeResult) -> Self {
+        Self { initialize_result }
+    }
+
+    /// The name and version of the server
+    pub fn server_info(&self) -> &Implementation {
+        &self.initialize_result.server_info
+    }
+
+    /// The protocol version negotiated with the server
+    pub fn protocol_version(&self) -> &str {
+        &self.initialize_result.protocol_version
+    }
+
+    /// The instructions of the server describing how to use it, if any
+    pub fn instructions(&self) -> Option<&str> {
+        self.initialize_result.instructions.as_deref()
+    }
+
+    /// The capabilities advertised by the server, as received
+    pub fn capabilities(&self) -> &ServerCapabilities {
+        &self.initialize_result.capabilities
+    }
+
+    /// Returns `true` if the server offers tools.
+    pub fn supports_tools(&self) -> bool {
+        self.capabilities().tools.is_some()
+    }
+
+    /// Returns `true` if the server notifies changes of its list of tools.
+    pub fn supports_tool_list_changed(&self) -> bool {
+        self.capabilities()
+            .tools
+            .as_ref()
+            .and_then(|tools| tools.list_changed)
+            .unwrap_or(false)
+    }
+
+    /// Returns `true` if the server offers prompts.
+    pub fn supports_prompts(&self) -> bool {
+        self.capabilities().prompts.is_some()
+    }
+
+    /// Returns `true` if the server notifies changes of its list of prompts.
+    pub fn supports_prompt_list_changed(&self) -> bool {
+        self.capabilities()
+            .prompts
+            .as_ref()
+            .and_then(|prompts| prompts.list_changed)
+            .unwrap_or(false)
+    }
+
+    /// Returns `true` if the server offers resources.
+    pub fn supports_resources(&self) -> bool {
+        self.capabilities().resources.is_some()
+    }
+
+    /// Returns `true` if the server notifies changes of its list of resources.
+    pub fn supports_resource_list_changed(&self) -> bool {
+        self.capabilities()
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.list_changed)
+            .unwrap_or(false)
+    }
+
+    /// Returns `true` if the server accepts subscriptions to the updates of its resources.
+    pub fn supports_resource_subscribe(&self) -> bool {
+        self.capabilities()
+            .resources
+            .as_ref()
+            .and_then(|resources| resources.subscribe)
+            .unwrap_or(false)
+    }
+
+    /// Returns `true` if the server sends log messages, and accepts `logging/setLevel` requests.
+    pub fn supports_logging(&self) -> bool {
+        self.capabilities().logging.is_some()
+    }
+
+    /// Returns `true` if the server advertises the given experimental capability.
+    pub fn supports_experimental(&self, name: &str) -> bool {
+        self.capabilities()
+            .experimental
+            .as_ref()
+            .is_some_and(|experimental| experimental.contains_key(name))
+    }
+}
+
+impl From<InitializeResult> for ServerProfile {
+    fn from(initialize_result: InitializeResult) -> Self {
+        Self::new(initialize_result)
+    }
+}
@@ -1,26 +1,92 @@
+mod completion_registry;
+mod load_shedding_policy;
 pub mod mcp_server_runtime;
 pub mod mcp_server_runtime_core;
+mod prompt_registry;
+mod rate_limiter;
+mod resource_registry;
+mod server_info_builder;
+mod server_logger;
+mod server_middleware;
+mod session_server;
+mod session_store;
+mod subscription_manager;
+mod tool_error_policy;
+mod tool_hooks;
+mod tool_result_limits;
 
 use async_trait::async_trait;
+use futures::FutureExt;
+use futures::Stream;
 use futures::StreamExt;
 use rust_mcp_schema::schema_utils::MessageFromServer;
-use rust_mcp_schema::{self, schema_utils, InitializeRequestParams, InitializeResult, RpcError};
-use rust_mcp_transport::{IoStream, McpDispatch, MessageDispatcher, Transport};
-use schema_utils::ClientMessage;
+use rust_mcp_schema::schema_utils::{RequestFromClient, ResultFromServer};
+use rust_mcp_schema::{
+    self, schema_utils, ClientNotification, ClientRequest, InitializeRequestParams,
+    InitializeResult, ListToolsRequest, ListToolsRequestParams, RequestId, RpcError, ServerResult,
+    Tool,
+};
+use rust_mcp_transport::{IoStream, McpDispatch, MessageDispatcher, SessionId, Transport};
+use schema_utils::{ClientMessage, NotificationFromClient};
+use std::any::Any;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
 use std::pin::Pin;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use tokio::io::AsyncWriteExt;
+use tokio::sync::watch;
 
-use crate::error::SdkResult;
+use crate::cancellation_token::CancellationToken;
+use crate::error::{McpSdkError, SdkResult};
+use crate::keep_alive::KeepAlive;
 use crate::mcp_traits::mcp_handler::McpServerHandler;
 use crate::mcp_traits::mcp_server::McpServer;
+use crate::mcp_traits::rpc_error_ext::RpcErrorExt;
+use crate::request_meta::{tool_call_meta, with_request_context, RequestContext};
+use crate::runtime_metrics::{measure_request, DefaultMetrics, Metrics, MetricsSnapshot};
+use crate::sampling_depth::{tool_call_depth, with_sampling_depth, DEFAULT_MAX_SAMPLING_DEPTH};
+use crate::tool_dry_run::{is_dry_run, mark_dry_run_result, supports_dry_run};
+
+pub use completion_registry::{CompletionProvider, CompletionRegistry, MAX_COMPLETION_VALUES};
+pub use load_shedding_policy::LoadSheddingPolicy;
+pub use prompt_registry::{validate_prompt_arguments, PromptRegistry};
+pub use rate_limiter::{RateLimit, RateLimiter};
+pub use resource_registry::ResourceRegistry;
+pub use server_info_builder::ServerInfoBuilder;
+#[cfg(any(feature = "log", feature = "tracing"))]
+pub(crate) use server_logger::severity;
+pub use server_logger::ServerLogger;
+pub use server_middleware::{RequestNext, ServerMiddleware};
+pub use session_server::SessionServer;
+pub use session_store::SessionStore;
+pub use subscription_manager::{SubscriptionManager, SubscriptionValidator};
+pub(crate) use tool_error_policy::from_call_tool_error;
+pub use tool_error_policy::ToolErrorPolicy;
+pub use tool_hooks::{ToolCallNext, ToolHook};
+pub use tool_result_limits::ToolResultLimits;
+
+/// Delay before notifying the client that tools were enabled or disabled, so that toggling
+/// several tools at once results in a single `tools/list_changed` notification.
+const TOOL_LIST_CHANGED_DEBOUNCE: Duration = Duration::from_millis(100);
+
+/// Maximum number of messages read ahead while a message is processed
+const MAX_READ_AHEAD_MESSAGES: usize = 1024;
+
+/// Maximum number of pages of tools listed from the handler to find a tool that is not known yet
+const MAX_TOOL_LIST_PAGES: usize = 100;
 
 /// Struct representing the runtime core of the MCP server, handling transport and client details
 pub struct ServerRuntime {
     // The transport interface for handling messages between client and server
     transport: Box<dyn Transport<ClientMessage, MessageFromServer>>,
-    // The handler for processing MCP messages
-    handler: Box<dyn McpServerHandler>,
+    // The handler for processing MCP messages, shared by the sessions of a `SessionServer`
+    handler: Arc<dyn McpServerHandler>,
+    // The id of the session, when created by a `SessionServer`
+    session_id: Option<SessionId>,
+    // The state of the client session
+    session_store: SessionStore,
     // Information about the server
     server_details: InitializeResult,
     // Details about the connected client
@@ -28,10 +94,127 @@ pub struct ServerRuntime {
 
     message_sender: tokio::sync::RwLock<Option<MessageDispatcher<ClientMessage>>>,
     error_stream: tokio::sync::RwLock<Option<Pin<Box<dyn tokio::io::AsyncWrite + Send + Sync>>>>,
+    // Size limits applied to the results of tool calls
+    tool_result_limits: ToolResultLimits,
+    // How the failures of tool calls are reported to the client
+    tool_error_policy: ToolErrorPolicy,
+    // Maximum nesting depth of the sampling requests sent, and of the tool calls accepted
+    max_sampling_depth: u32,
+    // Check outgoing messages against the capabilities of both parties
+    enforce_strict_capabilities: bool,
+    // Sheds requests once too many are pending, if any
+    load_shedding_policy: Option<LoadSheddingPolicy>,
+    // Pings the client periodically, closing the connection once it stops answering, if enabled
+    keep_alive: Option<KeepAlive>,
+    // Protocol versions accepted from clients during initialization
+    supported_protocol_versions: Vec<String>,
+    // Whether initialize requests are answered by the runtime for `ServerHandlerCore` handlers
+    auto_initialize: bool,
+    // Whether pulling messages from the transport is paused
+    paused: watch::Sender<bool>,
+    // Tools disabled at runtime, hidden from the client
+    disabled_tools: RwLock<HashSet<String>>,
+    // Signaled when tools are enabled or disabled, see `notify_tool_list_changes()`
+    tool_list_changed: watch::Sender<()>,
+    // Middlewares wrapping the requests received from the client
+    middlewares: Vec<Arc<dyn ServerMiddleware>>,
+    // Hooks intercepting the execution of tools
+    tool_hooks: Vec<Arc<dyn ToolHook>>,
+    // The tools listed by the handler, by name, telling whether they support dry runs
+    // and which of their arguments are encrypted
+    listed_tools: RwLock<HashMap<String, Tool>>,
+    // The cancellation tokens of the requests being processed, by request id
+    in_flight_requests: RwLock<HashMap<RequestId, CancellationToken>>,
+    // Records the processed requests and received notifications
+    metrics: Arc<dyn Metrics>,
+    // Filters the log messages sent to the client, and answers logging/setLevel requests, if enabled
+    logger: Option<ServerLogger>,
+    // Tracks the resources the client subscribed to, and answers resources/subscribe requests, if enabled
+    subscription_manager: Option<SubscriptionManager>,
+    // Lists and reads the resources declared by the server, if enabled
+    resource_registry: Option<ResourceRegistry>,
+    // Lists and renders the prompts declared by the server, if enabled
+    prompt_registry: Option<PromptRegistry>,
+    // Completes the arguments of prompts and the variables of resource templates, if enabled
+    completion_registry: Option<CompletionRegistry>,
+    // Decrypts the encrypted arguments of tool calls
+    #[cfg(feature = "encryption")]
+    key_provider: Option<Arc<dyn crate::encryption::KeyProvider>>,
+    // Signs the results and notifications sent to the client
+    #[cfg(feature = "signing")]
+    signer: Option<Arc<dyn crate::signing::Signer>>,
 }
 
 #[async_trait]
 impl McpServer for ServerRuntime {
+    fn enforce_strict_capabilities(&self) -> bool {
+        self.enforce_strict_capabilities
+    }
+
+    fn logger(&self) -> Option<&ServerLogger> {
+        self.logger.as_ref()
+    }
+
+    fn subscription_manager(&self) -> Option<&SubscriptionManager> {
+        self.subscription_manager.as_ref()
+    }
+
+    fn resource_registry(&self) -> Option<&ResourceRegistry> {
+        self.resource_registry.as_ref()
+    }
+
+    fn prompt_registry(&self) -> Option<&PromptRegistry> {
+        self.prompt_registry.as_ref()
+    }
+
+    fn completion_registry(&self) -> Option<&CompletionRegistry> {
+        self.completion_registry.as_ref()
+    }
+
+    fn max_sampling_depth(&self) -> u32 {
+        self.max_sampling_depth
+    }
+
+    fn session_id(&self) -> Option<&SessionId> {
+        self.session_id.as_ref()
+    }
+
+    fn session_store(&self) -> Option<&SessionStore> {
+        Some(&self.session_store)
+    }
+
+    #[cfg(feature = "signing")]
+    fn signer(&self) -> Option<&dyn crate::signing::Signer> {
+        self.signer.as_deref()
+    }
+
+    fn supported_protocol_versions(&self) -> Vec<String> {
+        self.supported_protocol_versions.clone()
+    }
+
+    fn auto_initialize(&self) -> bool {
+        self.auto_initialize
+    }
+
+    fn is_tool_enabled(&self, tool_name: &str) -> bool {
+        match self.disabled_tools.read() {
+            Ok(disabled_tools) => !disabled_tools.contains(tool_name),
+            // Failed to acquire read lock, likely due to PoisonError from a thread panic.
+            Err(_) => true,
+        }
+    }
+
+    fn set_tool_enabled(&self, tool_name: &str, enabled: bool) {
+        let changed = match self.disabled_tools.write() {
+            Ok(mut disabled_tools) if enabled => disabled_tools.remove(tool_name),
+            Ok(mut disabled_tools) => disabled_tools.insert(tool_name.to_string()),
+            Err(_) => false,
+        };
+        if changed {
+            self.tool_list_changed.send_replace(());
+        }
+    }
+
     /// Set the client details, storing them in client_details
     fn set_client_details(&self, client_details: InitializeRequestParams) -> SdkResult<()> {
         match self.client_details.write() {
@@ -75,7 +258,7 @@ impl McpServer for ServerRuntime {
         // self.transport.start().await?;
         // Open the transport stream
         // let mut stream = self.transport.open();
-        let (mut stream, sender, error_io) = self.transport.start().await?;
+        let (stream, sender, error_io) = self.transport.start().await?;
 
         self.set_message_sender(sender).await;
 
@@ -84,46 +267,17 @@ impl McpServer for ServerRuntime {
         }
 
         let sender = self.sender().await.read().await;
-        let sender = sender.as_ref().ok_or(crate::error::McpSdkError::SdkError(
-            schema_utils::SdkError::connection_closed(),
-        ))?;
+        let sender = sender.as_ref().ok_or(McpSdkError::ConnectionClosed)?;
 
         self.handler.on_server_started(self).await;
 
-        // Process incoming messages from the client
-        while let Some(mcp_message) = stream.next().await {
-            match mcp_message {
-                // Handle a client request
-                ClientMessage::Request(client_jsonrpc_request) => {
-                    let result = self
-                        .handler
-                        .handle_request(client_jsonrpc_request.request, self)
-                        .await;
-                    // create a response to send back to the client
-                    let response: MessageFromServer = match result {
-                        Ok(success_value) => success_value.into(),
-                        Err(error_value) => MessageFromServer::Error(error_value),
-                    };
-
-                    // send the response back with corresponding request id
-                    sender
-                        .send(response, Some(client_jsonrpc_request.id))
-                        .await?;
-                }
-                ClientMessage::Notification(client_jsonrpc_notification) => {
-                    self.handler
-                        .handle_notification(client_jsonrpc_notification.notification, self)
-                        .await?;
-                }
-                ClientMessage::Error(jsonrpc_error) => {
-                    self.handler.handle_error(jsonrpc_error.error, self).await?;
-                }
-                // The response is the result of a request, it is processed at the transport level.
-                ClientMessage::Response(_) => {}
-            }
+        // the client is notified of the tools enabled or disabled while messages are processed,
+        // and pinged if a keep-alive is enabled
+        tokio::select! {
+            result = self.process_messages(stream, sender) => result,
+            _ = self.notify_tool_list_changes() => Ok(()),
+            Err(error) = self.keep_connection_alive() => Err(error),
         }
-
-        return Ok(());
     }
 
     async fn stderr_message(&self, message: String) -> SdkResult<()> {
@@ -138,6 +292,712 @@ impl McpServer for ServerRuntime {
 }
 
 impl ServerRuntime {
+    /// Returns a `ServerInfoBuilder` to create the server details passed to `create_server()`.
+    pub fn info<N: Into<String>, V: Into<String>>(name: N, version: V) -> ServerInfoBuilder {
+        ServerInfoBuilder::new(name, version)
+    }
+
+    /// Sets the size limits applied to the text content of tool results, see `ToolResultLimits`.
+    /// No limit is applied by default.
+    pub fn with_tool_result_limits(mut self, tool_result_limits: ToolResultLimits) -> Self {
+        self.tool_result_limits = tool_result_limits;
+        self
+    }
+
+    /// Sets how the failures of tool calls are reported to the client, see `ToolErrorPolicy`.
+    /// `ToolErrorPolicy::Standard` by default.
+    pub fn with_tool_error_policy(mut self, tool_error_policy: ToolErrorPolicy) -> Self {
+        self.tool_error_policy = tool_error_policy;
+        self
+    }
+
+    /// Sets the maximum nesting depth of the sampling requests sent by the server, and of the tool
+    /// calls it accepts, `DEFAULT_MAX_SAMPLING_DEPTH` by default, see `sampling`.
+    ///
+    /// Deeper tool calls are rejected, and so are deeper sampling requests by `create_message()`,
+    /// breaking the loops where the LLM of the client keeps calling the tool requesting the sampling.
+    pub fn with_max_sampling_depth(mut self, max_sampling_depth: u32) -> Self {
+        self.max_sampling_depth = max_sampling_depth;
+        self
+    }
+
+    /// Sets the policy shedding requests once too many are pending, see `LoadSheddingPolicy`.
+    /// Requests are queued without limit by default.
+    pub fn with_load_shedding_policy(mut self, load_shedding_policy: LoadSheddingPolicy) -> Self {
+        self.load_shedding_policy = Some(load_shedding_policy);
+        self
+    }
+
+    /// Enables the keep-alive of the connection, pinging the client periodically and closing the
+    /// connection once it stops answering, see `KeepAlive`. Disabled by default.
+    pub fn with_keep_alive(mut self, keep_alive: KeepAlive) -> Self {
+        self.keep_alive = Some(keep_alive);
+        self
+    }
+
+    /// Pauses the processing of incoming messages.
+    ///
+    /// The request being processed, if any, is completed, but no further message is pulled from the
+    /// transport until `resume()` is called, leaving them buffered by the transport. This is useful
+    /// during maintenance operations, such as reloading a large index.
+    pub fn pause(&self) {
+        self.paused.send_replace(true);
+    }
+
+    /// Resumes the processing of incoming messages paused with `pause()`.
+    pub fn resume(&self) {
+        self.paused.send_replace(false);
+    }
+
+    /// Returns `true` if the processing of incoming messages is paused.
+    pub fn is_paused(&self) -> bool {
+        *self.paused.borrow()
+    }
+
+    /// Sets the tools disabled when the server starts, e.g. license-gated tools.
+    /// They can be enabled at runtime with `set_tool_enabled()`.
+    pub fn with_disabled_tools<I, S>(self, tool_names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        if let Ok(mut disabled_tools) = self.disabled_tools.write() {
+            disabled_tools.extend(tool_names.into_iter().map(Into::into));
+        }
+        self
+    }
+
+    /// Adds a middleware wrapping the requests received from the client, see `ServerMiddleware`.
+    /// Middlewares run in the order they are added.
+    pub fn with_middleware(mut self, middleware: impl ServerMiddleware) -> Self {
+        self.middlewares.push(Arc::new(middleware));
+        self
+    }
+
+    /// Adds a hook intercepting the execution of tools, see `ToolHook`.
+    /// Hooks run in the order they are added.
+    pub fn with_tool_hook(mut self, tool_hook: impl ToolHook) -> Self {
+        self.tool_hooks.push(Arc::new(tool_hook));
+        self
+    }
+
+    /// Sets the metrics recording the requests processed by the server and the notifications
+    /// it receives, `DefaultMetrics` by default.
+    pub fn with_metrics(mut self, metrics: impl Metrics) -> Self {
+        self.metrics = Arc::new(metrics);
+        self
+    }
+
+    /// Returns a snapshot of the metrics of the server, see `Metrics::snapshot()`.
+    pub fn metrics(&self) -> MetricsSnapshot {
+        self.metrics.snapshot()
+    }
+
+    /// Enables the logging facade of the server, see `ServerLogger`.
+    ///
+    /// The `logging` capability is then declared, and `logging/setLevel` requests are answered by the
+    /// server instead of the handler.
+    pub fn with_logger(mut self, logger: ServerLogger) -> Self {
+        self.server_details
+            .capabilities
+            .logging
+            .get_or_insert_with(Default::default);
+        self.logger = Some(logger);
+        self
+    }
+
+    /// Enables the tracking of the resources the client subscribed to, see `SubscriptionManager`.
+    ///
+    /// The `resources.subscribe` capability is then declared, and `resources/subscribe` and
+    /// `resources/unsubscribe` requests are answered by the server instead of the handler.
+    pub fn with_subscription_manager(mut self, subscription_manager: SubscriptionManager) -> Self {
+        self.server_details
+            .capabilities
+            .resources
+            .get_or_insert_with(Default::default)
+            .subscribe = Some(true);
+        self.subscription_manager = Some(subscription_manager);
+        self
+    }
+
+    /// Enables the declaration of the resources of the server, see `ResourceRegistry`.
+    ///
+    /// The `resources` capability is then declared, and the default implementations of the resource
+    /// handlers of `ServerHandler` list and read the registered resources.
+    pub fn with_resource_registry(mut self, resource_registry: ResourceRegistry) -> Self {
+        self.server_details
+            .capabilities
+            .resources
+            .get_or_insert_with(Default::default);
+        self.resource_registry = Some(resource_registry);
+        self
+    }
+
+    /// Enables the declaration of the prompts of the server, see `PromptRegistry`.
+    ///
+    /// The `prompts` capability is then declared, and the default implementations of the prompt
+    /// handlers of `ServerHandler` list and render the registered prompts.
+    pub fn with_prompt_registry(mut self, prompt_registry: PromptRegistry) -> Self {
+        self.server_details
+            .capabilities
+            .prompts
+            .get_or_insert_with(Default::default);
+        self.prompt_registry = Some(prompt_registry);
+        self
+    }
+
+    /// Enables the completion of the arguments of prompts and of the variables of resource templates,
+    /// see `CompletionRegistry`.
+    ///
+    /// The default implementation of `ServerHandler::handle_complete_request()` then answers
+    /// `completion/complete` requests with the registered providers.
+    pub fn with_completion_registry(mut self, completion_registry: CompletionRegistry) -> Self {
+        self.completion_registry = Some(completion_registry);
+        self
+    }
+
+    /// Enables or disables the strict enforcement of capabilities, disabled by default.
+    ///
+    /// When enabled, requests and notifications sent to the client fail without being sent
+    /// if the client or the server lacks the capability they require.
+    pub fn with_strict_capabilities(mut self, enforce_strict_capabilities: bool) -> Self {
+        self.enforce_strict_capabilities = enforce_strict_capabilities;
+        self
+    }
+
+    /// Sets the protocol versions accepted from clients, the protocol version of the server by default.
+    ///
+    /// Clients requesting another version during initialization receive the version mismatch
+    /// error defined by the specification, listing the supported versions.
+    pub fn with_supported_protocol_versions<I, S>(mut self, protocol_versions: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.supported_protocol_versions = protocol_versions.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Enables or disables the handling of initialization by the runtime for a `ServerHandlerCore`
+    /// handler, disabled by default. Has no effect on a `ServerHandler`, whose initialize requests
+    /// are always answered by the runtime, unless `handle_initialize_request()` is overridden.
+    ///
+    /// When enabled, initialize requests and initialized notifications are still passed to the
+    /// handler, so that it can observe them. The result returned by the handler for the initialize
+    /// request is sent to the client, unless it is a `method_not_found` error, in which case the
+    /// runtime answers with the server details and the protocol version negotiated with the client.
+    /// Errors returned by the handler for the initialized notification are ignored.
+    pub fn with_auto_initialize(mut self, auto_initialize: bool) -> Self {
+        self.auto_initialize = auto_initialize;
+        self
+    }
+
+    /// Sets the key provider used to decrypt the encrypted arguments of tool calls,
+    /// before they are passed to the handler.
+    ///
+    /// Only the arguments marked as encrypted in the schema of the tool, as listed by the handler,
+    /// are decrypted, and calls sending them in plaintext are rejected with an `invalid_params` error.
+    #[cfg(feature = "encryption")]
+    pub fn with_key_provider(
+        mut self,
+        key_provider: Arc<dyn crate::encryption::KeyProvider>,
+    ) -> Self {
+        self.key_provider = Some(key_provider);
+        self
+    }
+
+    /// Sets the signer of the results and notifications sent to the client, see `signing`.
+    #[cfg(feature = "signing")]
+    pub fn with_signer(mut self, signer: Arc<dyn crate::signing::Signer>) -> Self {
+        self.signer = Some(signer);
+        self
+    }
+
+    /// Processes the messages received from the client until the transport ends.
+    async fn process_messages(
+        &self,
+        mut stream: Pin<Box<dyn Stream<Item = ClientMessage> + Send>>,
+        sender: &MessageDispatcher<ClientMessage>,
+    ) -> SdkResult<()> {
+        let mut paused = self.paused.subscribe();
+        // messages read ahead while a request is processed, so that cancellations are applied
+        // immediately, and requests are shed according to the `LoadSheddingPolicy`, if any
+        let mut queue: VecDeque<ClientMessage> = VecDeque::new();
+        let mut stream_ended = false;
+
+        // Process incoming messages from the client
+        loop {
+            // while paused, messages are left in the transport until `resume()` is called
+            if paused.wait_for(|paused| !paused).await.is_err() {
+                break;
+            }
+            let mcp_message = match queue.pop_front() {
+                Some(mcp_message) => mcp_message,
+                None if stream_ended => break,
+                None => tokio::select! {
+                    biased;
+                    _ = paused.wait_for(|paused| *paused) => continue,
+                    mcp_message = stream.next() => match mcp_message {
+                        Some(mcp_message) => mcp_message,
+                        None => break,
+                    },
+                },
+            };
+
+            // read ahead while the message is processed
+            let processing = self.process_message(mcp_message, sender);
+            tokio::pin!(processing);
+            loop {
+                tokio::select! {
+                    biased;
+                    result = &mut processing => {
+                        result?;
+                        break;
+                    }
+                    // once the queue is full, the messages are left in the transport, which stops reading
+                    mcp_message = stream.next(), if !stream_ended && queue.len() < MAX_READ_AHEAD_MESSAGES => {
+                        let Some(mcp_message) = mcp_message else {
+                            stream_ended = true;
+                            continue;
+                        };
+                        // cancelled requests still queued are dropped without a response,
+                        // the notification is handled once the queued messages before it are
+                        if let Some(request_id) = cancelled_request_id(&mcp_message) {
+                            self.cancel_request(request_id);
+                            queue.retain(|message| {
+                                !matches!(message, ClientMessage::Request(request) if &request.id == request_id)
+                            });
+                        }
+                        // pings are processed right away, ahead of the queued messages, so that a long
+                        // request is not mistaken for a dead connection by the keep-alive of the client.
+                        // They go through the middlewares and the handler like any other request.
+                        if is_ping_request(&mcp_message) {
+                            self.process_message(mcp_message, sender).await?;
+                            continue;
+                        }
+                        // shed requests once too many are pending
+                        if let (
+                            ClientMessage::Request(client_jsonrpc_request),
+                            Some(load_shedding_policy),
+                        ) = (&mcp_message, self.load_shedding_policy.as_ref())
+                        {
+                            // the request being processed is pending as well
+                            let pending_requests = 1 + queue
+                                .iter()
+                                .filter(|message| matches!(message, ClientMessage::Request(_)))
+                                .count();
+                            if let Some(error) = load_shedding_policy
+                                .shed(&client_jsonrpc_request.request, pending_requests)
+                            {
+                                self.metrics.request_started(&client_jsonrpc_request.method);
+                                self.metrics.request_completed(
+                                    &client_jsonrpc_request.method,
+                                    Duration::ZERO,
+                                    true,
+                                );
+                                let response = match &client_jsonrpc_request.request {
+                                    RequestFromClient::ClientRequest(
+                                        ClientRequest::CallToolRequest(_),
+                                    ) => self.tool_error_policy.apply(Err(error)),
+                                    _ => Err(error),
+                                };
+                                sender
+                                    .send(
+                                        self.response_message(
+                                            response,
+                                            &client_jsonrpc_request.id,
+                                            &client_jsonrpc_request.method,
+                                        ),
+                                        Some(client_jsonrpc_request.id.clone()),
+                                    )
+                                    .await?;
+                                continue;
+                            }
+                        }
+                        queue.push_back(mcp_message);
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Pings the client while the connection is open, see `KeepAlive`, never completing if the
+    /// keep-alive is disabled. Shuts down the transport once the client stops answering.
+    async fn keep_connection_alive(&self) -> SdkResult<()> {
+        let Some(keep_alive) = self.keep_alive.as_ref() else {
+            return std::future::pending().await;
+        };
+        let result = keep_alive
+            .run(
+                // clients are only pinged once initialized
+                || async {
+                    match self.is_initialized() {
+                        true => self.ping().await,
+                        false => Ok(Default::default()),
+                    }
+                },
+                || async { !self.transport.is_shut_down().await },
+            )
+            .await;
+        if result.is_err() {
+            self.transport.shut_down().await?;
+        }
+        result
+    }
+
+    /// Sends a `tools/list_changed` notification once tools are enabled or disabled with
+    /// `set_tool_enabled()`, debounced by `TOOL_LIST_CHANGED_DEBOUNCE`.
+    async fn notify_tool_list_changes(&self) {
+        let mut tool_list_changed = self.tool_list_changed.subscribe();
+        while tool_list_changed.changed().await.is_ok() {
+            tokio::time::sleep(TOOL_LIST_CHANGED_DEBOUNCE).await;
+            // the changes made in the meantime are covered by this notification
+            tool_list_changed.borrow_and_update();
+            if !self.is_initialized() {
+                continue;
+            }
+            if let Err(error) = self.send_tool_list_changed(None).await {
+                let _ = self
+                    .stderr_message(format!(
+                        "Failed to send the tool list changed notification: {}",
+                        error
+                    ))
+                    .await;
+            }
+        }
+    }
+
+    /// Processes a message received from the client, sending the response of requests
+    async fn process_message(
+        &self,
+        mcp_message: ClientMessage,
+        sender: &MessageDispatcher<ClientMessage>,
+    ) -> SdkResult<()> {
+        match mcp_message {
+            // Handle a client request
+            ClientMessage::Request(client_jsonrpc_request) => {
+                let request_id = client_jsonrpc_request.id.clone();
+                let method = client_jsonrpc_request.method.clone();
+                let (tool_name, dry_run, sampling_depth, request_meta) =
+                    match &client_jsonrpc_request.request {
+                        RequestFromClient::ClientRequest(ClientRequest::CallToolRequest(
+                            call_tool_request,
+                        )) => (
+                            Some(call_tool_request.params.name.clone()),
+                            is_dry_run(&call_tool_request.params),
+                            tool_call_depth(&call_tool_request.params),
+                            tool_call_meta(&call_tool_request.params),
+                        ),
+                        _ => (None, false, 0, Default::default()),
+                    };
+                // disabled tools are reported as unknown tools, dry runs of tools not supporting them are rejected,
+                // and so are tool calls nested too deep in sampling requests,
+                // failures of tool calls are reported according to the tool error policy,
+                // tool results reporting an error are recorded as failures
+                let cancellation_token = self.register_request(&request_id);
+                let context = RequestContext::new(
+                    request_id.clone(),
+                    request_meta,
+                    cancellation_token.clone(),
+                    self.client_info()
+                        .map(|client_info| client_info.client_info),
+                )
+                .with_session_id(self.session_id.clone());
+                let processing = measure_request(
+                    self.metrics.as_ref(),
+                    &client_jsonrpc_request.method,
+                    async {
+                        let result = match &tool_name {
+                            Some(tool_name) if !self.is_tool_enabled(tool_name) => {
+                                Err(RpcError::tool_not_found(tool_name))
+                            }
+                            Some(_) if sampling_depth > self.max_sampling_depth => {
+                                let error = McpSdkError::SamplingDepthExceeded {
+                                    depth: sampling_depth,
+                                    max_depth: self.max_sampling_depth,
+                                };
+                                Err(RpcError::invalid_request().with_message(error.to_string()))
+                            }
+                            Some(tool_name)
+                                if dry_run && !self.tool_supports_dry_run(tool_name).await =>
+                            {
+                                Err(RpcError::invalid_params().with_message(format!(
+                                    "Tool {} does not support dry runs",
+                                    tool_name
+                                )))
+                            }
+                            // the sampling requests sent by the tool are one level deeper,
+                            // a panicking handler fails the request without ending the runtime
+                            _ => catch_panic(with_sampling_depth(
+                                sampling_depth,
+                                RequestNext::new(self, &self.middlewares)
+                                    .run(client_jsonrpc_request.request),
+                            ))
+                            .await
+                            .unwrap_or_else(|panic| {
+                                Err(RpcError::internal_error().with_message(format!(
+                                    "Internal error: the request handler panicked ({})",
+                                    panic_message(&panic)
+                                )))
+                            }),
+                        };
+                        match tool_name {
+                            Some(_) => self.tool_error_policy.apply(result),
+                            None => result,
+                        }
+                    },
+                    is_failed_request,
+                );
+                // the context of the request is available to its handler, and cancelled requests
+                // are not answered, as required by the specification
+                let result = tokio::select! {
+                    result = with_request_context(context, processing) => Some(result),
+                    _ = cancellation_token.cancelled() => None,
+                };
+                self.unregister_request(&request_id);
+                let Some(result) = result else {
+                    return Ok(());
+                };
+                // create a response to send back to the client
+                let result = result.map(|mut success_value| {
+                    // hide the disabled tools
+                    if let ResultFromServer::ServerResult(ServerResult::ListToolsResult(
+                        list_tools_result,
+                    )) = &mut success_value
+                    {
+                        self.record_listed_tools(&list_tools_result.tools);
+                        list_tools_result
+                            .tools
+                            .retain(|tool| self.is_tool_enabled(&tool.name));
+                    }
+                    // truncate oversized tool results
+                    if let (
+                        Some(tool_name),
+                        ResultFromServer::ServerResult(ServerResult::CallToolResult(
+                            call_tool_result,
+                        )),
+                    ) = (tool_name, &mut success_value)
+                    {
+                        self.tool_result_limits.apply(&tool_name, call_tool_result);
+                        if dry_run {
+                            mark_dry_run_result(call_tool_result);
+                        }
+                    }
+                    success_value
+                });
+
+                // send the response back with corresponding request id
+                sender
+                    .send(
+                        self.response_message(result, &request_id, &method),
+                        Some(request_id),
+                    )
+                    .await?;
+            }
+            ClientMessage::Notification(client_jsonrpc_notification) => {
+                self.metrics
+                    .notification_received(&client_jsonrpc_notification.method);
+                // a panicking handler only loses the notification, the panic is reported by the panic hook
+                if let Ok(result) = catch_panic(
+                    self.handler
+                        .handle_notification(client_jsonrpc_notification.notification, self),
+                )
+                .await
+                {
+                    result?;
+                }
+            }
+            ClientMessage::Error(jsonrpc_error) => {
+                if let Ok(result) =
+                    catch_panic(self.handler.handle_error(jsonrpc_error.error, self)).await
+                {
+                    result?;
+                }
+            }
+            // The response is the result of a request, it is processed at the transport level.
+            ClientMessage::Response(_) => {}
+        }
+        Ok(())
+    }
+
+    pub(crate) fn with_session_id(mut self, session_id: SessionId) -> Self {
+        self.session_id = Some(session_id);
+        self
+    }
+
+    // Registers a request being processed, returning its cancellation token
+    fn register_request(&self, request_id: &RequestId) -> CancellationToken {
+        let cancellation_token = CancellationToken::new();
+        if let Ok(mut in_flight_requests) = self.in_flight_requests.write() {
+            in_flight_requests.insert(request_id.clone(), cancellation_token.clone());
+        }
+        cancellation_token
+    }
+
+    fn unregister_request(&self, request_id: &RequestId) {
+        if let Ok(mut in_flight_requests) = self.in_flight_requests.write() {
+            in_flight_requests.remove(request_id);
+        }
+    }
+
+    // Cancels a request being processed, if any
+    fn cancel_request(&self, request_id: &RequestId) {
+        if let Ok(in_flight_requests) = self.in_flight_requests.read() {
+            if let Some(cancellation_token) = in_flight_requests.get(request_id) {
+                cancellation_token.cancel();
+            }
+        }
+    }
+
+    // Creates the response to a request, signing its result along with the id and method of the
+    // request if a signer is set
+    #[cfg_attr(not(feature = "signing"), allow(unused_variables))]
+    fn response_message(
+        &self,
+        result: Result<ResultFromServer, RpcError>,
+        request_id: &RequestId,
+        method: &str,
+    ) -> MessageFromServer {
+        #[cfg(feature = "signing")]
+        let result = result.and_then(|mut result| {
+            if let Some(signer) = &self.signer {
+                crate::signing::sign_result(&mut result, request_id, method, signer.as_ref())?;
+            }
+            Ok(result)
+        });
+        result.map_or_else(MessageFromServer::Error, Into::into)
+    }
+
+    // Records the tools listed by the handler
+    fn record_listed_tools(&self, tools: &[Tool]) {
+        if let Ok(mut listed_tools) = self.listed_tools.write() {
+            for tool in tools {
+                listed_tools.insert(tool.name.clone(), tool.clone());
+            }
+        }
+    }
+
+    /// Returns `true` if a tool advertises that it supports dry runs, see `dry_run`, so that a dry
+    /// run never reaches a tool that would execute it. Tools that are not listed by the handler
+    /// are considered not to support dry runs.
+    async fn tool_supports_dry_run(&self, tool_name: &str) -> bool {
+        self.listed_tool(tool_name)
+            .await
+            .is_some_and(|tool| supports_dry_run(&tool))
+    }
+
+    /// Returns a tool listed by the handler.
+    ///
+    /// Tools are known from the tool lists sent to the client. Tools that are not known yet are
+    /// listed from the handler. Listing stops once the tool is found, when a cursor repeats, or
+    /// after `MAX_TOOL_LIST_PAGES` pages.
+    async fn listed_tool(&self, tool_name: &str) -> Option<Tool> {
+        let known_tool = |tool_name: &str| {
+            self.listed_tools
+                .read()
+                .ok()
+                .and_then(|listed_tools| listed_tools.get(tool_name).cloned())
+        };
+        if let Some(tool) = known_tool(tool_name) {
+            return Some(tool);
+        }
+
+        let mut cursor = None;
+        let mut cursors = HashSet::new();
+        for _ in 0..MAX_TOOL_LIST_PAGES {
+            let request = ListToolsRequest::new(cursor.map(|cursor| ListToolsRequestParams {
+                cursor: Some(cursor),
+            }));
+            let Ok(ResultFromServer::ServerResult(ServerResult::ListToolsResult(
+                list_tools_result,
+            ))) = self.handler.handle_request(request.into(), self).await
+            else {
+                break;
+            };
+            self.record_listed_tools(&list_tools_result.tools);
+            if let Some(tool) = known_tool(tool_name) {
+                return Some(tool);
+            }
+            // a handler returning a cursor it already returned would be listed forever
+            cursor = match list_tools_result.next_cursor {
+                Some(next_cursor) if cursors.insert(next_cursor.clone()) => Some(next_cursor),
+                _ => break,
+            };
+        }
+        None
+    }
+
+    // Passes a request to the handler, after decrypting the arguments of tool calls
+    // and through the tool hooks. Sets the level of the logger, if enabled, on logging/setLevel requests,
+    // and updates the subscriptions, if tracked, on resources/subscribe and resources/unsubscribe requests.
+    pub(crate) async fn handle_request(
+        &self,
+        request: RequestFromClient,
+    ) -> Result<ResultFromServer, RpcError> {
+        #[cfg(feature = "encryption")]
+        let request = self.decrypt_request(request).await?;
+        if let (
+            Some(logger),
+            RequestFromClient::ClientRequest(ClientRequest::SetLevelRequest(set_level_request)),
+        ) = (&self.logger, &request)
+        {
+            logger.set_level(set_level_request.params.level);
+            return Ok(ServerResult::Result(Default::default()).into());
+        }
+        if let Some(subscription_manager) = &self.subscription_manager {
+            match &request {
+                RequestFromClient::ClientRequest(ClientRequest::SubscribeRequest(
+                    subscribe_request,
+                )) => {
+                    subscription_manager.subscribe(&subscribe_request.params.uri)?;
+                    return Ok(ServerResult::Result(Default::default()).into());
+                }
+                RequestFromClient::ClientRequest(ClientRequest::UnsubscribeRequest(
+                    unsubscribe_request,
+                )) => {
+                    subscription_manager.unsubscribe(&unsubscribe_request.params.uri);
+                    return Ok(ServerResult::Result(Default::default()).into());
+                }
+                _ => {}
+            }
+        }
+        match request {
+            RequestFromClient::ClientRequest(ClientRequest::CallToolRequest(call_tool_request))
+                if !self.tool_hooks.is_empty() =>
+            {
+                ToolCallNext::new(self, &self.tool_hooks)
+                    .run(call_tool_request.params)
+                    .await
+                    .map(Into::into)
+            }
+            request => self.handler.handle_request(request, self).await,
+        }
+    }
+
+    // Decrypts the arguments of a tool call request marked as encrypted in the schema of the tool,
+    // other requests are returned as is, and so are the calls of tools that are not listed
+    #[cfg(feature = "encryption")]
+    async fn decrypt_request(
+        &self,
+        mut request: RequestFromClient,
+    ) -> Result<RequestFromClient, RpcError> {
+        if let (
+            Some(key_provider),
+            RequestFromClient::ClientRequest(ClientRequest::CallToolRequest(call_tool_request)),
+        ) = (&self.key_provider, &mut request)
+        {
+            if let (Some(tool), Some(arguments)) = (
+                self.listed_tool(&call_tool_request.params.name).await,
+                call_tool_request.params.arguments.as_mut(),
+            ) {
+                crate::encryption::decrypt_arguments(&tool, arguments, key_provider.as_ref())?;
+            }
+        }
+        Ok(request)
+    }
+
     pub(crate) async fn set_message_sender(&self, sender: MessageDispatcher<ClientMessage>) {
         let mut lock = self.message_sender.write().await;
         *lock = Some(sender);
@@ -154,15 +1014,370 @@ impl ServerRuntime {
     pub(crate) fn new(
         server_details: InitializeResult,
         transport: impl Transport<ClientMessage, MessageFromServer>,
-        handler: Box<dyn McpServerHandler>,
+        handler: Arc<dyn McpServerHandler>,
     ) -> Self {
         Self {
+            supported_protocol_versions: vec![server_details.protocol_version.clone()],
+            auto_initialize: false,
             server_details,
             client_details: Arc::new(RwLock::new(None)),
             transport: Box::new(transport),
             handler,
+            session_id: None,
+            session_store: SessionStore::new(),
             message_sender: tokio::sync::RwLock::new(None),
             error_stream: tokio::sync::RwLock::new(None),
+            tool_result_limits: ToolResultLimits::default(),
+            tool_error_policy: ToolErrorPolicy::default(),
+            max_sampling_depth: DEFAULT_MAX_SAMPLING_DEPTH,
+            enforce_strict_capabilities: false,
+            load_shedding_policy: None,
+            keep_alive: None,
+            paused: watch::Sender::new(false),
+            disabled_tools: RwLock::new(HashSet::new()),
+            tool_list_changed: watch::Sender::new(()),
+            middlewares: vec![],
+            tool_hooks: vec![],
+            listed_tools: RwLock::new(HashMap::new()),
+            in_flight_requests: RwLock::new(HashMap::new()),
+            metrics: Arc::new(DefaultMetrics::new()),
+            logger: None,
+            subscription_manager: None,
+            resource_registry: None,
+            prompt_registry: None,
+            completion_registry: None,
+            #[cfg(feature = "encryption")]
+            key_provider: None,
+            #[cfg(feature = "signing")]
+            signer: None,
+        }
+    }
+}
+
+// Whether the result of a request is a failure: an error, or a tool result reporting an error
+fn is_failed_request(result: &Result<ResultFromServer, RpcError>) -> bool {
+    match result {
+        Ok(ResultFromServer::ServerResult(ServerResult::CallToolResult(call_tool_result))) => {
+            call_tool_result.is_error == Some(true)
+        }
+        Ok(_) => false,
+        Err(_) => true,
+    }
+}
+
+// Runs a future, returning the payload of its panic if it panics
+async fn catch_panic<F: Future>(future: F) -> Result<F::Output, Box<dyn Any + Send>> {
+    AssertUnwindSafe(future).catch_unwind().await
+}
+
+// Returns the message of a panic, if it was raised with a message
+fn panic_message(panic: &Box<dyn Any + Send>) -> &str {
+    panic
+        .downcast_ref::<&str>()
+        .copied()
+        .or_else(|| panic.downcast_ref::<String>().map(String::as_str))
+        .unwrap_or("no message")
+}
+
+// Returns `true` if the message is a ping request
+fn is_ping_request(message: &ClientMessage) -> bool {
+    matches!(
+        message,
+        ClientMessage::Request(client_jsonrpc_request)
+            if matches!(
+                client_jsonrpc_request.request,
+                RequestFromClient::ClientRequest(ClientRequest::PingRequest(_))
+            )
+    )
+}
+
+// Returns the id of the request cancelled by a cancelled notification
+fn cancelled_request_id(mcp_message: &ClientMessage) -> Option<&RequestId> {
+    match mcp_message {
+        ClientMessage::Notification(client_jsonrpc_notification) => {
+            match &client_jsonrpc_notification.notification {
+                NotificationFromClient::ClientNotification(
+                    ClientNotification::CancelledNotification(cancelled_notification),
+                ) => Some(&cancelled_notification.params.request_id),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcp_server::ServerHandler;
+    use crate::tool_dry_run::set_dry_run;
+    use rust_mcp_schema::schema_utils::{CallToolError, ClientJsonrpcRequest};
+    use rust_mcp_schema::{
+        CallToolRequest, CallToolRequestParams, CallToolResult, ListToolsResult, PingRequest,
+    };
+    use rust_mcp_transport::error::TransportResult;
+    use rust_mcp_transport::PendingRequests;
+    use serde_json::Value;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tokio::io::{AsyncBufReadExt, BufReader, DuplexStream, Lines};
+    use tokio::sync::mpsc;
+
+    // A transport receiving the messages sent to `messages`, and writing the messages of the server
+    // to the other end of `output`
+    pub(super) struct TestTransport {
+        messages: std::sync::Mutex<Option<mpsc::UnboundedReceiver<ClientMessage>>>,
+        output: std::sync::Mutex<Option<DuplexStream>>,
+    }
+
+    #[async_trait]
+    impl Transport<ClientMessage, MessageFromServer> for TestTransport {
+        async fn start(
+            &self,
+        ) -> TransportResult<(
+            Pin<Box<dyn Stream<Item = ClientMessage> + Send>>,
+            MessageDispatcher<ClientMessage>,
+            IoStream,
+        )> {
+            let messages = self.messages.lock().unwrap().take().unwrap();
+            let stream = futures::stream::unfold(messages, |mut messages| async move {
+                messages.recv().await.map(|message| (message, messages))
+            });
+            let output = self.output.lock().unwrap().take().unwrap();
+            let pending_requests = Arc::new(PendingRequests::new());
+            let sender = MessageDispatcher::new(
+                pending_requests.clone(),
+                tokio::sync::Mutex::new(Box::pin(output)),
+                pending_requests.message_id_counter(),
+                5_000,
+            );
+            Ok((
+                Box::pin(stream),
+                sender,
+                IoStream::Writable(Box::pin(tokio::io::sink())),
+            ))
+        }
+
+        async fn shut_down(&self) -> TransportResult<()> {
+            Ok(())
+        }
+
+        async fn is_shut_down(&self) -> bool {
+            false
+        }
+    }
+
+    // Lists its tools slowly, and answers pings with an error
+    struct SlowHandler;
+
+    #[async_trait]
+    impl ServerHandler for SlowHandler {
+        async fn handle_ping_request(
+            &self,
+            _: PingRequest,
+            _: &dyn McpServer,
+        ) -> Result<rust_mcp_schema::Result, RpcError> {
+            Err(RpcError::internal_error().with_message("Ping handled by the handler".to_string()))
+        }
+
+        async fn handle_list_tools_request(
+            &self,
+            _: ListToolsRequest,
+            _: &dyn McpServer,
+        ) -> Result<ListToolsResult, RpcError> {
+            tokio::time::sleep(Duration::from_millis(300)).await;
+            Ok(ListToolsResult {
+                meta: None,
+                next_cursor: None,
+                tools: vec![],
+            })
+        }
+    }
+
+    // Records the methods of the requests passed through the middleware chain
+    struct RecordMethods(Arc<std::sync::Mutex<Vec<String>>>);
+
+    #[async_trait]
+    impl ServerMiddleware for RecordMethods {
+        async fn handle(
+            &self,
+            request: RequestFromClient,
+            _: &dyn McpServer,
+            next: RequestNext<'_>,
+        ) -> Result<ResultFromServer, RpcError> {
+            self.0.lock().unwrap().push(request.method().to_string());
+            next.run(request).await
+        }
+    }
+
+    // Creates a transport along with the sender of the messages it receives, and the lines it sends
+    pub(super) fn test_transport() -> (
+        TestTransport,
+        mpsc::UnboundedSender<ClientMessage>,
+        Lines<BufReader<DuplexStream>>,
+    ) {
+        let (messages_tx, messages_rx) = mpsc::unbounded_channel();
+        let (output, client_io) = tokio::io::duplex(64 * 1024);
+        let transport = TestTransport {
+            messages: std::sync::Mutex::new(Some(messages_rx)),
+            output: std::sync::Mutex::new(Some(output)),
+        };
+        (transport, messages_tx, BufReader::new(client_io).lines())
+    }
+
+    pub(super) async fn next_response(lines: &mut Lines<BufReader<DuplexStream>>) -> Value {
+        let line = tokio::time::timeout(Duration::from_secs(5), lines.next_line())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        serde_json::from_str(&line).unwrap()
+    }
+
+    pub(super) fn request(id: i64, request: ClientRequest) -> ClientMessage {
+        ClientMessage::Request(ClientJsonrpcRequest::new(
+            RequestId::Integer(id),
+            request.into(),
+        ))
+    }
+
+    #[tokio::test]
+    async fn test_pings_received_during_a_request_are_handled_by_the_handler() {
+        let (transport, messages_tx, mut lines) = test_transport();
+        let methods = Arc::new(std::sync::Mutex::new(vec![]));
+        let server = mcp_server_runtime::create_server(
+            ServerRuntime::info("test-server", "1.0.0").build(),
+            transport,
+            SlowHandler,
+        )
+        .with_middleware(RecordMethods(methods.clone()));
+        let server = tokio::spawn(async move { server.start().await });
+
+        // the ping is received while the tools are listed
+        messages_tx
+            .send(request(1, ListToolsRequest::new(None).into()))
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        messages_tx
+            .send(request(2, PingRequest::new(None).into()))
+            .unwrap();
+
+        // the ping is answered first, by the handler
+        let ping_response = next_response(&mut lines).await;
+        assert_eq!(ping_response["id"], 2);
+        assert_eq!(
+            ping_response["error"]["message"],
+            "Ping handled by the handler"
+        );
+        let list_tools_response = next_response(&mut lines).await;
+        assert_eq!(list_tools_response["id"], 1);
+        assert!(list_tools_response["result"]["tools"].is_array());
+
+        assert_eq!(*methods.lock().unwrap(), ["tools/list", "ping"]);
+
+        drop(messages_tx);
+        server.await.unwrap().unwrap();
+    }
+
+    // Returns a new page of tools forever, each page pointing to the next one with the same cursor
+    struct EndlessToolPages(Arc<AtomicUsize>);
+
+    #[async_trait]
+    impl ServerHandler for EndlessToolPages {
+        async fn handle_list_tools_request(
+            &self,
+            _: ListToolsRequest,
+            _: &dyn McpServer,
+        ) -> Result<ListToolsResult, RpcError> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(ListToolsResult {
+                meta: None,
+                next_cursor: Some("next".to_string()),
+                tools: vec![],
+            })
         }
     }
+
+    #[tokio::test]
+    async fn test_dry_runs_of_unknown_tools_stop_listing_on_repeated_cursors() {
+        let (transport, messages_tx, mut lines) = test_transport();
+        let pages = Arc::new(AtomicUsize::new(0));
+        let server = mcp_server_runtime::create_server(
+            ServerRuntime::info("test-server", "1.0.0").build(),
+            transport,
+            EndlessToolPages(pages.clone()),
+        );
+        let server = tokio::spawn(async move { server.start().await });
+
+        let mut params = CallToolRequestParams {
+            arguments: None,
+            name: "unknown".to_string(),
+        };
+        set_dry_run(&mut params, true);
+        messages_tx
+            .send(request(1, CallToolRequest::new(params).into()))
+            .unwrap();
+
+        let response = next_response(&mut lines).await;
+        assert_eq!(response["id"], 1);
+        assert_eq!(
+            response["error"]["message"],
+            "Tool unknown does not support dry runs"
+        );
+        // the first page, then the page of the cursor, whose next cursor is the same
+        assert_eq!(pages.load(Ordering::SeqCst), 2);
+
+        drop(messages_tx);
+        server.await.unwrap().unwrap();
+    }
+
+    // Panics when a tool is called
+    struct PanickingTools;
+
+    #[async_trait]
+    impl ServerHandler for PanickingTools {
+        async fn handle_call_tool_request(
+            &self,
+            _: CallToolRequest,
+            _: &dyn McpServer,
+        ) -> std::result::Result<CallToolResult, CallToolError> {
+            panic!("the tool is broken")
+        }
+    }
+
+    #[tokio::test]
+    async fn test_panicking_handlers_fail_the_request_only() {
+        let (transport, messages_tx, mut lines) = test_transport();
+        let server = mcp_server_runtime::create_server(
+            ServerRuntime::info("test-server", "1.0.0").build(),
+            transport,
+            PanickingTools,
+        );
+        let server = tokio::spawn(async move { server.start().await });
+
+        let params = CallToolRequestParams {
+            arguments: None,
+            name: "broken".to_string(),
+        };
+        messages_tx
+            .send(request(1, CallToolRequest::new(params).into()))
+            .unwrap();
+        let response = next_response(&mut lines).await;
+        assert_eq!(response["id"], 1);
+        assert_eq!(response["error"]["code"], RpcError::internal_error().code);
+        assert_eq!(
+            response["error"]["message"],
+            "Internal error: the request handler panicked (the tool is broken)"
+        );
+
+        // the runtime keeps serving the following requests
+        messages_tx
+            .send(request(2, PingRequest::new(None).into()))
+            .unwrap();
+        let response = next_response(&mut lines).await;
+        assert_eq!(response["id"], 2);
+        assert!(response["result"].is_object());
+
+        drop(messages_tx);
+        server.await.unwrap().unwrap();
+    }
 }
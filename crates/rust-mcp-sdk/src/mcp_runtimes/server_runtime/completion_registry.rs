@@ -0,0 +1,261 @@
+use std::sync::{Arc, RwLock};
+
+use async_trait::async_trait;
+use rust_mcp_schema::{
+    CompleteRequestParams, CompleteRequestParamsRef, CompleteResult, CompleteResultCompletion,
+    RpcError,
+};
+
+/// Maximum number of values of a completion, as required by the MCP specification
+pub const MAX_COMPLETION_VALUES: usize = 100;
+
+/// Provides the candidate values of a prompt argument or of a variable of a resource template,
+/// to complete the value typed by the user.
+///
+/// The candidates do not need to be filtered: the `CompletionRegistry` keeps those starting with the
+/// typed value, and caps them to `MAX_COMPLETION_VALUES`.
+///
+/// # Example
+/// ```ignore
+/// struct TableNames(Arc<Database>);
+///
+/// #[async_trait]
+/// impl CompletionProvider for TableNames {
+///     async fn candidates(&self, value: &str) -> Result<Vec<String>, RpcError> {
+///         self.0.table_names().await
+///     }
+/// }
+/// ```
+#[async_trait]
+pub trait CompletionProvider: Send + Sync + 'static {
+    /// Returns the candidate values, given the value typed so far.
+    async fn candidates(&self, value: &str) -> Result<Vec<String>, RpcError>;
+}
+
+/// A fixed list of candidate values
+#[async_trait]
+impl CompletionProvider for Vec<String> {
+    async fn candidates(&self, _value: &str) -> Result<Vec<String>, RpcError> {
+        Ok(self.clone())
+    }
+}
+
+/// A fixed list of candidate values
+#[async_trait]
+impl<const N: usize> CompletionProvider for [&'static str; N] {
+    async fn candidates(&self, _value: &str) -> Result<Vec<String>, RpcError> {
+        Ok(self.iter().map(|value| value.to_string()).collect())
+    }
+}
+
+// What a completion provider completes: a prompt argument or a variable of a resource template
+#[derive(PartialEq, Eq)]
+enum CompletionTarget {
+    PromptArgument {
+        prompt: String,
+        argument: String,
+    },
+    TemplateVariable {
+        uri_template: String,
+        variable: String,
+    },
+}
+
+/// Completes the arguments of prompts and the variables of resource templates, so that handlers do
+/// not need to implement `completion/complete` themselves.
+///
+/// A `CompletionProvider` is registered for each prompt argument or template variable that can be
+/// completed. Once enabled with `ServerRuntime::with_completion_registry()`, the default
+/// implementation of `ServerHandler::handle_complete_request()` finds the provider matching the
+/// reference and the argument of the request, keeps the candidates starting with the typed value and
+/// returns at most `MAX_COMPLETION_VALUES` of them, along with their total number. Arguments without
+/// a provider have no completions.
+///
+/// # Example
+/// ```ignore
+/// let registry = CompletionRegistry::new();
+/// registry
+///     .register_prompt_argument("code_review", "language", ["rust", "python", "typescript"])
+///     .register_template_variable("db://{table}/{id}", "table", TableNames(Arc::clone(&db)));
+///
+/// let server = server_runtime::create_server(server_details, transport, handler)
+///     .with_completion_registry(registry);
+/// ```
+#[derive(Default)]
+pub struct CompletionRegistry {
+    providers: RwLock<Vec<(CompletionTarget, Arc<dyn CompletionProvider>)>>,
+}
+
+impl CompletionRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the provider completing an argument of a prompt.
+    /// Replaces the provider previously registered for that argument, if any.
+    pub fn register_prompt_argument(
+        &self,
+        prompt: impl Into<String>,
+        argument: impl Into<String>,
+        provider: impl CompletionProvider,
+    ) -> &Self {
+        let target = CompletionTarget::PromptArgument {
+            prompt: prompt.into(),
+            argument: argument.into(),
+        };
+        self.register(target, Arc::new(provider))
+    }
+
+    /// Registers the provider completing a variable of a resource template, identified by its uri template.
+    /// Replaces the provider previously registered for that variable, if any.
+    pub fn register_template_variable(
+        &self,
+        uri_template: impl Into<String>,
+        variable: impl Into<String>,
+        provider: impl CompletionProvider,
+    ) -> &Self {
+        let target = CompletionTarget::TemplateVariable {
+            uri_template: uri_template.into(),
+            variable: variable.into(),
+        };
+        self.register(target, Arc::new(provider))
+    }
+
+    /// Completes the argument of a `completion/complete` request.
+    ///
+    /// # Errors
+    /// Returns the error returned by the provider of the argument.
+    pub async fn complete(
+        &self,
+        params: &CompleteRequestParams,
+    ) -> Result<CompleteResult, RpcError> {
+        let target = match &params.ref_ {
+            CompleteRequestParamsRef::PromptReference(prompt_reference) => {
+                CompletionTarget::PromptArgument {
+                    prompt: prompt_reference.name.clone(),
+                    argument: params.argument.name.clone(),
+                }
+            }
+            CompleteRequestParamsRef::ResourceReference(resource_reference) => {
+                CompletionTarget::TemplateVariable {
+                    uri_template: resource_reference.uri.clone(),
+                    variable: params.argument.name.clone(),
+                }
+            }
+        };
+        let provider = self.providers.read().ok().and_then(|providers| {
+            providers
+                .iter()
+                .find(|(registered, _)| *registered == target)
+                .map(|(_, provider)| Arc::clone(provider))
+        });
+
+        let value = &params.argument.value;
+        let mut values = match provider {
+            Some(provider) => provider.candidates(value).await?,
+            None => vec![],
+        };
+        values.retain(|candidate| candidate.starts_with(value.as_str()));
+        let total = values.len();
+        values.truncate(MAX_COMPLETION_VALUES);
+
+        Ok(CompleteResult {
+            completion: CompleteResultCompletion {
+                has_more: Some(total > values.len()),
+                total: Some(total as i64),
+                values,
+            },
+            meta: None,
+        })
+    }
+
+    fn register(&self, target: CompletionTarget, provider: Arc<dyn CompletionProvider>) -> &Self {
+        if let Ok(mut providers) = self.providers.write() {
+            match providers
+                .iter_mut()
+                .find(|(registered, _)| *registered == target)
+            {
+                Some((_, registered)) => *registered = provider,
+                None => providers.push((target, provider)),
+            }
+        }
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_mcp_schema::{CompleteRequestParamsArgument, PromptReference, ResourceReference};
+
+    fn params(
+        ref_: CompleteRequestParamsRef,
+        argument: &str,
+        value: &str,
+    ) -> CompleteRequestParams {
+        CompleteRequestParams {
+            argument: CompleteRequestParamsArgument {
+                name: argument.to_string(),
+                value: value.to_string(),
+            },
+            ref_,
+        }
+    }
+
+    fn prompt(name: &str) -> CompleteRequestParamsRef {
+        CompleteRequestParamsRef::PromptReference(PromptReference::new(name.to_string()))
+    }
+
+    #[tokio::test]
+    async fn test_complete_prompt_arguments_and_template_variables() {
+        let registry = CompletionRegistry::new();
+        registry
+            .register_prompt_argument("code_review", "language", ["rust", "ruby", "python"])
+            .register_template_variable(
+                "db://{table}/{id}",
+                "table",
+                vec!["users".to_string(), "orders".to_string()],
+            );
+
+        let result = registry
+            .complete(&params(prompt("code_review"), "language", "ru"))
+            .await
+            .unwrap();
+        assert_eq!(result.completion.values, ["rust", "ruby"]);
+        assert_eq!(result.completion.total, Some(2));
+        assert_eq!(result.completion.has_more, Some(false));
+
+        let template = CompleteRequestParamsRef::ResourceReference(ResourceReference::new(
+            "db://{table}/{id}".to_string(),
+        ));
+        let result = registry
+            .complete(&params(template, "table", ""))
+            .await
+            .unwrap();
+        assert_eq!(result.completion.values, ["users", "orders"]);
+
+        // arguments without a provider have no completions
+        let result = registry
+            .complete(&params(prompt("code_review"), "code", ""))
+            .await
+            .unwrap();
+        assert!(result.completion.values.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_complete_caps_the_values() {
+        let registry = CompletionRegistry::new();
+        let candidates: Vec<String> = (0..150).map(|index| format!("value{}", index)).collect();
+        registry
+            .register_prompt_argument("numbers", "value", vec!["replaced".to_string()])
+            .register_prompt_argument("numbers", "value", candidates);
+
+        let result = registry
+            .complete(&params(prompt("numbers"), "value", "value"))
+            .await
+            .unwrap();
+        assert_eq!(result.completion.values.len(), MAX_COMPLETION_VALUES);
+        assert_eq!(result.completion.total, Some(150));
+        assert_eq!(result.completion.has_more, Some(true));
+    }
+}
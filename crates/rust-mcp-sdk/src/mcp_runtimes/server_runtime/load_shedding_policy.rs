@@ -0,0 +1,69 @@
+use rust_mcp_schema::schema_utils::RequestFromClient;
+use rust_mcp_schema::{InitializeRequest, PingRequest, RpcError};
+use serde_json::json;
+
+use crate::error::SERVER_BUSY_ERROR_CODE;
+
+/// Load shedding policy of a server, limiting the number of requests waiting to be processed.
+///
+/// Requests are processed one at a time. While a request is processed, the following messages are
+/// read ahead: once `max_pending_requests` requests are pending, including the one being processed,
+/// new requests are immediately answered with a retryable "server busy" error, with the
+/// `SERVER_BUSY_ERROR_CODE` code, instead of being queued.
+///
+/// Essential requests, `initialize` and `ping` by default, as well as notifications, are never shed.
+///
+/// # Example
+/// ```ignore
+/// let server = server_runtime::create_server(server_details, transport, handler)
+///     .with_load_shedding_policy(
+///         LoadSheddingPolicy::new(32).with_essential_method("resources/unsubscribe"),
+///     );
+/// ```
+#[derive(Debug, Clone)]
+pub struct LoadSheddingPolicy {
+    /// The maximum number of pending requests, including the one being processed
+    pub max_pending_requests: usize,
+    /// Methods of the requests that are never shed
+    pub essential_methods: Vec<String>,
+}
+
+impl LoadSheddingPolicy {
+    /// Creates a policy shedding requests when `max_pending_requests` are pending,
+    /// with `initialize` and `ping` as essential methods.
+    pub fn new(max_pending_requests: usize) -> Self {
+        Self {
+            max_pending_requests,
+            essential_methods: vec![InitializeRequest::method_name(), PingRequest::method_name()],
+        }
+    }
+
+    /// Adds a method whose requests are never shed.
+    pub fn with_essential_method(mut self, method: impl Into<String>) -> Self {
+        self.essential_methods.push(method.into());
+        self
+    }
+
+    /// Returns `true` if requests of the given method are never shed.
+    pub fn is_essential(&self, method: &str) -> bool {
+        self.essential_methods
+            .iter()
+            .any(|essential_method| essential_method == method)
+    }
+
+    /// Returns the error answering `request` if it must be shed, given the number of pending requests.
+    pub(crate) fn shed(
+        &self,
+        request: &RequestFromClient,
+        pending_requests: usize,
+    ) -> Option<RpcError> {
+        if pending_requests < self.max_pending_requests || self.is_essential(request.method()) {
+            return None;
+        }
+        Some(RpcError {
+            code: SERVER_BUSY_ERROR_CODE,
+            data: Some(json!({ "pendingRequests": pending_requests })),
+            message: "Server busy, retry later".to_string(),
+        })
+    }
+}
@@ -1,10 +1,12 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use rust_mcp_schema::{
     schema_utils::{
-        CallToolError, ClientMessage, MessageFromServer, NotificationFromClient, RequestFromClient,
+        ClientMessage, MessageFromServer, NotificationFromClient, RequestFromClient,
         ResultFromServer,
     },
-    CallToolResult, InitializeResult, RpcError,
+    InitializeResult, RpcError,
 };
 use rust_mcp_transport::Transport;
 
@@ -14,7 +16,7 @@ use crate::{
     mcp_traits::{mcp_handler::McpServerHandler, mcp_server::McpServer},
 };
 
-use super::ServerRuntime;
+use super::{from_call_tool_error, ServerRuntime, SessionServer};
 
 /// Creates a new MCP server runtime with the specified configuration.
 ///
@@ -42,7 +44,19 @@ pub fn create_server(
     ServerRuntime::new(
         server_details,
         transport,
-        Box::new(ServerRuntimeInternalHandler::new(Box::new(handler))),
+        Arc::new(ServerRuntimeInternalHandler::new(Box::new(handler))),
+    )
+}
+
+/// Creates a server serving multiple concurrent client sessions with the same server details and
+/// handler, e.g. one session per connection of a network transport, see `SessionServer`.
+pub fn create_session_server(
+    server_details: InitializeResult,
+    handler: impl ServerHandler,
+) -> SessionServer {
+    SessionServer::new(
+        server_details,
+        Arc::new(ServerRuntimeInternalHandler::new(Box::new(handler))),
     )
 }
 
@@ -62,98 +76,7 @@ impl McpServerHandler for ServerRuntimeInternalHandler<Box<dyn ServerHandler>> {
         client_jsonrpc_request: RequestFromClient,
         runtime: &dyn McpServer,
     ) -> std::result::Result<ResultFromServer, RpcError> {
-        match client_jsonrpc_request {
-            rust_mcp_schema::schema_utils::RequestFromClient::ClientRequest(client_request) => {
-                match client_request {
-                    rust_mcp_schema::ClientRequest::InitializeRequest(initialize_request) => self
-                        .handler
-                        .handle_initialize_request(initialize_request, runtime)
-                        .await
-                        .map(|value| value.into()),
-                    rust_mcp_schema::ClientRequest::PingRequest(ping_request) => self
-                        .handler
-                        .handle_ping_request(ping_request, runtime)
-                        .await
-                        .map(|value| value.into()),
-                    rust_mcp_schema::ClientRequest::ListResourcesRequest(
-                        list_resources_request,
-                    ) => self
-                        .handler
-                        .handle_list_resources_request(list_resources_request, runtime)
-                        .await
-                        .map(|value| value.into()),
-                    rust_mcp_schema::ClientRequest::ListResourceTemplatesRequest(
-                        list_resource_templates_request,
-                    ) => self
-                        .handler
-                        .handle_list_resource_templates_request(
-                            list_resource_templates_request,
-                            runtime,
-                        )
-                        .await
-                        .map(|value| value.into()),
-                    rust_mcp_schema::ClientRequest::ReadResourceRequest(read_resource_request) => {
-                        self.handler
-                            .handle_read_resource_request(read_resource_request, runtime)
-                            .await
-                            .map(|value| value.into())
-                    }
-                    rust_mcp_schema::ClientRequest::SubscribeRequest(subscribe_request) => self
-                        .handler
-                        .handle_subscribe_request(subscribe_request, runtime)
-                        .await
-                        .map(|value| value.into()),
-                    rust_mcp_schema::ClientRequest::UnsubscribeRequest(unsubscribe_request) => self
-                        .handler
-                        .handle_unsubscribe_request(unsubscribe_request, runtime)
-                        .await
-                        .map(|value| value.into()),
-                    rust_mcp_schema::ClientRequest::ListPromptsRequest(list_prompts_request) => {
-                        self.handler
-                            .handle_list_prompts_request(list_prompts_request, runtime)
-                            .await
-                            .map(|value| value.into())
-                    }
-
-                    rust_mcp_schema::ClientRequest::GetPromptRequest(prompt_request) => self
-                        .handler
-                        .handle_get_prompt_request(prompt_request, runtime)
-                        .await
-                        .map(|value| value.into()),
-                    rust_mcp_schema::ClientRequest::ListToolsRequest(list_tools_request) => self
-                        .handler
-                        .handle_list_tools_request(list_tools_request, runtime)
-                        .await
-                        .map(|value| value.into()),
-                    rust_mcp_schema::ClientRequest::CallToolRequest(call_tool_request) => {
-                        let result = self
-                            .handler
-                            .handle_call_tool_request(call_tool_request, runtime)
-                            .await;
-
-                        Ok(result.map_or_else(
-                            |err| CallToolResult::with_error(CallToolError::new(err)).into(),
-                            |value| value.into(),
-                        ))
-                    }
-                    rust_mcp_schema::ClientRequest::SetLevelRequest(set_level_request) => self
-                        .handler
-                        .handle_set_level_request(set_level_request, runtime)
-                        .await
-                        .map(|value| value.into()),
-                    rust_mcp_schema::ClientRequest::CompleteRequest(complete_request) => self
-                        .handler
-                        .handle_complete_request(complete_request, runtime)
-                        .await
-                        .map(|value| value.into()),
-                }
-            }
-            rust_mcp_schema::schema_utils::RequestFromClient::CustomRequest(value) => self
-                .handler
-                .handle_custom_request(value, runtime)
-                .await
-                .map(|value| value.into()),
-        }
+        dispatch_request(self.handler.as_ref(), client_jsonrpc_request, runtime).await
     }
 
     async fn handle_error(
@@ -170,51 +93,143 @@ impl McpServerHandler for ServerRuntimeInternalHandler<Box<dyn ServerHandler>> {
         client_jsonrpc_notification: NotificationFromClient,
         runtime: &dyn McpServer,
     ) -> SdkResult<()> {
-        match client_jsonrpc_notification {
-            rust_mcp_schema::schema_utils::NotificationFromClient::ClientNotification(
-                client_notification,
-            ) => match client_notification {
-                rust_mcp_schema::ClientNotification::CancelledNotification(
-                    cancelled_notification,
-                ) => {
-                    self.handler
-                        .handle_cancelled_notification(cancelled_notification, runtime)
-                        .await?;
-                }
-                rust_mcp_schema::ClientNotification::InitializedNotification(
-                    initialized_notification,
-                ) => {
-                    self.handler
-                        .handle_initialized_notification(initialized_notification, runtime)
-                        .await?;
-                    self.handler.on_initialized(runtime).await;
+        dispatch_notification(self.handler.as_ref(), client_jsonrpc_notification, runtime).await?;
+        Ok(())
+    }
+
+    async fn on_server_started(&self, runtime: &dyn McpServer) {
+        self.handler.on_server_started(runtime).await;
+    }
+}
+
+// Passes a request to the method of the handler handling its type
+pub(crate) async fn dispatch_request(
+    handler: &dyn ServerHandler,
+    client_jsonrpc_request: RequestFromClient,
+    runtime: &dyn McpServer,
+) -> std::result::Result<ResultFromServer, RpcError> {
+    match client_jsonrpc_request {
+        rust_mcp_schema::schema_utils::RequestFromClient::ClientRequest(client_request) => {
+            match client_request {
+                rust_mcp_schema::ClientRequest::InitializeRequest(initialize_request) => handler
+                    .handle_initialize_request(initialize_request, runtime)
+                    .await
+                    .map(|value| value.into()),
+                rust_mcp_schema::ClientRequest::PingRequest(ping_request) => handler
+                    .handle_ping_request(ping_request, runtime)
+                    .await
+                    .map(|value| value.into()),
+                rust_mcp_schema::ClientRequest::ListResourcesRequest(list_resources_request) => {
+                    handler
+                        .handle_list_resources_request(list_resources_request, runtime)
+                        .await
+                        .map(|value| value.into())
                 }
-                rust_mcp_schema::ClientNotification::ProgressNotification(
-                    progress_notification,
-                ) => {
-                    self.handler
-                        .handle_progress_notification(progress_notification, runtime)
-                        .await?;
+                rust_mcp_schema::ClientRequest::ListResourceTemplatesRequest(
+                    list_resource_templates_request,
+                ) => handler
+                    .handle_list_resource_templates_request(
+                        list_resource_templates_request,
+                        runtime,
+                    )
+                    .await
+                    .map(|value| value.into()),
+                rust_mcp_schema::ClientRequest::ReadResourceRequest(read_resource_request) => {
+                    handler
+                        .handle_read_resource_request(read_resource_request, runtime)
+                        .await
+                        .map(|value| value.into())
                 }
-                rust_mcp_schema::ClientNotification::RootsListChangedNotification(
-                    roots_list_changed_notification,
-                ) => {
-                    self.handler
-                        .handle_roots_list_changed_notification(
-                            roots_list_changed_notification,
-                            runtime,
-                        )
-                        .await?;
+                rust_mcp_schema::ClientRequest::SubscribeRequest(subscribe_request) => handler
+                    .handle_subscribe_request(subscribe_request, runtime)
+                    .await
+                    .map(|value| value.into()),
+                rust_mcp_schema::ClientRequest::UnsubscribeRequest(unsubscribe_request) => handler
+                    .handle_unsubscribe_request(unsubscribe_request, runtime)
+                    .await
+                    .map(|value| value.into()),
+                rust_mcp_schema::ClientRequest::ListPromptsRequest(list_prompts_request) => handler
+                    .handle_list_prompts_request(list_prompts_request, runtime)
+                    .await
+                    .map(|value| value.into()),
+
+                rust_mcp_schema::ClientRequest::GetPromptRequest(prompt_request) => handler
+                    .handle_get_prompt_request(prompt_request, runtime)
+                    .await
+                    .map(|value| value.into()),
+                rust_mcp_schema::ClientRequest::ListToolsRequest(list_tools_request) => handler
+                    .handle_list_tools_request(list_tools_request, runtime)
+                    .await
+                    .map(|value| value.into()),
+                rust_mcp_schema::ClientRequest::CallToolRequest(call_tool_request) => {
+                    let result = handler
+                        .handle_call_tool_request(call_tool_request, runtime)
+                        .await;
+
+                    // errors of the tool are sent as a result, see `ToolErrorPolicy`
+                    result
+                        .or_else(from_call_tool_error)
+                        .map(|value| value.into())
                 }
-            },
-            rust_mcp_schema::schema_utils::NotificationFromClient::CustomNotification(value) => {
-                self.handler.handle_custom_notification(value).await?;
+                rust_mcp_schema::ClientRequest::SetLevelRequest(set_level_request) => handler
+                    .handle_set_level_request(set_level_request, runtime)
+                    .await
+                    .map(|value| value.into()),
+                rust_mcp_schema::ClientRequest::CompleteRequest(complete_request) => handler
+                    .handle_complete_request(complete_request, runtime)
+                    .await
+                    .map(|value| value.into()),
             }
         }
-        Ok(())
+        rust_mcp_schema::schema_utils::RequestFromClient::CustomRequest(value) => handler
+            .handle_custom_request(value, runtime)
+            .await
+            .map(|value| value.into()),
     }
+}
 
-    async fn on_server_started(&self, runtime: &dyn McpServer) {
-        self.handler.on_server_started(runtime).await;
+// Passes a notification to the method of the handler handling its type
+pub(crate) async fn dispatch_notification(
+    handler: &dyn ServerHandler,
+    client_jsonrpc_notification: NotificationFromClient,
+    runtime: &dyn McpServer,
+) -> std::result::Result<(), RpcError> {
+    match client_jsonrpc_notification {
+        rust_mcp_schema::schema_utils::NotificationFromClient::ClientNotification(
+            client_notification,
+        ) => match client_notification {
+            rust_mcp_schema::ClientNotification::CancelledNotification(cancelled_notification) => {
+                handler
+                    .handle_cancelled_notification(cancelled_notification, runtime)
+                    .await?;
+            }
+            rust_mcp_schema::ClientNotification::InitializedNotification(
+                initialized_notification,
+            ) => {
+                handler
+                    .handle_initialized_notification(initialized_notification, runtime)
+                    .await?;
+                handler.on_initialized(runtime).await;
+            }
+            rust_mcp_schema::ClientNotification::ProgressNotification(progress_notification) => {
+                handler
+                    .handle_progress_notification(progress_notification, runtime)
+                    .await?;
+            }
+            rust_mcp_schema::ClientNotification::RootsListChangedNotification(
+                roots_list_changed_notification,
+            ) => {
+                handler
+                    .handle_roots_list_changed_notification(
+                        roots_list_changed_notification,
+                        runtime,
+                    )
+                    .await?;
+            }
+        },
+        rust_mcp_schema::schema_utils::NotificationFromClient::CustomNotification(value) => {
+            handler.handle_custom_notification(value).await?;
+        }
     }
+    Ok(())
 }
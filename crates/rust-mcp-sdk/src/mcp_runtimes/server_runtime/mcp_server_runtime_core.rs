@@ -1,9 +1,11 @@
+use std::sync::Arc;
+
 use async_trait::async_trait;
 use rust_mcp_schema::schema_utils::{
     self, ClientMessage, MessageFromServer, NotificationFromClient, RequestFromClient,
     ResultFromServer,
 };
-use rust_mcp_schema::{InitializeResult, RpcError};
+use rust_mcp_schema::{InitializeResult, RpcError, METHOD_NOT_FOUND};
 use rust_mcp_transport::Transport;
 
 use crate::error::SdkResult;
@@ -11,7 +13,7 @@ use crate::mcp_handlers::mcp_server_handler_core::ServerHandlerCore;
 use crate::mcp_traits::mcp_handler::McpServerHandler;
 use crate::mcp_traits::mcp_server::McpServer;
 
-use super::ServerRuntime;
+use super::{ServerRuntime, SessionServer};
 
 /// Creates a new MCP server runtime with the specified configuration.
 ///
@@ -39,7 +41,19 @@ pub fn create_server(
     ServerRuntime::new(
         server_details,
         transport,
-        Box::new(RuntimeCoreInternalHandler::new(Box::new(handler))),
+        Arc::new(RuntimeCoreInternalHandler::new(Box::new(handler))),
+    )
+}
+
+/// Creates a server serving multiple concurrent client sessions with the same server details and
+/// handler, e.g. one session per connection of a network transport, see `SessionServer`.
+pub fn create_session_server(
+    server_details: InitializeResult,
+    handler: impl ServerHandlerCore,
+) -> SessionServer {
+    SessionServer::new(
+        server_details,
+        Arc::new(RuntimeCoreInternalHandler::new(Box::new(handler))),
     )
 }
 
@@ -61,20 +75,40 @@ impl McpServerHandler for RuntimeCoreInternalHandler<Box<dyn ServerHandlerCore>>
         runtime: &dyn McpServer,
     ) -> std::result::Result<ResultFromServer, RpcError> {
         // store the client details if the request is a client initialization request
+        let mut protocol_version = None;
         if let schema_utils::RequestFromClient::ClientRequest(
             rust_mcp_schema::ClientRequest::InitializeRequest(initialize_request),
         ) = &client_jsonrpc_request
         {
+            // reject clients using an unsupported protocol version before they go any further
+            runtime
+                .assert_protocol_version_supported(&initialize_request.params.protocol_version)?;
+
             // keep a copy of the InitializeRequestParams which includes client_info and capabilities
             runtime
                 .set_client_details(initialize_request.params.clone())
                 .map_err(|err| RpcError::internal_error().with_message(format!("{}", err)))?;
+            protocol_version = Some(initialize_request.params.protocol_version.clone());
         }
 
         // handle request and get the result
-        self.handler
+        let result = self
+            .handler
             .handle_request(client_jsonrpc_request, runtime)
-            .await
+            .await;
+
+        // answer the initialize request in place of a handler not handling it
+        match (protocol_version, result) {
+            (Some(protocol_version), Err(error))
+                if runtime.auto_initialize() && error.code == METHOD_NOT_FOUND =>
+            {
+                // respond with the version requested by the client, as it is supported
+                let mut server_info = runtime.server_info().to_owned();
+                server_info.protocol_version = protocol_version;
+                Ok(server_info.into())
+            }
+            (_, result) => result,
+        }
     }
     async fn handle_error(
         &self,
@@ -94,10 +128,16 @@ impl McpServerHandler for RuntimeCoreInternalHandler<Box<dyn ServerHandlerCore>>
             self.handler.on_initialized(runtime).await;
         }
 
-        // handle notification
-        self.handler
+        // handle notification, only observed by the handler if initialization is handled by the runtime
+        let is_observed =
+            runtime.auto_initialize() && client_jsonrpc_notification.is_initialized_notification();
+        let result = self
+            .handler
             .handle_notification(client_jsonrpc_notification, runtime)
-            .await?;
+            .await;
+        if !is_observed {
+            result?;
+        }
         Ok(())
     }
     async fn on_server_started(&self, runtime: &dyn McpServer) {
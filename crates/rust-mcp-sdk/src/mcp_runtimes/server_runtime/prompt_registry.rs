@@ -0,0 +1,466 @@
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+
+use futures::future::BoxFuture;
+use rust_mcp_schema::{GetPromptRequestParams, GetPromptResult, Prompt, PromptArgument, RpcError};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+
+use crate::mcp_traits::rpc_error_ext::RpcErrorExt;
+
+// Renders a prompt from its validated arguments
+type PromptRenderFn = Arc<
+    dyn Fn(Map<String, Value>) -> BoxFuture<'static, Result<GetPromptResult, RpcError>>
+        + Send
+        + Sync,
+>;
+
+struct RegisteredPrompt {
+    prompt: Prompt,
+    // The JSON schema of the arguments, used to convert their string values
+    arguments_schema: Map<String, Value>,
+    render: PromptRenderFn,
+}
+
+/// Declares the prompts of a server, so that handlers do not need to implement
+/// `prompts/list` and `prompts/get` themselves.
+///
+/// Prompts are registered with their name, description, the JSON schema of their arguments and an
+/// async function rendering their messages. The arguments are declared by a struct deriving
+/// `JsonSchema` and `Deserialize`, whose fields become the arguments of the prompt, described by the
+/// doc comments of the fields, and required unless they are optional.
+///
+/// Once enabled with `ServerRuntime::with_prompt_registry()`, the server declares the `prompts`
+/// capability, and the default implementations of the prompt handlers of `ServerHandler` list the
+/// registered prompts and render them. Prompts are rendered after their arguments are validated:
+/// unknown prompts, missing required arguments, unknown arguments and values that cannot be
+/// converted to the type of their field are rejected with an `invalid_params` error.
+///
+/// # Example
+/// ```ignore
+/// #[derive(Deserialize, JsonSchema)]
+/// struct CodeReviewArgs {
+///     /// The code to review
+///     code: String,
+///     /// The maximum number of comments
+///     max_comments: Option<u32>,
+/// }
+///
+/// let registry = PromptRegistry::new();
+/// registry.register(
+///     "code_review",
+///     "Asks the model to review a piece of code",
+///     CodeReviewArgs::json_schema(),
+///     |args: CodeReviewArgs| async move {
+///         let text = format!("Review this code in {} comments at most:\n{}", args.max_comments.unwrap_or(5), args.code);
+///         Ok(GetPromptResult {
+///             description: None,
+///             messages: vec![PromptMessage { content: TextContent::new(text, None).into(), role: Role::User }],
+///             meta: None,
+///         })
+///     },
+/// );
+///
+/// let server = server_runtime::create_server(server_details, transport, handler)
+///     .with_prompt_registry(registry);
+/// ```
+#[derive(Default)]
+pub struct PromptRegistry {
+    prompts: RwLock<Vec<RegisteredPrompt>>,
+}
+
+impl PromptRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a prompt whose arguments are deserialized into `A`, described by `arguments_schema`,
+    /// usually `A::json_schema()` generated by the `JsonSchema` derive macro, and rendered by the
+    /// async function `render`.
+    /// Replaces the prompt previously registered with the same name, if any.
+    pub fn register<A, F, Fut>(
+        &self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        arguments_schema: Map<String, Value>,
+        render: F,
+    ) -> &Self
+    where
+        A: DeserializeOwned + Send + 'static,
+        F: Fn(A) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<GetPromptResult, RpcError>> + Send + 'static,
+    {
+        let required: Vec<&str> = arguments_schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|required| required.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let properties = arguments_schema
+            .get("properties")
+            .and_then(Value::as_object);
+
+        let arguments = properties
+            .into_iter()
+            .flatten()
+            .map(|(name, property)| PromptArgument {
+                description: property
+                    .get("description")
+                    .and_then(Value::as_str)
+                    .map(str::to_string),
+                name: name.clone(),
+                required: Some(required.contains(&name.as_str())),
+            })
+            .collect();
+
+        let prompt = Prompt {
+            arguments,
+            description: Some(description.into()),
+            name: name.into(),
+        };
+        let render: PromptRenderFn = Arc::new(move |arguments| {
+            match serde_json::from_value::<A>(Value::Object(arguments)) {
+                Ok(arguments) => Box::pin(render(arguments)),
+                Err(error) => Box::pin(std::future::ready(Err(RpcError::invalid_params()
+                    .with_message(format!("Invalid prompt arguments: {}", error))))),
+            }
+        });
+        self.insert(RegisteredPrompt {
+            prompt,
+            arguments_schema,
+            render,
+        })
+    }
+
+    /// Registers a prompt described by `prompt`, rendered by the async function `render` from the
+    /// values of its arguments, which are passed as strings.
+    /// Replaces the prompt previously registered with the same name, if any.
+    pub fn register_prompt<F, Fut>(&self, prompt: Prompt, render: F) -> &Self
+    where
+        F: Fn(HashMap<String, String>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<GetPromptResult, RpcError>> + Send + 'static,
+    {
+        let render: PromptRenderFn = Arc::new(move |arguments| {
+            let arguments = arguments
+                .into_iter()
+                .map(|(name, value)| match value {
+                    Value::String(value) => (name, value),
+                    value => (name, value.to_string()),
+                })
+                .collect();
+            Box::pin(render(arguments))
+        });
+        self.insert(RegisteredPrompt {
+            prompt,
+            arguments_schema: Map::new(),
+            render,
+        })
+    }
+
+    /// Registers existing prompts, e.g. the ones listed by a `handle_list_prompts_request()`
+    /// implementation, all rendered by the async function `render` from their name and the values of
+    /// their arguments, e.g. the logic of the matching `handle_get_prompt_request()`.
+    pub fn register_prompts<F, Fut>(
+        &self,
+        prompts: impl IntoIterator<Item = Prompt>,
+        render: F,
+    ) -> &Self
+    where
+        F: Fn(String, HashMap<String, String>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<GetPromptResult, RpcError>> + Send + 'static,
+    {
+        let render = Arc::new(render);
+        for prompt in prompts {
+            let render = Arc::clone(&render);
+            let name = prompt.name.clone();
+            self.register_prompt(prompt, move |arguments| render(name.clone(), arguments));
+        }
+        self
+    }
+
+    /// Removes the prompt registered with the given name, returning `true` if there was one.
+    pub fn unregister(&self, name: &str) -> bool {
+        self.prompts
+            .write()
+            .map(|mut prompts| {
+                let count = prompts.len();
+                prompts.retain(|prompt| prompt.prompt.name != name);
+                prompts.len() != count
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns the registered prompts, in the order they were registered.
+    pub fn prompts(&self) -> Vec<Prompt> {
+        self.prompts
+            .read()
+            .map(|prompts| prompts.iter().map(|prompt| prompt.prompt.clone()).collect())
+            .unwrap_or_default()
+    }
+
+    /// Validates the arguments of a prompt and renders it.
+    ///
+    /// # Errors
+    /// Returns an `invalid_params` error if the prompt is unknown or its arguments are invalid,
+    /// or the error returned by the function rendering the prompt.
+    pub async fn get(&self, params: GetPromptRequestParams) -> Result<GetPromptResult, RpcError> {
+        let (render, arguments) = {
+            let prompts = self
+                .prompts
+                .read()
+                .map_err(|error| RpcError::internal_error().with_message(error.to_string()))?;
+            let prompt = prompts
+                .iter()
+                .find(|prompt| prompt.prompt.name == params.name)
+                .ok_or_else(|| RpcError::prompt_not_found(&params.name))?;
+            let arguments = validate_prompt_arguments(
+                &prompt.prompt,
+                &prompt.arguments_schema,
+                params.arguments.unwrap_or_default(),
+            )?;
+            (Arc::clone(&prompt.render), arguments)
+        };
+        render(arguments).await
+    }
+
+    fn insert(&self, registered: RegisteredPrompt) -> &Self {
+        if let Ok(mut prompts) = self.prompts.write() {
+            match prompts
+                .iter_mut()
+                .find(|prompt| prompt.prompt.name == registered.prompt.name)
+            {
+                Some(prompt) => *prompt = registered,
+                None => prompts.push(registered),
+            }
+        }
+        self
+    }
+}
+
+/// Checks the arguments of a `prompts/get` request against the arguments declared by `prompt`, and
+/// converts their string values to the JSON types declared in `arguments_schema`, e.g. the
+/// `json_schema()` of the struct deriving `JsonSchema` the arguments are deserialized into.
+/// Values of arguments not described by the schema are kept as strings.
+///
+/// # Errors
+/// Returns an `invalid_params` error if a required argument is missing, an argument is unknown, or a
+/// value cannot be converted to the type of its argument.
+pub fn validate_prompt_arguments(
+    prompt: &Prompt,
+    arguments_schema: &Map<String, Value>,
+    arguments: HashMap<String, String>,
+) -> Result<Map<String, Value>, RpcError> {
+    if let Some(missing) = prompt
+        .arguments
+        .iter()
+        .find(|argument| argument.required == Some(true) && !arguments.contains_key(&argument.name))
+    {
+        return Err(RpcError::invalid_params().with_message(format!(
+            "Missing required argument '{}' of prompt '{}'",
+            missing.name, prompt.name
+        )));
+    }
+
+    let mut values = Map::new();
+    for (name, value) in arguments {
+        if !prompt
+            .arguments
+            .iter()
+            .any(|argument| argument.name == name)
+        {
+            return Err(RpcError::invalid_params().with_message(format!(
+                "Unknown argument '{}' of prompt '{}'",
+                name, prompt.name
+            )));
+        }
+        let argument_type = arguments_schema
+            .get("properties")
+            .and_then(|properties| properties.get(&name))
+            .and_then(|property| match property.get("type") {
+                // optional arguments may be typed e.g. `["integer", "null"]`
+                Some(Value::Array(types)) => types
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .find(|argument_type| *argument_type != "null"),
+                argument_type => argument_type.and_then(Value::as_str),
+            });
+        let value = match argument_type {
+            Some("integer" | "number" | "boolean" | "array" | "object") => {
+                serde_json::from_str(&value).map_err(|_| {
+                    RpcError::invalid_params().with_message(format!(
+                        "Invalid value '{}' of argument '{}' of prompt '{}'",
+                        value, name, prompt.name
+                    ))
+                })?
+            }
+            _ => Value::String(value),
+        };
+        values.insert(name, value);
+    }
+    Ok(values)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_mcp_schema::INVALID_PARAMS;
+    use serde::Deserialize;
+    use serde_json::json;
+
+    #[derive(Deserialize)]
+    struct CodeReviewArgs {
+        code: String,
+        max_comments: Option<u32>,
+    }
+
+    fn code_review_schema() -> Map<String, Value> {
+        let Value::Object(schema) = json!({
+            "type": "object",
+            "properties": {
+                "code": {"type": "string", "description": "The code to review"},
+                "max_comments": {"type": ["integer", "null"]}
+            },
+            "required": ["code"]
+        }) else {
+            unreachable!()
+        };
+        schema
+    }
+
+    // Renders the prompt as its description, for the tests to check the arguments
+    fn rendered(description: String) -> Result<GetPromptResult, RpcError> {
+        Ok(GetPromptResult {
+            description: Some(description),
+            messages: vec![],
+            meta: None,
+        })
+    }
+
+    fn params(name: &str, arguments: &[(&str, &str)]) -> GetPromptRequestParams {
+        GetPromptRequestParams {
+            arguments: Some(
+                arguments
+                    .iter()
+                    .map(|(name, value)| (name.to_string(), value.to_string()))
+                    .collect(),
+            ),
+            name: name.to_string(),
+        }
+    }
+
+    fn registry() -> PromptRegistry {
+        let registry = PromptRegistry::new();
+        registry.register(
+            "code_review",
+            "Reviews code",
+            code_review_schema(),
+            |args: CodeReviewArgs| async move {
+                rendered(format!(
+                    "{} in {} comments",
+                    args.code,
+                    args.max_comments.unwrap_or(5)
+                ))
+            },
+        );
+        registry
+    }
+
+    #[tokio::test]
+    async fn test_register_replaces_the_same_name() {
+        let registry = registry();
+        registry.register_prompt(
+            Prompt {
+                arguments: vec![],
+                description: None,
+                name: "code_review".to_string(),
+            },
+            |_| async { rendered("replaced".to_string()) },
+        );
+        assert_eq!(registry.prompts().len(), 1);
+        assert!(registry.prompts()[0].arguments.is_empty());
+        let result = registry.get(params("code_review", &[])).await.unwrap();
+        assert_eq!(result.description.unwrap(), "replaced");
+
+        assert!(registry.unregister("code_review"));
+        assert!(registry.prompts().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_get_converts_the_arguments() {
+        let registry = registry();
+        let prompt = &registry.prompts()[0];
+        assert_eq!(prompt.arguments[0].name, "code");
+        assert_eq!(
+            prompt.arguments[0].description.as_deref(),
+            Some("The code to review")
+        );
+        assert_eq!(prompt.arguments[0].required, Some(true));
+        assert_eq!(prompt.arguments[1].required, Some(false));
+
+        let result = registry
+            .get(params(
+                "code_review",
+                &[("code", "fn main() {}"), ("max_comments", "3")],
+            ))
+            .await
+            .unwrap();
+        assert_eq!(result.description.unwrap(), "fn main() {} in 3 comments");
+
+        let error = registry.get(params("unknown", &[])).await.unwrap_err();
+        assert_eq!(error.code, INVALID_PARAMS);
+        assert_eq!(error.message, "Unknown prompt: unknown");
+    }
+
+    #[test]
+    fn test_validate_prompt_arguments() {
+        let registry = registry();
+        let prompt = &registry.prompts()[0];
+        let schema = code_review_schema();
+        let arguments = |arguments: &[(&str, &str)]| {
+            arguments
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect()
+        };
+
+        let values = validate_prompt_arguments(
+            prompt,
+            &schema,
+            arguments(&[("code", "42"), ("max_comments", "42")]),
+        )
+        .unwrap();
+        // string arguments are kept as strings, the others are parsed
+        assert_eq!(values["code"], "42");
+        assert_eq!(values["max_comments"], 42);
+
+        let error = validate_prompt_arguments(prompt, &schema, arguments(&[("max_comments", "1")]))
+            .unwrap_err();
+        assert_eq!(error.code, INVALID_PARAMS);
+        assert_eq!(
+            error.message,
+            "Missing required argument 'code' of prompt 'code_review'"
+        );
+
+        let error = validate_prompt_arguments(
+            prompt,
+            &schema,
+            arguments(&[("code", "x"), ("language", "rust")]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.message,
+            "Unknown argument 'language' of prompt 'code_review'"
+        );
+
+        let error = validate_prompt_arguments(
+            prompt,
+            &schema,
+            arguments(&[("code", "x"), ("max_comments", "many")]),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error.message,
+            "Invalid value 'many' of argument 'max_comments' of prompt 'code_review'"
+        );
+    }
+}
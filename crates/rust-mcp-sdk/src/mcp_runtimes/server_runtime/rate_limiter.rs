@@ -0,0 +1,319 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use rust_mcp_schema::schema_utils::{RequestFromClient, ResultFromServer};
+use rust_mcp_schema::{InitializeRequest, PingRequest, RpcError};
+use rust_mcp_transport::SessionId;
+use serde_json::json;
+
+use crate::error::RATE_LIMITED_ERROR_CODE;
+use crate::mcp_traits::mcp_server::McpServer;
+
+use super::{RequestNext, ServerMiddleware};
+
+/// A rate limit: a token bucket holding up to `burst` requests, refilled at `requests_per_second`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// The number of requests accepted at once after a quiet period
+    pub burst: u32,
+    /// The sustained number of requests accepted per second
+    pub requests_per_second: f64,
+}
+
+impl RateLimit {
+    /// Accepts `requests` requests per second, in bursts of up to `requests` requests.
+    pub fn per_second(requests: u32) -> Self {
+        Self {
+            burst: requests,
+            requests_per_second: requests as f64,
+        }
+    }
+
+    /// Accepts `requests` requests per minute, in bursts of up to `requests` requests.
+    pub fn per_minute(requests: u32) -> Self {
+        Self {
+            burst: requests,
+            requests_per_second: requests as f64 / 60.0,
+        }
+    }
+
+    /// Sets the number of requests accepted at once after a quiet period.
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst;
+        self
+    }
+}
+
+// The key of the bucket of requests, by session if limited per session, and by method if the method has its own limit
+type BucketKey = (Option<SessionId>, Option<String>);
+
+/// The interval between the evictions of the buckets that have refilled
+const BUCKET_EVICTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A `ServerMiddleware` limiting the rate of the requests of clients with token buckets, protecting
+/// public-facing servers from runaway clients.
+///
+/// Requests are limited by the limit of their method, if set with `with_method_limit()`, or else by
+/// the limit shared by the other methods, if set with `with_limit()`. Limits apply per session, see
+/// `SessionServer`, unless disabled with `per_session(false)`. Requests exceeding their limit are
+/// answered with an error with the `RATE_LIMITED_ERROR_CODE` code, or the one set with
+/// `with_error()`, whose data holds the delay before the next request is accepted, in `retryAfterMs`.
+///
+/// `initialize` and `ping` requests are never limited. Clones of a rate limiter share its buckets,
+/// e.g. to limit the sessions of a `SessionServer` with the same limiter. The buckets that have
+/// refilled are evicted every minute, so that the buckets of ended sessions do not accumulate.
+///
+/// # Example
+/// ```ignore
+/// let rate_limiter = RateLimiter::new()
+///     .with_limit(RateLimit::per_second(20))
+///     .with_method_limit("tools/call", RateLimit::per_minute(30).with_burst(5));
+///
+/// let server = server_runtime::create_server(server_details, transport, handler)
+///     .with_middleware(rate_limiter);
+/// ```
+#[derive(Clone)]
+pub struct RateLimiter {
+    limit: Option<RateLimit>,
+    method_limits: HashMap<String, RateLimit>,
+    exempt_methods: Vec<String>,
+    per_session: bool,
+    error_code: i64,
+    error_message: String,
+    buckets: Arc<Mutex<Buckets>>,
+}
+
+struct Buckets {
+    buckets: HashMap<BucketKey, TokenBucket>,
+    evicted_at: Instant,
+}
+
+impl Default for RateLimiter {
+    fn default() -> Self {
+        Self {
+            limit: None,
+            method_limits: HashMap::new(),
+            exempt_methods: vec![InitializeRequest::method_name(), PingRequest::method_name()],
+            per_session: true,
+            error_code: RATE_LIMITED_ERROR_CODE,
+            error_message: "Rate limit exceeded, retry later".to_string(),
+            buckets: Arc::new(Mutex::new(Buckets {
+                buckets: HashMap::new(),
+                evicted_at: Instant::now(),
+            })),
+        }
+    }
+}
+
+impl RateLimiter {
+    /// Creates a rate limiter without limits.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the limit shared by the requests of the methods without a limit of their own.
+    pub fn with_limit(mut self, limit: RateLimit) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Sets the limit of the requests of a method, e.g. `tools/call`.
+    pub fn with_method_limit(mut self, method: impl Into<String>, limit: RateLimit) -> Self {
+        self.method_limits.insert(method.into(), limit);
+        self
+    }
+
+    /// Adds a method whose requests are never limited, in addition to `initialize` and `ping`.
+    pub fn with_exempt_method(mut self, method: impl Into<String>) -> Self {
+        self.exempt_methods.push(method.into());
+        self
+    }
+
+    /// Sets whether the limits apply to each session, or to all sessions together. Per session by default.
+    pub fn per_session(mut self, per_session: bool) -> Self {
+        self.per_session = per_session;
+        self
+    }
+
+    /// Sets the code and message of the error answering the requests exceeding their limit.
+    pub fn with_error(mut self, code: i64, message: impl Into<String>) -> Self {
+        self.error_code = code;
+        self.error_message = message.into();
+        self
+    }
+
+    /// Takes a token from the bucket of a request, returning the delay before the next token
+    /// is available if there is none.
+    fn acquire(&self, method: &str, session_id: Option<&SessionId>) -> Result<(), Duration> {
+        self.acquire_at(method, session_id, Instant::now())
+    }
+
+    fn acquire_at(
+        &self,
+        method: &str,
+        session_id: Option<&SessionId>,
+        now: Instant,
+    ) -> Result<(), Duration> {
+        if self.exempt_methods.iter().any(|exempt| exempt == method) {
+            return Ok(());
+        }
+        let (limit, method_key) = match self.method_limits.get(method) {
+            Some(limit) => (limit, Some(method.to_string())),
+            None => match self.limit.as_ref() {
+                Some(limit) => (limit, None),
+                None => return Ok(()),
+            },
+        };
+        let session_key = session_id.filter(|_| self.per_session).cloned();
+        let Ok(mut buckets) = self.buckets.lock() else {
+            return Ok(());
+        };
+        if now.saturating_duration_since(buckets.evicted_at) >= BUCKET_EVICTION_INTERVAL {
+            // a bucket that has refilled is the same as a new one
+            buckets.buckets.retain(|(_, method), bucket| {
+                let limit = match method {
+                    Some(method) => self.method_limits.get(method),
+                    None => self.limit.as_ref(),
+                };
+                limit.is_some_and(|limit| !bucket.is_full(limit, now))
+            });
+            buckets.evicted_at = now;
+        }
+        buckets
+            .buckets
+            .entry((session_key, method_key))
+            .or_insert_with(|| TokenBucket::new(limit, now))
+            .try_acquire(limit, now)
+    }
+}
+
+#[async_trait]
+impl ServerMiddleware for RateLimiter {
+    async fn handle(
+        &self,
+        request: RequestFromClient,
+        runtime: &dyn McpServer,
+        next: RequestNext<'_>,
+    ) -> Result<ResultFromServer, RpcError> {
+        if let Err(retry_after) = self.acquire(request.method(), runtime.session_id()) {
+            return Err(RpcError {
+                code: self.error_code,
+                data: Some(json!({ "retryAfterMs": retry_after.as_millis() as u64 })),
+                message: self.error_message.clone(),
+            });
+        }
+        next.run(request).await
+    }
+}
+
+#[derive(Debug)]
+struct TokenBucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl TokenBucket {
+    fn new(limit: &RateLimit, now: Instant) -> Self {
+        Self {
+            tokens: limit.burst as f64,
+            updated_at: now,
+        }
+    }
+
+    // Whether the bucket has refilled to its burst by `now`
+    fn is_full(&self, limit: &RateLimit, now: Instant) -> bool {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens + elapsed * limit.requests_per_second >= limit.burst as f64
+    }
+
+    // Refills the bucket for the time elapsed, then takes a token, or returns the delay before the next one
+    fn try_acquire(&mut self, limit: &RateLimit, now: Instant) -> Result<(), Duration> {
+        let elapsed = now.saturating_duration_since(self.updated_at).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * limit.requests_per_second).min(limit.burst as f64);
+        self.updated_at = now;
+        if self.tokens >= 1.0 {
+            self.tokens -= 1.0;
+            return Ok(());
+        }
+        if limit.requests_per_second <= 0.0 {
+            return Err(Duration::MAX);
+        }
+        Err(Duration::from_secs_f64(
+            (1.0 - self.tokens) / limit.requests_per_second,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bucket_accepts_bursts_and_refills() {
+        let limit = RateLimit::per_second(2);
+        let mut bucket = TokenBucket::new(&limit, Instant::now());
+        let start = bucket.updated_at;
+        assert!(bucket.try_acquire(&limit, start).is_ok());
+        assert!(bucket.try_acquire(&limit, start).is_ok());
+        assert_eq!(
+            bucket.try_acquire(&limit, start),
+            Err(Duration::from_millis(500))
+        );
+        assert!(bucket
+            .try_acquire(&limit, start + Duration::from_millis(500))
+            .is_ok());
+        // the bucket never holds more than the burst
+        let later = start + Duration::from_secs(60);
+        assert!(bucket.try_acquire(&limit, later).is_ok());
+        assert!(bucket.try_acquire(&limit, later).is_ok());
+        assert!(bucket.try_acquire(&limit, later).is_err());
+    }
+
+    #[test]
+    fn test_limits_by_method_and_session() {
+        let limiter = RateLimiter::new()
+            .with_limit(RateLimit::per_minute(2))
+            .with_method_limit("tools/call", RateLimit::per_minute(1));
+        let alice = Some("alice".to_string());
+        let bob = Some("bob".to_string());
+
+        assert!(limiter.acquire("tools/call", alice.as_ref()).is_ok());
+        assert!(limiter.acquire("tools/call", alice.as_ref()).is_err());
+        assert!(limiter.acquire("tools/call", bob.as_ref()).is_ok());
+        // the other methods share their own limit
+        assert!(limiter.acquire("tools/list", alice.as_ref()).is_ok());
+        assert!(limiter.acquire("prompts/list", alice.as_ref()).is_ok());
+        assert!(limiter.acquire("resources/list", alice.as_ref()).is_err());
+        // exempt methods are never limited
+        assert!(limiter.acquire("ping", alice.as_ref()).is_ok());
+
+        let shared = RateLimiter::new()
+            .with_limit(RateLimit::per_minute(1))
+            .per_session(false);
+        assert!(shared.acquire("tools/list", alice.as_ref()).is_ok());
+        assert!(shared.clone().acquire("tools/list", bob.as_ref()).is_err());
+    }
+
+    #[test]
+    fn test_evicts_refilled_buckets() {
+        let limiter = RateLimiter::new().with_limit(RateLimit::per_second(10));
+        let start = Instant::now();
+        let alice = Some("alice".to_string());
+        let bob = Some("bob".to_string());
+        assert!(limiter
+            .acquire_at("tools/list", alice.as_ref(), start)
+            .is_ok());
+        assert_eq!(limiter.buckets.lock().unwrap().buckets.len(), 1);
+
+        // the bucket of alice has refilled when bob sends a request after the eviction interval
+        let later = start + BUCKET_EVICTION_INTERVAL;
+        assert!(limiter
+            .acquire_at("tools/list", bob.as_ref(), later)
+            .is_ok());
+        let buckets = limiter.buckets.lock().unwrap();
+        assert_eq!(buckets.buckets.len(), 1);
+        assert!(buckets.buckets.contains_key(&(bob, None)));
+    }
+}
@@ -0,0 +1,448 @@
+use std::future::Future;
+use std::sync::{Arc, RwLock};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use futures::future::BoxFuture;
+use rust_mcp_schema::{
+    BlobResourceContents, ReadResourceResult, Resource, ResourceTemplate, RpcError,
+    TextResourceContents,
+};
+
+use crate::mcp_traits::mcp_resource::McpResource;
+use crate::mcp_traits::rpc_error_ext::RpcErrorExt;
+use crate::uri_template::{UriParams, UriTemplate};
+
+// Reads a resource from its uri and the variables of the template it matched
+type ResourceReadFn = Arc<
+    dyn Fn(String, UriParams) -> BoxFuture<'static, Result<ReadResourceResult, RpcError>>
+        + Send
+        + Sync,
+>;
+
+struct RegisteredResource {
+    resource: Resource,
+    read: ResourceReadFn,
+}
+
+struct RegisteredTemplate {
+    template: ResourceTemplate,
+    matcher: UriTemplate,
+    read: ResourceReadFn,
+}
+
+/// Declares the resources of a server, so that handlers do not need to implement
+/// `resources/list`, `resources/templates/list` and `resources/read` themselves.
+///
+/// Static resources are registered with their uri and a function returning their content when
+/// they are read, resource templates with a uri template such as `db://{table}/{id}` and a function
+/// reading the resources matching it. Once enabled with `ServerRuntime::with_resource_registry()`,
+/// the server declares the `resources` capability, and the default implementations of the resource
+/// handlers of `ServerHandler` list and read the registered resources.
+///
+/// Resources are listed in the order they were registered. A uri is read from the resource
+/// registered with that uri if any, otherwise from the first template it matches, see `UriTemplate`.
+///
+/// # Example
+/// ```ignore
+/// let registry = ResourceRegistry::new();
+/// registry
+///     .register_text("app://status", "text/plain", move || format!("Up for {}s", started.elapsed().as_secs()))
+///     .register_template("db://{table}/{id}", move |uri, params| {
+///         let db = Arc::clone(&db);
+///         async move {
+///             let row = db.fetch(&params["table"], &params["id"]).await?;
+///             Ok(ReadResourceResult {
+///                 contents: vec![TextResourceContents { mime_type: None, text: row, uri }.into()],
+///                 meta: None,
+///             })
+///         }
+///     });
+///
+/// let server = server_runtime::create_server(server_details, transport, handler)
+///     .with_resource_registry(registry);
+/// ```
+#[derive(Default)]
+pub struct ResourceRegistry {
+    resources: RwLock<Vec<RegisteredResource>>,
+    templates: RwLock<Vec<RegisteredTemplate>>,
+}
+
+impl ResourceRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a text resource, whose content is returned by `content` each time it is read.
+    ///
+    /// `content` is called on the executor of the runtime, so it must not block, e.g. on file or
+    /// network IO: resources read with IO are registered with the async function of `register_resource()`.
+    pub fn register_text<F>(
+        &self,
+        uri: impl Into<String>,
+        mime_type: impl Into<String>,
+        content: F,
+    ) -> &Self
+    where
+        F: Fn() -> String + Send + Sync + 'static,
+    {
+        let uri = uri.into();
+        let mime_type = mime_type.into();
+        let resource = Resource {
+            annotations: None,
+            description: None,
+            mime_type: Some(mime_type.clone()),
+            name: uri.clone(),
+            size: None,
+            uri,
+        };
+        self.register_resource(resource, move |uri| {
+            let contents = TextResourceContents {
+                mime_type: Some(mime_type.clone()),
+                text: content(),
+                uri,
+            };
+            async move {
+                Ok(ReadResourceResult {
+                    contents: vec![contents.into()],
+                    meta: None,
+                })
+            }
+        })
+    }
+
+    /// Registers a binary resource, whose content is returned by `content` each time it is read.
+    ///
+    /// `content` must not block, see `register_text()`.
+    pub fn register_blob<F>(
+        &self,
+        uri: impl Into<String>,
+        mime_type: impl Into<String>,
+        content: F,
+    ) -> &Self
+    where
+        F: Fn() -> Vec<u8> + Send + Sync + 'static,
+    {
+        let uri = uri.into();
+        let mime_type = mime_type.into();
+        let resource = Resource {
+            annotations: None,
+            description: None,
+            mime_type: Some(mime_type.clone()),
+            name: uri.clone(),
+            size: None,
+            uri,
+        };
+        self.register_resource(resource, move |uri| {
+            let contents = BlobResourceContents {
+                blob: STANDARD.encode(content()),
+                mime_type: Some(mime_type.clone()),
+                uri,
+            };
+            async move {
+                Ok(ReadResourceResult {
+                    contents: vec![contents.into()],
+                    meta: None,
+                })
+            }
+        })
+    }
+
+    /// Registers a resource described by `resource`, read by the async function `read` from its uri.
+    /// Replaces the resource previously registered with the same uri, if any.
+    pub fn register_resource<F, Fut>(&self, resource: Resource, read: F) -> &Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ReadResourceResult, RpcError>> + Send + 'static,
+    {
+        let registered = RegisteredResource {
+            resource,
+            read: Arc::new(move |uri, _| Box::pin(read(uri))),
+        };
+        if let Ok(mut resources) = self.resources.write() {
+            match resources
+                .iter_mut()
+                .find(|resource| resource.resource.uri == registered.resource.uri)
+            {
+                Some(resource) => *resource = registered,
+                None => resources.push(registered),
+            }
+        }
+        self
+    }
+
+    /// Registers a resource described by `resource`, usually the `resource()` generated by the
+    /// `#[mcp_resource]` macro, read by `handler`.
+    /// Replaces the resource previously registered with the same uri, if any.
+    ///
+    /// # Example
+    /// ```ignore
+    /// registry.register_handler(ReadmeResource::resource(), ReadmeResource);
+    /// ```
+    pub fn register_handler<R>(&self, resource: Resource, handler: R) -> &Self
+    where
+        R: McpResource + 'static,
+    {
+        let handler = Arc::new(handler);
+        self.register_resource(resource, move |uri| {
+            let handler = Arc::clone(&handler);
+            async move { handler.read_resource(uri).await }
+        })
+    }
+
+    /// Registers existing resources, e.g. the ones listed by a `handle_list_resources_request()`
+    /// implementation, all read by the async function `read` from their uri, e.g. the logic of the
+    /// matching `handle_read_resource_request()`.
+    pub fn register_resources<F, Fut>(
+        &self,
+        resources: impl IntoIterator<Item = Resource>,
+        read: F,
+    ) -> &Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ReadResourceResult, RpcError>> + Send + 'static,
+    {
+        let read = Arc::new(read);
+        for resource in resources {
+            let read = Arc::clone(&read);
+            self.register_resource(resource, move |uri| read(uri));
+        }
+        self
+    }
+
+    /// Registers the resources matching `uri_template`, read by the async function `read` from their
+    /// uri and the values of the variables of the template.
+    pub fn register_template<F, Fut>(&self, uri_template: impl Into<String>, read: F) -> &Self
+    where
+        F: Fn(String, UriParams) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ReadResourceResult, RpcError>> + Send + 'static,
+    {
+        let uri_template = uri_template.into();
+        let template = ResourceTemplate {
+            annotations: None,
+            description: None,
+            mime_type: None,
+            name: uri_template.clone(),
+            uri_template,
+        };
+        self.register_resource_template(template, read)
+    }
+
+    /// Registers the resources matching the template described by `template`, see `register_template()`.
+    /// Replaces the template previously registered with the same uri template, if any.
+    pub fn register_resource_template<F, Fut>(&self, template: ResourceTemplate, read: F) -> &Self
+    where
+        F: Fn(String, UriParams) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ReadResourceResult, RpcError>> + Send + 'static,
+    {
+        let registered = RegisteredTemplate {
+            matcher: UriTemplate::new(template.uri_template.as_str()),
+            template,
+            read: Arc::new(move |uri, params| Box::pin(read(uri, params))),
+        };
+        if let Ok(mut templates) = self.templates.write() {
+            match templates
+                .iter_mut()
+                .find(|template| template.template.uri_template == registered.template.uri_template)
+            {
+                Some(template) => *template = registered,
+                None => templates.push(registered),
+            }
+        }
+        self
+    }
+
+    /// Registers existing resource templates, all read by the async function `read`,
+    /// see `register_resources()`.
+    pub fn register_resource_templates<F, Fut>(
+        &self,
+        templates: impl IntoIterator<Item = ResourceTemplate>,
+        read: F,
+    ) -> &Self
+    where
+        F: Fn(String, UriParams) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<ReadResourceResult, RpcError>> + Send + 'static,
+    {
+        let read = Arc::new(read);
+        for template in templates {
+            let read = Arc::clone(&read);
+            self.register_resource_template(template, move |uri, params| read(uri, params));
+        }
+        self
+    }
+
+    /// Removes the resource registered with the given uri, returning `true` if there was one.
+    pub fn unregister(&self, uri: &str) -> bool {
+        self.resources
+            .write()
+            .map(|mut resources| {
+                let count = resources.len();
+                resources.retain(|resource| resource.resource.uri != uri);
+                resources.len() != count
+            })
+            .unwrap_or(false)
+    }
+
+    /// Removes the template registered with the given uri template, returning `true` if there was one.
+    pub fn unregister_template(&self, uri_template: &str) -> bool {
+        self.templates
+            .write()
+            .map(|mut templates| {
+                let count = templates.len();
+                templates.retain(|template| template.template.uri_template != uri_template);
+                templates.len() != count
+            })
+            .unwrap_or(false)
+    }
+
+    /// Returns the registered resources, in the order they were registered.
+    pub fn resources(&self) -> Vec<Resource> {
+        self.resources
+            .read()
+            .map(|resources| {
+                resources
+                    .iter()
+                    .map(|resource| resource.resource.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the registered resource templates, in the order they were registered.
+    pub fn resource_templates(&self) -> Vec<ResourceTemplate> {
+        self.templates
+            .read()
+            .map(|templates| {
+                templates
+                    .iter()
+                    .map(|template| template.template.clone())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Reads the resource with the given uri.
+    ///
+    /// # Errors
+    /// Returns a `resource_not_found` error if no resource or template matches the uri,
+    /// or the error returned by the function reading the resource.
+    pub async fn read(&self, uri: &str) -> Result<ReadResourceResult, RpcError> {
+        let (read, params) = self
+            .find(uri)
+            .ok_or_else(|| RpcError::resource_not_found(uri))?;
+        read(uri.to_string(), params).await
+    }
+
+    // Returns the function reading the resource with the given uri, and the variables of its template
+    fn find(&self, uri: &str) -> Option<(ResourceReadFn, UriParams)> {
+        let resources = self.resources.read().ok()?;
+        if let Some(resource) = resources
+            .iter()
+            .find(|resource| resource.resource.uri == uri)
+        {
+            return Some((Arc::clone(&resource.read), UriParams::new()));
+        }
+        drop(resources);
+
+        let templates = self.templates.read().ok()?;
+        templates.iter().find_map(|template| {
+            template
+                .matcher
+                .matches(uri)
+                .map(|params| (Arc::clone(&template.read), params))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error_codes::RESOURCE_NOT_FOUND;
+    use rust_mcp_schema::ReadResourceResultContentsItem;
+
+    fn text(result: &ReadResourceResult) -> &str {
+        match &result.contents[0] {
+            ReadResourceResultContentsItem::TextResourceContents(contents) => &contents.text,
+            ReadResourceResultContentsItem::BlobResourceContents(contents) => &contents.blob,
+        }
+    }
+
+    fn template_text(uri: String, text: String) -> ReadResourceResult {
+        ReadResourceResult {
+            contents: vec![TextResourceContents {
+                mime_type: None,
+                text,
+                uri,
+            }
+            .into()],
+            meta: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_register_replaces_the_same_uri() {
+        let registry = ResourceRegistry::new();
+        registry
+            .register_text("app://status", "text/plain", || "starting".to_string())
+            .register_blob("app://logo", "image/png", || vec![1, 2, 3])
+            .register_text("app://status", "text/plain", || "ready".to_string());
+        let uris: Vec<_> = registry
+            .resources()
+            .into_iter()
+            .map(|resource| resource.uri)
+            .collect();
+        assert_eq!(uris, ["app://status", "app://logo"]);
+        assert_eq!(text(&registry.read("app://status").await.unwrap()), "ready");
+        assert_eq!(
+            text(&registry.read("app://logo").await.unwrap()),
+            STANDARD.encode([1, 2, 3])
+        );
+
+        registry
+            .register_template("db://{table}", |uri, _| async move {
+                Ok(template_text(uri, "first".to_string()))
+            })
+            .register_template("db://{table}", |uri, _| async move {
+                Ok(template_text(uri, "second".to_string()))
+            });
+        assert_eq!(registry.resource_templates().len(), 1);
+        assert_eq!(text(&registry.read("db://users").await.unwrap()), "second");
+
+        assert!(registry.unregister("app://status"));
+        assert!(!registry.unregister("app://status"));
+        assert!(registry.unregister_template("db://{table}"));
+        assert_eq!(registry.resources().len(), 1);
+        assert!(registry.resource_templates().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_read_exact_uris_before_templates() {
+        let registry = ResourceRegistry::new();
+        registry
+            .register_template("file:///{name}", |uri, params| async move {
+                Ok(template_text(uri, format!("template {}", params["name"])))
+            })
+            .register_text("file:///readme", "text/markdown", || "# Readme".to_string());
+
+        // the resource is read even though the template was registered first
+        assert_eq!(
+            text(&registry.read("file:///readme").await.unwrap()),
+            "# Readme"
+        );
+        assert_eq!(
+            text(&registry.read("file:///license").await.unwrap()),
+            "template license"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_read_unknown_uris() {
+        let registry = ResourceRegistry::new();
+        registry.register_template("db://{table}/{id}", |uri, _| async move {
+            Ok(template_text(uri, String::new()))
+        });
+
+        let error = registry.read("db://users").await.unwrap_err();
+        assert_eq!(error.code, RESOURCE_NOT_FOUND);
+        assert_eq!(error.data, Some(serde_json::json!({ "uri": "db://users" })));
+    }
+}
@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+
+use rust_mcp_schema::{
+    Implementation, InitializeResult, ServerCapabilities, ServerCapabilitiesPrompts,
+    ServerCapabilitiesResources, ServerCapabilitiesTools, LATEST_PROTOCOL_VERSION,
+};
+use serde_json::{Map, Value};
+
+/// Builds the `InitializeResult` describing a server, with the latest protocol version filled in.
+///
+/// # Example
+/// ```ignore
+/// let server_details = ServerRuntime::info("Hello World MCP Server", "0.1.0")
+///     .with_tools(false)
+///     .with_resources(true, false)
+///     .with_instructions("server instructions...")
+///     .build();
+/// ```
+#[derive(Debug, Clone)]
+pub struct ServerInfoBuilder {
+    server_info: Implementation,
+    capabilities: ServerCapabilities,
+    instructions: Option<String>,
+    meta: Option<Map<String, Value>>,
+    protocol_version: String,
+}
+
+impl ServerInfoBuilder {
+    /// Creates a builder for a server with the given name and version, and no capabilities.
+    pub fn new<N: Into<String>, V: Into<String>>(name: N, version: V) -> Self {
+        Self {
+            server_info: Implementation {
+                name: name.into(),
+                version: version.into(),
+            },
+            capabilities: ServerCapabilities::default(),
+            instructions: None,
+            meta: None,
+            protocol_version: LATEST_PROTOCOL_VERSION.to_string(),
+        }
+    }
+
+    /// Advertises support for tools. `list_changed` indicates whether the server sends
+    /// notifications when the list of tools changes.
+    pub fn with_tools(mut self, list_changed: bool) -> Self {
+        self.capabilities.tools = Some(ServerCapabilitiesTools {
+            list_changed: Some(list_changed),
+        });
+        self
+    }
+
+    /// Advertises support for prompts. `list_changed` indicates whether the server sends
+    /// notifications when the list of prompts changes.
+    pub fn with_prompts(mut self, list_changed: bool) -> Self {
+        self.capabilities.prompts = Some(ServerCapabilitiesPrompts {
+            list_changed: Some(list_changed),
+        });
+        self
+    }
+
+    /// Advertises support for resources. `subscribe` indicates whether clients can subscribe to
+    /// resource updates, and `list_changed` whether the server sends notifications when the list
+    /// of resources changes.
+    pub fn with_resources(mut self, subscribe: bool, list_changed: bool) -> Self {
+        self.capabilities.resources = Some(ServerCapabilitiesResources {
+            list_changed: Some(list_changed),
+            subscribe: Some(subscribe),
+        });
+        self
+    }
+
+    /// Advertises support for sending log messages to the client.
+    pub fn with_logging(mut self) -> Self {
+        self.capabilities.logging = Some(Map::new());
+        self
+    }
+
+    /// Advertises a non-standard, experimental capability.
+    pub fn with_experimental<N: Into<String>>(
+        mut self,
+        name: N,
+        value: Map<String, Value>,
+    ) -> Self {
+        self.capabilities
+            .experimental
+            .get_or_insert_with(HashMap::new)
+            .insert(name.into(), value);
+        self
+    }
+
+    /// Replaces all capabilities of the server.
+    pub fn with_capabilities(mut self, capabilities: ServerCapabilities) -> Self {
+        self.capabilities = capabilities;
+        self
+    }
+
+    /// Sets instructions describing how to use the server and its features.
+    pub fn with_instructions<T: Into<String>>(mut self, instructions: T) -> Self {
+        self.instructions = Some(instructions.into());
+        self
+    }
+
+    /// Sets the metadata attached to the `InitializeResult`.
+    pub fn with_meta(mut self, meta: Map<String, Value>) -> Self {
+        self.meta = Some(meta);
+        self
+    }
+
+    /// Overrides the protocol version, `LATEST_PROTOCOL_VERSION` by default.
+    pub fn with_protocol_version<T: Into<String>>(mut self, protocol_version: T) -> Self {
+        self.protocol_version = protocol_version.into();
+        self
+    }
+
+    /// Returns the `InitializeResult` to pass to `create_server()`.
+    pub fn build(self) -> InitializeResult {
+        InitializeResult {
+            capabilities: self.capabilities,
+            instructions: self.instructions,
+            meta: self.meta,
+            protocol_version: self.protocol_version,
+            server_info: self.server_info,
+        }
+    }
+}
+
+impl From<ServerInfoBuilder> for InitializeResult {
+    fn from(builder: ServerInfoBuilder) -> Self {
+        builder.build()
+    }
+}
@@ -0,0 +1,75 @@
+use std::sync::RwLock;
+
+use rust_mcp_schema::LoggingLevel;
+
+/// Logging facade of a server, filtering the log messages sent to the client by their level.
+///
+/// Once enabled with `ServerRuntime::with_logger()`, the server declares the `logging` capability,
+/// answers `logging/setLevel` requests itself by storing the requested level, and log messages
+/// sent with `McpServer::send_logging_message()` or the `log_*()` methods are dropped if they are
+/// less severe than that level. Until the client sets a level, the initial level of the logger is used.
+///
+/// # Example
+/// ```ignore
+/// let server = server_runtime::create_server(server_details, transport, handler)
+///     .with_logger(ServerLogger::new(LoggingLevel::Warning));
+///
+/// // in a handler, sent if the client requested the info level or a less severe one
+/// runtime.log_info("indexer", json!("Indexing 42 files")).await?;
+/// ```
+#[derive(Debug)]
+pub struct ServerLogger {
+    level: RwLock<LoggingLevel>,
+}
+
+impl Default for ServerLogger {
+    fn default() -> Self {
+        Self::new(LoggingLevel::Info)
+    }
+}
+
+impl ServerLogger {
+    /// Creates a logger sending the messages of `level` and more severe levels,
+    /// until the client sets another level.
+    pub fn new(level: LoggingLevel) -> Self {
+        Self {
+            level: RwLock::new(level),
+        }
+    }
+
+    /// Returns the minimum level of the messages sent to the client.
+    pub fn level(&self) -> LoggingLevel {
+        self.level
+            .read()
+            .map(|level| *level)
+            .unwrap_or(LoggingLevel::Info)
+    }
+
+    /// Sets the minimum level of the messages sent to the client,
+    /// done by the server when the client sends a `logging/setLevel` request.
+    pub fn set_level(&self, level: LoggingLevel) {
+        if let Ok(mut lock) = self.level.write() {
+            *lock = level;
+        }
+    }
+
+    /// Returns `true` if messages of the given level are sent to the client.
+    pub fn is_enabled(&self, level: LoggingLevel) -> bool {
+        severity(level) >= severity(self.level())
+    }
+}
+
+// The severity of a level, as defined by RFC 5424 with increasing values.
+// The derived ordering of `LoggingLevel` is alphabetical.
+pub(crate) fn severity(level: LoggingLevel) -> u8 {
+    match level {
+        LoggingLevel::Debug => 0,
+        LoggingLevel::Info => 1,
+        LoggingLevel::Notice => 2,
+        LoggingLevel::Warning => 3,
+        LoggingLevel::Error => 4,
+        LoggingLevel::Critical => 5,
+        LoggingLevel::Alert => 6,
+        LoggingLevel::Emergency => 7,
+    }
+}
@@ -0,0 +1,88 @@
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use rust_mcp_schema::schema_utils::{RequestFromClient, ResultFromServer};
+use rust_mcp_schema::RpcError;
+
+use crate::mcp_traits::mcp_server::McpServer;
+
+use super::ServerRuntime;
+
+/// A middleware wrapping the requests received by a `ServerRuntime`, for cross-cutting concerns
+/// such as authorization, rate limiting or audit logging, without modifying each handler method.
+///
+/// Middlewares are registered with `ServerRuntime::with_middleware()` and run for every request
+/// received from the client, in the order they are registered. A middleware can inspect or modify
+/// the request, pass it on with `next.run(request)` and inspect or modify the result, or return a
+/// result or an error without passing the request on.
+///
+/// Middlewares run before the tool hooks and the handler, and receive the arguments of tool calls
+/// as sent by the client, i.e. before their decryption. Calls of disabled tools are rejected before
+/// reaching the middlewares.
+///
+/// # Example
+/// ```ignore
+/// struct RequireAuthentication;
+///
+/// #[async_trait]
+/// impl ServerMiddleware for RequireAuthentication {
+///     async fn handle(
+///         &self,
+///         request: RequestFromClient,
+///         runtime: &dyn McpServer,
+///         next: RequestNext<'_>,
+///     ) -> Result<ResultFromServer, RpcError> {
+///         if !is_authenticated(runtime.client_info()) {
+///             return Err(RpcError::invalid_request().with_message("Unauthenticated".to_string()));
+///         }
+///         next.run(request).await
+///     }
+/// }
+///
+/// let server = server_runtime::create_server(server_details, transport, handler)
+///     .with_middleware(RequireAuthentication);
+/// ```
+#[async_trait]
+pub trait ServerMiddleware: Send + Sync + 'static {
+    async fn handle(
+        &self,
+        request: RequestFromClient,
+        runtime: &dyn McpServer,
+        next: RequestNext<'_>,
+    ) -> Result<ResultFromServer, RpcError>;
+}
+
+/// The rest of the middleware chain of a request, ending with its processing by the server.
+pub struct RequestNext<'a> {
+    runtime: &'a ServerRuntime,
+    middlewares: &'a [Arc<dyn ServerMiddleware>],
+}
+
+impl<'a> RequestNext<'a> {
+    pub(crate) fn new(
+        runtime: &'a ServerRuntime,
+        middlewares: &'a [Arc<dyn ServerMiddleware>],
+    ) -> Self {
+        Self {
+            runtime,
+            middlewares,
+        }
+    }
+
+    /// Passes the request to the next middleware, or to the server after the last one.
+    pub fn run(
+        self,
+        request: RequestFromClient,
+    ) -> BoxFuture<'a, Result<ResultFromServer, RpcError>> {
+        Box::pin(async move {
+            match self.middlewares.split_first() {
+                Some((middleware, middlewares)) => {
+                    let next = RequestNext::new(self.runtime, middlewares);
+                    middleware.handle(request, self.runtime, next).await
+                }
+                None => self.runtime.handle_request(request).await,
+            }
+        })
+    }
+}
@@ -0,0 +1,251 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use rust_mcp_schema::schema_utils::{ClientMessage, MessageFromServer};
+use rust_mcp_schema::InitializeResult;
+use rust_mcp_transport::{SessionId, Transport};
+
+use crate::error::SdkResult;
+use crate::mcp_traits::mcp_handler::McpServerHandler;
+use crate::mcp_traits::mcp_server::McpServer;
+
+use super::ServerRuntime;
+
+// Configures the runtime of each session
+type SessionSetup = Arc<dyn Fn(ServerRuntime) -> ServerRuntime + Send + Sync>;
+
+/// Serves multiple concurrent client sessions with a single server definition, e.g. one session per
+/// connection of a network transport.
+///
+/// Each session is a `ServerRuntime` bound to the transport of the connection, with its own client
+/// details, identified by a `SessionId` available to handlers with `McpServer::session_id()` and
+/// `RequestContext::current()`. The handler and the server details are shared by all sessions,
+/// and the runtime of each session is configured by the function set with `with_session_setup()`.
+///
+/// Created by `server_runtime::create_session_server()`, or `server_runtime_core::create_session_server()`.
+///
+/// # Example
+/// ```ignore
+/// let server = Arc::new(
+///     server_runtime::create_session_server(server_details, handler)
+///         .with_session_setup(|runtime| runtime.with_logger(ServerLogger::new(LoggingLevel::Info))),
+/// );
+///
+/// while let Some(transport) = accept_connection().await {
+///     let server = Arc::clone(&server);
+///     tokio::spawn(async move { server.serve(transport).await });
+/// }
+/// ```
+pub struct SessionServer {
+    server_details: InitializeResult,
+    handler: Arc<dyn McpServerHandler>,
+    setup: Option<SessionSetup>,
+    sessions: RwLock<HashMap<SessionId, Arc<ServerRuntime>>>,
+}
+
+impl SessionServer {
+    pub(crate) fn new(
+        server_details: InitializeResult,
+        handler: Arc<dyn McpServerHandler>,
+    ) -> Self {
+        Self {
+            server_details,
+            handler,
+            setup: None,
+            sessions: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Sets the function configuring the runtime of each session, e.g. with `ServerRuntime::with_logger()`.
+    /// Facilities holding state, such as loggers and registries, are created for each session.
+    pub fn with_session_setup<F>(mut self, setup: F) -> Self
+    where
+        F: Fn(ServerRuntime) -> ServerRuntime + Send + Sync + 'static,
+    {
+        self.setup = Some(Arc::new(setup));
+        self
+    }
+
+    /// Creates a session communicating over `transport`, registered until it is removed with
+    /// `remove_session()`. The session is started with `McpServer::start()`.
+    pub fn create_session(
+        &self,
+        transport: impl Transport<ClientMessage, MessageFromServer>,
+    ) -> Arc<ServerRuntime> {
+        self.create_session_with_id(self.new_session_id(), transport)
+    }
+
+    /// Creates a session like `create_session()`, identified by the given id, e.g. the one assigned
+    /// by the transport. Replaces the existing session with the same id, if any.
+    pub fn create_session_with_id(
+        &self,
+        session_id: impl Into<SessionId>,
+        transport: impl Transport<ClientMessage, MessageFromServer>,
+    ) -> Arc<ServerRuntime> {
+        let session_id = session_id.into();
+        let runtime = ServerRuntime::new(
+            self.server_details.clone(),
+            transport,
+            Arc::clone(&self.handler),
+        )
+        .with_session_id(session_id.clone());
+        let runtime = Arc::new(match &self.setup {
+            Some(setup) => setup(runtime),
+            None => runtime,
+        });
+        if let Ok(mut sessions) = self.sessions.write() {
+            sessions.insert(session_id, Arc::clone(&runtime));
+        }
+        runtime
+    }
+
+    /// Serves a session communicating over `transport` until the transport ends,
+    /// the session is then removed, unless it was replaced by another session with the same id.
+    pub async fn serve(
+        &self,
+        transport: impl Transport<ClientMessage, MessageFromServer>,
+    ) -> SdkResult<()> {
+        let runtime = self.create_session(transport);
+        let result = runtime.start().await;
+        if let (Some(session_id), Ok(mut sessions)) = (runtime.session_id(), self.sessions.write())
+        {
+            if sessions
+                .get(session_id)
+                .is_some_and(|session| Arc::ptr_eq(session, &runtime))
+            {
+                sessions.remove(session_id);
+            }
+        }
+        result
+    }
+
+    /// Returns the session with the given id, if it exists.
+    pub fn session(&self, session_id: &str) -> Option<Arc<ServerRuntime>> {
+        self.sessions
+            .read()
+            .ok()
+            .and_then(|sessions| sessions.get(session_id).cloned())
+    }
+
+    /// Returns the ids of the existing sessions.
+    pub fn session_ids(&self) -> Vec<SessionId> {
+        self.sessions
+            .read()
+            .map(|sessions| sessions.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns the number of existing sessions.
+    pub fn session_count(&self) -> usize {
+        self.sessions
+            .read()
+            .map(|sessions| sessions.len())
+            .unwrap_or_default()
+    }
+
+    /// Removes a session, returning it if it existed.
+    pub fn remove_session(&self, session_id: &str) -> Option<Arc<ServerRuntime>> {
+        self.sessions
+            .write()
+            .ok()
+            .and_then(|mut sessions| sessions.remove(session_id))
+    }
+
+    /// Returns the details of the server shared by the sessions.
+    pub fn server_info(&self) -> &InitializeResult {
+        &self.server_details
+    }
+
+    // Returns a unique session id, hard to guess as it may authenticate the requests of a client:
+    // 128 bits from the random number generator of the operating system
+    fn new_session_id(&self) -> SessionId {
+        let mut bytes = [0u8; 16];
+        // there is no safe fallback for the session ids if the operating system can not provide randomness
+        getrandom::getrandom(&mut bytes).expect("Failed to generate a random session id");
+        bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::mcp_server_runtime;
+    use super::super::tests::{next_response, request, test_transport};
+    use super::*;
+    use crate::mcp_server::ServerHandler;
+    use async_trait::async_trait;
+    use rust_mcp_schema::{ListToolsRequest, ListToolsResult, RpcError, Tool, ToolInputSchema};
+
+    // Lists a single tool named after the session id
+    struct SessionTools;
+
+    #[async_trait]
+    impl ServerHandler for SessionTools {
+        async fn handle_list_tools_request(
+            &self,
+            _: ListToolsRequest,
+            runtime: &dyn McpServer,
+        ) -> Result<ListToolsResult, RpcError> {
+            Ok(ListToolsResult {
+                meta: None,
+                next_cursor: None,
+                tools: vec![Tool {
+                    description: None,
+                    input_schema: ToolInputSchema::new(vec![], None),
+                    name: runtime.session_id().cloned().unwrap_or_default(),
+                }],
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_sessions() {
+        let server = Arc::new(mcp_server_runtime::create_session_server(
+            ServerRuntime::info("test-server", "1.0.0").build(),
+            SessionTools,
+        ));
+
+        let mut clients = vec![];
+        for _ in 0..2 {
+            let (transport, messages_tx, lines) = test_transport();
+            let session_server = Arc::clone(&server);
+            let session = tokio::spawn(async move { session_server.serve(transport).await });
+            clients.push((session, messages_tx, lines));
+        }
+
+        // each session answers with its own id
+        let mut client_session_ids = vec![];
+        for (_, messages_tx, lines) in &mut clients {
+            messages_tx
+                .send(request(1, ListToolsRequest::new(None).into()))
+                .unwrap();
+            let response = next_response(lines).await;
+            let tool_name = response["result"]["tools"][0]["name"].as_str().unwrap();
+            client_session_ids.push(tool_name.to_string());
+        }
+        assert_eq!(client_session_ids[0].len(), 32);
+        assert_ne!(client_session_ids[0], client_session_ids[1]);
+        let mut session_ids = server.session_ids();
+        session_ids.sort();
+        let mut expected_ids = client_session_ids.clone();
+        expected_ids.sort();
+        assert_eq!(session_ids, expected_ids);
+
+        // the first session is replaced while it is served, its end does not remove the replacement
+        let (replacement_transport, _replacement_messages_tx, _) = test_transport();
+        let replacement =
+            server.create_session_with_id(client_session_ids[0].clone(), replacement_transport);
+        let (first_session, first_messages_tx, _) = clients.remove(0);
+        drop(first_messages_tx);
+        first_session.await.unwrap().unwrap();
+        let session = server.session(&client_session_ids[0]).unwrap();
+        assert!(Arc::ptr_eq(&session, &replacement));
+        assert_eq!(server.session_count(), 2);
+
+        // the end of the second session removes it
+        let (second_session, second_messages_tx, _) = clients.remove(0);
+        drop(second_messages_tx);
+        second_session.await.unwrap().unwrap();
+        assert!(server.session(&client_session_ids[1]).is_none());
+        assert_eq!(server.session_ids(), [client_session_ids[0].clone()]);
+    }
+}
@@ -0,0 +1,175 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::RwLock;
+
+type StoredValue = Box<dyn Any + Send + Sync>;
+
+/// Stores the state of a client session, one value per type, so that servers can keep per-client
+/// state, such as authentication details, open handles or cursors, without global maps.
+///
+/// Every `ServerRuntime` has its own store, dropped with the runtime, available to handlers with
+/// `McpServer::session_store()`. With a `SessionServer`, each session has its own store.
+/// Values are keyed by their type, define a dedicated type for each piece of state.
+///
+/// # Example
+/// ```ignore
+/// #[derive(Clone)]
+/// struct AuthenticatedUser(String);
+///
+/// // in a handler
+/// if let Some(store) = runtime.session_store() {
+///     store.insert(AuthenticatedUser(user_name));
+///     ...
+///     let user = store.get::<AuthenticatedUser>();
+///     store.with_mut(|cursor: &mut QueryCursor| cursor.advance());
+/// }
+/// ```
+#[derive(Default)]
+pub struct SessionStore {
+    values: RwLock<HashMap<TypeId, StoredValue>>,
+}
+
+impl SessionStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stores a value, returning the value of the same type previously stored, if any.
+    pub fn insert<T: Send + Sync + 'static>(&self, value: T) -> Option<T> {
+        self.values
+            .write()
+            .ok()?
+            .insert(TypeId::of::<T>(), Box::new(value))
+            .and_then(downcast)
+    }
+
+    /// Returns a clone of the value of type `T`, if any.
+    pub fn get<T: Clone + Send + Sync + 'static>(&self) -> Option<T> {
+        self.with(T::clone)
+    }
+
+    /// Returns a clone of the value of type `T`, storing the value returned by `create` first if there
+    /// is none.
+    pub fn get_or_insert_with<T, F>(&self, create: F) -> T
+    where
+        T: Clone + Send + Sync + 'static,
+        F: FnOnce() -> T,
+    {
+        let Ok(mut values) = self.values.write() else {
+            return create();
+        };
+        values
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(create()))
+            .downcast_ref::<T>()
+            .cloned()
+            .unwrap_or_else(|| unreachable!("values are stored by type"))
+    }
+
+    /// Calls `f` with a reference to the value of type `T`, returning its result, or `None` if there
+    /// is no such value.
+    pub fn with<T: Send + Sync + 'static, R>(&self, f: impl FnOnce(&T) -> R) -> Option<R> {
+        let values = self.values.read().ok()?;
+        values.get(&TypeId::of::<T>())?.downcast_ref::<T>().map(f)
+    }
+
+    /// Calls `f` with a mutable reference to the value of type `T`, returning its result, or `None` if
+    /// there is no such value.
+    pub fn with_mut<T: Send + Sync + 'static, R>(&self, f: impl FnOnce(&mut T) -> R) -> Option<R> {
+        let mut values = self.values.write().ok()?;
+        values
+            .get_mut(&TypeId::of::<T>())?
+            .downcast_mut::<T>()
+            .map(f)
+    }
+
+    /// Removes the value of type `T`, returning it if there was one.
+    pub fn remove<T: Send + Sync + 'static>(&self) -> Option<T> {
+        self.values
+            .write()
+            .ok()?
+            .remove(&TypeId::of::<T>())
+            .and_then(downcast)
+    }
+
+    /// Returns `true` if a value of type `T` is stored.
+    pub fn contains<T: Send + Sync + 'static>(&self) -> bool {
+        self.values
+            .read()
+            .map(|values| values.contains_key(&TypeId::of::<T>()))
+            .unwrap_or(false)
+    }
+
+    /// Returns the number of stored values.
+    pub fn len(&self) -> usize {
+        self.values.read().map(|values| values.len()).unwrap_or(0)
+    }
+
+    /// Returns `true` if no value is stored.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes all the stored values.
+    pub fn clear(&self) {
+        if let Ok(mut values) = self.values.write() {
+            values.clear();
+        }
+    }
+}
+
+impl fmt::Debug for SessionStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionStore")
+            .field("len", &self.len())
+            .finish()
+    }
+}
+
+fn downcast<T: 'static>(value: StoredValue) -> Option<T> {
+    value.downcast::<T>().ok().map(|value| *value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct User(String);
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct Cursor(usize);
+
+    #[test]
+    fn test_stores_one_value_per_type() {
+        let store = SessionStore::new();
+        assert!(store.is_empty());
+        assert_eq!(store.insert(User("alice".to_string())), None);
+        assert_eq!(store.insert(Cursor(1)), None);
+        assert_eq!(
+            store.insert(User("bob".to_string())),
+            Some(User("alice".to_string()))
+        );
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get::<User>(), Some(User("bob".to_string())));
+        assert_eq!(store.get::<Cursor>(), Some(Cursor(1)));
+        assert_eq!(store.get::<String>(), None);
+    }
+
+    #[test]
+    fn test_updates_and_removes_values() {
+        let store = SessionStore::new();
+        assert_eq!(store.with_mut(|cursor: &mut Cursor| cursor.0 += 1), None);
+        assert_eq!(store.get_or_insert_with(|| Cursor(5)), Cursor(5));
+        assert_eq!(store.get_or_insert_with(|| Cursor(9)), Cursor(5));
+        store.with_mut(|cursor: &mut Cursor| cursor.0 += 1);
+        assert_eq!(store.with(|cursor: &Cursor| cursor.0), Some(6));
+        assert_eq!(store.remove::<Cursor>(), Some(Cursor(6)));
+        assert!(!store.contains::<Cursor>());
+
+        store.insert(User("alice".to_string()));
+        store.clear();
+        assert!(store.is_empty());
+    }
+}
@@ -0,0 +1,98 @@
+use std::collections::BTreeSet;
+use std::sync::RwLock;
+
+use rust_mcp_schema::RpcError;
+
+/// Validates the uri of a resource the client subscribes to, see `SubscriptionManager::with_validator()`.
+pub type SubscriptionValidator = Box<dyn Fn(&str) -> Result<(), RpcError> + Send + Sync>;
+
+/// Tracks the resources the client subscribed to, so that handlers do not need to.
+///
+/// Once enabled with `ServerRuntime::with_subscription_manager()`, the server declares the
+/// `resources.subscribe` capability and answers `resources/subscribe` and `resources/unsubscribe`
+/// requests itself. `McpServer::notify_resource_updated()` then only notifies the client about the
+/// resources it subscribed to, or about their sub-resources: a subscription to `file:///logs`
+/// covers `file:///logs/app.log`.
+///
+/// # Example
+/// ```ignore
+/// let server = server_runtime::create_server(server_details, transport, handler)
+///     .with_subscription_manager(
+///         SubscriptionManager::new().with_validator(|uri| match uri.starts_with("file:///") {
+///             true => Ok(()),
+///             false => Err(RpcError::invalid_params().with_message(format!("Unknown resource: {}", uri))),
+///         }),
+///     );
+///
+/// // in a handler, sent if the client subscribed to the resource
+/// runtime.notify_resource_updated("file:///logs/app.log").await?;
+/// ```
+#[derive(Default)]
+pub struct SubscriptionManager {
+    subscriptions: RwLock<BTreeSet<String>>,
+    validator: Option<SubscriptionValidator>,
+}
+
+impl SubscriptionManager {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the function validating the uris of the resources the client subscribes to,
+    /// subscriptions to invalid uris are answered with the returned error.
+    pub fn with_validator(
+        mut self,
+        validator: impl Fn(&str) -> Result<(), RpcError> + Send + Sync + 'static,
+    ) -> Self {
+        self.validator = Some(Box::new(validator));
+        self
+    }
+
+    /// Subscribes the client to a resource after validating its uri,
+    /// returning `false` if it was already subscribed to it.
+    pub fn subscribe(&self, uri: &str) -> Result<bool, RpcError> {
+        if let Some(validator) = self.validator.as_ref() {
+            validator(uri)?;
+        }
+        let mut subscriptions = self.subscriptions.write().map_err(|_| {
+            RpcError::internal_error()
+                .with_message("Internal Error: Failed to acquire write lock.".to_string())
+        })?;
+        Ok(subscriptions.insert(uri.to_string()))
+    }
+
+    /// Unsubscribes the client from a resource, returning `false` if it was not subscribed to it.
+    pub fn unsubscribe(&self, uri: &str) -> bool {
+        self.subscriptions
+            .write()
+            .map(|mut subscriptions| subscriptions.remove(uri))
+            .unwrap_or(false)
+    }
+
+    /// Returns `true` if the client subscribed to the resource, or to a resource containing it.
+    pub fn is_subscribed(&self, uri: &str) -> bool {
+        let Ok(subscriptions) = self.subscriptions.read() else {
+            return false;
+        };
+        subscriptions.iter().any(|subscription| {
+            uri.strip_prefix(subscription.as_str()).is_some_and(|rest| {
+                rest.is_empty() || rest.starts_with('/') || subscription.ends_with('/')
+            })
+        })
+    }
+
+    /// Returns the uris of the resources the client subscribed to, in alphabetical order.
+    pub fn subscriptions(&self) -> Vec<String> {
+        self.subscriptions
+            .read()
+            .map(|subscriptions| subscriptions.iter().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Removes all the subscriptions of the client.
+    pub fn clear(&self) {
+        if let Ok(mut subscriptions) = self.subscriptions.write() {
+            subscriptions.clear();
+        }
+    }
+}
@@ -0,0 +1,64 @@
+use rust_mcp_schema::schema_utils::{CallToolError, ResultFromServer, UnknownTool};
+use rust_mcp_schema::{CallToolResult, RpcError, ServerResult, TextContent};
+
+/// How the failures of tool calls are reported to the client, with the same rules for
+/// `ServerHandler` and `ServerHandlerCore` handlers.
+///
+/// A tool call fails either because the tool failed, e.g. a file it reads does not exist, or because
+/// it could not be called at all: the tool is unknown or disabled, its arguments cannot be decrypted,
+/// it does not support the requested dry run, a middleware or a tool hook rejected the call, or the
+/// server failed internally.
+///
+/// The `CallToolError`s returned by `ServerHandler::handle_call_tool_request()` are errors of the tool,
+/// except for `CallToolError::unknown_tool()` and the wrapped `RpcError`s, which are failures of the call.
+/// The `RpcError`s returned by the other handlers, middlewares and tool hooks are failures of the call.
+///
+/// The policy is set with `ServerRuntime::with_tool_error_policy()`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ToolErrorPolicy {
+    /// Errors of the tools are sent as a `CallToolResult` with `is_error`, so that the model sees them
+    /// and can recover, failures of the call are sent as JSON-RPC errors, as recommended by the
+    /// MCP specification.
+    #[default]
+    Standard,
+    /// Every failure of a tool call is sent as a `CallToolResult` with `is_error`, for clients that
+    /// only show tool results to the model.
+    ResultErrors,
+}
+
+impl ToolErrorPolicy {
+    // Applies the policy to the outcome of a tools/call request
+    pub(crate) fn apply(
+        &self,
+        result: Result<ResultFromServer, RpcError>,
+    ) -> Result<ResultFromServer, RpcError> {
+        match (self, result) {
+            (ToolErrorPolicy::ResultErrors, Err(error)) => {
+                Ok(ServerResult::CallToolResult(CallToolResult {
+                    content: vec![TextContent::new(error.message, None).into()],
+                    is_error: Some(true),
+                    meta: None,
+                })
+                .into())
+            }
+            (_, result) => result,
+        }
+    }
+}
+
+// Converts the error returned by `ServerHandler::handle_call_tool_request()`: errors of the tool
+// become a result with `is_error`, failures of the call remain JSON-RPC errors
+pub(crate) fn from_call_tool_error(error: CallToolError) -> Result<CallToolResult, RpcError> {
+    // errors are often wrapped twice, e.g. by `try_from(request.params).map_err(CallToolError::new)`
+    let mut source: &dyn std::error::Error = error.0.as_ref();
+    while let Some(call_tool_error) = source.downcast_ref::<CallToolError>() {
+        source = call_tool_error.0.as_ref();
+    }
+    if let Some(unknown_tool) = source.downcast_ref::<UnknownTool>() {
+        return Err(RpcError::invalid_params().with_message(unknown_tool.to_string()));
+    }
+    if let Some(rpc_error) = source.downcast_ref::<RpcError>() {
+        return Err(rpc_error.clone());
+    }
+    Ok(CallToolResult::with_error(error))
+}
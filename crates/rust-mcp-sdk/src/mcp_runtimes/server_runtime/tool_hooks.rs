@@ -0,0 +1,139 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use futures::future::BoxFuture;
+use rust_mcp_schema::schema_utils::{RequestFromClient, ResultFromServer};
+use rust_mcp_schema::{
+    CallToolRequest, CallToolRequestParams, CallToolResult, ClientRequest, RpcError, ServerResult,
+};
+
+use crate::mcp_traits::mcp_server::McpServer;
+
+use super::ServerRuntime;
+
+/// A hook intercepting the execution of tools, for cross-cutting concerns such as caching,
+/// auditing or metrics, without modifying the handler of each tool.
+///
+/// Hooks are registered with `ServerRuntime::with_tool_hook()` and run for every call of an
+/// enabled tool, in the order they are registered. For each hook:
+/// - `before_call()` can inspect or modify the arguments, or reject the call with an error,
+/// - `around_call()` wraps the execution of the following hooks and of the tool, and can return
+///   a result without executing them, e.g. a cached result,
+/// - `after_call()` observes the arguments, the result or error, and the duration of the execution.
+///
+/// All methods have a default implementation, so only the relevant ones need to be implemented.
+/// Hooks receive the arguments as passed to the tool, i.e. after their decryption.
+///
+/// # Example
+/// ```ignore
+/// struct AuditTools;
+///
+/// #[async_trait]
+/// impl ToolHook for AuditTools {
+///     async fn after_call(
+///         &self,
+///         params: &CallToolRequestParams,
+///         result: &Result<CallToolResult, RpcError>,
+///         duration: Duration,
+///         runtime: &dyn McpServer,
+///     ) {
+///         let failed = result.as_ref().map_or(true, |result| result.is_error == Some(true));
+///         eprintln!("{} completed in {:?}, failed: {}", params.name, duration, failed);
+///     }
+/// }
+///
+/// let server = server_runtime::create_server(server_details, transport, handler)
+///     .with_tool_hook(AuditTools);
+/// ```
+#[async_trait]
+pub trait ToolHook: Send + Sync + 'static {
+    /// Called before the tool is executed. Returning an error rejects the call,
+    /// the error is sent to the client and the tool is not executed.
+    async fn before_call(
+        &self,
+        params: &mut CallToolRequestParams,
+        runtime: &dyn McpServer,
+    ) -> Result<(), RpcError> {
+        let _ = (params, runtime);
+        Ok(())
+    }
+
+    /// Wraps the execution of the tool: passes the call on with `next.run(params)`,
+    /// or returns a result without executing the tool.
+    async fn around_call(
+        &self,
+        params: CallToolRequestParams,
+        runtime: &dyn McpServer,
+        next: ToolCallNext<'_>,
+    ) -> Result<CallToolResult, RpcError> {
+        let _ = runtime;
+        next.run(params).await
+    }
+
+    /// Called once the tool is executed, with the duration of `around_call()`.
+    /// Not called if `before_call()` rejected the call.
+    async fn after_call(
+        &self,
+        params: &CallToolRequestParams,
+        result: &Result<CallToolResult, RpcError>,
+        duration: Duration,
+        runtime: &dyn McpServer,
+    ) {
+        let _ = (params, result, duration, runtime);
+    }
+}
+
+/// The rest of the tool hooks of a tool call, ending with the execution of the tool by the handler.
+pub struct ToolCallNext<'a> {
+    runtime: &'a ServerRuntime,
+    hooks: &'a [Arc<dyn ToolHook>],
+}
+
+impl<'a> ToolCallNext<'a> {
+    pub(crate) fn new(runtime: &'a ServerRuntime, hooks: &'a [Arc<dyn ToolHook>]) -> Self {
+        Self { runtime, hooks }
+    }
+
+    /// Passes the call to the next hook, or to the handler after the last one.
+    pub fn run(
+        self,
+        mut params: CallToolRequestParams,
+    ) -> BoxFuture<'a, Result<CallToolResult, RpcError>> {
+        Box::pin(async move {
+            let Some((hook, hooks)) = self.hooks.split_first() else {
+                return self.call_handler(params).await;
+            };
+
+            hook.before_call(&mut params, self.runtime).await?;
+            let started = Instant::now();
+            let next = ToolCallNext::new(self.runtime, hooks);
+            let result = hook.around_call(params.clone(), self.runtime, next).await;
+            hook.after_call(&params, &result, started.elapsed(), self.runtime)
+                .await;
+            result
+        })
+    }
+
+    async fn call_handler(
+        &self,
+        params: CallToolRequestParams,
+    ) -> Result<CallToolResult, RpcError> {
+        let request = RequestFromClient::ClientRequest(ClientRequest::CallToolRequest(
+            CallToolRequest::new(params),
+        ));
+        match self
+            .runtime
+            .handler
+            .handle_request(request, self.runtime)
+            .await?
+        {
+            ResultFromServer::ServerResult(ServerResult::CallToolResult(call_tool_result)) => {
+                Ok(call_tool_result)
+            }
+            _ => Err(RpcError::internal_error().with_message(
+                "The handler did not return the result of the tool call.".to_string(),
+            )),
+        }
+    }
+}
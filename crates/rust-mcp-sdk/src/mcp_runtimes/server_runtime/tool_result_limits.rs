@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use rust_mcp_schema::{CallToolResult, CallToolResultContentItem};
+
+/// Size limits applied to the text content of tool results before they are sent to the client.
+///
+/// Oversized text content is truncated, an explicit truncation marker is appended to it and a warning
+/// is added to the `_meta` of the result. Image and embedded resource contents are left untouched.
+///
+/// # Example
+/// ```ignore
+/// let limits = ToolResultLimits {
+///     // at most 100KB of text for any tool
+///     max_size: Some(100 * 1024),
+///     // except for the `read_file` tool
+///     tool_max_sizes: HashMap::from([("read_file".to_string(), 1024 * 1024)]),
+/// };
+/// let server = server_runtime::create_server(server_details, transport, handler)
+///     .with_tool_result_limits(limits);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ToolResultLimits {
+    /// The maximum size in bytes of the text content of a tool result, applied to every tool
+    /// without a specific limit. `None` means no limit.
+    pub max_size: Option<usize>,
+    /// Maximum sizes in bytes for specific tools, by tool name. They take precedence over `max_size`.
+    pub tool_max_sizes: HashMap<String, usize>,
+}
+
+impl ToolResultLimits {
+    /// Returns the maximum size in bytes of the text content of the given tool's results, if any.
+    pub fn limit_for(&self, tool_name: &str) -> Option<usize> {
+        self.tool_max_sizes
+            .get(tool_name)
+            .copied()
+            .or(self.max_size)
+    }
+
+    /// Truncates the text content of a tool result exceeding the limit of the tool.
+    ///
+    /// The limit applies to the text content items combined: the item exceeding the remaining size is
+    /// truncated and marked as such, and the text content items following it are removed.
+    ///
+    /// # Returns
+    /// `true` if the result was truncated.
+    pub fn apply(&self, tool_name: &str, result: &mut CallToolResult) -> bool {
+        let Some(max_size) = self.limit_for(tool_name) else {
+            return false;
+        };
+
+        let original_size: usize = result
+            .content
+            .iter()
+            .filter_map(|content| content.as_text_content().ok())
+            .map(|text_content| text_content.text.len())
+            .sum();
+        if original_size <= max_size {
+            return false;
+        }
+
+        let mut remaining = max_size;
+        let mut truncated = false;
+        result.content.retain_mut(|content| {
+            let CallToolResultContentItem::TextContent(text_content) = content else {
+                return true;
+            };
+            if truncated {
+                return false;
+            }
+            if text_content.text.len() <= remaining {
+                remaining -= text_content.text.len();
+                return true;
+            }
+            let end = floor_char_boundary(&text_content.text, remaining);
+            let omitted = text_content.text.len() - end;
+            text_content.text.truncate(end);
+            text_content
+                .text
+                .push_str(&format!("\n[truncated: {} bytes omitted]", omitted));
+            truncated = true;
+            true
+        });
+
+        let meta = result.meta.get_or_insert_with(serde_json::Map::new);
+        meta.insert("truncated".to_string(), serde_json::Value::Bool(true));
+        meta.insert(
+            "warning".to_string(),
+            serde_json::Value::String(format!(
+                "The result of the '{}' tool was truncated to {} bytes, its original size was {} bytes.",
+                tool_name, max_size, original_size
+            )),
+        );
+        true
+    }
+}
+
+// Returns the largest index not greater than `index` that is on a char boundary of `text`
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    (0..=index.min(text.len()))
+        .rev()
+        .find(|i| text.is_char_boundary(*i))
+        .unwrap_or(0)
+}
@@ -1,3 +1,9 @@
+pub mod call_tool_params_ext;
+pub mod call_tool_result_ext;
 pub mod mcp_client;
 pub mod mcp_handler;
+pub mod mcp_resource;
 pub mod mcp_server;
+pub mod mcp_tool;
+pub mod rpc_error_ext;
+pub mod tool_input_schema_ext;
@@ -0,0 +1,46 @@
+use rust_mcp_schema::{CallToolRequestParams, RpcError};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{McpSdkError, SdkResult};
+
+/// Builds the `CallToolRequestParams` of a tool call from any `Serialize` value, such as a struct or
+/// a `serde_json::json!` object, instead of filling the map of arguments by hand.
+///
+/// # Example
+/// ```ignore
+/// let params = CallToolRequestParams::with_args("get_weather", &WeatherArgs { city: "Paris".to_string() })?;
+/// let params = CallToolRequestParams::with_args("say_hello", &json!({ "name": "Ali" }))?;
+/// let result = client.call_tool(params).await?;
+/// ```
+pub trait CallToolRequestParamsExt: Sized {
+    /// Returns the params calling the tool `name` with the arguments serialized from `arguments`.
+    ///
+    /// # Errors
+    /// Returns an `invalid_params` error if `arguments` does not serialize to a JSON object or to
+    /// `null`, which stands for no arguments, or the error of its serialization.
+    fn with_args<A: Serialize + ?Sized>(name: impl Into<String>, arguments: &A) -> SdkResult<Self>;
+}
+
+impl CallToolRequestParamsExt for CallToolRequestParams {
+    fn with_args<A: Serialize + ?Sized>(name: impl Into<String>, arguments: &A) -> SdkResult<Self> {
+        let arguments = match serde_json::to_value(arguments)
+            .map_err(|error| McpSdkError::AnyError(Box::new(error)))?
+        {
+            Value::Object(arguments) => Some(arguments),
+            Value::Null => None,
+            value => {
+                return Err(RpcError::invalid_params()
+                    .with_message(format!(
+                        "The arguments of a tool call must be a JSON object, got: {}",
+                        value
+                    ))
+                    .into())
+            }
+        };
+        Ok(Self {
+            arguments,
+            name: name.into(),
+        })
+    }
+}
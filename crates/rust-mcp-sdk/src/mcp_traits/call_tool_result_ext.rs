@@ -0,0 +1,117 @@
+use rust_mcp_schema::{CallToolResult, CallToolResultContentItem, EmbeddedResourceResource};
+
+/// Helpers to flatten the mixed content of a `CallToolResult` into a single string,
+/// suitable for displaying it or for feeding it back to an LLM.
+///
+/// Text content is kept as is, images are replaced by an alternative text mentioning their mime type,
+/// and embedded resources are rendered with their uri followed by their text, if any.
+/// Results with `is_error` set are prefixed with an error label.
+///
+/// # Example
+/// ```ignore
+/// let result = client.call_tool(params).await?;
+/// let prompt = format!("The tool returned:\n{}", result.render_markdown());
+/// ```
+pub trait CallToolResultExt {
+    /// Renders the result as plain text.
+    fn render_text(&self) -> String;
+    /// Renders the result as markdown, with embedded text resources as fenced code blocks.
+    fn render_markdown(&self) -> String;
+}
+
+impl CallToolResultExt for CallToolResult {
+    fn render_text(&self) -> String {
+        let parts: Vec<String> = self
+            .content
+            .iter()
+            .map(|content| match content {
+                CallToolResultContentItem::TextContent(text_content) => text_content.text.clone(),
+                CallToolResultContentItem::ImageContent(image_content) => {
+                    format!("[image: {}]", image_content.mime_type)
+                }
+                CallToolResultContentItem::EmbeddedResource(embedded_resource) => {
+                    match &embedded_resource.resource {
+                        EmbeddedResourceResource::TextResourceContents(resource) => {
+                            format!("[resource: {}]\n{}", resource.uri, resource.text)
+                        }
+                        EmbeddedResourceResource::BlobResourceContents(resource) => format!(
+                            "[resource: {} ({})]",
+                            resource.uri,
+                            resource.mime_type.as_deref().unwrap_or("binary")
+                        ),
+                    }
+                }
+            })
+            .collect();
+
+        with_error_label(self, "Error: ", parts.join("\n"))
+    }
+
+    fn render_markdown(&self) -> String {
+        let parts: Vec<String> = self
+            .content
+            .iter()
+            .map(|content| match content {
+                CallToolResultContentItem::TextContent(text_content) => text_content.text.clone(),
+                CallToolResultContentItem::ImageContent(image_content) => {
+                    format!("_[image: {}]_", image_content.mime_type)
+                }
+                CallToolResultContentItem::EmbeddedResource(embedded_resource) => {
+                    match &embedded_resource.resource {
+                        EmbeddedResourceResource::TextResourceContents(resource) => {
+                            let fence = code_fence(&resource.text);
+                            format!(
+                                "**Resource:** `{}`\n\n{}{}\n{}\n{}",
+                                resource.uri,
+                                fence,
+                                fence_language(resource.mime_type.as_deref()),
+                                resource.text,
+                                fence
+                            )
+                        }
+                        EmbeddedResourceResource::BlobResourceContents(resource) => format!(
+                            "**Resource:** `{}` _({}, binary content omitted)_",
+                            resource.uri,
+                            resource.mime_type.as_deref().unwrap_or("unknown type")
+                        ),
+                    }
+                }
+            })
+            .collect();
+
+        with_error_label(self, "**Error:** ", parts.join("\n\n"))
+    }
+}
+
+fn with_error_label(result: &CallToolResult, label: &str, rendered: String) -> String {
+    if result.is_error.unwrap_or(false) {
+        format!("{}{}", label, rendered)
+    } else {
+        rendered
+    }
+}
+
+// Returns a code fence longer than any sequence of backticks in the text, and at least three backticks long
+fn code_fence(text: &str) -> String {
+    let longest_run = text
+        .split(|c| c != '`')
+        .map(|run| run.len())
+        .max()
+        .unwrap_or(0);
+    "`".repeat(longest_run.max(2) + 1)
+}
+
+// Maps the mime type of a text resource to the language of its code block
+fn fence_language(mime_type: Option<&str>) -> &str {
+    match mime_type {
+        Some("application/json") => "json",
+        Some("text/markdown") => "markdown",
+        Some("text/html") => "html",
+        Some("text/css") => "css",
+        Some("text/javascript") | Some("application/javascript") => "javascript",
+        Some("application/xml") | Some("text/xml") => "xml",
+        Some("application/x-yaml") | Some("application/yaml") | Some("text/yaml") => "yaml",
+        Some("text/csv") => "csv",
+        _ => "",
+    }
+}
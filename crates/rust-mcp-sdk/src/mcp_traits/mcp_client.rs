@@ -1,24 +1,45 @@
+use std::collections::HashSet;
+use std::future::Future;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use rust_mcp_schema::{
     schema_utils::{
-        self, MCPMessage, MessageFromClient, NotificationFromClient, RequestFromClient,
-        ResultFromServer, ServerMessage,
+        MCPMessage, MessageFromClient, NotificationFromClient, RequestFromClient, ResultFromServer,
+        ServerMessage,
     },
-    CallToolRequest, CallToolRequestParams, CallToolResult, CompleteRequest, CompleteRequestParams,
-    CreateMessageRequest, GetPromptRequest, GetPromptRequestParams, Implementation,
-    InitializeRequestParams, InitializeResult, ListPromptsRequest, ListPromptsRequestParams,
-    ListResourceTemplatesRequest, ListResourceTemplatesRequestParams, ListResourcesRequest,
-    ListResourcesRequestParams, ListRootsRequest, ListToolsRequest, ListToolsRequestParams,
-    LoggingLevel, PingRequest, ReadResourceRequest, ReadResourceRequestParams,
+    CallToolRequest, CallToolRequestParams, CallToolResult, CallToolResultContentItem,
+    CompleteRequest, CompleteRequestParams, CreateMessageRequest, GetPromptRequest,
+    GetPromptRequestParams, Implementation, InitializeRequest, InitializeRequestParams,
+    InitializeResult, ListPromptsRequest, ListPromptsRequestParams, ListResourceTemplatesRequest,
+    ListResourceTemplatesRequestParams, ListResourcesRequest, ListResourcesRequestParams,
+    ListRootsRequest, ListToolsRequest, ListToolsRequestParams, LoggingLevel, PingRequest, Prompt,
+    ReadResourceRequest, ReadResourceRequestParams, Resource, ResourceTemplate, Root,
     RootsListChangedNotification, RootsListChangedNotificationParams, RpcError, ServerCapabilities,
-    SetLevelRequest, SetLevelRequestParams, SubscribeRequest, SubscribeRequestParams,
+    SetLevelRequest, SetLevelRequestParams, SubscribeRequest, SubscribeRequestParams, Tool,
     UnsubscribeRequest, UnsubscribeRequestParams,
 };
-use rust_mcp_transport::{McpDispatch, MessageDispatcher};
+use rust_mcp_transport::{McpDispatch, MessageDispatcher, ProcessStatus};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::error::{McpSdkError, SdkResult};
+use crate::mcp_handlers::sampling_handler::SamplingHandler;
+use crate::mcp_runtimes::client_runtime::{RetryPolicy, ServerProfile};
+use crate::mcp_traits::call_tool_params_ext::CallToolRequestParamsExt;
+use crate::mcp_traits::call_tool_result_ext::CallToolResultExt;
+use crate::request_meta::RequestOptions;
+use crate::resource_reader::ResourceReader;
+use crate::runtime_metrics::{measure_request, Metrics};
+use crate::sampling_depth::{
+    current_sampling_depth, set_tool_call_depth, DEFAULT_MAX_SAMPLING_DEPTH,
+};
 
-use crate::{error::SdkResult, utils::format_assertion_message};
+/// Maximum number of pages requested by the `list_all_*()` methods of `McpClient`
+pub const MAX_LIST_PAGES: usize = 1000;
 
 #[async_trait]
 pub trait McpClient: Sync + Send {
@@ -28,6 +49,29 @@ pub trait McpClient: Sync + Send {
     async fn shut_down(&self) -> SdkResult<()>;
     async fn is_shut_down(&self) -> bool;
 
+    /// Completes once the initialization with the server started by `start()` completed, returning
+    /// `McpSdkError::InitializeFailed` if it failed.
+    ///
+    /// Tasks sharing the client wait for it before sending requests, instead of racing against
+    /// `start()`. It waits for the client to be started.
+    async fn ready(&self) -> SdkResult<()>;
+
+    /// Completes once the session with the server ended: the server closed the connection,
+    /// stopped answering the pings of the keep-alive, or the client was shut down.
+    ///
+    /// Returns the error that ended the session, such as an error of the handler or a
+    /// `KeepAliveTimeout`. Only one of concurrent callers receives it, the others return `Ok(())`.
+    async fn wait_for_exit(&self) -> SdkResult<()>;
+
+    /// Starts the client and waits for the end of the session, see `wait_for_exit()`.
+    async fn run(self: Arc<Self>) -> SdkResult<()>
+    where
+        Self: 'static,
+    {
+        Arc::clone(&self).start().await?;
+        self.wait_for_exit().await
+    }
+
     async fn sender(&self) -> &tokio::sync::RwLock<Option<MessageDispatcher<ServerMessage>>>
     where
         MessageDispatcher<ServerMessage>: McpDispatch<ServerMessage, MessageFromClient>;
@@ -35,6 +79,33 @@ pub trait McpClient: Sync + Send {
     fn client_info(&self) -> &InitializeRequestParams;
     fn server_info(&self) -> Option<InitializeResult>;
 
+    /// Returns the sampling handler registered on the client, if any.
+    fn sampling_handler(&self) -> Option<Arc<dyn SamplingHandler>>;
+
+    /// Returns the roots currently exposed by the client.
+    ///
+    /// These roots are used to answer `ListRootsRequest`s from the server unless
+    /// `handle_list_roots_request` is overridden in the client handler.
+    fn roots(&self) -> Vec<Root>;
+
+    /// Replaces the list of roots exposed by the client.
+    ///
+    /// If the client is initialized and advertises the `roots.listChanged` capability,
+    /// a `RootsListChangedNotification` is sent to the server.
+    async fn set_roots(&self, roots: Vec<Root>) -> SdkResult<()>;
+
+    /// Adds a root to the list of roots exposed by the client, replacing any existing root with the same uri.
+    ///
+    /// If the client is initialized and advertises the `roots.listChanged` capability,
+    /// a `RootsListChangedNotification` is sent to the server.
+    async fn add_root(&self, root: Root) -> SdkResult<()>;
+
+    /// Removes the root with the given uri, returning `true` if a root was removed.
+    ///
+    /// If a root was removed, the client is initialized and it advertises the `roots.listChanged`
+    /// capability, a `RootsListChangedNotification` is sent to the server.
+    async fn remove_root(&self, uri: &str) -> SdkResult<bool>;
+
     /// Checks whether the server has been initialized with client
     fn is_initialized(&self) -> bool {
         self.server_info().is_some()
@@ -47,6 +118,12 @@ pub trait McpClient: Sync + Send {
             .map(|server_details| server_details.server_info)
     }
 
+    /// Returns the details negotiated with the server, with accessors for its capabilities,
+    /// see `ServerProfile`. `None` until the initialization completed.
+    fn server_profile(&self) -> Option<ServerProfile> {
+        self.server_info().map(ServerProfile::new)
+    }
+
     /// Returns the server's capabilities.
     /// After initialization has completed, this will be populated with the server's reported capabilities.
     fn server_capabilities(&self) -> Option<ServerCapabilities> {
@@ -140,49 +217,88 @@ pub trait McpClient: Sync + Send {
         self.server_info()?.instructions
     }
 
+    /// Returns the policy used to retry failed requests, requests are not retried by default.
+    fn retry_policy(&self) -> RetryPolicy {
+        RetryPolicy::default()
+    }
+
+    /// Whether outgoing requests and notifications are checked against the capabilities of both parties
+    /// before being sent, failing fast when they are not supported.
+    ///
+    /// Disabled by default, messages are then sent regardless of the capabilities.
+    fn enforce_strict_capabilities(&self) -> bool {
+        false
+    }
+
+    /// Returns the maximum nesting depth of the sampling requests accepted from the server,
+    /// see `sampling`. Defaults to `DEFAULT_MAX_SAMPLING_DEPTH`.
+    fn max_sampling_depth(&self) -> u32 {
+        DEFAULT_MAX_SAMPLING_DEPTH
+    }
+
+    /// Returns the verifier of the signatures of the results and notifications received from the
+    /// server, if any, see `signing`.
+    #[cfg(feature = "signing")]
+    fn signature_verifier(&self) -> Option<Arc<dyn crate::signing::SignatureVerifier>> {
+        None
+    }
+
+    /// Returns the metrics recording the requests sent by the client, if any.
+    fn metrics_recorder(&self) -> Option<Arc<dyn Metrics>> {
+        None
+    }
+
+    /// Returns the status of the MCP server process launched by the client's transport,
+    /// or `None` if the transport did not launch a process.
+    async fn server_process_status(&self) -> Option<ProcessStatus> {
+        None
+    }
+
     /// Sends a request to the server and processes the response.
     ///
     /// This function sends a `RequestFromClient` message to the server, waits for the response,
     /// and handles the result. If the response is empty or of an invalid type, an error is returned.
     /// Otherwise, it returns the result from the server.
+    ///
+    /// Failed requests are retried according to the `retry_policy()` of the client.
     async fn request(&self, request: RequestFromClient) -> SdkResult<ResultFromServer> {
-        let sender = self.sender().await.read().await;
-        let sender = sender.as_ref().ok_or(crate::error::McpSdkError::SdkError(
-            schema_utils::SdkError::connection_closed(),
-        ))?;
-
-        // Send the request and receive the response.
-        let response = sender
-            .send(MessageFromClient::RequestFromClient(request), None)
-            .await?;
-
-        let server_message = response.ok_or_else(|| {
-            RpcError::internal_error()
-                .with_message("An empty response was received from the server.".to_string())
-        })?;
-
-        if server_message.is_error() {
-            return Err(server_message.as_error()?.error.into());
+        let retry_policy = self.retry_policy();
+        let mut attempt = 1;
+        loop {
+            match self.request_once(request.clone()).await {
+                Err(error) if retry_policy.should_retry(attempt, &error) => {
+                    tokio::time::sleep(retry_policy.backoff(attempt)).await;
+                    attempt += 1;
+                }
+                result => return result,
+            }
         }
+    }
+
+    /// Sends a request to the server like `request()`, without retrying it if it fails.
+    async fn request_once(&self, request: RequestFromClient) -> SdkResult<ResultFromServer> {
+        send_request(self, request, None, None).await
+    }
 
-        return Ok(server_message.as_response()?.result);
+    /// Sends a request to the server like `request_once()`, through the same middlewares and
+    /// checks, unless a request sent with the same `idempotency_token` is still awaiting its
+    /// response, in which case that response is returned instead of sending the request again.
+    ///
+    /// Use a token identifying the logical request, so that retried sends do not result
+    /// in the request being processed twice by the server.
+    async fn request_deduplicated(
+        &self,
+        request: RequestFromClient,
+        idempotency_token: &str,
+    ) -> SdkResult<ResultFromServer> {
+        send_request(self, request, None, Some(idempotency_token)).await
     }
 
     /// Sends a notification. This is a one-way message that is not expected
     /// to return any response. The method asynchronously sends the notification using
     /// the transport layer and does not wait for any acknowledgement or result.
     async fn send_notification(&self, notification: NotificationFromClient) -> SdkResult<()> {
-        let sender = self.sender().await.read().await;
-        let sender = sender.as_ref().ok_or(crate::error::McpSdkError::SdkError(
-            schema_utils::SdkError::connection_closed(),
-        ))?;
-        sender
-            .send(
-                MessageFromClient::NotificationFromClient(notification),
-                None,
-            )
-            .await?;
-        Ok(())
+        send_notification(self, notification).await
     }
 
     /// A ping request to check that the other party is still alive.
@@ -234,6 +350,21 @@ pub trait McpClient: Sync + Send {
         Ok(response.try_into()?)
     }
 
+    /// Retrieves all the prompts of the server, requesting pages until the server returns no `next_cursor`.
+    ///
+    /// # Errors
+    /// Fails if the server returns a cursor it already returned, or more than `MAX_LIST_PAGES` pages.
+    async fn list_all_prompts(&self) -> SdkResult<Vec<Prompt>> {
+        list_all_pages(|cursor| async move {
+            let params = cursor.map(|cursor| ListPromptsRequestParams {
+                cursor: Some(cursor),
+            });
+            let result = self.list_prompts(params).await?;
+            Ok((result.prompts, result.next_cursor))
+        })
+        .await
+    }
+
     async fn list_resources(
         &self,
         params: Option<ListResourcesRequestParams>,
@@ -248,6 +379,21 @@ pub trait McpClient: Sync + Send {
         Ok(response.try_into()?)
     }
 
+    /// Retrieves all the resources of the server, requesting pages until the server returns no `next_cursor`.
+    ///
+    /// # Errors
+    /// Fails if the server returns a cursor it already returned, or more than `MAX_LIST_PAGES` pages.
+    async fn list_all_resources(&self) -> SdkResult<Vec<Resource>> {
+        list_all_pages(|cursor| async move {
+            let params = cursor.map(|cursor| ListResourcesRequestParams {
+                cursor: Some(cursor),
+            });
+            let result = self.list_resources(params).await?;
+            Ok((result.resources, result.next_cursor))
+        })
+        .await
+    }
+
     async fn list_resource_templates(
         &self,
         params: Option<ListResourceTemplatesRequestParams>,
@@ -257,6 +403,21 @@ pub trait McpClient: Sync + Send {
         Ok(response.try_into()?)
     }
 
+    /// Retrieves all the resource templates of the server, requesting pages until the server returns no `next_cursor`.
+    ///
+    /// # Errors
+    /// Fails if the server returns a cursor it already returned, or more than `MAX_LIST_PAGES` pages.
+    async fn list_all_resource_templates(&self) -> SdkResult<Vec<ResourceTemplate>> {
+        list_all_pages(|cursor| async move {
+            let params = cursor.map(|cursor| ListResourceTemplatesRequestParams {
+                cursor: Some(cursor),
+            });
+            let result = self.list_resource_templates(params).await?;
+            Ok((result.resource_templates, result.next_cursor))
+        })
+        .await
+    }
+
     async fn read_resource(
         &self,
         params: ReadResourceRequestParams,
@@ -266,6 +427,37 @@ pub trait McpClient: Sync + Send {
         Ok(response.try_into()?)
     }
 
+    /// Reads a resource as a stream of bytes, decoding binary contents from base64 as they are read,
+    /// see `ResourceReader`.
+    async fn read_resource_stream(&self, uri: &str) -> SdkResult<ResourceReader> {
+        let result = self
+            .read_resource(ReadResourceRequestParams {
+                uri: uri.to_string(),
+            })
+            .await?;
+        Ok(ResourceReader::new(result))
+    }
+
+    /// Reads a resource and writes its contents to the file at `path`, creating or truncating it.
+    /// Binary contents are decoded from base64 as they are written, and the file is removed
+    /// if the contents can not be decoded.
+    ///
+    /// # Returns
+    /// The number of bytes written.
+    async fn download_resource(&self, uri: &str, path: &Path) -> SdkResult<u64> {
+        let mut reader = self.read_resource_stream(uri).await?;
+        let mut file = tokio::fs::File::create(path).await?;
+        let result = match tokio::io::copy(&mut reader, &mut file).await {
+            Ok(size) => file.sync_all().await.map(|_| size),
+            Err(error) => Err(error),
+        };
+        if result.is_err() {
+            drop(file);
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        Ok(result?)
+    }
+
     async fn subscribe_resource(
         &self,
         params: SubscribeRequestParams,
@@ -284,12 +476,51 @@ pub trait McpClient: Sync + Send {
         Ok(response.try_into()?)
     }
 
-    async fn call_tool(&self, params: CallToolRequestParams) -> SdkResult<CallToolResult> {
+    /// Calls a tool of the server. Called while fulfilling a sampling request, the call carries
+    /// the depth of the request, see `sampling`.
+    async fn call_tool(&self, mut params: CallToolRequestParams) -> SdkResult<CallToolResult> {
+        set_tool_call_depth(&mut params, current_sampling_depth());
         let request = CallToolRequest::new(params);
         let response = self.request(request.into()).await?;
         Ok(response.try_into()?)
     }
 
+    /// Calls a tool with the given options, adding their `_meta` entries to the call,
+    /// see `request_context`.
+    async fn call_tool_with_options(
+        &self,
+        mut params: CallToolRequestParams,
+        options: RequestOptions,
+    ) -> SdkResult<CallToolResult> {
+        options.apply_to_tool_call(&mut params);
+        self.call_tool(params).await
+    }
+
+    /// Requests a dry run of a tool call, validating it and describing what it would do without
+    /// side effects, see `dry_run`. The server rejects the dry runs of tools that do not support them.
+    async fn call_tool_dry_run(
+        &self,
+        mut params: CallToolRequestParams,
+    ) -> SdkResult<CallToolResult> {
+        crate::dry_run::set_dry_run(&mut params, true);
+        self.call_tool(params).await
+    }
+
+    /// Calls a tool after encrypting the arguments marked as encrypted in its input schema,
+    /// so that they can only be read by a server sharing the same `KeyProvider`.
+    #[cfg(feature = "encryption")]
+    async fn call_tool_encrypted(
+        &self,
+        tool: &Tool,
+        mut params: CallToolRequestParams,
+        key_provider: &dyn crate::encryption::KeyProvider,
+    ) -> SdkResult<CallToolResult> {
+        if let Some(arguments) = params.arguments.as_mut() {
+            crate::encryption::encrypt_arguments(tool, arguments, key_provider)?;
+        }
+        self.call_tool(params).await
+    }
+
     async fn list_tools(
         &self,
         params: Option<ListToolsRequestParams>,
@@ -299,6 +530,31 @@ pub trait McpClient: Sync + Send {
         Ok(response.try_into()?)
     }
 
+    /// Retrieves all the tools of the server, requesting pages until the server returns no `next_cursor`.
+    ///
+    /// # Errors
+    /// Fails if the server returns a cursor it already returned, or more than `MAX_LIST_PAGES` pages.
+    async fn list_all_tools(&self) -> SdkResult<Vec<Tool>> {
+        list_all_pages(|cursor| async move {
+            let params = cursor.map(|cursor| ListToolsRequestParams {
+                cursor: Some(cursor),
+            });
+            let result = self.list_tools(params).await?;
+            Ok((result.tools, result.next_cursor))
+        })
+        .await
+    }
+
+    /// Checks if the client advertises the `roots.listChanged` capability.
+    fn client_supports_roots_list_changed(&self) -> bool {
+        self.client_info()
+            .capabilities
+            .roots
+            .as_ref()
+            .and_then(|roots| roots.list_changed)
+            .unwrap_or(false)
+    }
+
     async fn send_roots_list_changed(
         &self,
         params: Option<RootsListChangedNotificationParams>,
@@ -315,14 +571,16 @@ pub trait McpClient: Sync + Send {
     fn assert_server_capabilities(&self, request_method: &String) -> SdkResult<()> {
         let entity = "Server";
 
-        let capabilities = self.server_capabilities().ok_or::<RpcError>(
-            RpcError::internal_error().with_message("Server is not initialized!".to_string()),
-        )?;
+        let capabilities = self
+            .server_capabilities()
+            .ok_or(McpSdkError::NotInitialized)?;
 
         if *request_method == SetLevelRequest::method_name() && capabilities.logging.is_none() {
-            return Err(RpcError::internal_error()
-                .with_message(format_assertion_message(entity, "logging", request_method))
-                .into());
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "logging",
+                request_method,
+            ));
         }
 
         if [
@@ -332,9 +590,11 @@ pub trait McpClient: Sync + Send {
         .contains(request_method)
             && capabilities.prompts.is_none()
         {
-            return Err(RpcError::internal_error()
-                .with_message(format_assertion_message(entity, "prompts", request_method))
-                .into());
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "prompts",
+                request_method,
+            ));
         }
 
         if [
@@ -347,13 +607,11 @@ pub trait McpClient: Sync + Send {
         .contains(request_method)
             && capabilities.resources.is_none()
         {
-            return Err(RpcError::internal_error()
-                .with_message(format_assertion_message(
-                    entity,
-                    "resources",
-                    request_method,
-                ))
-                .into());
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "resources",
+                request_method,
+            ));
         }
 
         if [
@@ -363,9 +621,11 @@ pub trait McpClient: Sync + Send {
         .contains(request_method)
             && capabilities.tools.is_none()
         {
-            return Err(RpcError::internal_error()
-                .with_message(format_assertion_message(entity, "tools", request_method))
-                .into());
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "tools",
+                request_method,
+            ));
         }
 
         Ok(())
@@ -374,20 +634,17 @@ pub trait McpClient: Sync + Send {
     fn assert_client_notification_capabilities(
         &self,
         notification_method: &String,
-    ) -> std::result::Result<(), RpcError> {
+    ) -> SdkResult<()> {
         let entity = "Client";
-        let capabilities = &self.client_info().capabilities;
 
         if *notification_method == RootsListChangedNotification::method_name()
-            && capabilities.roots.is_some()
+            && !self.client_supports_roots_list_changed()
         {
-            return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "roots list changed notifications",
-                    notification_method,
-                )),
-            );
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "roots list changed notifications",
+                notification_method,
+            ));
         }
 
         Ok(())
@@ -400,27 +657,277 @@ pub trait McpClient: Sync + Send {
         let entity = "Client";
         let capabilities = &self.client_info().capabilities;
 
-        if *request_method == CreateMessageRequest::method_name() && capabilities.sampling.is_some()
+        if *request_method == CreateMessageRequest::method_name() && capabilities.sampling.is_none()
         {
-            return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "sampling capability",
-                    request_method,
-                )),
-            );
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "sampling capability",
+                request_method,
+            )
+            .into());
         }
 
-        if *request_method == ListRootsRequest::method_name() && capabilities.roots.is_some() {
-            return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "roots capability",
-                    request_method,
-                )),
-            );
+        if *request_method == ListRootsRequest::method_name() && capabilities.roots.is_none() {
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "roots capability",
+                request_method,
+            )
+            .into());
         }
 
         Ok(())
     }
 }
+
+/// Typed tool calls, serializing the arguments from a struct and deserializing the result into
+/// another one, instead of building `CallToolRequestParams` and reading the content by hand.
+///
+/// Implemented for every `McpClient`, including `dyn McpClient`.
+///
+/// # Example
+/// ```ignore
+/// #[derive(Serialize)]
+/// struct WeatherArgs { city: String }
+///
+/// #[derive(Deserialize)]
+/// struct WeatherOutput { temperature: f64 }
+///
+/// let weather: WeatherOutput = client
+///     .call_tool_typed("get_weather", WeatherArgs { city: "Paris".to_string() })
+///     .await?;
+/// ```
+#[async_trait]
+pub trait McpClientExt: McpClient {
+    /// Calls a tool with the arguments serialized from `arguments`, see
+    /// `CallToolRequestParamsExt::with_args()`, and deserializes its result into `T`.
+    ///
+    /// The result is read from the first text content of the result: its JSON content, such as the
+    /// structured output returned by tools declared with `#[mcp_tool(output = ...)]`, or the text
+    /// itself if it is not JSON, e.g. to read it as a `String`.
+    ///
+    /// # Errors
+    /// Returns `McpSdkError::ToolCallFailed` if the tool returned a result with `is_error`, and
+    /// `McpSdkError::UnexpectedToolResult` if its result cannot be deserialized into `T`.
+    async fn call_tool_typed<T, A>(&self, name: &str, arguments: A) -> SdkResult<T>
+    where
+        T: DeserializeOwned,
+        A: Serialize + Send,
+    {
+        let params = CallToolRequestParams::with_args(name, &arguments)?;
+        let result = self.call_tool(params).await?;
+
+        if result.is_error.unwrap_or(false) {
+            // the content without the error label of `render_text()`
+            let message = CallToolResult {
+                is_error: None,
+                ..result
+            }
+            .render_text();
+            return Err(McpSdkError::ToolCallFailed {
+                name: name.to_string(),
+                message,
+            });
+        }
+        typed_content(&result).map_err(|message| McpSdkError::UnexpectedToolResult {
+            name: name.to_string(),
+            message,
+        })
+    }
+}
+
+impl<C: McpClient + ?Sized> McpClientExt for C {}
+
+// Deserializes the first text content of a tool result, as JSON or as a JSON string
+fn typed_content<T: DeserializeOwned>(result: &CallToolResult) -> std::result::Result<T, String> {
+    let text = result
+        .content
+        .iter()
+        .find_map(|content| match content {
+            CallToolResultContentItem::TextContent(text_content) => Some(&text_content.text),
+            _ => None,
+        })
+        .ok_or_else(|| "the result has no text content".to_string())?;
+
+    serde_json::from_str(text).or_else(|error| {
+        serde_json::from_value(Value::String(text.clone())).map_err(|_| error.to_string())
+    })
+}
+
+// Requests the pages of a list with `list_page` until the server returns no next cursor
+async fn list_all_pages<T, F, Fut>(mut list_page: F) -> SdkResult<Vec<T>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = SdkResult<(Vec<T>, Option<String>)>>,
+{
+    let mut items = vec![];
+    // the cursors returned by the server, a cursor returned twice would paginate forever
+    let mut cursors = HashSet::new();
+    let mut cursor = None;
+    for _ in 0..MAX_LIST_PAGES {
+        let (page, next_cursor) = list_page(cursor.take()).await?;
+        items.extend(page);
+        match next_cursor {
+            Some(next_cursor) if !cursors.insert(next_cursor.clone()) => {
+                return Err(RpcError::internal_error()
+                    .with_message(format!(
+                        "The server returned the same pagination cursor twice: \"{}\"",
+                        next_cursor
+                    ))
+                    .into());
+            }
+            Some(next_cursor) => cursor = Some(next_cursor),
+            None => return Ok(items),
+        }
+    }
+    Err(RpcError::internal_error()
+        .with_message(format!(
+            "The server returned more than {} pages",
+            MAX_LIST_PAGES
+        ))
+        .into())
+}
+
+// Sends a single request to the server, awaiting its response for `timeout` if given,
+// or for the timeout of the transport. Given an `idempotency_token`, the response of the request
+// sent with the same token that is still awaiting its response is returned instead, if any,
+// awaited for the timeout of the transport.
+pub(crate) async fn send_request<C: McpClient + ?Sized>(
+    client: &C,
+    request: RequestFromClient,
+    timeout: Option<Duration>,
+    idempotency_token: Option<&str>,
+) -> SdkResult<ResultFromServer> {
+    // the initialize request is what makes the capabilities of the server known
+    if client.enforce_strict_capabilities() && request.method() != InitializeRequest::method_name()
+    {
+        client.assert_server_capabilities(&request.method().to_string())?;
+    }
+
+    let sender = client.sender().await.read().await;
+    let sender = sender.as_ref().ok_or(McpSdkError::ConnectionClosed)?;
+
+    // Send the request and receive the response.
+    let method = request.method().to_string();
+    let message = MessageFromClient::RequestFromClient(request);
+    let send = async {
+        match (idempotency_token, timeout) {
+            (Some(idempotency_token), _) => {
+                sender.send_deduplicated(message, idempotency_token).await
+            }
+            (None, Some(timeout)) => sender.send_with_timeout(message, None, timeout).await,
+            (None, None) => sender.send(message, None).await,
+        }
+    };
+    let response = match client.metrics_recorder() {
+        Some(metrics) => measure_request(metrics.as_ref(), &method, send, Result::is_err).await?,
+        None => send.await?,
+    };
+
+    let server_message = response.ok_or_else(|| {
+        RpcError::internal_error()
+            .with_message("An empty response was received from the server.".to_string())
+    })?;
+
+    if server_message.is_error() {
+        return Err(server_message.as_error()?.error.into());
+    }
+
+    let response = server_message.as_response()?;
+    #[cfg(feature = "signing")]
+    verify_result(client, &response.result, &response.id, &method)?;
+    Ok(response.result)
+}
+
+// Verifies the signature of a result received from the server, if a signature verifier is set.
+// Responses are matched to their pending request by id, so the id of the response is the one of
+// the request, bound to the result by its signature along with the method of the request.
+#[cfg(feature = "signing")]
+fn verify_result<C: McpClient + ?Sized>(
+    client: &C,
+    result: &ResultFromServer,
+    request_id: &rust_mcp_schema::RequestId,
+    method: &str,
+) -> SdkResult<()> {
+    if let Some(verifier) = client.signature_verifier() {
+        crate::signing::verify_result(result, request_id, method, verifier.as_ref())?;
+    }
+    Ok(())
+}
+
+// Sends a notification to the server
+pub(crate) async fn send_notification<C: McpClient + ?Sized>(
+    client: &C,
+    notification: NotificationFromClient,
+) -> SdkResult<()> {
+    if let (true, NotificationFromClient::ClientNotification(client_notification)) =
+        (client.enforce_strict_capabilities(), &notification)
+    {
+        client
+            .assert_client_notification_capabilities(&client_notification.method().to_string())?;
+    }
+
+    let sender = client.sender().await.read().await;
+    let sender = sender.as_ref().ok_or(McpSdkError::ConnectionClosed)?;
+    sender
+        .send(
+            MessageFromClient::NotificationFromClient(notification),
+            None,
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_message(error: McpSdkError) -> String {
+        match error {
+            McpSdkError::RpcError(error) => error.message,
+            error => panic!("unexpected error: {}", error),
+        }
+    }
+
+    // Lists pages of a single item, following the given cursors
+    async fn list_pages(next_cursors: &[Option<&str>]) -> SdkResult<Vec<usize>> {
+        let mut page_index = 0;
+        list_all_pages(|_| {
+            let page = (
+                vec![page_index],
+                next_cursors[page_index].map(str::to_string),
+            );
+            page_index += 1;
+            async move { Ok(page) }
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_list_all_pages() {
+        let items = list_pages(&[Some("a"), Some("b"), None]).await.unwrap();
+        assert_eq!(items, [0, 1, 2]);
+    }
+
+    #[tokio::test]
+    async fn test_list_all_pages_stops_on_cursor_cycles() {
+        let error = list_pages(&[Some("a"), Some("b"), Some("a")])
+            .await
+            .unwrap_err();
+        assert_eq!(
+            error_message(error),
+            "The server returned the same pagination cursor twice: \"a\""
+        );
+    }
+
+    #[tokio::test]
+    async fn test_list_all_pages_stops_after_the_page_limit() {
+        let cursors: Vec<String> = (0..=MAX_LIST_PAGES).map(|page| page.to_string()).collect();
+        let cursors: Vec<_> = cursors.iter().map(|cursor| Some(cursor.as_str())).collect();
+        let error = list_pages(&cursors).await.unwrap_err();
+        assert_eq!(
+            error_message(error),
+            format!("The server returned more than {} pages", MAX_LIST_PAGES)
+        );
+    }
+}
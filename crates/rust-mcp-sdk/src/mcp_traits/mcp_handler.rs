@@ -1,3 +1,5 @@
+use std::process::ExitStatus;
+
 use async_trait::async_trait;
 use rust_mcp_schema::{
     schema_utils::{
@@ -48,4 +50,6 @@ pub trait McpClientHandler: Send + Sync {
         error_message: String,
         runtime: &dyn McpClient,
     ) -> SdkResult<()>;
+
+    async fn on_server_process_exit(&self, status: ExitStatus, runtime: &dyn McpClient);
 }
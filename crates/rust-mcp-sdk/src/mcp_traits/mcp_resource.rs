@@ -0,0 +1,12 @@
+use async_trait::async_trait;
+use rust_mcp_schema::{ReadResourceResult, RpcError};
+
+/// A resource that is read asynchronously.
+///
+/// Implement this trait for the structs annotated with `#[mcp_resource]`, to register them with
+/// `ResourceRegistry::register_handler()`.
+#[async_trait]
+pub trait McpResource: Send + Sync {
+    /// Reads the content of the resource with the given uri.
+    async fn read_resource(&self, uri: String) -> Result<ReadResourceResult, RpcError>;
+}
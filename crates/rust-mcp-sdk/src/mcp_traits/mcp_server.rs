@@ -7,18 +7,29 @@ use rust_mcp_schema::{
     CallToolRequest, CreateMessageRequest, CreateMessageRequestParams, CreateMessageResult,
     GetPromptRequest, Implementation, InitializeRequestParams, InitializeResult,
     ListPromptsRequest, ListResourceTemplatesRequest, ListResourcesRequest, ListRootsRequest,
-    ListRootsRequestParams, ListRootsResult, ListToolsRequest, LoggingMessageNotification,
-    LoggingMessageNotificationParams, PingRequest, PromptListChangedNotification,
+    ListRootsRequestParams, ListRootsResult, ListToolsRequest, LoggingLevel,
+    LoggingMessageNotification, LoggingMessageNotificationParams, PingRequest,
+    ProgressNotification, ProgressToken, PromptListChangedNotification,
     PromptListChangedNotificationParams, ReadResourceRequest, ResourceListChangedNotification,
     ResourceListChangedNotificationParams, ResourceUpdatedNotification,
     ResourceUpdatedNotificationParams, RpcError, ServerCapabilities, SetLevelRequest,
     ToolListChangedNotification, ToolListChangedNotificationParams,
 };
-use rust_mcp_transport::{McpDispatch, MessageDispatcher};
+use rust_mcp_transport::{McpDispatch, MessageDispatcher, SessionId};
+use serde_json::{json, Value};
 
-use crate::{error::SdkResult, utils::format_assertion_message};
+use crate::error::{McpSdkError, SdkResult};
+use crate::mcp_runtimes::server_runtime::{
+    CompletionRegistry, PromptRegistry, ResourceRegistry, ServerLogger, SessionStore,
+    SubscriptionManager,
+};
+use crate::progress::Progress;
+use crate::request_meta::RequestOptions;
+use crate::sampling_depth::{
+    check_sampling_depth, current_sampling_depth, set_sampling_request_depth,
+    DEFAULT_MAX_SAMPLING_DEPTH,
+};
 
-//TODO: support options , such as enforceStrictCapabilities
 #[async_trait]
 pub trait McpServer: Sync + Send {
     async fn start(&self) -> SdkResult<()>;
@@ -47,15 +58,106 @@ pub trait McpServer: Sync + Send {
         &self.server_info().capabilities
     }
 
+    /// Whether outgoing requests and notifications are checked against the capabilities of both parties
+    /// before being sent, failing fast when they are not supported.
+    ///
+    /// Disabled by default, messages are then sent regardless of the capabilities.
+    fn enforce_strict_capabilities(&self) -> bool {
+        false
+    }
+
+    /// Returns the logging facade of the server filtering the log messages sent to the client,
+    /// if enabled, see `ServerLogger`.
+    fn logger(&self) -> Option<&ServerLogger> {
+        None
+    }
+
+    /// Returns the manager tracking the resources the client subscribed to,
+    /// if enabled, see `SubscriptionManager`.
+    fn subscription_manager(&self) -> Option<&SubscriptionManager> {
+        None
+    }
+
+    /// Returns the registry declaring the resources of the server, if enabled, see `ResourceRegistry`.
+    fn resource_registry(&self) -> Option<&ResourceRegistry> {
+        None
+    }
+
+    /// Returns the registry declaring the prompts of the server, if enabled, see `PromptRegistry`.
+    fn prompt_registry(&self) -> Option<&PromptRegistry> {
+        None
+    }
+
+    /// Returns the registry completing the arguments of prompts and the variables of resource
+    /// templates, if enabled, see `CompletionRegistry`.
+    fn completion_registry(&self) -> Option<&CompletionRegistry> {
+        None
+    }
+
+    /// Returns the maximum nesting depth of the sampling requests sent by the server, and of the tool
+    /// calls it accepts, see `sampling`. Defaults to `DEFAULT_MAX_SAMPLING_DEPTH`.
+    fn max_sampling_depth(&self) -> u32 {
+        DEFAULT_MAX_SAMPLING_DEPTH
+    }
+
+    /// Returns the id of the client session served, if the server serves multiple sessions,
+    /// see `SessionServer`.
+    fn session_id(&self) -> Option<&SessionId> {
+        None
+    }
+
+    /// Returns the store of the state of the client session, see `SessionStore`.
+    /// `ServerRuntime` always has one.
+    fn session_store(&self) -> Option<&SessionStore> {
+        None
+    }
+
+    /// Returns the signer of the results and notifications sent by the server, if enabled,
+    /// see `signing`.
+    #[cfg(feature = "signing")]
+    fn signer(&self) -> Option<&dyn crate::signing::Signer> {
+        None
+    }
+
+    /// Whether the runtime answers the initialize requests of a `ServerHandlerCore` handler itself,
+    /// as it does for a `ServerHandler`, see `ServerRuntime::with_auto_initialize()`.
+    ///
+    /// Disabled by default, the handler then builds the initialize result.
+    fn auto_initialize(&self) -> bool {
+        false
+    }
+
+    /// Returns the protocol versions accepted from clients during initialization.
+    ///
+    /// Defaults to the protocol version of the server.
+    fn supported_protocol_versions(&self) -> Vec<String> {
+        vec![self.server_info().protocol_version.clone()]
+    }
+
+    /// Returns `true` unless the tool was disabled with `set_tool_enabled()`.
+    fn is_tool_enabled(&self, tool_name: &str) -> bool;
+
+    /// Enables or disables a tool at runtime, e.g. for feature-flagged or license-gated tools.
+    ///
+    /// Disabled tools are removed from the results of `tools/list`, and calls to them fail with
+    /// the invalid params error returned for unknown tools. When a tool is toggled, the client is
+    /// sent a `notifications/tools/list_changed` notification, debounced so that toggling several
+    /// tools at once results in a single notification.
+    fn set_tool_enabled(&self, tool_name: &str, enabled: bool);
+
     /// Sends a request to the client and processes the response.
     ///
     /// This function sends a `RequestFromServer` message to the client, waits for the response,
     /// and handles the result. If the response is empty or of an invalid type, an error is returned.
     /// Otherwise, it returns the result from the client.
     async fn request(&self, request: RequestFromServer) -> SdkResult<ResultFromClient> {
+        if self.enforce_strict_capabilities() {
+            self.assert_client_capabilities(&request.method().to_string())?;
+        }
+
         let sender = self.sender().await;
         let sender = sender.read().await;
-        let sender = sender.as_ref().unwrap();
+        let sender = sender.as_ref().ok_or(McpSdkError::ConnectionClosed)?;
 
         // Send the request and receive the response.
         let response = sender
@@ -77,9 +179,21 @@ pub trait McpServer: Sync + Send {
     /// to return any response. The method asynchronously sends the notification using
     /// the transport layer and does not wait for any acknowledgement or result.
     async fn send_notification(&self, notification: NotificationFromServer) -> SdkResult<()> {
+        if self.enforce_strict_capabilities() {
+            self.assert_server_notification_capabilities(&notification.method().to_string())?;
+        }
+        #[cfg(feature = "signing")]
+        let notification = {
+            let mut notification = notification;
+            if let Some(signer) = self.signer() {
+                crate::signing::sign_notification(&mut notification, signer)?;
+            }
+            notification
+        };
+
         let sender = self.sender().await;
         let sender = sender.read().await;
-        let sender = sender.as_ref().unwrap();
+        let sender = sender.as_ref().ok_or(McpSdkError::ConnectionClosed)?;
 
         sender
             .send(
@@ -106,14 +220,63 @@ pub trait McpServer: Sync + Send {
 
     /// Send log message notification from server to client.
     /// If no logging/setLevel request has been sent from the client, the server MAY decide which messages to send automatically.
+    ///
+    /// When the `logger()` of the server is enabled, messages less severe than its level are dropped.
     async fn send_logging_message(
         &self,
         params: LoggingMessageNotificationParams,
     ) -> SdkResult<()> {
+        if self
+            .logger()
+            .is_some_and(|logger| !logger.is_enabled(params.level))
+        {
+            return Ok(());
+        }
         let notification = LoggingMessageNotification::new(params);
         self.send_notification(notification.into()).await
     }
 
+    /// Sends a log message of the given level to the client, see `send_logging_message()`.
+    async fn log(&self, level: LoggingLevel, logger_name: &str, data: Value) -> SdkResult<()> {
+        self.send_logging_message(LoggingMessageNotificationParams {
+            data,
+            level,
+            logger: Some(logger_name.to_string()),
+        })
+        .await
+    }
+
+    /// Sends a debug log message to the client, see `log()`.
+    async fn log_debug(&self, logger_name: &str, data: Value) -> SdkResult<()> {
+        self.log(LoggingLevel::Debug, logger_name, data).await
+    }
+
+    /// Sends an info log message to the client, see `log()`.
+    async fn log_info(&self, logger_name: &str, data: Value) -> SdkResult<()> {
+        self.log(LoggingLevel::Info, logger_name, data).await
+    }
+
+    /// Sends a warning log message to the client, see `log()`.
+    async fn log_warning(&self, logger_name: &str, data: Value) -> SdkResult<()> {
+        self.log(LoggingLevel::Warning, logger_name, data).await
+    }
+
+    /// Sends an error log message to the client, see `log()`.
+    async fn log_error(&self, logger_name: &str, data: Value) -> SdkResult<()> {
+        self.log(LoggingLevel::Error, logger_name, data).await
+    }
+
+    /// Reports the progress of a long running request to the client.
+    /// `progress_token` is the token given by the client in the request.
+    async fn send_progress(
+        &self,
+        progress_token: ProgressToken,
+        progress: Progress,
+    ) -> SdkResult<()> {
+        let notification = ProgressNotification::new(progress.to_params(progress_token));
+        self.send_notification(notification.into()).await
+    }
+
     /// An optional notification from the server to the client, informing it that
     /// the list of prompts it offers has changed.
     /// This may be issued by servers without any previous subscription from the client.
@@ -147,6 +310,23 @@ pub trait McpServer: Sync + Send {
         self.send_notification(notification.into()).await
     }
 
+    /// Notifies the client that a resource was updated, if it subscribed to the resource or to a
+    /// resource containing it, see `SubscriptionManager`.
+    ///
+    /// Without subscription manager, the client is always notified.
+    async fn notify_resource_updated(&self, uri: &str) -> SdkResult<()> {
+        if self
+            .subscription_manager()
+            .is_some_and(|subscription_manager| !subscription_manager.is_subscribed(uri))
+        {
+            return Ok(());
+        }
+        self.send_resource_updated(ResourceUpdatedNotificationParams {
+            uri: uri.to_string(),
+        })
+        .await
+    }
+
     /// An optional notification from the server to the client, informing it that
     /// the list of tools it offers has changed.
     /// This may be issued by servers without any previous subscription from the client.
@@ -179,15 +359,32 @@ pub trait McpServer: Sync + Send {
     /// The client should also inform the user before beginning sampling,
     /// to allow them to inspect the request (human in the loop)
     /// and decide whether to approve it.
+    ///
+    /// Sent from a tool call, the request is one level deeper than the call, and fails with
+    /// `McpSdkError::SamplingDepthExceeded` beyond `max_sampling_depth()`, see `sampling`.
     async fn create_message(
         &self,
-        params: CreateMessageRequestParams,
+        mut params: CreateMessageRequestParams,
     ) -> SdkResult<CreateMessageResult> {
+        let sampling_depth = current_sampling_depth() + 1;
+        check_sampling_depth(sampling_depth, self.max_sampling_depth())?;
+        set_sampling_request_depth(&mut params, sampling_depth);
         let ping_request = CreateMessageRequest::new(params);
         let response = self.request(ping_request.into()).await?;
         Ok(response.try_into()?)
     }
 
+    /// Sends a sampling request with the given options, adding their `_meta` entries to the request,
+    /// see `request_context`.
+    async fn create_message_with_options(
+        &self,
+        mut params: CreateMessageRequestParams,
+        options: RequestOptions,
+    ) -> SdkResult<CreateMessageResult> {
+        options.apply_to_sampling_request(&mut params);
+        self.create_message(params).await
+    }
+
     /// Checks if the client supports sampling.
     ///
     /// This function retrieves the client information and checks if the
@@ -239,6 +436,26 @@ pub trait McpServer: Sync + Send {
     /// Sends a message to the standard error output (stderr) asynchronously.
     async fn stderr_message(&self, message: String) -> SdkResult<()>;
 
+    /// Asserts that the protocol version requested by a client during initialization is supported.
+    ///
+    /// Returns the version mismatch error defined by the specification otherwise, listing the
+    /// supported versions so the client can retry with one of them.
+    fn assert_protocol_version_supported(&self, protocol_version: &str) -> Result<(), RpcError> {
+        let supported_versions = self.supported_protocol_versions();
+        if supported_versions
+            .iter()
+            .any(|version| version == protocol_version)
+        {
+            return Ok(());
+        }
+        Err(RpcError::invalid_params()
+            .with_message("Unsupported protocol version".to_string())
+            .with_data(Some(json!({
+                "supported": supported_versions,
+                "requested": protocol_version
+            }))))
+    }
+
     /// Asserts that client capabilities are available for a given server request.
     ///
     /// This method verifies that the client capabilities required to process the specified
@@ -246,32 +463,25 @@ pub trait McpServer: Sync + Send {
     /// capabilities are not available.
     ///
     /// This can be utilized to avoid sending requests when the opposing party lacks support for them.
-    fn assert_client_capabilities(
-        &self,
-        request_method: &String,
-    ) -> std::result::Result<(), RpcError> {
+    fn assert_client_capabilities(&self, request_method: &String) -> SdkResult<()> {
         let entity = "Client";
         if *request_method == CreateMessageRequest::method_name()
             && !self.client_supports_sampling().unwrap_or(false)
         {
-            return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "sampling",
-                    request_method,
-                )),
-            );
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "sampling",
+                request_method,
+            ));
         }
         if *request_method == ListRootsRequest::method_name()
             && !self.client_supports_root_list().unwrap_or(false)
         {
-            return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "listing roots",
-                    request_method,
-                )),
-            );
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "listing roots",
+                request_method,
+            ));
         }
         Ok(())
     }
@@ -279,7 +489,7 @@ pub trait McpServer: Sync + Send {
     fn assert_server_notification_capabilities(
         &self,
         notification_method: &String,
-    ) -> std::result::Result<(), RpcError> {
+    ) -> SdkResult<()> {
         let entity = "Server";
 
         let capabilities = &self.server_info().capabilities;
@@ -287,46 +497,38 @@ pub trait McpServer: Sync + Send {
         if *notification_method == LoggingMessageNotification::method_name()
             && capabilities.logging.is_none()
         {
-            return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "logging",
-                    notification_method,
-                )),
-            );
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "logging",
+                notification_method,
+            ));
         }
         if *notification_method == ResourceUpdatedNotification::method_name()
             && capabilities.resources.is_none()
         {
-            return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "notifying about resources",
-                    notification_method,
-                )),
-            );
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "notifying about resources",
+                notification_method,
+            ));
         }
         if *notification_method == ToolListChangedNotification::method_name()
             && capabilities.tools.is_none()
         {
-            return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "notifying of tool list changes",
-                    notification_method,
-                )),
-            );
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "notifying of tool list changes",
+                notification_method,
+            ));
         }
         if *notification_method == PromptListChangedNotification::method_name()
             && capabilities.prompts.is_none()
         {
-            return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "notifying of prompt list changes",
-                    notification_method,
-                )),
-            );
+            return Err(McpSdkError::capability_not_supported(
+                entity,
+                "notifying of prompt list changes",
+                notification_method,
+            ));
         }
 
         Ok(())
@@ -341,11 +543,7 @@ pub trait McpServer: Sync + Send {
 
         if *request_method == SetLevelRequest::method_name() && capabilities.logging.is_none() {
             return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "logging",
-                    request_method,
-                )),
+                McpSdkError::capability_not_supported(entity, "logging", request_method).into(),
             );
         }
         if [
@@ -356,11 +554,7 @@ pub trait McpServer: Sync + Send {
             && capabilities.prompts.is_none()
         {
             return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "prompts",
-                    request_method,
-                )),
+                McpSdkError::capability_not_supported(entity, "prompts", request_method).into(),
             );
         }
         if [
@@ -372,11 +566,7 @@ pub trait McpServer: Sync + Send {
             && capabilities.resources.is_none()
         {
             return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "resources",
-                    request_method,
-                )),
+                McpSdkError::capability_not_supported(entity, "resources", request_method).into(),
             );
         }
         if [
@@ -387,11 +577,7 @@ pub trait McpServer: Sync + Send {
             && capabilities.tools.is_none()
         {
             return Err(
-                RpcError::internal_error().with_message(format_assertion_message(
-                    entity,
-                    "tools",
-                    request_method,
-                )),
+                McpSdkError::capability_not_supported(entity, "tools", request_method).into(),
             );
         }
         Ok(())
@@ -0,0 +1,36 @@
+use async_trait::async_trait;
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+
+use crate::tool_dry_run::DryRunNotSupported;
+use crate::McpServer;
+
+/// A tool that is executed synchronously.
+///
+/// Implement this trait for the tools of a `tool_box!` declared with `call`, to generate a
+/// `call()` method dispatching a `CallToolRequest` to the matching tool.
+pub trait McpTool {
+    /// Executes the tool and returns its result.
+    fn call_tool(&self, runtime: &dyn McpServer) -> Result<CallToolResult, CallToolError>;
+
+    /// Validates the call and describes what it would do, without side effects.
+    /// Tools supporting dry runs implement it, and are declared with `#[mcp_tool(dry_run)]`.
+    fn dry_run(&self, _runtime: &dyn McpServer) -> Result<CallToolResult, CallToolError> {
+        Err(CallToolError::new(DryRunNotSupported))
+    }
+}
+
+/// A tool that is executed asynchronously.
+///
+/// Implement this trait for the tools of a `tool_box!` declared with `async call`, to generate an
+/// async `call()` method dispatching a `CallToolRequest` to the matching tool.
+#[async_trait]
+pub trait McpAsyncTool: Send + Sync {
+    /// Executes the tool and returns its result.
+    async fn call_tool(&self, runtime: &dyn McpServer) -> Result<CallToolResult, CallToolError>;
+
+    /// Validates the call and describes what it would do, without side effects.
+    /// Tools supporting dry runs implement it, and are declared with `#[mcp_tool(dry_run)]`.
+    async fn dry_run(&self, _runtime: &dyn McpServer) -> Result<CallToolResult, CallToolError> {
+        Err(CallToolError::new(DryRunNotSupported))
+    }
+}
@@ -0,0 +1,61 @@
+use rust_mcp_schema::RpcError;
+use serde_json::json;
+
+use crate::error_codes::{INVALID_PARAMS, RESOURCE_NOT_FOUND};
+
+/// Constructors of the errors commonly answered by server handlers, with their standard code,
+/// message and data, completing the ones of `RpcError`, such as `RpcError::invalid_request()`.
+///
+/// Unknown tools and prompts are invalid params errors, as recommended by the MCP specification,
+/// and unknown resources have their own code. The name or uri is also set in the data of the error,
+/// so that clients can tell which one was not found without parsing the message.
+///
+/// # Example
+/// ```ignore
+/// async fn handle_read_resource_request(&self, request: ReadResourceRequest, runtime: &dyn McpServer)
+///     -> Result<ReadResourceResult, RpcError> {
+///     let contents = self.files.get(&request.params.uri)
+///         .ok_or_else(|| RpcError::resource_not_found(&request.params.uri))?;
+///     ...
+/// }
+/// ```
+pub trait RpcErrorExt {
+    /// The resource with the given uri does not exist, with the `RESOURCE_NOT_FOUND` code and the
+    /// uri in the data of the error.
+    fn resource_not_found(uri: impl Into<String>) -> Self;
+    /// The tool with the given name does not exist or is disabled, with the `INVALID_PARAMS` code and
+    /// the name in the data of the error.
+    fn tool_not_found(name: impl Into<String>) -> Self;
+    /// The prompt with the given name does not exist, with the `INVALID_PARAMS` code and the name in
+    /// the data of the error.
+    fn prompt_not_found(name: impl Into<String>) -> Self;
+}
+
+impl RpcErrorExt for RpcError {
+    fn resource_not_found(uri: impl Into<String>) -> Self {
+        let uri = uri.into();
+        RpcError {
+            code: RESOURCE_NOT_FOUND,
+            message: format!("Resource not found: {}", uri),
+            data: Some(json!({ "uri": uri })),
+        }
+    }
+
+    fn tool_not_found(name: impl Into<String>) -> Self {
+        let name = name.into();
+        RpcError {
+            code: INVALID_PARAMS,
+            message: format!("Unknown tool: {}", name),
+            data: Some(json!({ "name": name })),
+        }
+    }
+
+    fn prompt_not_found(name: impl Into<String>) -> Self {
+        let name = name.into();
+        RpcError {
+            code: INVALID_PARAMS,
+            message: format!("Unknown prompt: {}", name),
+            data: Some(json!({ "name": name })),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use rust_mcp_schema::ToolInputSchema;
+use serde::de::Error;
+use serde_json::Value;
+
+use crate::tool_schema_validation::{validate_json_schema, SchemaError};
+
+/// Conversions of the input schema of a tool to and from JSON, and its validation.
+///
+/// # Example
+/// ```ignore
+/// let schema = ToolInputSchema::from_json_value(generated_schema)?;
+/// if let Err(errors) = schema.validate() {
+///     panic!("invalid input schema: {:?}", errors);
+/// }
+/// ```
+pub trait ToolInputSchemaExt: Sized {
+    /// Returns the schema as a JSON value, e.g. to validate it with a JSON Schema library.
+    fn to_json_value(&self) -> Value;
+    /// Parses a schema from a JSON value, e.g. one generated by a JSON Schema library.
+    ///
+    /// # Errors
+    /// Returns an error if the value is not a schema of type `object` whose properties are schemas.
+    fn from_json_value(value: Value) -> Result<Self, serde_json::Error>;
+    /// Validates the schema against JSON Schema draft 2020-12, see `validate_json_schema()`.
+    ///
+    /// # Errors
+    /// Returns the invalid keywords of the schema.
+    fn validate(&self) -> Result<(), Vec<SchemaError>>;
+}
+
+impl ToolInputSchemaExt for ToolInputSchema {
+    fn to_json_value(&self) -> Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+
+    fn from_json_value(value: Value) -> Result<Self, serde_json::Error> {
+        let schema: Self = serde_json::from_value(value)?;
+        if schema.type_() != "object" {
+            return Err(serde_json::Error::custom(format!(
+                "expected an input schema of type \"object\", got \"{}\"",
+                schema.type_()
+            )));
+        }
+        Ok(schema)
+    }
+
+    fn validate(&self) -> Result<(), Vec<SchemaError>> {
+        validate_json_schema(&self.to_json_value())
+    }
+}
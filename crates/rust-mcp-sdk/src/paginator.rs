@@ -0,0 +1,372 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rust_mcp_schema::{Prompt, Resource, ResourceTemplate, RpcError, Tool};
+
+/// Default number of items per page
+const DEFAULT_PAGE_SIZE: usize = 50;
+
+/// A page of items, returned by `Paginator::paginate()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    /// The items of the requested page
+    pub items: Vec<T>,
+    /// An opaque cursor to request the next page with, `None` if this is the last page
+    pub next_cursor: Option<String>,
+}
+
+/// The order of the items listed by a `Paginator`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ListOrder {
+    /// Items are listed in the order they are passed to the paginator, e.g. the order they were
+    /// registered in, which should be the same for every page. Pages remain consistent on a
+    /// best-effort basis only when the list changes while it is listed, see `Paginator`.
+    #[default]
+    Insertion,
+    /// Items are sorted by their key, see `ListItem`.
+    Name,
+}
+
+/// An item listed by a server, identified by a unique key: the name of tools and prompts,
+/// the uri of resources and the uri template of resource templates.
+///
+/// The key of the last item of a page is stored in the cursor of the next page, so that the
+/// listing resumes after it even if items were added or removed in the meantime.
+pub trait ListItem {
+    fn list_key(&self) -> &str;
+}
+
+impl ListItem for Tool {
+    fn list_key(&self) -> &str {
+        &self.name
+    }
+}
+
+impl ListItem for Prompt {
+    fn list_key(&self) -> &str {
+        &self.name
+    }
+}
+
+impl ListItem for Resource {
+    fn list_key(&self) -> &str {
+        &self.uri
+    }
+}
+
+impl ListItem for ResourceTemplate {
+    fn list_key(&self) -> &str {
+        &self.uri_template
+    }
+}
+
+/// Splits the full list of tools, resources, prompts, etc. of a server into pages.
+///
+/// Items are listed in a deterministic order, see `ListOrder`. Cursors are opaque to the client:
+/// they hold the key of the last item of the previous page, so that pages remain consistent when
+/// the list changes between two requests:
+/// - in `ListOrder::Name`, the listing resumes with the first item whose key follows that key,
+///   items added or removed before it do not shift the following pages, and every item present
+///   during the whole listing is listed exactly once,
+/// - in `ListOrder::Insertion`, the listing resumes after that item, or at its former position
+///   if it was removed. This is best-effort: if other items before it were removed too, some
+///   items are skipped, and an item removed and added again is listed twice. Use `ListOrder::Name`
+///   for lists that change while clients page through them.
+///
+/// # Example
+/// ```ignore
+/// async fn handle_list_tools_request(
+///     &self,
+///     request: ListToolsRequest,
+///     runtime: &dyn McpServer,
+/// ) -> std::result::Result<ListToolsResult, RpcError> {
+///     let cursor = request.params.and_then(|params| params.cursor);
+///     let page = Paginator::new(20)
+///         .with_order(ListOrder::Name)
+///         .paginate(MyTools::tools(), cursor.as_deref())?;
+///     Ok(ListToolsResult {
+///         meta: None,
+///         next_cursor: page.next_cursor,
+///         tools: page.items,
+///     })
+/// }
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Paginator {
+    page_size: usize,
+    order: ListOrder,
+}
+
+impl Default for Paginator {
+    fn default() -> Self {
+        Self {
+            page_size: DEFAULT_PAGE_SIZE,
+            order: ListOrder::default(),
+        }
+    }
+}
+
+/// The position a page starts at, decoded from its cursor.
+struct Cursor {
+    // the offset of the first item of the page when the cursor was created
+    offset: usize,
+    // the key of the last item of the previous page, absent from cursors created by previous versions
+    after_key: Option<String>,
+}
+
+impl Paginator {
+    /// Creates a paginator returning pages of `page_size` items. A `page_size` of 0 is treated as 1.
+    pub fn new(page_size: usize) -> Self {
+        Self {
+            page_size: page_size.max(1),
+            order: ListOrder::default(),
+        }
+    }
+
+    /// Sets the order of the listed items, `ListOrder::Insertion` by default.
+    pub fn with_order(mut self, order: ListOrder) -> Self {
+        self.order = order;
+        self
+    }
+
+    /// Returns the number of items per page
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    /// Returns the order of the listed items
+    pub fn order(&self) -> ListOrder {
+        self.order
+    }
+
+    /// Returns the page of `items` starting at the given cursor, or the first page if `cursor` is `None`.
+    ///
+    /// # Errors
+    /// Returns an `invalid_params` error if the cursor was not created by a `Paginator`.
+    pub fn paginate<T: ListItem>(
+        &self,
+        items: Vec<T>,
+        cursor: Option<&str>,
+    ) -> Result<Page<T>, RpcError> {
+        self.paginate_by(items, cursor, ListItem::list_key)
+    }
+
+    /// Returns the page of `items` starting at the given cursor like `paginate()`,
+    /// for items identified by the unique key returned by `key`.
+    pub fn paginate_by<T>(
+        &self,
+        mut items: Vec<T>,
+        cursor: Option<&str>,
+        key: impl Fn(&T) -> &str,
+    ) -> Result<Page<T>, RpcError> {
+        if self.order == ListOrder::Name {
+            items.sort_by(|a, b| key(a).cmp(key(b)));
+        }
+
+        let start = match cursor {
+            Some(cursor) => {
+                let cursor = Self::decode_cursor(cursor).ok_or_else(|| {
+                    RpcError::invalid_params().with_message(format!("Invalid cursor: {}", cursor))
+                })?;
+                self.resume_position(&items, &cursor, &key)
+            }
+            None => 0,
+        };
+
+        let end = start.saturating_add(self.page_size).min(items.len());
+        let next_cursor =
+            (end < items.len()).then(|| Self::encode_cursor(end, key(&items[end - 1])));
+        let items = items.into_iter().skip(start).take(end - start).collect();
+
+        Ok(Page { items, next_cursor })
+    }
+
+    // Returns the position of the first item of the page starting at `cursor`
+    fn resume_position<T>(&self, items: &[T], cursor: &Cursor, key: impl Fn(&T) -> &str) -> usize {
+        let Some(after_key) = cursor.after_key.as_deref() else {
+            return cursor.offset.min(items.len());
+        };
+        match self.order {
+            ListOrder::Name => items.partition_point(|item| key(item) <= after_key),
+            ListOrder::Insertion => match items.iter().position(|item| key(item) == after_key) {
+                Some(position) => position + 1,
+                // the last item of the previous page was removed, the following ones moved up,
+                // assuming that no other item before it was removed
+                None => cursor.offset.saturating_sub(1).min(items.len()),
+            },
+        }
+    }
+
+    fn encode_cursor(offset: usize, after_key: &str) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", offset, after_key))
+    }
+
+    fn decode_cursor(cursor: &str) -> Option<Cursor> {
+        let decoded = String::from_utf8(URL_SAFE_NO_PAD.decode(cursor).ok()?).ok()?;
+        let (offset, after_key) = match decoded.split_once(':') {
+            Some((offset, after_key)) => (offset, Some(after_key.to_string())),
+            None => (decoded.as_str(), None),
+        };
+        Some(Cursor {
+            offset: offset.parse().ok()?,
+            after_key,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, RwLock};
+
+    fn keys(items: &[String]) -> Vec<&str> {
+        items.iter().map(String::as_str).collect()
+    }
+
+    // Lists all pages of `items`, calling `mutate` on the list between pages
+    fn list_all(
+        paginator: Paginator,
+        items: &mut Vec<String>,
+        mut mutate: impl FnMut(usize, &mut Vec<String>),
+    ) -> Vec<String> {
+        let mut listed = vec![];
+        let mut cursor = None;
+        for page_index in 0.. {
+            let page = paginator
+                .paginate_by(items.clone(), cursor.as_deref(), String::as_str)
+                .unwrap();
+            listed.extend(page.items);
+            cursor = page.next_cursor;
+            if cursor.is_none() {
+                break;
+            }
+            mutate(page_index, items);
+        }
+        listed
+    }
+
+    fn letters(letters: &str) -> Vec<String> {
+        letters.chars().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_name_order_is_deterministic() {
+        let paginator = Paginator::new(2).with_order(ListOrder::Name);
+        let listed = list_all(paginator, &mut letters("dbeac"), |_, _| {});
+        assert_eq!(keys(&listed), ["a", "b", "c", "d", "e"]);
+
+        let paginator = Paginator::new(2);
+        let listed = list_all(paginator, &mut letters("dbeac"), |_, _| {});
+        assert_eq!(keys(&listed), ["d", "b", "e", "a", "c"]);
+    }
+
+    #[test]
+    fn test_removed_items_are_skipped() {
+        // removing the last item of the first page, and an item before it
+        for order in [ListOrder::Name, ListOrder::Insertion] {
+            let paginator = Paginator::new(2).with_order(order);
+            let listed = list_all(paginator, &mut letters("abcdef"), |page_index, items| {
+                if page_index == 0 {
+                    items.retain(|item| item != "b");
+                }
+            });
+            assert_eq!(keys(&listed), ["a", "b", "c", "d", "e", "f"], "{:?}", order);
+        }
+
+        let paginator = Paginator::new(2).with_order(ListOrder::Name);
+        let listed = list_all(paginator, &mut letters("abcdef"), |page_index, items| {
+            if page_index == 0 {
+                items.retain(|item| item != "a" && item != "d");
+            }
+        });
+        assert_eq!(keys(&listed), ["a", "b", "c", "e", "f"]);
+    }
+
+    #[test]
+    fn test_added_items_do_not_shift_pages() {
+        let paginator = Paginator::new(2).with_order(ListOrder::Name);
+        let listed = list_all(paginator, &mut letters("bdf"), |page_index, items| {
+            if page_index == 0 {
+                // before and after the cursor
+                items.extend(letters("ae"));
+            }
+        });
+        assert_eq!(keys(&listed), ["b", "d", "e", "f"]);
+
+        let paginator = Paginator::new(2);
+        let listed = list_all(paginator, &mut letters("bdf"), |page_index, items| {
+            if page_index == 0 {
+                items.insert(0, "a".to_string());
+                items.push("e".to_string());
+            }
+        });
+        assert_eq!(keys(&listed), ["b", "d", "f", "e"]);
+    }
+
+    #[test]
+    fn test_concurrent_mutation_during_paging() {
+        // multiples of 3 are stable, the items following them are added and removed while paging
+        let stable: Vec<String> = (0..100).map(|i| format!("{:03}", i * 3)).collect();
+        let registry = Arc::new(RwLock::new(stable.clone()));
+
+        let stop = Arc::new(AtomicBool::new(false));
+        let mutator = {
+            let registry = registry.clone();
+            let stop = stop.clone();
+            std::thread::spawn(move || {
+                for round in 0.. {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    let item = format!("{:03}", (round % 100) * 3 + 1);
+                    let mut items = registry.write().unwrap();
+                    match items.iter().position(|existing| *existing == item) {
+                        Some(position) => {
+                            items.remove(position);
+                        }
+                        None => items.push(item),
+                    }
+                }
+            })
+        };
+
+        // the insertion order is best-effort when items are removed, and only the name order
+        // lists every stable item exactly once
+        let paginator = Paginator::new(7).with_order(ListOrder::Name);
+        for _ in 0..20 {
+            let mut listed = vec![];
+            let mut cursor = None;
+            loop {
+                let items = registry.read().unwrap().clone();
+                let page = paginator.paginate_by(items, cursor.as_deref(), String::as_str);
+                let page = page.unwrap();
+                listed.extend(page.items);
+                cursor = page.next_cursor;
+                if cursor.is_none() {
+                    break;
+                }
+            }
+
+            // every stable item is listed exactly once, without duplicates
+            let unique: HashSet<&String> = listed.iter().collect();
+            assert_eq!(unique.len(), listed.len());
+            assert!(stable.iter().all(|item| unique.contains(item)));
+        }
+        stop.store(true, Ordering::Relaxed);
+        mutator.join().unwrap();
+    }
+
+    #[test]
+    fn test_offset_cursors_are_accepted() {
+        let cursor = URL_SAFE_NO_PAD.encode("2");
+        let page = Paginator::new(2)
+            .paginate_by(letters("abcde"), Some(&cursor), String::as_str)
+            .unwrap();
+        assert_eq!(keys(&page.items), ["c", "d"]);
+
+        let error = Paginator::new(2)
+            .paginate_by(letters("abc"), Some("not a cursor!"), String::as_str)
+            .unwrap_err();
+        assert_eq!(error.code, RpcError::invalid_params().code);
+    }
+}
@@ -0,0 +1,93 @@
+use rust_mcp_schema::{ProgressNotificationParams, ProgressToken};
+
+/// The progress of a long running operation, as reported by progress notifications.
+///
+/// Servers report progress with `McpServer::send_progress()`, clients receive it in
+/// `handle_progress_notification()` and convert the notification params with `Progress::from()`.
+///
+/// Progress notifications of the current protocol revision do not carry a message, `message`
+/// is not sent and is `None` for received progress.
+///
+/// # Example
+/// ```ignore
+/// // third of four steps completed
+/// let progress = Progress::step(3, 4).with_message("Indexing files");
+/// assert_eq!(progress.percent(), Some(75.0));
+///
+/// runtime.send_progress(progress_token, progress).await?;
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Progress {
+    /// The progress so far, increasing with each notification even if the total is unknown
+    pub current: f64,
+    /// The total progress required to complete the operation, if known
+    pub total: Option<f64>,
+    /// A description of the current stage of the operation
+    pub message: Option<String>,
+}
+
+impl Progress {
+    /// Creates a progress of `current`, with an unknown total.
+    pub fn new(current: f64) -> Self {
+        Self {
+            current,
+            total: None,
+            message: None,
+        }
+    }
+
+    /// Creates the progress of an operation made of `steps` steps, `step` of which are completed.
+    pub fn step(step: u64, steps: u64) -> Self {
+        Self::new(step as f64).with_total(steps as f64)
+    }
+
+    /// Sets the total progress required to complete the operation.
+    pub fn with_total(mut self, total: f64) -> Self {
+        self.total = Some(total);
+        self
+    }
+
+    /// Sets the description of the current stage of the operation.
+    pub fn with_message<T: Into<String>>(mut self, message: T) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Returns the progress as a percentage between 0 and 100,
+    /// or `None` if the total is unknown or not positive.
+    pub fn percent(&self) -> Option<f64> {
+        self.total
+            .filter(|total| *total > 0.0)
+            .map(|total| (self.current / total * 100.0).clamp(0.0, 100.0))
+    }
+
+    /// Returns `true` if the total is known and has been reached.
+    pub fn is_complete(&self) -> bool {
+        self.total.is_some_and(|total| self.current >= total)
+    }
+
+    /// Returns the params of a progress notification for the request associated with `progress_token`.
+    pub fn to_params(&self, progress_token: ProgressToken) -> ProgressNotificationParams {
+        ProgressNotificationParams {
+            progress: self.current,
+            progress_token,
+            total: self.total,
+        }
+    }
+}
+
+impl From<&ProgressNotificationParams> for Progress {
+    fn from(params: &ProgressNotificationParams) -> Self {
+        Self {
+            current: params.progress,
+            total: params.total,
+            message: None,
+        }
+    }
+}
+
+impl From<ProgressNotificationParams> for Progress {
+    fn from(params: ProgressNotificationParams) -> Self {
+        Self::from(&params)
+    }
+}
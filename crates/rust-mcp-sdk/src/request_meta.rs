@@ -0,0 +1,207 @@
+use std::future::Future;
+
+use rust_mcp_schema::{
+    CallToolRequestParams, CreateMessageRequestParams, Implementation, ProgressToken, RequestId,
+};
+use rust_mcp_transport::SessionId;
+use serde_json::{Map, Value};
+
+use crate::cancellation_token::CancellationToken;
+use crate::tool_dry_run::META_ARGUMENT;
+
+/// Key of the `_meta` of a request holding the token of the progress notifications of the request.
+pub const PROGRESS_TOKEN_KEY: &str = "progressToken";
+
+tokio::task_local! {
+    // The context of the request handled by the current task
+    static REQUEST_CONTEXT: RequestContext;
+}
+
+/// Options of an outgoing request: the entries of its `_meta`, such as trace ids.
+///
+/// The request params of the current protocol revision have no `_meta`, the SDK carries it in the
+/// reserved `_meta` argument of tool calls, see `dry_run::META_ARGUMENT`, and in a reserved `_meta`
+/// entry of the metadata of sampling requests, like the sampling depth. The other requests have no
+/// room for it.
+///
+/// # Example
+/// ```ignore
+/// let options = RequestOptions::new().with_meta("traceId", "4bf92f3577b34da6");
+/// let result = client.call_tool_with_options(params, options).await?;
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RequestOptions {
+    /// Entries added to the `_meta` of the request
+    pub meta: Map<String, Value>,
+}
+
+impl RequestOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an entry to the `_meta` of the request.
+    pub fn with_meta(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.meta.insert(key.into(), value.into());
+        self
+    }
+
+    /// Requests progress notifications for the request, associated with `progress_token`.
+    pub fn with_progress_token(self, progress_token: ProgressToken) -> Self {
+        let progress_token = match progress_token {
+            ProgressToken::String(token) => Value::from(token),
+            ProgressToken::Integer(token) => Value::from(token),
+        };
+        self.with_meta(PROGRESS_TOKEN_KEY, progress_token)
+    }
+
+    /// Adds the `_meta` entries of the options to a tool call.
+    pub fn apply_to_tool_call(&self, params: &mut CallToolRequestParams) {
+        if !self.meta.is_empty() {
+            self.apply_to(params.arguments.get_or_insert_with(Map::new));
+        }
+    }
+
+    /// Adds the `_meta` entries of the options to a sampling request.
+    pub fn apply_to_sampling_request(&self, params: &mut CreateMessageRequestParams) {
+        if !self.meta.is_empty() {
+            self.apply_to(params.metadata.get_or_insert_with(Map::new));
+        }
+    }
+
+    fn apply_to(&self, map: &mut Map<String, Value>) {
+        let meta = map
+            .entry(META_ARGUMENT)
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !meta.is_object() {
+            *meta = Value::Object(Map::new());
+        }
+        if let Value::Object(meta) = meta {
+            meta.extend(self.meta.clone());
+        }
+    }
+}
+
+/// The context of the request handled by the current task: its JSON-RPC id, its `_meta` and
+/// progress token, the token signaling its cancellation, and the details of the other party.
+///
+/// Handlers read it with `RequestContext::current()`, without changes to their signature, e.g. to
+/// report the progress of the request, to stop its work once it is cancelled, or to continue the
+/// trace of the caller in their logs and in the requests they send while handling the request.
+///
+/// # Example
+/// ```ignore
+/// async fn handle_call_tool_request(&self, request: CallToolRequest, runtime: &dyn McpServer)
+///     -> Result<CallToolResult, CallToolError> {
+///     let context = RequestContext::current();
+///     tracing::info!(trace_id = ?context.meta_value("traceId"), "calling {}", request.params.name);
+///     if let Some(progress_token) = context.progress_token.clone() {
+///         runtime.send_progress(progress_token, Progress::step(1, 2)).await?;
+///     }
+///     // propagate the trace id to the sampling requests of the tool
+///     let options = context.propagate(&["traceId"]);
+///     let result = runtime.create_message_with_options(params, options).await?;
+///     ...
+/// }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct RequestContext {
+    /// The JSON-RPC id of the request, `None` outside of requests
+    pub request_id: Option<RequestId>,
+    /// The token of the progress notifications requested by the sender of the request, if any
+    pub progress_token: Option<ProgressToken>,
+    /// The `_meta` of the request, empty outside of tool calls and sampling requests
+    pub meta: Map<String, Value>,
+    /// Cancelled when the client cancels the request, see `CancellationToken`.
+    /// Requests received by clients are not cancelled.
+    pub cancellation_token: CancellationToken,
+    /// The details of the other party: the client on a server, the server on a client
+    pub peer_info: Option<Implementation>,
+    /// The id of the client session, on a server serving multiple sessions, see `SessionServer`
+    pub session_id: Option<SessionId>,
+}
+
+impl RequestContext {
+    pub(crate) fn new(
+        request_id: RequestId,
+        meta: Map<String, Value>,
+        cancellation_token: CancellationToken,
+        peer_info: Option<Implementation>,
+    ) -> Self {
+        let progress_token = meta
+            .get(PROGRESS_TOKEN_KEY)
+            .and_then(|token| serde_json::from_value(token.clone()).ok());
+        Self {
+            request_id: Some(request_id),
+            progress_token,
+            meta,
+            cancellation_token,
+            peer_info,
+            session_id: None,
+        }
+    }
+
+    pub(crate) fn with_session_id(mut self, session_id: Option<SessionId>) -> Self {
+        self.session_id = session_id;
+        self
+    }
+
+    /// Returns the context of the request handled by the current task, or an empty context
+    /// outside of requests.
+    pub fn current() -> Self {
+        REQUEST_CONTEXT
+            .try_with(RequestContext::clone)
+            .unwrap_or_default()
+    }
+
+    /// Returns an entry of the `_meta` of the request.
+    pub fn meta_value(&self, key: &str) -> Option<&Value> {
+        self.meta.get(key)
+    }
+
+    /// Returns the options propagating the given `_meta` entries of the request to outgoing requests.
+    pub fn propagate(&self, keys: &[&str]) -> RequestOptions {
+        RequestOptions {
+            meta: keys
+                .iter()
+                .filter_map(|key| Some((key.to_string(), self.meta.get(*key)?.clone())))
+                .collect(),
+        }
+    }
+}
+
+// Runs `future` with the given request context, see `RequestContext::current()`
+pub(crate) async fn with_request_context<F: Future>(
+    context: RequestContext,
+    future: F,
+) -> F::Output {
+    REQUEST_CONTEXT.scope(context, future).await
+}
+
+// Returns the `_meta` of a tool call
+pub(crate) fn tool_call_meta(params: &CallToolRequestParams) -> Map<String, Value> {
+    params
+        .arguments
+        .as_ref()
+        .and_then(|arguments| arguments.get(META_ARGUMENT))
+        .and_then(Value::as_object)
+        .cloned()
+        .unwrap_or_default()
+}
+
+// Removes the `_meta` from a sampling request, returning it
+pub(crate) fn take_sampling_request_meta(
+    params: &mut CreateMessageRequestParams,
+) -> Map<String, Value> {
+    let Some(metadata) = params.metadata.as_mut() else {
+        return Map::new();
+    };
+    let meta = match metadata.remove(META_ARGUMENT) {
+        Some(Value::Object(meta)) => meta,
+        _ => Map::new(),
+    };
+    if metadata.is_empty() {
+        params.metadata = None;
+    }
+    meta
+}
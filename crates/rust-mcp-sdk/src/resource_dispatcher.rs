@@ -0,0 +1,213 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rust_mcp_schema::{ReadResourceResult, ReadResourceResultContentsItem, RpcError};
+use serde_json::Value;
+
+/// Mime type assumed for text contents without a mime type
+const DEFAULT_TEXT_MIME_TYPE: &str = "text/plain";
+/// Mime type assumed for binary contents without a mime type
+const DEFAULT_BLOB_MIME_TYPE: &str = "application/octet-stream";
+
+/// Data of a resource content, binary data being decoded from base64.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResourceData {
+    Text(String),
+    Blob(Vec<u8>),
+}
+
+/// A single content of a `ReadResourceResult`, passed to the handlers of a `ResourceDispatcher`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResourceContents {
+    pub uri: String,
+    /// The mime type of the content, lowercased and without parameters such as `charset`
+    pub mime_type: String,
+    pub data: ResourceData,
+}
+
+impl ResourceContents {
+    fn from_item(item: &ReadResourceResultContentsItem) -> Result<Self, RpcError> {
+        match item {
+            ReadResourceResultContentsItem::TextResourceContents(contents) => Ok(Self {
+                uri: contents.uri.clone(),
+                mime_type: essence(contents.mime_type.as_deref(), DEFAULT_TEXT_MIME_TYPE),
+                data: ResourceData::Text(contents.text.clone()),
+            }),
+            ReadResourceResultContentsItem::BlobResourceContents(contents) => {
+                let bytes = STANDARD.decode(&contents.blob).map_err(|err| {
+                    RpcError::parse_error().with_message(format!(
+                        "Invalid base64 data for resource '{}': {}",
+                        contents.uri, err
+                    ))
+                })?;
+                Ok(Self {
+                    uri: contents.uri.clone(),
+                    mime_type: essence(contents.mime_type.as_deref(), DEFAULT_BLOB_MIME_TYPE),
+                    data: ResourceData::Blob(bytes),
+                })
+            }
+        }
+    }
+
+    /// Returns the text of the content, or `None` for binary contents.
+    pub fn text(&self) -> Option<&str> {
+        match &self.data {
+            ResourceData::Text(text) => Some(text),
+            ResourceData::Blob(_) => None,
+        }
+    }
+
+    /// Returns the raw bytes of the content, the UTF-8 bytes of the text for text contents.
+    pub fn bytes(&self) -> &[u8] {
+        match &self.data {
+            ResourceData::Text(text) => text.as_bytes(),
+            ResourceData::Blob(bytes) => bytes,
+        }
+    }
+
+    /// Parses the content as JSON.
+    pub fn json(&self) -> Result<Value, RpcError> {
+        serde_json::from_slice(self.bytes()).map_err(|err| {
+            RpcError::parse_error().with_message(format!(
+                "Invalid JSON content for resource '{}': {}",
+                self.uri, err
+            ))
+        })
+    }
+}
+
+type ContentHandler<T> = Box<dyn Fn(&ResourceContents) -> T + Send + Sync>;
+
+/// Dispatches the contents of a `ReadResourceResult` to handlers registered by mime type,
+/// so hosts can present many kinds of resources without matching on them by hand.
+///
+/// Handlers are registered for a mime type (`application/json`), a type wildcard (`image/*`)
+/// or any mime type (`*/*`). The most specific handler is used for each content, contents
+/// matching no handler are passed to the fallback handler, or skipped if there is none.
+///
+/// # Example
+/// ```ignore
+/// let dispatcher = ResourceDispatcher::new()
+///     .on_text(|contents| println!("{}", contents.text().unwrap_or_default()))
+///     .on_image(|contents| println!("[image: {}]", contents.mime_type))
+///     .fallback(|contents| println!("unsupported resource: {}", contents.uri));
+///
+/// let result = client.read_resource(ReadResourceRequestParams { uri }).await?;
+/// dispatcher.dispatch(&result)?;
+/// ```
+pub struct ResourceDispatcher<T = ()> {
+    handlers: Vec<(String, ContentHandler<T>)>,
+    fallback: Option<ContentHandler<T>>,
+}
+
+impl<T> Default for ResourceDispatcher<T> {
+    fn default() -> Self {
+        Self {
+            handlers: vec![],
+            fallback: None,
+        }
+    }
+}
+
+impl<T> ResourceDispatcher<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a handler for contents matching `mime_pattern`, e.g. `application/json`, `image/*` or `*/*`.
+    /// A handler registered earlier for the same pattern is replaced.
+    pub fn on<P, F>(mut self, mime_pattern: P, handler: F) -> Self
+    where
+        P: Into<String>,
+        F: Fn(&ResourceContents) -> T + Send + Sync + 'static,
+    {
+        let mime_pattern = mime_pattern.into().to_ascii_lowercase();
+        self.handlers
+            .retain(|(pattern, _)| *pattern != mime_pattern);
+        self.handlers.push((mime_pattern, Box::new(handler)));
+        self
+    }
+
+    /// Registers a handler for `text/*` contents.
+    pub fn on_text<F>(self, handler: F) -> Self
+    where
+        F: Fn(&ResourceContents) -> T + Send + Sync + 'static,
+    {
+        self.on("text/*", handler)
+    }
+
+    /// Registers a handler for `application/json` contents, use `ResourceContents::json()` to parse them.
+    pub fn on_json<F>(self, handler: F) -> Self
+    where
+        F: Fn(&ResourceContents) -> T + Send + Sync + 'static,
+    {
+        self.on("application/json", handler)
+    }
+
+    /// Registers a handler for `image/*` contents.
+    pub fn on_image<F>(self, handler: F) -> Self
+    where
+        F: Fn(&ResourceContents) -> T + Send + Sync + 'static,
+    {
+        self.on("image/*", handler)
+    }
+
+    /// Registers a handler for `application/pdf` contents.
+    pub fn on_pdf<F>(self, handler: F) -> Self
+    where
+        F: Fn(&ResourceContents) -> T + Send + Sync + 'static,
+    {
+        self.on("application/pdf", handler)
+    }
+
+    /// Registers the handler for contents matching no other handler.
+    pub fn fallback<F>(mut self, handler: F) -> Self
+    where
+        F: Fn(&ResourceContents) -> T + Send + Sync + 'static,
+    {
+        self.fallback = Some(Box::new(handler));
+        self
+    }
+
+    /// Passes each content of `result` to the handler registered for its mime type,
+    /// returning the values produced by the handlers in the order of the contents.
+    ///
+    /// Returns an error if a binary content is not valid base64.
+    pub fn dispatch(&self, result: &ReadResourceResult) -> Result<Vec<T>, RpcError> {
+        let mut outputs = Vec::with_capacity(result.contents.len());
+        for item in &result.contents {
+            let contents = ResourceContents::from_item(item)?;
+            if let Some(handler) = self.handler_for(&contents.mime_type) {
+                outputs.push(handler(&contents));
+            }
+        }
+        Ok(outputs)
+    }
+
+    // Returns the most specific handler for `mime_type`: exact match, then type wildcard, then any mime type
+    fn handler_for(&self, mime_type: &str) -> Option<&ContentHandler<T>> {
+        let type_wildcard = mime_type
+            .split_once('/')
+            .map(|(main_type, _)| format!("{}/*", main_type));
+
+        let find = |pattern: &str| {
+            self.handlers
+                .iter()
+                .find(|(registered, _)| registered == pattern)
+                .map(|(_, handler)| handler)
+        };
+
+        find(mime_type)
+            .or_else(|| type_wildcard.as_deref().and_then(find))
+            .or_else(|| find("*/*"))
+            .or(self.fallback.as_ref())
+    }
+}
+
+// Returns the lowercased mime type without its parameters, e.g. `text/html` for `text/HTML; charset=utf-8`
+fn essence(mime_type: Option<&str>, default: &str) -> String {
+    mime_type
+        .and_then(|mime_type| mime_type.split(';').next())
+        .map(str::trim)
+        .filter(|mime_type| !mime_type.is_empty())
+        .unwrap_or(default)
+        .to_ascii_lowercase()
+}
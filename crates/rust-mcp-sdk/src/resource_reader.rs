@@ -0,0 +1,112 @@
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rust_mcp_schema::{ReadResourceResult, ReadResourceResultContentsItem};
+use tokio::io::{AsyncRead, ReadBuf};
+
+/// Number of base64 characters decoded at once, a multiple of 4
+const BASE64_CHUNK_SIZE: usize = 16 * 1024;
+
+/// Reads the contents of a `ReadResourceResult` as a stream of bytes, returned by
+/// `McpClient::read_resource_stream()`.
+///
+/// Text contents are read as UTF-8, binary contents are decoded from base64 a chunk at a time,
+/// so that the decoded data is never buffered in memory as a whole. When a resource has multiple
+/// contents, they are read one after another.
+///
+/// # Example
+/// ```ignore
+/// let mut reader = client.read_resource_stream("file:///report.pdf").await?;
+/// tokio::io::copy(&mut reader, &mut upload).await?;
+/// ```
+pub struct ResourceReader {
+    contents: std::vec::IntoIter<ReadResourceResultContentsItem>,
+    // the content being read, and the position of the next byte to read in its text or blob
+    current: Option<(ReadResourceResultContentsItem, usize)>,
+    // decoded bytes of the current blob not read yet
+    decoded: Vec<u8>,
+    decoded_position: usize,
+}
+
+impl ResourceReader {
+    /// Creates a reader over the contents of the given result.
+    pub fn new(result: ReadResourceResult) -> Self {
+        Self {
+            contents: result.contents.into_iter(),
+            current: None,
+            decoded: vec![],
+            decoded_position: 0,
+        }
+    }
+
+    // Fills `buf` with the next bytes of the contents, leaving it untouched at the end of the contents
+    fn read_next(&mut self, buf: &mut ReadBuf<'_>) -> std::io::Result<()> {
+        loop {
+            if self.decoded_position < self.decoded.len() {
+                let available = &self.decoded[self.decoded_position..];
+                let size = available.len().min(buf.remaining());
+                buf.put_slice(&available[..size]);
+                self.decoded_position += size;
+                return Ok(());
+            }
+
+            let Some((content, position)) = self.current.as_mut() else {
+                match self.contents.next() {
+                    Some(content) => self.current = Some((content, 0)),
+                    None => return Ok(()),
+                }
+                continue;
+            };
+
+            match content {
+                ReadResourceResultContentsItem::TextResourceContents(contents) => {
+                    let available = &contents.text.as_bytes()[*position..];
+                    let size = available.len().min(buf.remaining());
+                    buf.put_slice(&available[..size]);
+                    *position += size;
+                    if *position == contents.text.len() {
+                        self.current = None;
+                    }
+                    if size > 0 {
+                        return Ok(());
+                    }
+                }
+                ReadResourceResultContentsItem::BlobResourceContents(contents) => {
+                    let blob = contents.blob.as_bytes();
+                    let end = position.saturating_add(BASE64_CHUNK_SIZE).min(blob.len());
+                    self.decoded.clear();
+                    self.decoded_position = 0;
+                    STANDARD
+                        .decode_vec(&blob[*position..end], &mut self.decoded)
+                        .map_err(|err| {
+                            std::io::Error::new(
+                                std::io::ErrorKind::InvalidData,
+                                format!(
+                                    "Invalid base64 data for resource '{}': {}",
+                                    contents.uri, err
+                                ),
+                            )
+                        })?;
+                    *position = end;
+                    if end == blob.len() {
+                        self.current = None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl AsyncRead for ResourceReader {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        if buf.remaining() == 0 {
+            return Poll::Ready(Ok(()));
+        }
+        Poll::Ready(self.get_mut().read_next(buf))
+    }
+}
@@ -0,0 +1,462 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rust_mcp_schema::schema_utils::{NotificationFromServer, ResultFromServer};
+use rust_mcp_schema::{RequestId, RpcError, ServerNotification, ServerResult};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::INVALID_SIGNATURE_ERROR_CODE;
+
+/// Key of the `_meta` of results and notifications holding their signature:
+/// `{"algorithm": "ed25519", "keyId": "<key id>", "value": "<base64 encoded signature>"}`
+pub const SIGNATURE_KEY: &str = "signature";
+
+/// Algorithm of the signatures, unless the `Signer` uses another one
+pub const ED25519: &str = "ed25519";
+
+/// Signs the results and notifications sent by a server, with the private key identifying it.
+///
+/// The SDK does not ship any signature scheme: implement this trait on top of the crypto library of
+/// your choice, preferably with an ed25519 key (e.g. with the `ed25519-dalek` crate). Clients verify
+/// the signatures with a `SignatureVerifier` knowing the public key of the server.
+pub trait Signer: Send + Sync {
+    /// Identifies the key, so that verifiers can pick the public key of the server
+    fn key_id(&self) -> &str;
+
+    /// Name of the signature algorithm, `ED25519` by default
+    fn algorithm(&self) -> &str {
+        ED25519
+    }
+
+    /// Signs the canonical JSON serialization of a result, along with the id and method of its
+    /// request, or of a notification
+    fn sign(&self, payload: &[u8]) -> Result<Vec<u8>, RpcError>;
+}
+
+/// Verifies the signatures of the results and notifications received from a server, see `Signer`.
+pub trait SignatureVerifier: Send + Sync {
+    /// Verifies the signature of the canonical JSON serialization of a result, along with the id and
+    /// method of its request, or of a notification, returning an error if the key is not trusted or
+    /// the signature is invalid.
+    ///
+    /// The errors are reported with the `INVALID_SIGNATURE_ERROR_CODE` code, whatever their code.
+    fn verify(&self, payload: &[u8], signature: &Signature) -> Result<(), RpcError>;
+}
+
+/// The signature of a result or notification, as found in its `_meta`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub algorithm: String,
+    pub key_id: String,
+    pub value: Vec<u8>,
+}
+
+impl Signature {
+    fn to_value(&self) -> Value {
+        let mut signature = Map::new();
+        signature.insert("algorithm".to_string(), self.algorithm.clone().into());
+        signature.insert("keyId".to_string(), self.key_id.clone().into());
+        signature.insert("value".to_string(), STANDARD.encode(&self.value).into());
+        Value::Object(signature)
+    }
+
+    fn from_value(value: &Value) -> Result<Self, RpcError> {
+        let field = |name: &str| {
+            value.get(name).and_then(Value::as_str).ok_or_else(|| {
+                invalid_signature(format!("the '{}' of the signature is missing", name))
+            })
+        };
+        Ok(Self {
+            algorithm: field("algorithm")?.to_string(),
+            key_id: field("keyId")?.to_string(),
+            value: STANDARD
+                .decode(field("value")?)
+                .map_err(|err| invalid_signature(err.to_string()))?,
+        })
+    }
+}
+
+/// Signs the result of a request, adding its signature to its `_meta`.
+///
+/// The signature covers the id and the method of the request along with the result, so that a
+/// signed result cannot be replayed as the response of another request.
+pub fn sign_result(
+    result: &mut ResultFromServer,
+    request_id: &RequestId,
+    method: &str,
+    signer: &dyn Signer,
+) -> Result<(), RpcError> {
+    // the `_meta` is created before the payload is computed, so that verifiers compute the same one
+    if result_meta(result).is_none() {
+        return Ok(());
+    }
+    let mut value = to_value(result)?;
+    take_signature(&mut value, false);
+    let signature = sign(&bind_request(value, request_id, method), signer)?;
+    if let Some(meta) = result_meta(result) {
+        meta.insert(SIGNATURE_KEY.to_string(), signature.to_value());
+    }
+    Ok(())
+}
+
+/// Signs a notification, adding its signature to the `_meta` of its parameters.
+///
+/// Only the notifications whose parameters have a `_meta` in the schema can be signed: the list
+/// changed notifications and custom notifications. The other ones are left unsigned: the
+/// `notifications/progress`, `notifications/message` (logging), `notifications/cancelled` and
+/// `notifications/resources/updated` notifications.
+pub fn sign_notification(
+    notification: &mut NotificationFromServer,
+    signer: &dyn Signer,
+) -> Result<(), RpcError> {
+    if notification_meta(notification).is_none() {
+        return Ok(());
+    }
+    let mut value = to_value(notification)?;
+    take_signature(&mut value, true);
+    let signature = sign(&value, signer)?;
+    if let Some(meta) = notification_meta(notification) {
+        meta.insert(SIGNATURE_KEY.to_string(), signature.to_value());
+    }
+    Ok(())
+}
+
+/// Verifies the signature of the result of a request, given the id and the method of the pending
+/// request it answers.
+///
+/// # Errors
+/// Returns an `INVALID_SIGNATURE_ERROR_CODE` error if the result is not signed, or if its signature
+/// is invalid, including when it was signed as the result of another request.
+pub fn verify_result(
+    result: &ResultFromServer,
+    request_id: &RequestId,
+    method: &str,
+    verifier: &dyn SignatureVerifier,
+) -> Result<(), RpcError> {
+    let mut value = to_value(result)?;
+    let signature = take_signature(&mut value, false);
+    verify(
+        &bind_request(value, request_id, method),
+        signature,
+        verifier,
+    )
+}
+
+/// Verifies the signature of a notification.
+///
+/// Notifications that cannot be signed, see `sign_notification()`, are accepted as is: progress,
+/// logging, cancellation and resource update notifications are never verified.
+///
+/// # Errors
+/// Returns an `INVALID_SIGNATURE_ERROR_CODE` error if the notification is not signed, or if its
+/// signature is invalid.
+pub fn verify_notification(
+    notification: &NotificationFromServer,
+    verifier: &dyn SignatureVerifier,
+) -> Result<(), RpcError> {
+    if !can_be_signed(notification) {
+        return Ok(());
+    }
+    let mut value = to_value(notification)?;
+    let signature = take_signature(&mut value, true);
+    verify(&value, signature, verifier)
+}
+
+// The payload signed for a result: the result along with the id and method of its request
+fn bind_request(result: Value, request_id: &RequestId, method: &str) -> Value {
+    let mut payload = Map::new();
+    payload.insert("id".to_string(), request_id_value(request_id));
+    payload.insert("method".to_string(), method.into());
+    payload.insert("result".to_string(), result);
+    Value::Object(payload)
+}
+
+// Ids are bound with their type, so that the result of request `1` does not verify for request `"1"`
+fn request_id_value(request_id: &RequestId) -> Value {
+    match request_id {
+        RequestId::String(id) => id.clone().into(),
+        RequestId::Integer(id) => (*id).into(),
+    }
+}
+
+// Signs the canonical serialization of a payload, without its signature
+fn sign(payload: &Value, signer: &dyn Signer) -> Result<Signature, RpcError> {
+    Ok(Signature {
+        algorithm: signer.algorithm().to_string(),
+        key_id: signer.key_id().to_string(),
+        value: signer.sign(canonical_json(payload).as_bytes())?,
+    })
+}
+
+// Verifies a signature against the canonical serialization of a payload
+fn verify(
+    payload: &Value,
+    signature: Option<Value>,
+    verifier: &dyn SignatureVerifier,
+) -> Result<(), RpcError> {
+    let signature =
+        signature.ok_or_else(|| invalid_signature("the message is not signed".to_string()))?;
+    let signature = Signature::from_value(&signature)?;
+    verifier
+        .verify(canonical_json(payload).as_bytes(), &signature)
+        .map_err(|error| RpcError {
+            code: INVALID_SIGNATURE_ERROR_CODE,
+            ..error
+        })
+}
+
+// Removes the signature from the `_meta` of a serialized result, or of the parameters of
+// a serialized notification, returning it
+fn take_signature(value: &mut Value, in_params: bool) -> Option<Value> {
+    let container = match in_params {
+        true => value.get_mut("params")?,
+        false => value,
+    };
+    let meta = container.get_mut("_meta")?.as_object_mut()?;
+    let signature = meta.remove(SIGNATURE_KEY);
+    if meta.is_empty() {
+        container.as_object_mut()?.remove("_meta");
+    }
+    signature
+}
+
+// Returns the `_meta` of a result, created if missing
+fn result_meta(result: &mut ResultFromServer) -> Option<&mut Map<String, Value>> {
+    let meta = match result {
+        ResultFromServer::ServerResult(server_result) => match server_result {
+            ServerResult::InitializeResult(result) => &mut result.meta,
+            ServerResult::ListResourcesResult(result) => &mut result.meta,
+            ServerResult::ListResourceTemplatesResult(result) => &mut result.meta,
+            ServerResult::ReadResourceResult(result) => &mut result.meta,
+            ServerResult::ListPromptsResult(result) => &mut result.meta,
+            ServerResult::GetPromptResult(result) => &mut result.meta,
+            ServerResult::ListToolsResult(result) => &mut result.meta,
+            ServerResult::CallToolResult(result) => &mut result.meta,
+            ServerResult::CompleteResult(result) => &mut result.meta,
+            ServerResult::Result(result) => &mut result.meta,
+        },
+        ResultFromServer::CustomResult(value) => return object_meta(value),
+    };
+    Some(meta.get_or_insert_with(Map::new))
+}
+
+// Returns the `_meta` of the parameters of a notification, created if missing,
+// or `None` if its parameters have no `_meta`
+fn notification_meta(notification: &mut NotificationFromServer) -> Option<&mut Map<String, Value>> {
+    let meta = match notification {
+        NotificationFromServer::ServerNotification(server_notification) => {
+            match server_notification {
+                ServerNotification::ResourceListChangedNotification(notification) => {
+                    &mut notification
+                        .params
+                        .get_or_insert_with(Default::default)
+                        .meta
+                }
+                ServerNotification::PromptListChangedNotification(notification) => {
+                    &mut notification
+                        .params
+                        .get_or_insert_with(Default::default)
+                        .meta
+                }
+                ServerNotification::ToolListChangedNotification(notification) => {
+                    &mut notification
+                        .params
+                        .get_or_insert_with(Default::default)
+                        .meta
+                }
+                _ => return None,
+            }
+        }
+        NotificationFromServer::CustomNotification(value) => {
+            let params = value
+                .as_object_mut()?
+                .entry("params")
+                .or_insert_with(|| Value::Object(Map::new()));
+            return object_meta(params);
+        }
+    };
+    Some(meta.get_or_insert_with(Map::new))
+}
+
+fn object_meta(value: &mut Value) -> Option<&mut Map<String, Value>> {
+    value
+        .as_object_mut()?
+        .entry("_meta")
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+}
+
+fn can_be_signed(notification: &NotificationFromServer) -> bool {
+    match notification {
+        NotificationFromServer::ServerNotification(server_notification) => matches!(
+            server_notification,
+            ServerNotification::ResourceListChangedNotification(_)
+                | ServerNotification::PromptListChangedNotification(_)
+                | ServerNotification::ToolListChangedNotification(_)
+        ),
+        NotificationFromServer::CustomNotification(value) => {
+            value.get("params").is_none_or(|params| params.is_object())
+        }
+    }
+}
+
+fn to_value<T: Serialize>(message: &T) -> Result<Value, RpcError> {
+    serde_json::to_value(message).map_err(|err| {
+        RpcError::internal_error().with_message(format!("Failed to serialize the message: {}", err))
+    })
+}
+
+/// Serializes a JSON value with the keys of its objects sorted and without whitespace,
+/// so that the signer and the verifier sign the same bytes.
+pub fn canonical_json(value: &Value) -> String {
+    match value {
+        Value::Object(map) => {
+            let mut entries: Vec<(&String, &Value)> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+            let entries: Vec<String> = entries
+                .into_iter()
+                .map(|(key, value)| {
+                    format!("{}:{}", Value::from(key.as_str()), canonical_json(value))
+                })
+                .collect();
+            format!("{{{}}}", entries.join(","))
+        }
+        Value::Array(values) => {
+            let values: Vec<String> = values.iter().map(canonical_json).collect();
+            format!("[{}]", values.join(","))
+        }
+        value => value.to_string(),
+    }
+}
+
+fn invalid_signature(reason: String) -> RpcError {
+    RpcError {
+        code: INVALID_SIGNATURE_ERROR_CODE,
+        data: None,
+        message: format!("Invalid signature: {}", reason),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_mcp_schema::{
+        CallToolResult, ProgressNotification, ProgressNotificationParams, ProgressToken,
+        ToolListChangedNotification,
+    };
+
+    // A toy signature: the key id followed by the payload, reversed
+    struct TestKey;
+
+    impl Signer for TestKey {
+        fn key_id(&self) -> &str {
+            "test-key"
+        }
+
+        fn sign(&self, payload: &[u8]) -> Result<Vec<u8>, RpcError> {
+            Ok([self.key_id().as_bytes(), payload]
+                .concat()
+                .into_iter()
+                .rev()
+                .collect())
+        }
+    }
+
+    impl SignatureVerifier for TestKey {
+        fn verify(&self, payload: &[u8], signature: &Signature) -> Result<(), RpcError> {
+            match signature.key_id == self.key_id() && self.sign(payload)? == signature.value {
+                true => Ok(()),
+                false => Err(invalid_signature("signature mismatch".to_string())),
+            }
+        }
+    }
+
+    // Sends a message over the wire
+    fn relay<T: Serialize + serde::de::DeserializeOwned>(message: &T) -> T {
+        serde_json::from_str(&serde_json::to_string(message).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn test_signed_result() {
+        let mut result: ResultFromServer =
+            ServerResult::CallToolResult(CallToolResult::text_content("42".to_string(), None))
+                .into();
+        let request_id = RequestId::Integer(1);
+        sign_result(&mut result, &request_id, "tools/call", &TestKey).unwrap();
+
+        let value = serde_json::to_value(&result).unwrap();
+        assert_eq!(value["_meta"][SIGNATURE_KEY]["algorithm"], ED25519);
+        assert_eq!(value["_meta"][SIGNATURE_KEY]["keyId"], "test-key");
+        assert!(verify_result(&relay(&result), &request_id, "tools/call", &TestKey).is_ok());
+
+        let mut tampered = value;
+        tampered["content"][0]["text"] = "43".into();
+        let tampered: ResultFromServer = serde_json::from_value(tampered).unwrap();
+        let error = verify_result(&tampered, &request_id, "tools/call", &TestKey).unwrap_err();
+        assert_eq!(error.code, INVALID_SIGNATURE_ERROR_CODE);
+    }
+
+    #[test]
+    fn test_replayed_result() {
+        let mut result: ResultFromServer =
+            ServerResult::CallToolResult(CallToolResult::text_content("42".to_string(), None))
+                .into();
+        sign_result(&mut result, &RequestId::Integer(1), "tools/call", &TestKey).unwrap();
+        let result = relay(&result);
+
+        // the result is only valid for the request it answers
+        for (request_id, method) in [
+            (RequestId::Integer(2), "tools/call"),
+            (RequestId::String("1".to_string()), "tools/call"),
+            (RequestId::Integer(1), "prompts/get"),
+        ] {
+            let error = verify_result(&result, &request_id, method, &TestKey).unwrap_err();
+            assert_eq!(error.code, INVALID_SIGNATURE_ERROR_CODE);
+        }
+    }
+
+    #[test]
+    fn test_unsigned_result() {
+        let result: ResultFromServer = ServerResult::Result(Default::default()).into();
+        let error =
+            verify_result(&relay(&result), &RequestId::Integer(1), "ping", &TestKey).unwrap_err();
+        assert_eq!(error.code, INVALID_SIGNATURE_ERROR_CODE);
+        assert_eq!(
+            error.message,
+            "Invalid signature: the message is not signed"
+        );
+    }
+
+    #[test]
+    fn test_signed_notification() {
+        let mut notification: NotificationFromServer =
+            ToolListChangedNotification::new(None).into();
+        sign_notification(&mut notification, &TestKey).unwrap();
+        assert!(verify_notification(&relay(&notification), &TestKey).is_ok());
+
+        let unsigned: NotificationFromServer = ToolListChangedNotification::new(None).into();
+        assert!(verify_notification(&unsigned, &TestKey).is_err());
+
+        // notifications without a `_meta` cannot be signed, and are accepted unsigned
+        let mut progress: NotificationFromServer =
+            ProgressNotification::new(ProgressNotificationParams {
+                progress: 1.0,
+                progress_token: ProgressToken::Integer(1),
+                total: None,
+            })
+            .into();
+        sign_notification(&mut progress, &TestKey).unwrap();
+        assert!(serde_json::to_value(&progress).unwrap()["params"]
+            .get("_meta")
+            .is_none());
+        assert!(verify_notification(&relay(&progress), &TestKey).is_ok());
+    }
+
+    #[test]
+    fn test_canonical_json() {
+        let value = serde_json::json!({"b": [1, {"d": null, "c": "x"}], "a": 1.5});
+        assert_eq!(
+            canonical_json(&value),
+            r#"{"a":1.5,"b":[1,{"c":"x","d":null}]}"#
+        );
+    }
+}
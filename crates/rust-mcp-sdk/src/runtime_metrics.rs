@@ -0,0 +1,398 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+/// Default upper bounds of the latency histogram buckets
+const DEFAULT_LATENCY_BUCKETS: [Duration; 12] = [
+    Duration::from_millis(1),
+    Duration::from_millis(5),
+    Duration::from_millis(10),
+    Duration::from_millis(25),
+    Duration::from_millis(50),
+    Duration::from_millis(100),
+    Duration::from_millis(250),
+    Duration::from_millis(500),
+    Duration::from_secs(1),
+    Duration::from_millis(2500),
+    Duration::from_secs(5),
+    Duration::from_secs(10),
+];
+
+/// The method label under which `DefaultMetrics` records the requests and notifications whose
+/// method is not an MCP method, e.g. custom ones
+pub const OTHER_METHODS: &str = "other";
+
+/// The methods of the MCP protocol, recorded by `DefaultMetrics` under their own label
+const MCP_METHODS: [&str; 24] = [
+    "initialize",
+    "ping",
+    "resources/list",
+    "resources/templates/list",
+    "resources/read",
+    "resources/subscribe",
+    "resources/unsubscribe",
+    "prompts/list",
+    "prompts/get",
+    "tools/list",
+    "tools/call",
+    "logging/setLevel",
+    "completion/complete",
+    "sampling/createMessage",
+    "roots/list",
+    "notifications/initialized",
+    "notifications/cancelled",
+    "notifications/progress",
+    "notifications/message",
+    "notifications/resources/updated",
+    "notifications/resources/list_changed",
+    "notifications/tools/list_changed",
+    "notifications/prompts/list_changed",
+    "notifications/roots/list_changed",
+];
+
+/// Records the activity of a runtime, by method.
+///
+/// A `ServerRuntime` records the requests it processes, from their reception to their response,
+/// and the notifications it receives. A `ClientRuntime` records the requests it sends, from their
+/// sending to the reception of their response, and the notifications it receives.
+///
+/// `DefaultMetrics` is used by default. Implement this trait to record the activity directly in
+/// the metrics of your application instead. Methods are called while messages are processed,
+/// so they should return quickly. The method names are chosen by the peer for custom requests
+/// and notifications, so they should not be used as labels as is.
+pub trait Metrics: Send + Sync + 'static {
+    /// Called when a request starts.
+    fn request_started(&self, method: &str);
+
+    /// Called when a request completes, after `duration`. `failed` is `true` if it resulted
+    /// in an error, or in a tool result reporting an error.
+    fn request_completed(&self, method: &str, duration: Duration, failed: bool);
+
+    /// Called when a notification is received.
+    fn notification_received(&self, method: &str);
+
+    /// Returns a snapshot of the recorded metrics, returned by the `metrics()` method of runtimes.
+    /// Returns an empty snapshot by default.
+    fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot::default()
+    }
+}
+
+/// A histogram of request latencies.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct LatencyHistogram {
+    /// The upper bounds of the buckets, in seconds, in ascending order
+    pub bounds: Vec<f64>,
+    /// The number of requests per bucket, the last count being the number of requests
+    /// exceeding the last bound. Counts are not cumulative.
+    pub counts: Vec<u64>,
+    /// The total duration of the requests, in seconds
+    pub sum: f64,
+    /// The number of requests
+    pub count: u64,
+}
+
+impl LatencyHistogram {
+    fn new(bounds: &[Duration]) -> Self {
+        Self {
+            bounds: bounds.iter().map(Duration::as_secs_f64).collect(),
+            counts: vec![0; bounds.len() + 1],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn record(&mut self, duration: Duration) {
+        let seconds = duration.as_secs_f64();
+        let bucket = self.bounds.partition_point(|bound| *bound < seconds);
+        self.counts[bucket] += 1;
+        self.sum += seconds;
+        self.count += 1;
+    }
+}
+
+/// The metrics recorded for a method.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MethodMetrics {
+    /// The number of completed requests
+    pub requests: u64,
+    /// The number of completed requests that resulted in an error
+    pub errors: u64,
+    /// The number of requests in progress
+    pub in_flight: u64,
+    /// The number of received notifications
+    pub notifications: u64,
+    /// The latencies of the completed requests
+    pub latency: LatencyHistogram,
+}
+
+/// A snapshot of the metrics of a runtime, serializable to export them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct MetricsSnapshot {
+    /// The metrics of each method, sorted by method
+    pub methods: BTreeMap<String, MethodMetrics>,
+}
+
+impl MetricsSnapshot {
+    /// Returns the number of requests in progress, for all methods.
+    pub fn in_flight(&self) -> u64 {
+        self.methods.values().map(|metrics| metrics.in_flight).sum()
+    }
+
+    /// Formats the snapshot in the Prometheus text exposition format, with metric names
+    /// starting with `prefix`, e.g. `mcp_server`.
+    ///
+    /// # Example
+    /// ```ignore
+    /// // served by the /metrics endpoint of the host
+    /// let body = server.metrics().to_prometheus("mcp_server");
+    /// ```
+    pub fn to_prometheus(&self, prefix: &str) -> String {
+        let mut output = String::new();
+        let methods: Vec<_> = self
+            .methods
+            .iter()
+            .map(|(method, metrics)| (escape_label_value(method), metrics))
+            .collect();
+        let counters = [
+            ("requests_total", "Number of completed requests"),
+            (
+                "request_errors_total",
+                "Number of requests that resulted in an error",
+            ),
+            ("notifications_total", "Number of received notifications"),
+        ];
+        for (name, help) in counters {
+            let _ = writeln!(output, "# HELP {prefix}_{name} {help}.");
+            let _ = writeln!(output, "# TYPE {prefix}_{name} counter");
+            for (method, metrics) in &methods {
+                let value = match name {
+                    "requests_total" => metrics.requests,
+                    "request_errors_total" => metrics.errors,
+                    _ => metrics.notifications,
+                };
+                let _ = writeln!(output, "{prefix}_{name}{{method=\"{method}\"}} {value}");
+            }
+        }
+
+        let _ = writeln!(
+            output,
+            "# HELP {prefix}_requests_in_flight Number of requests in progress."
+        );
+        let _ = writeln!(output, "# TYPE {prefix}_requests_in_flight gauge");
+        for (method, metrics) in &methods {
+            let _ = writeln!(
+                output,
+                "{prefix}_requests_in_flight{{method=\"{method}\"}} {}",
+                metrics.in_flight
+            );
+        }
+
+        let _ = writeln!(
+            output,
+            "# HELP {prefix}_request_duration_seconds Latency of the completed requests."
+        );
+        let _ = writeln!(output, "# TYPE {prefix}_request_duration_seconds histogram");
+        for (method, metrics) in &methods {
+            let latency = &metrics.latency;
+            let mut cumulative = 0;
+            for (bound, count) in latency.bounds.iter().zip(&latency.counts) {
+                cumulative += count;
+                let _ = writeln!(
+                    output,
+                    "{prefix}_request_duration_seconds_bucket{{method=\"{method}\",le=\"{bound}\"}} {cumulative}"
+                );
+            }
+            let _ = writeln!(
+                output,
+                "{prefix}_request_duration_seconds_bucket{{method=\"{method}\",le=\"+Inf\"}} {}",
+                latency.count
+            );
+            let _ = writeln!(
+                output,
+                "{prefix}_request_duration_seconds_sum{{method=\"{method}\"}} {}",
+                latency.sum
+            );
+            let _ = writeln!(
+                output,
+                "{prefix}_request_duration_seconds_count{{method=\"{method}\"}} {}",
+                latency.count
+            );
+        }
+        output
+    }
+}
+
+// Escapes a label value as required by the Prometheus text exposition format
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+/// The default implementation of `Metrics`, keeping the metrics in memory.
+///
+/// The MCP methods are recorded under their own name, and the other ones, e.g. custom requests
+/// and notifications, under `OTHER_METHODS`, so that peers sending arbitrary methods can not grow
+/// the metrics without limit.
+///
+/// # Example
+/// ```ignore
+/// let server = server_runtime::create_server(server_details, transport, handler)
+///     .with_metrics(DefaultMetrics::with_latency_buckets(vec![
+///         Duration::from_millis(10),
+///         Duration::from_millis(100),
+///         Duration::from_secs(1),
+///     ]));
+/// ```
+pub struct DefaultMetrics {
+    latency_buckets: Vec<Duration>,
+    methods: Mutex<HashMap<String, MethodMetrics>>,
+}
+
+impl Default for DefaultMetrics {
+    fn default() -> Self {
+        Self::with_latency_buckets(DEFAULT_LATENCY_BUCKETS.to_vec())
+    }
+}
+
+impl DefaultMetrics {
+    /// Creates metrics with latency buckets from 1ms to 10s.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates metrics with the given upper bounds of the latency histogram buckets.
+    pub fn with_latency_buckets(mut latency_buckets: Vec<Duration>) -> Self {
+        latency_buckets.sort();
+        latency_buckets.dedup();
+        Self {
+            latency_buckets,
+            methods: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Applies `update` to the metrics of `method`
+    fn update(&self, method: &str, update: impl FnOnce(&mut MethodMetrics)) {
+        let Ok(mut methods) = self.methods.lock() else {
+            return;
+        };
+        let method = match MCP_METHODS.contains(&method) {
+            true => method,
+            false => OTHER_METHODS,
+        };
+        let metrics = match methods.get_mut(method) {
+            Some(metrics) => metrics,
+            None => methods.entry(method.to_string()).or_insert(MethodMetrics {
+                latency: LatencyHistogram::new(&self.latency_buckets),
+                ..Default::default()
+            }),
+        };
+        update(metrics);
+    }
+}
+
+impl Metrics for DefaultMetrics {
+    fn request_started(&self, method: &str) {
+        self.update(method, |metrics| metrics.in_flight += 1);
+    }
+
+    fn request_completed(&self, method: &str, duration: Duration, failed: bool) {
+        self.update(method, |metrics| {
+            metrics.in_flight = metrics.in_flight.saturating_sub(1);
+            metrics.requests += 1;
+            if failed {
+                metrics.errors += 1;
+            }
+            metrics.latency.record(duration);
+        });
+    }
+
+    fn notification_received(&self, method: &str) {
+        self.update(method, |metrics| metrics.notifications += 1);
+    }
+
+    fn snapshot(&self) -> MetricsSnapshot {
+        let methods = match self.methods.lock() {
+            Ok(methods) => methods
+                .iter()
+                .map(|(method, metrics)| (method.clone(), metrics.clone()))
+                .collect(),
+            Err(_) => BTreeMap::new(),
+        };
+        MetricsSnapshot { methods }
+    }
+}
+
+// Records a request with `metrics` while awaiting `request`, `failed` telling whether its result is a failure.
+// A request dropped before its completion is recorded as failed.
+pub(crate) async fn measure_request<T>(
+    metrics: &dyn Metrics,
+    method: &str,
+    request: impl Future<Output = T>,
+    failed: impl FnOnce(&T) -> bool,
+) -> T {
+    struct InFlightRequest<'a> {
+        metrics: &'a dyn Metrics,
+        method: &'a str,
+        started: Instant,
+        failed: bool,
+    }
+
+    impl Drop for InFlightRequest<'_> {
+        fn drop(&mut self) {
+            self.metrics
+                .request_completed(self.method, self.started.elapsed(), self.failed);
+        }
+    }
+
+    metrics.request_started(method);
+    let mut in_flight = InFlightRequest {
+        metrics,
+        method,
+        started: Instant::now(),
+        failed: true,
+    };
+    let result = request.await;
+    in_flight.failed = failed(&result);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_custom_methods_are_recorded_together() {
+        let metrics = DefaultMetrics::new();
+        metrics.request_completed("tools/call", Duration::from_millis(2), false);
+        for index in 0..100 {
+            metrics.notification_received(&format!("custom/{}", index));
+        }
+        let snapshot = metrics.snapshot();
+        assert_eq!(
+            snapshot.methods.keys().collect::<Vec<_>>(),
+            ["other", "tools/call"]
+        );
+        assert_eq!(snapshot.methods[OTHER_METHODS].notifications, 100);
+    }
+
+    #[test]
+    fn test_prometheus_label_values_are_escaped() {
+        let mut snapshot = MetricsSnapshot::default();
+        snapshot.methods.insert(
+            "a\\b\"} 1\nfake_total{method=\"x".to_string(),
+            MethodMetrics::default(),
+        );
+        let output = snapshot.to_prometheus("mcp");
+        assert!(output.contains(
+            "mcp_requests_total{method=\"a\\\\b\\\"} 1\\nfake_total{method=\\\"x\"} 0\n"
+        ));
+        assert!(!output.lines().any(|line| line.starts_with("fake_total")));
+    }
+}
@@ -0,0 +1,107 @@
+use std::future::Future;
+
+use rust_mcp_schema::{CallToolRequestParams, CreateMessageRequestParams};
+use serde_json::{Map, Value};
+
+use crate::error::McpSdkError;
+use crate::tool_dry_run::META_ARGUMENT;
+
+/// Key of the `_meta` of tool calls and sampling requests holding their sampling depth.
+pub const SAMPLING_DEPTH_KEY: &str = "samplingDepth";
+
+/// Default maximum sampling depth of the server and client runtimes.
+pub const DEFAULT_MAX_SAMPLING_DEPTH: u32 = 3;
+
+tokio::task_local! {
+    // The sampling depth of the request handled by the current task
+    static SAMPLING_DEPTH: u32;
+}
+
+/// Returns the sampling depth of the request handled by the current task: the depth of the sampling
+/// request being fulfilled on a client, or the depth of the tool call being executed on a server.
+///
+/// It is 0 outside of sampling requests and of tool calls made while fulfilling them.
+pub fn current_sampling_depth() -> u32 {
+    SAMPLING_DEPTH.try_with(|depth| *depth).unwrap_or(0)
+}
+
+// Runs `future` with the given sampling depth, see `current_sampling_depth()`
+pub(crate) async fn with_sampling_depth<F: Future>(depth: u32, future: F) -> F::Output {
+    SAMPLING_DEPTH.scope(depth, future).await
+}
+
+// Returns an error if `depth` exceeds `max_depth`
+pub(crate) fn check_sampling_depth(depth: u32, max_depth: u32) -> Result<(), McpSdkError> {
+    match depth > max_depth {
+        true => Err(McpSdkError::SamplingDepthExceeded { depth, max_depth }),
+        false => Ok(()),
+    }
+}
+
+/// Returns the sampling depth of a tool call, 0 if it was not made while fulfilling a sampling request.
+///
+/// The `CallToolRequestParams` of the schema have no `_meta`, the depth is carried in the
+/// reserved `_meta` argument, see `dry_run::META_ARGUMENT`.
+pub fn tool_call_depth(params: &CallToolRequestParams) -> u32 {
+    depth_in(params.arguments.as_ref())
+}
+
+/// Sets the sampling depth of a tool call, which is left unchanged if `depth` is 0.
+pub fn set_tool_call_depth(params: &mut CallToolRequestParams, depth: u32) {
+    if depth > 0 {
+        set_depth_in(params.arguments.get_or_insert_with(Map::new), depth);
+    }
+}
+
+/// Returns the sampling depth of a sampling request, 1 for the requests sent while executing a tool
+/// called by the user, 0 for the requests sent outside of tool calls.
+///
+/// The `CreateMessageRequestParams` of the schema have no `_meta`, the depth is carried in a
+/// reserved `_meta` entry of their `metadata`, removed by the client runtime before the request
+/// is passed to the handler.
+pub fn sampling_request_depth(params: &CreateMessageRequestParams) -> u32 {
+    depth_in(params.metadata.as_ref())
+}
+
+/// Sets the sampling depth of a sampling request, which is left unchanged if `depth` is 0.
+pub fn set_sampling_request_depth(params: &mut CreateMessageRequestParams, depth: u32) {
+    if depth > 0 {
+        set_depth_in(params.metadata.get_or_insert_with(Map::new), depth);
+    }
+}
+
+// Removes the sampling depth from a sampling request, returning it
+pub(crate) fn take_sampling_request_depth(params: &mut CreateMessageRequestParams) -> u32 {
+    let depth = sampling_request_depth(params);
+    if let Some(metadata) = params.metadata.as_mut() {
+        if let Some(Value::Object(meta)) = metadata.get_mut(META_ARGUMENT) {
+            meta.remove(SAMPLING_DEPTH_KEY);
+            if meta.is_empty() {
+                metadata.remove(META_ARGUMENT);
+            }
+        }
+        if metadata.is_empty() {
+            params.metadata = None;
+        }
+    }
+    depth
+}
+
+fn depth_in(map: Option<&Map<String, Value>>) -> u32 {
+    map.and_then(|map| map.get(META_ARGUMENT))
+        .and_then(|meta| meta.get(SAMPLING_DEPTH_KEY))
+        .and_then(Value::as_u64)
+        .map_or(0, |depth| u32::try_from(depth).unwrap_or(u32::MAX))
+}
+
+fn set_depth_in(map: &mut Map<String, Value>, depth: u32) {
+    let meta = map
+        .entry(META_ARGUMENT)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !meta.is_object() {
+        *meta = Value::Object(Map::new());
+    }
+    if let Value::Object(meta) = meta {
+        meta.insert(SAMPLING_DEPTH_KEY.to_string(), depth.into());
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use serde_json::{Map, Value};
+
+/// Inlines the `$defs` of a schema into its properties, as the input schema of a tool has no `$defs`.
+///
+/// The definition of a type is inlined where it is first referred to, depth first, and the other
+/// references to it point there, e.g. `{"$ref": "#/properties/root"}` for a recursive type. The
+/// references between definitions, including mutually recursive ones, are resolved the same way.
+///
+/// Called by the `tool()` method generated by `mcp_tool`.
+pub fn inline_definitions(
+    properties: HashMap<String, Map<String, Value>>,
+    defs: &Map<String, Value>,
+) -> HashMap<String, Map<String, Value>> {
+    let mut defs = defs.clone();
+    let mut root = serde_json::json!({ "properties": properties });
+    let mut path = vec![];
+    while let Some(name) = first_reference(&root, &mut path) {
+        let pointer = format!("/{}", path.join("/"));
+        if let Some(Value::Object(node)) = root.pointer_mut(&pointer) {
+            node.remove("$ref");
+            if let Some(Value::Object(definition)) = defs.remove(&name) {
+                for (key, value) in definition {
+                    node.entry(key).or_insert(value);
+                }
+            }
+        }
+        let reference = format!("#/$defs/{}", name);
+        let pointer = format!("#{}", pointer);
+        replace_references(&mut root, &reference, &pointer);
+        defs.values_mut()
+            .for_each(|definition| replace_references(definition, &reference, &pointer));
+        path.clear();
+    }
+    serde_json::from_value(root["properties"].take()).unwrap_or_default()
+}
+
+// Returns the name of the first definition referred to in `value`, depth first, and sets `path` to
+// the segments of the JSON pointer to the reference.
+fn first_reference(value: &Value, path: &mut Vec<String>) -> Option<String> {
+    let children: Vec<(String, &Value)> = match value {
+        Value::Object(map) => {
+            if let Some(name) = map
+                .get("$ref")
+                .and_then(|reference| reference.as_str())
+                .and_then(|reference| reference.strip_prefix("#/$defs/"))
+            {
+                return Some(name.to_string());
+            }
+            map.iter()
+                .map(|(key, value)| (key.clone(), value))
+                .collect()
+        }
+        Value::Array(items) => items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| (index.to_string(), item))
+            .collect(),
+        _ => vec![],
+    };
+    for (key, child) in children {
+        path.push(key.replace('~', "~0").replace('/', "~1"));
+        if let Some(name) = first_reference(child, path) {
+            return Some(name);
+        }
+        path.pop();
+    }
+    None
+}
+
+fn replace_references(value: &mut Value, from: &str, to: &str) {
+    match value {
+        Value::Object(map) => {
+            if map.get("$ref").and_then(|reference| reference.as_str()) == Some(from) {
+                map.insert("$ref".to_string(), Value::String(to.to_string()));
+            }
+            map.values_mut()
+                .for_each(|value| replace_references(value, from, to));
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| replace_references(item, from, to)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn inline(properties: Value, defs: Value) -> Value {
+        let properties = serde_json::from_value(properties).unwrap();
+        let Value::Object(defs) = defs else {
+            panic!("defs must be an object");
+        };
+        serde_json::to_value(inline_definitions(properties, &defs)).unwrap()
+    }
+
+    #[test]
+    fn test_inline_recursive_definitions() {
+        let properties = inline(
+            json!({
+                "root": {"$ref": "#/$defs/Node"},
+                "other": {"$ref": "#/$defs/Node"}
+            }),
+            json!({
+                "Node": {
+                    "type": "object",
+                    "properties": {
+                        "children": {"type": "array", "items": {"$ref": "#/$defs/Node"}}
+                    }
+                }
+            }),
+        );
+
+        // properties are visited in key order, `other` is the first reference
+        assert_eq!(properties["other"]["type"], "object");
+        assert_eq!(
+            properties["other"]["properties"]["children"]["items"],
+            json!({"$ref": "#/properties/other"})
+        );
+        assert_eq!(properties["root"], json!({"$ref": "#/properties/other"}));
+    }
+
+    #[test]
+    fn test_inline_mutually_recursive_definitions() {
+        let properties = inline(
+            json!({
+                "folder": {"$ref": "#/$defs/Folder", "description": "The root folder"}
+            }),
+            json!({
+                "Folder": {
+                    "type": "object",
+                    "properties": {
+                        "files": {"type": "array", "items": {"$ref": "#/$defs/File"}}
+                    }
+                },
+                "File": {
+                    "type": "object",
+                    "properties": {
+                        "parent": {"$ref": "#/$defs/Folder"},
+                        "links": {"type": "array", "items": {"$ref": "#/$defs/File"}}
+                    }
+                }
+            }),
+        );
+
+        let folder = &properties["folder"];
+        assert!(folder.get("$ref").is_none());
+        assert_eq!(folder["description"], "The root folder");
+        let file = &folder["properties"]["files"]["items"];
+        assert_eq!(file["type"], "object");
+        assert_eq!(
+            file["properties"]["parent"],
+            json!({"$ref": "#/properties/folder"})
+        );
+        assert_eq!(
+            file["properties"]["links"]["items"],
+            json!({"$ref": "#/properties/folder/properties/files/items"})
+        );
+        assert!(!properties.to_string().contains("$defs"));
+    }
+}
@@ -0,0 +1,97 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+
+/// Writes the schema snapshots of tools to `<dir>/<tool name>.json`, as pretty-printed JSON, so that
+/// schema changes can be diffed in code review and published, e.g. from an xtask or a CI job.
+///
+/// A snapshot is either a `rust_mcp_schema::Tool` as listed by a server, or the value returned by the
+/// `schema_snapshot()` method generated by `mcp_tool`, which includes the schema of the structured
+/// output of the tool. A file is only written if its content changed. Returns the paths of the files.
+///
+/// ```ignore
+/// write_schema_snapshots("docs/tool_schemas", FileSystemTools::tools())?;
+/// write_schema_snapshots(
+///     "docs/tool_schemas",
+///     [ReadFileTool::schema_snapshot(), WriteFileTool::schema_snapshot()],
+/// )?;
+/// ```
+///
+/// # Errors
+/// Returns an error of kind `InvalidInput` if a snapshot has no `name`, or the error of writing a file.
+pub fn write_schema_snapshots<T: Serialize>(
+    dir: impl AsRef<Path>,
+    snapshots: impl IntoIterator<Item = T>,
+) -> io::Result<Vec<PathBuf>> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+    snapshots
+        .into_iter()
+        .map(|snapshot| {
+            let snapshot = serde_json::to_value(snapshot).map_err(io::Error::other)?;
+            let name = snapshot
+                .get("name")
+                .and_then(|name| name.as_str())
+                .ok_or_else(|| {
+                    io::Error::new(io::ErrorKind::InvalidInput, "The snapshot has no name")
+                })?;
+            let mut content = serde_json::to_string_pretty(&snapshot).map_err(io::Error::other)?;
+            content.push('\n');
+
+            let path = dir.join(format!("{}.json", name));
+            if std::fs::read_to_string(&path).ok().as_deref() != Some(content.as_str()) {
+                std::fs::write(&path, content)?;
+            }
+            Ok(path)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_mcp_schema::{Tool, ToolInputSchema};
+    use serde_json::json;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("{}_{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_write_schema_snapshots() {
+        let dir = temp_dir("test_write_schema_snapshots");
+        let tool = Tool {
+            name: "read_file".to_string(),
+            description: None,
+            input_schema: ToolInputSchema::new(vec![], None),
+        };
+        let paths = write_schema_snapshots(&dir, [tool]).unwrap();
+        assert_eq!(paths, vec![dir.join("read_file.json")]);
+
+        let content = std::fs::read_to_string(&paths[0]).unwrap();
+        let snapshot: serde_json::Value = serde_json::from_str(&content).unwrap();
+        assert_eq!(snapshot["name"], "read_file");
+        assert_eq!(snapshot["inputSchema"]["type"], "object");
+        assert!(content.ends_with("}\n"));
+
+        let paths = write_schema_snapshots(
+            &dir,
+            [json!({"name": "write_file", "outputSchema": {"type": "object"}})],
+        )
+        .unwrap();
+        let content = std::fs::read_to_string(&paths[0]).unwrap();
+        assert!(content.contains("outputSchema"));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_write_schema_snapshots_without_name() {
+        let dir = temp_dir("test_write_schema_snapshots_without_name");
+        let error = write_schema_snapshots(&dir, [json!({"description": "No name"})]).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::InvalidInput);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,213 @@
+use std::time::{Duration, Instant};
+
+use rust_mcp_schema::{
+    CallToolRequestParams, CallToolResult, Implementation, Prompt, Resource, ResourceTemplate, Tool,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::error::SdkResult;
+use crate::McpClient;
+
+/// The capabilities advertised by a server, `None` when unknown.
+#[derive(Debug, Clone, Serialize)]
+pub struct CapabilitiesReport {
+    pub tools: Option<bool>,
+    pub prompts: Option<bool>,
+    pub resources: Option<bool>,
+    pub logging: Option<bool>,
+    pub experimental: Option<bool>,
+}
+
+/// A step of an inspection that failed, the other steps are run regardless.
+#[derive(Debug, Clone, Serialize)]
+pub struct InspectionError {
+    /// The method of the request that failed
+    pub method: String,
+    pub message: String,
+}
+
+/// The result of `inspect_server()`: the details of the server and everything it lists.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerInspection {
+    pub server_info: Option<Implementation>,
+    pub protocol_version: Option<String>,
+    pub instructions: Option<String>,
+    pub capabilities: CapabilitiesReport,
+    /// Empty if the server does not support tools
+    pub tools: Vec<Tool>,
+    /// Empty if the server does not support prompts
+    pub prompts: Vec<Prompt>,
+    /// Empty if the server does not support resources
+    pub resources: Vec<Resource>,
+    /// Empty if the server does not support resources
+    pub resource_templates: Vec<ResourceTemplate>,
+    pub errors: Vec<InspectionError>,
+}
+
+/// The result of `call_tool()`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolCallReport {
+    pub tool_name: String,
+    pub duration_ms: f64,
+    /// The result of the tool, `None` if the request failed
+    pub result: Option<CallToolResult>,
+    /// The error of the request, `None` if it succeeded
+    pub error: Option<String>,
+}
+
+impl ToolCallReport {
+    /// Returns `true` if the request succeeded and the tool did not report an error.
+    pub fn is_success(&self) -> bool {
+        self.result
+            .as_ref()
+            .is_some_and(|result| !result.is_error.unwrap_or(false))
+    }
+}
+
+/// A ping of `ping_server()`.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PingAttempt {
+    pub duration_ms: f64,
+    /// The error of the ping, `None` if it succeeded
+    pub error: Option<String>,
+}
+
+/// The result of `ping_server()`.
+#[derive(Debug, Clone, Serialize)]
+pub struct PingReport {
+    pub attempts: Vec<PingAttempt>,
+}
+
+impl PingReport {
+    /// Returns the number of successful pings.
+    pub fn successes(&self) -> usize {
+        self.attempts
+            .iter()
+            .filter(|attempt| attempt.error.is_none())
+            .count()
+    }
+}
+
+/// Inspects an initialized server: its details and capabilities, along with all the tools,
+/// prompts, resources and resource templates it lists, according to its capabilities.
+///
+/// Failing requests are reported in `errors` rather than ending the inspection.
+pub async fn inspect_server(client: &dyn McpClient) -> ServerInspection {
+    let server_details = client.server_info();
+    let mut errors = vec![];
+
+    let tools = if client.server_has_tools().unwrap_or(false) {
+        collect(client.list_all_tools().await, "tools/list", &mut errors)
+    } else {
+        vec![]
+    };
+    let prompts = if client.server_has_prompts().unwrap_or(false) {
+        collect(client.list_all_prompts().await, "prompts/list", &mut errors)
+    } else {
+        vec![]
+    };
+    let (resources, resource_templates) = if client.server_has_resources().unwrap_or(false) {
+        (
+            collect(
+                client.list_all_resources().await,
+                "resources/list",
+                &mut errors,
+            ),
+            collect(
+                client.list_all_resource_templates().await,
+                "resources/templates/list",
+                &mut errors,
+            ),
+        )
+    } else {
+        (vec![], vec![])
+    };
+
+    ServerInspection {
+        server_info: server_details
+            .as_ref()
+            .map(|details| details.server_info.clone()),
+        protocol_version: server_details
+            .as_ref()
+            .map(|details| details.protocol_version.clone()),
+        instructions: server_details.and_then(|details| details.instructions),
+        capabilities: CapabilitiesReport {
+            tools: client.server_has_tools(),
+            prompts: client.server_has_prompts(),
+            resources: client.server_has_resources(),
+            logging: client.server_supports_logging(),
+            experimental: client.server_has_experimental(),
+        },
+        tools,
+        prompts,
+        resources,
+        resource_templates,
+        errors,
+    }
+}
+
+/// Calls a tool, timing the call.
+pub async fn call_tool(
+    client: &dyn McpClient,
+    tool_name: &str,
+    arguments: Option<Map<String, Value>>,
+) -> ToolCallReport {
+    let started = Instant::now();
+    let result = client
+        .call_tool(CallToolRequestParams {
+            name: tool_name.to_string(),
+            arguments,
+        })
+        .await;
+    let duration_ms = duration_ms(started);
+    let (result, error) = match result {
+        Ok(result) => (Some(result), None),
+        Err(error) => (None, Some(error.to_string())),
+    };
+    ToolCallReport {
+        tool_name: tool_name.to_string(),
+        duration_ms,
+        result,
+        error,
+    }
+}
+
+/// Pings the server `count` times, waiting for `interval` between two pings.
+pub async fn ping_server(client: &dyn McpClient, count: usize, interval: Duration) -> PingReport {
+    let mut attempts = Vec::with_capacity(count);
+    for index in 0..count {
+        if index > 0 {
+            tokio::time::sleep(interval).await;
+        }
+        let started = Instant::now();
+        let error = client.ping().await.err().map(|error| error.to_string());
+        attempts.push(PingAttempt {
+            duration_ms: duration_ms(started),
+            error,
+        });
+    }
+    PingReport { attempts }
+}
+
+// Returns the items of a successful listing, or records the error of a failed one
+fn collect<T>(
+    result: SdkResult<Vec<T>>,
+    method: &str,
+    errors: &mut Vec<InspectionError>,
+) -> Vec<T> {
+    result.unwrap_or_else(|error| {
+        errors.push(InspectionError {
+            method: method.to_string(),
+            message: error.to_string(),
+        });
+        vec![]
+    })
+}
+
+fn duration_ms(started: Instant) -> f64 {
+    started.elapsed().as_secs_f64() * 1000.0
+}
@@ -0,0 +1,108 @@
+use rust_mcp_schema::{CallToolRequestParams, CallToolResult, Tool};
+use serde_json::{json, Map, Value};
+use thiserror::Error;
+
+/// Reserved argument holding the `_meta` of a tool call.
+///
+/// The `CallToolRequestParams` of the schema have no `_meta`, so the SDK carries it in this
+/// argument instead. It is removed by `tool_box!` before the arguments are deserialized.
+pub const META_ARGUMENT: &str = "_meta";
+
+/// Key of the `_meta` of a tool call requesting a dry run, and of the `_meta` of its result.
+pub const DRY_RUN_KEY: &str = "dryRun";
+
+/// Returned by the tools that do not implement a dry run, see `McpTool::dry_run()`.
+#[derive(Debug, Error)]
+#[error("The tool does not support dry runs")]
+pub struct DryRunNotSupported;
+
+/// Returns the schema of the `_meta` argument of the tools supporting dry runs,
+/// the one emitted by `#[mcp_tool(dry_run)]`.
+pub fn dry_run_meta_schema() -> Map<String, Value> {
+    let schema = json!({
+        "type": "object",
+        "description": "Metadata of the tool call",
+        "properties": {
+            DRY_RUN_KEY: {
+                "type": "boolean",
+                "description": "Validates the call and describes what it would do, without side effects"
+            }
+        }
+    });
+    match schema {
+        Value::Object(schema) => schema,
+        _ => Map::new(),
+    }
+}
+
+/// Advertises that `tool` supports dry runs, by declaring the `_meta` argument in its input schema.
+/// Tools declared with `#[mcp_tool(dry_run)]` advertise it already.
+pub fn advertise_dry_run(tool: &mut Tool) {
+    tool.input_schema
+        .properties
+        .get_or_insert_with(Default::default)
+        .insert(META_ARGUMENT.to_string(), dry_run_meta_schema());
+}
+
+/// Returns `true` if `tool` advertises that it supports dry runs.
+pub fn supports_dry_run(tool: &Tool) -> bool {
+    tool.input_schema
+        .properties
+        .as_ref()
+        .and_then(|properties| properties.get(META_ARGUMENT))
+        .and_then(|schema| schema.get("properties"))
+        .and_then(|properties| properties.get(DRY_RUN_KEY))
+        .is_some()
+}
+
+/// Returns `true` if the tool call requests a dry run.
+pub fn is_dry_run(params: &CallToolRequestParams) -> bool {
+    params
+        .arguments
+        .as_ref()
+        .and_then(|arguments| arguments.get(META_ARGUMENT))
+        .and_then(|meta| meta.get(DRY_RUN_KEY))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+/// Requests a dry run of the tool call, or a regular call if `dry_run` is `false`.
+pub fn set_dry_run(params: &mut CallToolRequestParams, dry_run: bool) {
+    let arguments = params.arguments.get_or_insert_with(Default::default);
+    if !dry_run {
+        if let Some(Value::Object(meta)) = arguments.get_mut(META_ARGUMENT) {
+            meta.remove(DRY_RUN_KEY);
+            if meta.is_empty() {
+                arguments.remove(META_ARGUMENT);
+            }
+        }
+        return;
+    }
+    let meta = arguments
+        .entry(META_ARGUMENT)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !meta.is_object() {
+        *meta = Value::Object(Map::new());
+    }
+    if let Value::Object(meta) = meta {
+        meta.insert(DRY_RUN_KEY.to_string(), Value::Bool(true));
+    }
+}
+
+/// Returns `true` if the result is the one of a dry run, see `ServerRuntime`.
+pub fn is_dry_run_result(result: &CallToolResult) -> bool {
+    result
+        .meta
+        .as_ref()
+        .and_then(|meta| meta.get(DRY_RUN_KEY))
+        .and_then(Value::as_bool)
+        .unwrap_or(false)
+}
+
+// Marks the result of a dry run in its `_meta`
+pub(crate) fn mark_dry_run_result(result: &mut CallToolResult) {
+    result
+        .meta
+        .get_or_insert_with(Map::new)
+        .insert(DRY_RUN_KEY.to_string(), Value::Bool(true));
+}
@@ -0,0 +1,444 @@
+use std::fmt;
+
+use rust_mcp_schema::Tool;
+use serde_json::{Map, Value};
+use thiserror::Error;
+
+/// An argument of a tool call that does not match the input schema of the tool
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArgumentError {
+    /// Path of the argument, e.g. `filter.tags[2]`
+    pub path: String,
+    /// What is wrong with the argument, e.g. `expected a number, got a string`
+    pub message: String,
+}
+
+impl fmt::Display for ArgumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}': {}", self.path, self.message)
+    }
+}
+
+/// Returned when the arguments of a tool call do not match the input schema of the tool,
+/// listing every invalid argument, so that the model can fix them all at once.
+#[derive(Debug, Error)]
+#[error("Invalid arguments of tool '{tool_name}': {}", join_errors(.errors))]
+pub struct InvalidToolArguments {
+    pub tool_name: String,
+    pub errors: Vec<ArgumentError>,
+}
+
+fn join_errors(errors: &[ArgumentError]) -> String {
+    errors
+        .iter()
+        .map(ArgumentError::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Validates the arguments of a tool call against the input schema of the tool.
+///
+/// The types, required properties, `nullable`, `anyOf`, `enum` and `additionalProperties` are checked,
+/// along with the numeric, length and item count constraints emitted by `#[json_schema(...)]`
+/// attributes. The type may be a list of types, e.g. `["string", "null"]`.
+/// `pattern` and `format` are not checked. Arguments not declared in the schema are accepted.
+///
+/// `tool_box!` validates the arguments before deserializing them, so that invalid arguments are
+/// reported to the model with their path instead of as a serde error.
+///
+/// # Errors
+/// Returns an `InvalidToolArguments` listing the arguments that do not match the schema.
+pub fn validate_tool_arguments(
+    tool: &Tool,
+    arguments: &Map<String, Value>,
+) -> Result<(), InvalidToolArguments> {
+    let mut errors = vec![];
+    for name in &tool.input_schema.required {
+        if !arguments.contains_key(name) {
+            errors.push(ArgumentError {
+                path: name.clone(),
+                message: "missing required argument".to_string(),
+            });
+        }
+    }
+    if let Some(properties) = tool.input_schema.properties.as_ref() {
+        for (name, value) in arguments {
+            if let Some(schema) = properties.get(name) {
+                validate_value(schema, value, name, &mut errors);
+            }
+        }
+    }
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(InvalidToolArguments {
+            tool_name: tool.name.clone(),
+            errors,
+        })
+    }
+}
+
+// Describes the kind of a value, for the error messages
+pub(crate) fn kind(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+// Returns `Some(true)` if the value is of the JSON schema `type`, `None` for unsupported types
+fn has_type(value: &Value, schema_type: &str) -> Option<bool> {
+    let matches = match schema_type {
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|v| v.fract() == 0.0)
+        }
+        "boolean" => value.is_boolean(),
+        "array" => value.is_array(),
+        "object" => value.is_object(),
+        "null" => value.is_null(),
+        _ => return None,
+    };
+    Some(matches)
+}
+
+fn child_path(path: &str, name: &str) -> String {
+    if path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", path, name)
+    }
+}
+
+fn validate_value(
+    schema: &Map<String, Value>,
+    value: &Value,
+    path: &str,
+    errors: &mut Vec<ArgumentError>,
+) {
+    let mut error = |message: String| {
+        errors.push(ArgumentError {
+            path: path.to_string(),
+            message,
+        })
+    };
+
+    if value.is_null() && schema.get("nullable").and_then(Value::as_bool) == Some(true) {
+        return;
+    }
+    let schema_types: Vec<&str> = match schema.get("type") {
+        Some(Value::String(schema_type)) => vec![schema_type],
+        Some(Value::Array(schema_types)) => schema_types.iter().filter_map(Value::as_str).collect(),
+        _ => vec![],
+    };
+    let matches: Vec<bool> = schema_types
+        .iter()
+        .filter_map(|schema_type| has_type(value, schema_type))
+        .collect();
+    if !matches.is_empty() && !matches.contains(&true) {
+        let expected: Vec<String> = schema_types
+            .iter()
+            .map(|schema_type| match *schema_type {
+                "null" => schema_type.to_string(),
+                _ if schema_type.starts_with(['a', 'e', 'i', 'o', 'u']) => {
+                    format!("an {}", schema_type)
+                }
+                _ => format!("a {}", schema_type),
+            })
+            .collect();
+        error(format!(
+            "expected {}, got {}",
+            expected.join(" or "),
+            kind(value)
+        ));
+        return;
+    }
+    if let Some(schemas) = schema.get("anyOf").and_then(Value::as_array) {
+        let matches_any = schemas.iter().filter_map(Value::as_object).any(|schema| {
+            let mut schema_errors = vec![];
+            validate_value(schema, value, path, &mut schema_errors);
+            schema_errors.is_empty()
+        });
+        if !matches_any {
+            error(format!("{} matches none of the allowed schemas", value));
+            return;
+        }
+    }
+    if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+        if !allowed.contains(value) {
+            let allowed: Vec<_> = allowed.iter().map(Value::to_string).collect();
+            error(format!(
+                "expected one of {}, got {}",
+                allowed.join(", "),
+                value
+            ));
+            return;
+        }
+    }
+
+    let constraint = |keyword: &str| schema.get(keyword).and_then(Value::as_f64);
+    match value {
+        Value::Number(number) => {
+            let number = number.as_f64().unwrap_or_default();
+            if let Some(minimum) = constraint("minimum").filter(|minimum| number < *minimum) {
+                error(format!("must be at least {}, got {}", minimum, number));
+            }
+            if let Some(maximum) = constraint("maximum").filter(|maximum| number > *maximum) {
+                error(format!("must be at most {}, got {}", maximum, number));
+            }
+            if let Some(minimum) = constraint("exclusiveMinimum").filter(|min| number <= *min) {
+                error(format!("must be greater than {}, got {}", minimum, number));
+            }
+            if let Some(maximum) = constraint("exclusiveMaximum").filter(|max| number >= *max) {
+                error(format!("must be less than {}, got {}", maximum, number));
+            }
+            if let Some(multiple) = constraint("multipleOf")
+                .filter(|multiple| *multiple != 0.0 && (number / multiple).fract() != 0.0)
+            {
+                error(format!(
+                    "must be a multiple of {}, got {}",
+                    multiple, number
+                ));
+            }
+        }
+        Value::String(string) => {
+            let length = string.chars().count() as f64;
+            if let Some(min_length) = constraint("minLength").filter(|min| length < *min) {
+                error(format!(
+                    "must be at least {} characters long, got {}",
+                    min_length, length
+                ));
+            }
+            if let Some(max_length) = constraint("maxLength").filter(|max| length > *max) {
+                error(format!(
+                    "must be at most {} characters long, got {}",
+                    max_length, length
+                ));
+            }
+        }
+        Value::Array(items) => {
+            let count = items.len() as f64;
+            if let Some(min_items) = constraint("minItems").filter(|min| count < *min) {
+                error(format!(
+                    "must have at least {} items, got {}",
+                    min_items, count
+                ));
+            }
+            if let Some(max_items) = constraint("maxItems").filter(|max| count > *max) {
+                error(format!(
+                    "must have at most {} items, got {}",
+                    max_items, count
+                ));
+            }
+            if let Some(item_schema) = schema.get("items").and_then(Value::as_object) {
+                for (index, item) in items.iter().enumerate() {
+                    validate_value(item_schema, item, &format!("{}[{}]", path, index), errors);
+                }
+            }
+        }
+        Value::Object(object) => validate_object(schema, object, path, errors),
+        _ => {}
+    }
+}
+
+fn validate_object(
+    schema: &Map<String, Value>,
+    object: &Map<String, Value>,
+    path: &str,
+    errors: &mut Vec<ArgumentError>,
+) {
+    let required = schema.get("required").and_then(Value::as_array);
+    for name in required.into_iter().flatten().filter_map(Value::as_str) {
+        if !object.contains_key(name) {
+            errors.push(ArgumentError {
+                path: child_path(path, name),
+                message: "missing required property".to_string(),
+            });
+        }
+    }
+
+    let properties = schema.get("properties").and_then(Value::as_object);
+    let additional_properties = schema.get("additionalProperties");
+    for (name, value) in object {
+        let property_schema = properties
+            .and_then(|properties| properties.get(name))
+            .and_then(Value::as_object);
+        match (property_schema, additional_properties) {
+            (Some(property_schema), _) => {
+                validate_value(property_schema, value, &child_path(path, name), errors)
+            }
+            (None, Some(Value::Object(value_schema))) => {
+                validate_value(value_schema, value, &child_path(path, name), errors)
+            }
+            (None, Some(Value::Bool(false))) => errors.push(ArgumentError {
+                path: child_path(path, name),
+                message: "unknown property".to_string(),
+            }),
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_mcp_schema::ToolInputSchema;
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    fn tool(required: &[&str], properties: Value) -> Tool {
+        let properties: HashMap<String, Map<String, Value>> =
+            serde_json::from_value(properties).unwrap();
+        Tool {
+            description: None,
+            input_schema: ToolInputSchema::new(
+                required.iter().map(|name| name.to_string()).collect(),
+                Some(properties),
+            ),
+            name: "test_tool".to_string(),
+        }
+    }
+
+    fn arguments(value: Value) -> Map<String, Value> {
+        match value {
+            Value::Object(arguments) => arguments,
+            _ => panic!("arguments must be an object"),
+        }
+    }
+
+    fn paths(result: Result<(), InvalidToolArguments>) -> Vec<String> {
+        result
+            .unwrap_err()
+            .errors
+            .into_iter()
+            .map(|error| error.path)
+            .collect()
+    }
+
+    #[test]
+    fn test_accepts_valid_arguments() {
+        let tool = tool(
+            &["name"],
+            json!({
+                "name": {"type": "string", "minLength": 1},
+                "count": {"type": "number", "minimum": 1, "nullable": true},
+                "tags": {"type": "array", "items": {"type": "string"}}
+            }),
+        );
+        let args = arguments(json!({"name": "a", "count": null, "tags": ["x"], "extra": 1}));
+        assert!(validate_tool_arguments(&tool, &args).is_ok());
+    }
+
+    #[test]
+    fn test_reports_missing_and_mistyped_arguments() {
+        let tool = tool(
+            &["name", "count"],
+            json!({
+                "name": {"type": "string"},
+                "count": {"type": "number"}
+            }),
+        );
+        let error = validate_tool_arguments(&tool, &arguments(json!({"count": "3"}))).unwrap_err();
+        assert_eq!(error.errors.len(), 2);
+        assert_eq!(
+            error.to_string(),
+            "Invalid arguments of tool 'test_tool': 'name': missing required argument; \
+             'count': expected a number, got a string"
+        );
+    }
+
+    #[test]
+    fn test_reports_nested_paths() {
+        let tool = tool(
+            &["filter"],
+            json!({
+                "filter": {
+                    "type": "object",
+                    "properties": {
+                        "tags": {"type": "array", "items": {"type": "string"}, "maxItems": 3},
+                        "limit": {"type": "number", "maximum": 10}
+                    },
+                    "required": ["limit"]
+                }
+            }),
+        );
+        let args = arguments(json!({"filter": {"tags": ["a", 1, "c", "d"]}}));
+        let mut paths = paths(validate_tool_arguments(&tool, &args));
+        paths.sort();
+        assert_eq!(paths, ["filter.limit", "filter.tags", "filter.tags[1]"]);
+    }
+
+    #[test]
+    fn test_checks_constraints() {
+        let tool = tool(
+            &[],
+            json!({
+                "count": {"type": "integer", "exclusiveMinimum": 0, "multipleOf": 2},
+                "name": {"type": "string", "maxLength": 3},
+                "mode": {"type": "string", "enum": ["fast", "slow"]},
+                "labels": {"type": "object", "additionalProperties": {"type": "string"}}
+            }),
+        );
+        let args = arguments(json!({
+            "count": 3,
+            "name": "abcd",
+            "mode": "medium",
+            "labels": {"a": "x", "b": 2}
+        }));
+        let mut paths = paths(validate_tool_arguments(&tool, &args));
+        paths.sort();
+        assert_eq!(paths, ["count", "labels.b", "mode", "name"]);
+
+        let args = arguments(json!({"count": 1.5}));
+        let error = validate_tool_arguments(&tool, &args).unwrap_err();
+        assert_eq!(error.errors[0].message, "expected an integer, got a number");
+    }
+
+    #[test]
+    fn test_accepts_type_lists_and_any_of() {
+        let tool = tool(
+            &[],
+            json!({
+                "limit": {"type": ["integer", "null"], "minimum": 1},
+                "parent": {"anyOf": [{"type": "object", "required": ["name"]}, {"type": "null"}]}
+            }),
+        );
+        let args = arguments(json!({"limit": null, "parent": null}));
+        assert!(validate_tool_arguments(&tool, &args).is_ok());
+        let args = arguments(json!({"limit": 5, "parent": {"name": "a"}}));
+        assert!(validate_tool_arguments(&tool, &args).is_ok());
+
+        let args = arguments(json!({"limit": "5", "parent": {}}));
+        let error = validate_tool_arguments(&tool, &args).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid arguments of tool 'test_tool': 'limit': expected an integer or null, got a \
+             string; 'parent': {} matches none of the allowed schemas"
+        );
+    }
+
+    #[test]
+    fn test_rejects_unknown_properties_when_not_allowed() {
+        let tool = tool(
+            &[],
+            json!({
+                "options": {
+                    "type": "object",
+                    "properties": {"verbose": {"type": "boolean"}},
+                    "additionalProperties": false
+                }
+            }),
+        );
+        let args = arguments(json!({"options": {"verbose": true, "debug": true}}));
+        assert_eq!(
+            paths(validate_tool_arguments(&tool, &args)),
+            ["options.debug"]
+        );
+    }
+}
@@ -0,0 +1,382 @@
+use std::fmt;
+
+use rust_mcp_schema::Tool;
+use serde_json::Value;
+use thiserror::Error;
+
+use crate::mcp_traits::tool_input_schema_ext::ToolInputSchemaExt;
+use crate::tool_input_validation::kind;
+
+/// The types a JSON schema `type` can name
+const SCHEMA_TYPES: [&str; 7] = [
+    "array", "boolean", "integer", "null", "number", "object", "string",
+];
+
+/// A keyword of a schema that is not valid JSON Schema
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaError {
+    /// JSON pointer to the keyword, e.g. `#/properties/count/minimum`
+    pub path: String,
+    /// What is wrong with the keyword, e.g. `expected a number, got a string`
+    pub message: String,
+}
+
+impl fmt::Display for SchemaError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "'{}': {}", self.path, self.message)
+    }
+}
+
+/// Returned when the input schema of a tool is not valid JSON Schema, listing every invalid keyword.
+#[derive(Debug, Error)]
+#[error("Invalid input schema of tool '{tool_name}': {}", join_errors(.errors))]
+pub struct InvalidToolSchema {
+    pub tool_name: String,
+    pub errors: Vec<SchemaError>,
+}
+
+/// Returned when the input schemas of some tools are not valid JSON Schema, with one line per tool.
+#[derive(Debug, Error)]
+#[error("{}", join_tools(.tools))]
+pub struct InvalidToolSchemas {
+    pub tools: Vec<InvalidToolSchema>,
+}
+
+fn join_errors(errors: &[SchemaError]) -> String {
+    errors
+        .iter()
+        .map(SchemaError::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn join_tools(tools: &[InvalidToolSchema]) -> String {
+    tools
+        .iter()
+        .map(InvalidToolSchema::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Validates the input schema of a tool against JSON Schema draft 2020-12, see `validate_json_schema()`.
+///
+/// # Errors
+/// Returns an `InvalidToolSchema` listing the invalid keywords of the schema.
+pub fn validate_tool_schema(tool: &Tool) -> Result<(), InvalidToolSchema> {
+    tool.input_schema
+        .validate()
+        .map_err(|errors| InvalidToolSchema {
+            tool_name: tool.name.clone(),
+            errors,
+        })
+}
+
+/// Validates the input schemas of tools, e.g. the tools of a `tool_box!` when a server starts, so
+/// that a server fails fast with a report of every invalid schema instead of the errors of clients.
+///
+/// # Example
+/// ```ignore
+/// validate_tool_schemas(&FileSystemTools::tools())?;
+/// ```
+///
+/// # Errors
+/// Returns an `InvalidToolSchemas` listing the tools whose input schema is invalid.
+pub fn validate_tool_schemas<'a>(
+    tools: impl IntoIterator<Item = &'a Tool>,
+) -> Result<(), InvalidToolSchemas> {
+    let tools: Vec<_> = tools
+        .into_iter()
+        .filter_map(|tool| validate_tool_schema(tool).err())
+        .collect();
+    if tools.is_empty() {
+        Ok(())
+    } else {
+        Err(InvalidToolSchemas { tools })
+    }
+}
+
+/// Validates a schema against the JSON Schema draft 2020-12 meta-schema.
+///
+/// The values of the keywords of the draft are checked, recursively in subschemas, and local
+/// references such as `#/properties/root` must resolve within the schema. Unknown keywords, e.g.
+/// `nullable`, are allowed by JSON Schema and accepted. Regular expressions and formats are not checked.
+///
+/// # Errors
+/// Returns the invalid keywords of the schema.
+pub fn validate_json_schema(schema: &Value) -> Result<(), Vec<SchemaError>> {
+    let mut errors = vec![];
+    validate_schema(schema, schema, "#", &mut errors);
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
+fn child_path(path: &str, name: &str) -> String {
+    format!("{}/{}", path, name.replace('~', "~0").replace('/', "~1"))
+}
+
+fn validate_schema(root: &Value, schema: &Value, path: &str, errors: &mut Vec<SchemaError>) {
+    let schema = match schema {
+        Value::Object(schema) => schema,
+        Value::Bool(_) => return,
+        value => {
+            errors.push(SchemaError {
+                path: path.to_string(),
+                message: format!("expected a schema, got {}", kind(value)),
+            });
+            return;
+        }
+    };
+    for (keyword, value) in schema {
+        let path = child_path(path, keyword);
+        if let Err(message) = validate_keyword(root, keyword, value, &path, errors) {
+            errors.push(SchemaError { path, message });
+        }
+    }
+}
+
+// Validates the value of a keyword, returning the error of the keyword itself. The errors of its
+// subschemas are added to `errors`.
+fn validate_keyword(
+    root: &Value,
+    keyword: &str,
+    value: &Value,
+    path: &str,
+    errors: &mut Vec<SchemaError>,
+) -> Result<(), String> {
+    let expected = |expected: &str| Err(format!("expected {}, got {}", expected, kind(value)));
+    match keyword {
+        "type" => match value {
+            Value::String(schema_type) => validate_type(schema_type),
+            Value::Array(types) => {
+                for schema_type in types {
+                    match schema_type {
+                        Value::String(schema_type) => validate_type(schema_type)?,
+                        _ => return expected("an array of strings"),
+                    }
+                }
+                unique_items(types)
+            }
+            _ => expected("a string or an array of strings"),
+        },
+        "properties" | "patternProperties" | "$defs" | "dependentSchemas" => match value {
+            Value::Object(schemas) => {
+                for (name, schema) in schemas {
+                    validate_schema(root, schema, &child_path(path, name), errors);
+                }
+                Ok(())
+            }
+            _ => expected("an object"),
+        },
+        "items" if value.is_array() => {
+            Err("expected a schema, arrays of schemas are declared with `prefixItems`".to_string())
+        }
+        "items"
+        | "additionalProperties"
+        | "not"
+        | "if"
+        | "then"
+        | "else"
+        | "contains"
+        | "propertyNames"
+        | "unevaluatedItems"
+        | "unevaluatedProperties" => {
+            validate_schema(root, value, path, errors);
+            Ok(())
+        }
+        "allOf" | "anyOf" | "oneOf" | "prefixItems" => match value {
+            Value::Array(schemas) if !schemas.is_empty() => {
+                for (index, schema) in schemas.iter().enumerate() {
+                    validate_schema(root, schema, &child_path(path, &index.to_string()), errors);
+                }
+                Ok(())
+            }
+            _ => expected("a non-empty array of schemas"),
+        },
+        "required" => match value {
+            Value::Array(names) if names.iter().all(Value::is_string) => unique_items(names),
+            _ => expected("an array of strings"),
+        },
+        "dependentRequired" => match value {
+            Value::Object(dependencies) => {
+                for (name, names) in dependencies {
+                    if let Err(message) = validate_keyword(root, "required", names, path, errors) {
+                        errors.push(SchemaError {
+                            path: child_path(path, name),
+                            message,
+                        });
+                    }
+                }
+                Ok(())
+            }
+            _ => expected("an object"),
+        },
+        "enum" if !value.is_array() => expected("an array"),
+        "minimum" | "maximum" | "exclusiveMinimum" | "exclusiveMaximum" if !value.is_number() => {
+            expected("a number")
+        }
+        "multipleOf" if !value.as_f64().is_some_and(|multiple| multiple > 0.0) => {
+            expected("a number greater than 0")
+        }
+        "minLength" | "maxLength" | "minItems" | "maxItems" | "minProperties" | "maxProperties"
+        | "minContains" | "maxContains"
+            if value.as_u64().is_none() =>
+        {
+            expected("a non-negative integer")
+        }
+        "uniqueItems" | "readOnly" | "writeOnly" | "deprecated" if !value.is_boolean() => {
+            expected("a boolean")
+        }
+        "pattern" | "format" | "title" | "description" | "$id" | "$schema" | "$anchor"
+        | "$comment" | "contentEncoding" | "contentMediaType"
+            if !value.is_string() =>
+        {
+            expected("a string")
+        }
+        "$ref" => match value {
+            Value::String(reference) => match reference.strip_prefix('#') {
+                Some(pointer) if root.pointer(pointer).is_none() => {
+                    Err(format!("unresolved reference '{}'", reference))
+                }
+                _ => Ok(()),
+            },
+            _ => expected("a string"),
+        },
+        _ => Ok(()),
+    }
+}
+
+fn validate_type(schema_type: &str) -> Result<(), String> {
+    if SCHEMA_TYPES.contains(&schema_type) {
+        Ok(())
+    } else {
+        Err(format!(
+            "unknown type '{}', expected one of {}",
+            schema_type,
+            SCHEMA_TYPES.join(", ")
+        ))
+    }
+}
+
+fn unique_items(items: &[Value]) -> Result<(), String> {
+    match items
+        .iter()
+        .enumerate()
+        .find(|(index, item)| items[..*index].contains(item))
+    {
+        Some((_, item)) => Err(format!("duplicate item {}", item)),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_mcp_schema::ToolInputSchema;
+    use serde_json::{json, Map};
+    use std::collections::HashMap;
+
+    fn tool(name: &str, properties: Value) -> Tool {
+        let properties: HashMap<String, Map<String, Value>> =
+            serde_json::from_value(properties).unwrap();
+        Tool {
+            description: None,
+            input_schema: ToolInputSchema::new(vec!["path".to_string()], Some(properties)),
+            name: name.to_string(),
+        }
+    }
+
+    fn paths(schema: Value) -> Vec<String> {
+        let mut paths: Vec<_> = validate_json_schema(&schema)
+            .unwrap_err()
+            .into_iter()
+            .map(|error| error.path)
+            .collect();
+        paths.sort();
+        paths
+    }
+
+    #[test]
+    fn test_accepts_valid_schemas() {
+        let valid = tool(
+            "valid",
+            json!({
+                "path": {"type": "string", "minLength": 1, "description": "The path"},
+                "limit": {"type": ["integer", "null"], "minimum": 1, "nullable": true},
+                "root": {
+                    "type": "object",
+                    "properties": {"children": {"type": "array", "items": {"$ref": "#/properties/root"}}}
+                },
+                "mode": {"anyOf": [{"enum": ["fast", "slow"]}, {"type": "null"}]}
+            }),
+        );
+        assert!(validate_tool_schemas([&valid]).is_ok());
+        assert!(validate_json_schema(&json!(true)).is_ok());
+    }
+
+    #[test]
+    fn test_reports_invalid_keywords() {
+        let schema = json!({
+            "type": "object",
+            "required": ["a", "a"],
+            "properties": {
+                "a/b": {"type": "unknown"},
+                "count": {"type": "integer", "exclusiveMinimum": true, "minLength": -1},
+                "tags": {"type": "array", "items": [{"type": "string"}]},
+                "parent": {"$ref": "#/$defs/Node"},
+                "mode": {"anyOf": [], "not": 1}
+            }
+        });
+        assert_eq!(
+            paths(schema),
+            [
+                "#/properties/a~1b/type",
+                "#/properties/count/exclusiveMinimum",
+                "#/properties/count/minLength",
+                "#/properties/mode/anyOf",
+                "#/properties/mode/not",
+                "#/properties/parent/$ref",
+                "#/properties/tags/items",
+                "#/required",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_reports_every_invalid_tool() {
+        let tools = [
+            tool("valid", json!({"path": {"type": "string"}})),
+            tool("first", json!({"path": {"type": "text"}})),
+            tool(
+                "second",
+                json!({"path": {"type": "string", "maxLength": "10"}}),
+            ),
+        ];
+        let error = validate_tool_schemas(&tools).unwrap_err();
+        assert_eq!(error.tools.len(), 2);
+        assert_eq!(
+            error.to_string(),
+            "Invalid input schema of tool 'first': '#/properties/path/type': unknown type 'text', \
+             expected one of array, boolean, integer, null, number, object, string\n\
+             Invalid input schema of tool 'second': '#/properties/path/maxLength': expected a \
+             non-negative integer, got a string"
+        );
+    }
+
+    #[test]
+    fn test_round_trips_input_schemas() {
+        let schema = tool("valid", json!({"path": {"type": "string"}})).input_schema;
+        let value = schema.to_json_value();
+        assert_eq!(
+            value,
+            json!({"type": "object", "properties": {"path": {"type": "string"}}, "required": ["path"]})
+        );
+        let parsed = ToolInputSchema::from_json_value(value).unwrap();
+        assert_eq!(parsed.required, schema.required);
+        assert_eq!(parsed.properties, schema.properties);
+
+        assert!(ToolInputSchema::from_json_value(json!({"type": "array"})).is_err());
+    }
+}
@@ -0,0 +1,457 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+
+/// The values of the variables of a uri template, extracted from a uri by `UriTemplate::matches()`,
+/// by variable name.
+pub type UriParams = HashMap<String, String>;
+
+/// A uri template, such as the uri templates of resource templates, matched against uris to extract
+/// the values of its variables.
+///
+/// Templates follow the syntax of RFC 6570, with the following expressions:
+/// - `{var}` matches a non-empty value without `/`, `?` or `#`, e.g. a path segment,
+/// - `{+var}` matches a non-empty value without `?` or `#`, which may contain `/`, e.g. a path,
+/// - `{#var}` matches a fragment, `{.var}` a label such as an extension, `{/var}` a path segment,
+/// - `{?var1,var2}` and `{&var1,var2}` match optional query parameters, and `{;var}` path parameters.
+///
+/// Expressions with several variables, such as `{x,y}` or `{/x,y}`, assign the separated values to
+/// the variables in order. Values are percent-decoded, except the ones of `{+var}` and `{#var}`
+/// which may contain reserved characters. Prefix and explode modifiers are not supported.
+///
+/// # Example
+/// ```ignore
+/// let template = UriTemplate::new("db://{table}/{id}{?fields}");
+///
+/// let params = template.matches("db://users/42?fields=name").unwrap();
+/// assert_eq!(params["table"], "users");
+/// assert_eq!(params["id"], "42");
+/// assert_eq!(params["fields"], "name");
+///
+/// assert!(template.matches("db://users").is_none());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UriTemplate {
+    template: String,
+    parts: Vec<TemplatePart>,
+}
+
+// A part of a uri template: literal text, or an expression such as `{var}` or `{?var1,var2}`
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TemplatePart {
+    Literal(String),
+    Expression {
+        operator: Option<char>,
+        variables: Vec<String>,
+    },
+}
+
+impl UriTemplate {
+    /// Parses a uri template. Unclosed braces are treated as literal text.
+    pub fn new<T: Into<String>>(template: T) -> Self {
+        let template = template.into();
+        let parts = parse_template(&template);
+        Self { template, parts }
+    }
+
+    /// Returns the template as a string
+    pub fn as_str(&self) -> &str {
+        &self.template
+    }
+
+    /// Returns the names of the variables of the template, in order.
+    pub fn variables(&self) -> Vec<&str> {
+        self.parts
+            .iter()
+            .filter_map(|part| match part {
+                TemplatePart::Expression { variables, .. } => Some(variables),
+                TemplatePart::Literal(_) => None,
+            })
+            .flatten()
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// Matches a uri against the template, returning the values of its variables,
+    /// or `None` if the uri does not match the template.
+    ///
+    /// The variables of optional expressions, such as query parameters, that are absent from the uri
+    /// are absent from the returned values.
+    pub fn matches(&self, uri: &str) -> Option<UriParams> {
+        let mut params = UriParams::new();
+        match_parts(&self.parts, uri, &mut params, &mut HashSet::new()).then_some(params)
+    }
+}
+
+impl fmt::Display for UriTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.template)
+    }
+}
+
+impl From<&str> for UriTemplate {
+    fn from(template: &str) -> Self {
+        Self::new(template)
+    }
+}
+
+impl From<String> for UriTemplate {
+    fn from(template: String) -> Self {
+        Self::new(template)
+    }
+}
+
+// Splits a uri template into literal text and expressions
+fn parse_template(template: &str) -> Vec<TemplatePart> {
+    let mut parts = vec![];
+    let mut literal = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        let Some(length) = rest[start..].find('}') else {
+            break;
+        };
+        literal.push_str(&rest[..start]);
+        if !literal.is_empty() {
+            parts.push(TemplatePart::Literal(std::mem::take(&mut literal)));
+        }
+
+        let mut expression = &rest[start + 1..start + length];
+        let operator = expression
+            .chars()
+            .next()
+            .filter(|operator| "+#./;?&".contains(*operator));
+        if let Some(operator) = operator {
+            expression = &expression[operator.len_utf8()..];
+        }
+        let variables = expression
+            .split(',')
+            .map(|variable| variable.trim().to_string())
+            .filter(|variable| !variable.is_empty())
+            .collect();
+        parts.push(TemplatePart::Expression {
+            operator,
+            variables,
+        });
+        rest = &rest[start + length + 1..];
+    }
+    literal.push_str(rest);
+    if !literal.is_empty() {
+        parts.push(TemplatePart::Literal(literal));
+    }
+    parts
+}
+
+// Matches the rest of a uri against the rest of the parts of a template, backtracking over the
+// possible ends of each expression, longest first.
+//
+// An expression can only end where the next literal part starts, before the first character its
+// values can not contain, and the rests that failed to match are recorded in `failed`, by number
+// of parts and length of the uri left, so that a uri that does not match is rejected in polynomial time.
+fn match_parts(
+    parts: &[TemplatePart],
+    uri: &str,
+    params: &mut UriParams,
+    failed: &mut HashSet<(usize, usize)>,
+) -> bool {
+    let Some((part, next_parts)) = parts.split_first() else {
+        return uri.is_empty();
+    };
+    if failed.contains(&(parts.len(), uri.len())) {
+        return false;
+    }
+    let matched = match part {
+        TemplatePart::Literal(literal) => uri
+            .strip_prefix(literal.as_str())
+            .is_some_and(|rest| match_parts(next_parts, rest, params, failed)),
+        TemplatePart::Expression {
+            operator,
+            variables,
+        } => {
+            let limit = expansion_limit(*operator, uri);
+            let ends = uri[..limit]
+                .char_indices()
+                .map(|(index, _)| index)
+                .chain([limit])
+                .rev()
+                .filter(|end| match next_parts.first() {
+                    Some(TemplatePart::Literal(literal)) => {
+                        uri[*end..].starts_with(literal.as_str())
+                    }
+                    Some(TemplatePart::Expression { .. }) => true,
+                    None => *end == uri.len(),
+                });
+            let mut matched = false;
+            for end in ends {
+                // the rest is matched first, as its failures are recorded
+                let mut next_params = UriParams::new();
+                if !match_parts(next_parts, &uri[end..], &mut next_params, failed) {
+                    continue;
+                }
+                if let Some(values) = match_expression(*operator, variables, &uri[..end]) {
+                    params.extend(values);
+                    params.extend(next_params);
+                    matched = true;
+                    break;
+                }
+            }
+            matched
+        }
+    };
+    if !matched {
+        failed.insert((parts.len(), uri.len()));
+    }
+    matched
+}
+
+// Returns the end of the longest part of a uri an expression can expand to: the first character
+// its values can not contain, after the prefix of its operator, or the end of the uri
+fn expansion_limit(operator: Option<char>, uri: &str) -> usize {
+    let excluded: &[char] = match operator {
+        Some('#') => return uri.len(),
+        Some('.') | None => &['/', '?', '#'],
+        _ => &['?', '#'],
+    };
+    let start = match operator {
+        Some(operator) if operator != '+' && uri.starts_with(operator) => operator.len_utf8(),
+        _ => 0,
+    };
+    uri[start..]
+        .find(excluded)
+        .map_or(uri.len(), |index| start + index)
+}
+
+// Matches the part of a uri expanded from an expression, returning the values of its variables
+fn match_expression(
+    operator: Option<char>,
+    variables: &[String],
+    text: &str,
+) -> Option<Vec<(String, String)>> {
+    match operator {
+        Some(operator @ ('?' | '&' | ';')) => match_named_expression(operator, variables, text),
+        _ => {
+            let (prefix, separator, excluded): (&str, char, &str) = match operator {
+                Some('+') => ("", ',', "?#"),
+                Some('#') => ("#", ',', ""),
+                Some('.') => (".", '.', "/?#"),
+                Some('/') => ("/", '/', "/?#"),
+                _ => ("", ',', "/?#"),
+            };
+            let text = text.strip_prefix(prefix)?;
+            let values: Vec<&str> = text.split(separator).collect();
+            if values.len() > variables.len()
+                || values
+                    .iter()
+                    .any(|value| value.is_empty() || value.contains(|c| excluded.contains(c)))
+            {
+                return None;
+            }
+            let decode = !matches!(operator, Some('+' | '#'));
+            Some(
+                variables
+                    .iter()
+                    .zip(values)
+                    .map(|(variable, value)| {
+                        let value = match decode {
+                            true => percent_decode(value),
+                            false => value.to_string(),
+                        };
+                        (variable.clone(), value)
+                    })
+                    .collect(),
+            )
+        }
+    }
+}
+
+// Matches optional `name=value` pairs, such as query parameters, restricted to the given variables
+fn match_named_expression(
+    operator: char,
+    variables: &[String],
+    text: &str,
+) -> Option<Vec<(String, String)>> {
+    if text.is_empty() {
+        return Some(vec![]);
+    }
+    let separator = match operator {
+        '?' => '&',
+        separator => separator,
+    };
+    let mut values = vec![];
+    for pair in text.strip_prefix(operator)?.split(separator) {
+        if pair.contains(['#', '?']) {
+            return None;
+        }
+        let (name, value) = pair.split_once('=').unwrap_or((pair, ""));
+        if !variables.iter().any(|variable| variable == name) {
+            return None;
+        }
+        values.push((name.to_string(), percent_decode(value)));
+    }
+    Some(values)
+}
+
+// Decodes the percent-encoded bytes of a value, which is returned as is if they are not valid UTF-8
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        let byte = bytes[index];
+        let hex = bytes
+            .get(index + 1..index + 3)
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match (byte, hex) {
+            (b'%', Some(hex)) => {
+                decoded.push(hex);
+                index += 3;
+            }
+            _ => {
+                decoded.push(byte);
+                index += 1;
+            }
+        }
+    }
+    String::from_utf8(decoded).unwrap_or_else(|_| value.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(pairs: &[(&str, &str)]) -> UriParams {
+        pairs
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_simple_variables() {
+        let template = UriTemplate::new("db://{table}/{id}");
+        assert_eq!(template.variables(), vec!["table", "id"]);
+        assert_eq!(
+            template.matches("db://users/42"),
+            Some(params(&[("table", "users"), ("id", "42")]))
+        );
+        assert_eq!(template.matches("db://users/42/posts"), None);
+        assert_eq!(template.matches("db://users/"), None);
+        assert_eq!(template.matches("file://users/42"), None);
+    }
+
+    #[test]
+    fn test_percent_decoding() {
+        let template = UriTemplate::new("file:///{name}");
+        assert_eq!(
+            template.matches("file:///hello%20world.txt"),
+            Some(params(&[("name", "hello world.txt")]))
+        );
+        assert_eq!(
+            template.matches("file:///100%"),
+            Some(params(&[("name", "100%")]))
+        );
+    }
+
+    #[test]
+    fn test_reserved_expansion() {
+        let template = UriTemplate::new("file:///{+path}");
+        assert_eq!(
+            template.matches("file:///src/lib.rs"),
+            Some(params(&[("path", "src/lib.rs")]))
+        );
+        assert_eq!(
+            UriTemplate::new("file:///{path}").matches("file:///src/lib.rs"),
+            None
+        );
+
+        let template = UriTemplate::new("file:///{+path}.{ext}");
+        assert_eq!(
+            template.matches("file:///archive.tar.gz"),
+            Some(params(&[("path", "archive.tar"), ("ext", "gz")]))
+        );
+    }
+
+    #[test]
+    fn test_path_and_label_expressions() {
+        let template = UriTemplate::new("repo://{owner}{/name,branch}{.format}");
+        assert_eq!(
+            template.matches("repo://acme/widgets/main.json"),
+            Some(params(&[
+                ("owner", "acme"),
+                ("name", "widgets"),
+                ("branch", "main"),
+                ("format", "json")
+            ]))
+        );
+        assert_eq!(
+            template.matches("repo://acme/widgets.json"),
+            Some(params(&[
+                ("owner", "acme"),
+                ("name", "widgets"),
+                ("format", "json")
+            ]))
+        );
+        assert_eq!(template.matches("repo://acme"), None);
+    }
+
+    #[test]
+    fn test_query_expression() {
+        let template = UriTemplate::new("search://{index}{?q,limit}");
+        assert_eq!(
+            template.matches("search://docs?q=mcp%20sdk&limit=10"),
+            Some(params(&[
+                ("index", "docs"),
+                ("q", "mcp sdk"),
+                ("limit", "10")
+            ]))
+        );
+        assert_eq!(
+            template.matches("search://docs"),
+            Some(params(&[("index", "docs")]))
+        );
+        assert_eq!(template.matches("search://docs?page=2"), None);
+    }
+
+    #[test]
+    fn test_fragment_and_multiple_values() {
+        let template = UriTemplate::new("doc://{x,y}{#section}");
+        assert_eq!(
+            template.matches("doc://1,2#intro/setup"),
+            Some(params(&[
+                ("x", "1"),
+                ("y", "2"),
+                ("section", "intro/setup")
+            ]))
+        );
+        assert_eq!(template.matches("doc://1,2,3#intro"), None);
+    }
+
+    #[test]
+    fn test_literal_templates() {
+        let template = UriTemplate::new("file:///readme");
+        assert!(template.variables().is_empty());
+        assert_eq!(template.matches("file:///readme"), Some(UriParams::new()));
+        assert_eq!(template.matches("file:///readme.md"), None);
+        assert_eq!(
+            UriTemplate::new("file:///{unclosed").matches("file:///{unclosed"),
+            Some(UriParams::new())
+        );
+    }
+
+    #[test]
+    fn test_long_uris_that_do_not_match() {
+        let long_id = "a".repeat(100_000);
+        let template = UriTemplate::new("db://{table}/{id}.json");
+        assert_eq!(template.matches(&format!("db://t/{}", long_id)), None);
+        assert_eq!(
+            template.matches(&format!("db://t/{}.json", long_id)),
+            Some(params(&[("table", "t"), ("id", &long_id)]))
+        );
+
+        let template = UriTemplate::new("db://{a}{b}{c}{d}.json");
+        assert_eq!(template.matches(&format!("db://{}", &long_id[..500])), None);
+        assert_eq!(
+            template.matches("db://1234.json"),
+            Some(params(&[("a", "1"), ("b", "2"), ("c", "3"), ("d", "4")]))
+        );
+    }
+}
@@ -0,0 +1,93 @@
+use rust_mcp_schema::JSONRPC_VERSION;
+use serde_json::{Map, Value};
+
+/// Controls how strictly the JSON-RPC envelope of received messages is validated.
+///
+/// Real-world peers are frequently sloppy with the envelope of their messages. Each tolerated
+/// deviation is converted into its canonical form before the message is deserialized, and
+/// reported as a warning on stderr.
+///
+/// The default is strict: messages are deserialized as received.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EnvelopeValidation {
+    /// Accept messages with a missing or different `jsonrpc` version, treated as `"2.0"`
+    pub allow_missing_version: bool,
+    /// Accept responses whose id is the string form of an integer id, such as `"1"` for `1`,
+    /// so they are matched with the request they answer. The id is only converted if a request
+    /// with the integer id is pending, and none with the string id, e.g. when the ids of the
+    /// requests are prefixed with an empty prefix.
+    pub allow_string_ids: bool,
+    /// Accept `"params": null`, treated as absent params
+    pub allow_null_params: bool,
+}
+
+impl EnvelopeValidation {
+    /// Messages are deserialized as received
+    pub fn strict() -> Self {
+        Self::default()
+    }
+
+    /// All supported deviations from the JSON-RPC 2.0 envelope are tolerated
+    pub fn lenient() -> Self {
+        Self {
+            allow_missing_version: true,
+            allow_string_ids: true,
+            allow_null_params: true,
+        }
+    }
+
+    pub(crate) fn is_strict(&self) -> bool {
+        *self == Self::strict()
+    }
+
+    /// Converts the tolerated deviations of `message` into their canonical form,
+    /// returning a description of each conversion made.
+    ///
+    /// The string id of a response is converted if `integer_id_pending` is set, see `string_response_id()`.
+    pub(crate) fn normalize(&self, message: &mut Value, integer_id_pending: bool) -> Vec<String> {
+        let mut diagnostics = vec![];
+        let Value::Object(message) = message else {
+            return diagnostics;
+        };
+
+        if self.allow_missing_version
+            && message.get("jsonrpc").and_then(Value::as_str) != Some(JSONRPC_VERSION)
+        {
+            diagnostics.push(match message.get("jsonrpc") {
+                Some(version) => format!("unexpected jsonrpc version {}", version),
+                None => "missing jsonrpc version".to_string(),
+            });
+            message.insert(
+                "jsonrpc".to_string(),
+                Value::String(JSONRPC_VERSION.to_string()),
+            );
+        }
+
+        if self.allow_string_ids && integer_id_pending {
+            if let Some((_, id)) = string_response_id(message) {
+                diagnostics.push(format!("string id \"{}\" for an integer id", id));
+                message.insert("id".to_string(), Value::from(id));
+            }
+        }
+
+        if self.allow_null_params && message.get("params") == Some(&Value::Null) {
+            diagnostics.push("null params".to_string());
+            message.remove("params");
+        }
+
+        diagnostics
+    }
+}
+
+/// Returns the id of a response that is the string form of an integer id, along with that integer.
+///
+/// Only ids of responses are converted, the id of a request must be echoed back as received.
+pub(crate) fn string_response_id(message: &Map<String, Value>) -> Option<(&str, i64)> {
+    let is_response = !message.contains_key("method")
+        && (message.contains_key("result") || message.contains_key("error"));
+    if !is_response {
+        return None;
+    }
+    let id = message.get("id")?.as_str()?;
+    Some((id, id.parse::<i64>().ok()?))
+}
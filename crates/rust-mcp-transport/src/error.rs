@@ -1,11 +1,11 @@
-use rust_mcp_schema::{schema_utils::SdkError, RpcError};
+use rust_mcp_schema::{schema_utils::SdkError, RequestId, RpcError};
 use thiserror::Error;
 
 use core::fmt;
 use std::any::Any;
-use tokio::sync::broadcast;
+use tokio::sync::mpsc;
 
-/// A wrapper around a broadcast send error. This structure allows for generic error handling
+/// A wrapper around a channel send error. This structure allows for generic error handling
 /// by boxing the underlying error into a type-erased form.
 #[derive(Debug)]
 pub struct GenericSendError {
@@ -14,19 +14,19 @@ pub struct GenericSendError {
 
 #[allow(unused)]
 impl GenericSendError {
-    pub fn new<T: Send + 'static>(error: broadcast::error::SendError<T>) -> Self {
+    pub fn new<T: Send + 'static>(error: mpsc::error::SendError<T>) -> Self {
         Self {
             inner: Box::new(error),
         }
     }
 
-    /// Attempts to downcast the wrapped error to a specific `broadcast::error::SendError` type.
+    /// Attempts to downcast the wrapped error to a specific `mpsc::error::SendError` type.
     ///
     /// # Returns
     /// `Some(T)` if the error can be downcasted, `None` otherwise.
-    fn downcast<T: Send + 'static>(self) -> Option<broadcast::error::SendError<T>> {
+    fn downcast<T: Send + 'static>(self) -> Option<mpsc::error::SendError<T>> {
         self.inner
-            .downcast::<broadcast::error::SendError<T>>()
+            .downcast::<mpsc::error::SendError<T>>()
             .ok()
             .map(|boxed| *boxed)
     }
@@ -34,7 +34,7 @@ impl GenericSendError {
 
 impl fmt::Display for GenericSendError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "Broadcast SendError: Failed to send a message.")
+        write!(f, "SendError: Failed to send a message.")
     }
 }
 // Implementing `Error` trait
@@ -93,6 +93,24 @@ pub enum TransportError {
     ProcessError(String),
     #[error("{0}")]
     FromString(String),
+    #[error("Message of {size} bytes exceeds the maximum message size of {max_size} bytes")]
+    MessageTooLarge { size: usize, max_size: usize },
+    #[error("Message of {size} bytes is not valid UTF-8")]
+    InvalidUtf8 { size: usize },
+    #[error(
+        "Received a response to request {}, which is not pending: it is unknown, timed out, was cancelled or was already answered",
+        display_request_id(.request_id)
+    )]
+    UnexpectedResponse { request_id: RequestId },
+    #[error("Too many pending requests, at most {max_pending_requests} can await their response")]
+    TooManyPendingRequests { max_pending_requests: usize },
     #[error("{0}")]
     OneshotRecvError(#[from] tokio::sync::oneshot::error::RecvError),
 }
+
+fn display_request_id(request_id: &RequestId) -> String {
+    match request_id {
+        RequestId::String(request_id) => format!("\"{}\"", request_id),
+        RequestId::Integer(request_id) => request_id.to_string(),
+    }
+}
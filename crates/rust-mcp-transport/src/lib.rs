@@ -2,13 +2,30 @@
 // Licensed under the MIT License. See LICENSE file for details.
 // Modifications to this file must be documented with a description of the changes made.
 
+mod envelope;
 pub mod error;
+mod line_reader;
+mod malformed_message;
 mod mcp_stream;
 mod message_dispatcher;
+mod message_tracer;
+mod pending_requests;
+mod request_id;
+mod server_launch_options;
 mod stdio;
+mod stdio_builder;
 mod transport;
 mod utils;
 
+pub use envelope::EnvelopeValidation;
+pub use malformed_message::{MalformedMessage, MalformedMessageHandler};
 pub use message_dispatcher::*;
+pub use message_tracer::{
+    MessageDirection, MessageTracer, ParsedMessage, TraceableMessage, TracedMessage,
+};
+pub use pending_requests::{PendingRequests, SessionId};
+pub use request_id::RequestIdStrategy;
+pub use server_launch_options::{ProcessPriority, ServerLaunchOptions, StderrMode};
 pub use stdio::*;
+pub use stdio_builder::StdioTransportBuilder;
 pub use transport::*;
@@ -0,0 +1,84 @@
+use tokio::io::{AsyncBufRead, AsyncBufReadExt};
+
+use crate::error::{TransportError, TransportResult};
+
+/// Reads newline-delimited messages, bounding the size of a message buffered in memory.
+///
+/// Like `tokio::io::Lines`, lines are returned without their line ending, and `next_line()` is
+/// cancellation safe: a partially read line is kept until the next call.
+pub(crate) struct LineReader<B> {
+    reader: B,
+    max_message_size: Option<usize>,
+    line: Vec<u8>,
+    // the size of the current line, which is not buffered once it exceeds the maximum size
+    line_size: usize,
+}
+
+impl<B: AsyncBufRead + Unpin> LineReader<B> {
+    pub(crate) fn new(reader: B, max_message_size: Option<usize>) -> Self {
+        Self {
+            reader,
+            max_message_size,
+            line: vec![],
+            line_size: 0,
+        }
+    }
+
+    /// Returns the next line, or `None` once the reader ends.
+    ///
+    /// A line exceeding the maximum message size is consumed without being buffered, and reported
+    /// with a `MessageTooLarge` error, a line that is not valid UTF-8 with an `InvalidUtf8` error.
+    /// The following lines can still be read.
+    pub(crate) async fn next_line(&mut self) -> TransportResult<Option<String>> {
+        loop {
+            let buffer = self.reader.fill_buf().await?;
+            if buffer.is_empty() {
+                // the last line may not end with a line ending
+                if self.line_size == 0 {
+                    return Ok(None);
+                }
+                return self.take_line();
+            }
+
+            let (chunk, line_ended) = match buffer.iter().position(|byte| *byte == b'\n') {
+                Some(position) => (&buffer[..position], true),
+                None => (buffer, false),
+            };
+            self.line_size += chunk.len();
+            if self
+                .max_message_size
+                .is_some_and(|max_message_size| self.line_size > max_message_size)
+            {
+                self.line = vec![];
+            } else {
+                self.line.extend_from_slice(chunk);
+            }
+            let consumed = chunk.len() + usize::from(line_ended);
+            self.reader.consume(consumed);
+
+            if line_ended {
+                return self.take_line();
+            }
+        }
+    }
+
+    fn take_line(&mut self) -> TransportResult<Option<String>> {
+        let line_size = std::mem::take(&mut self.line_size);
+        let mut line = std::mem::take(&mut self.line);
+        if let Some(max_size) = self
+            .max_message_size
+            .filter(|max_size| line_size > *max_size)
+        {
+            return Err(TransportError::MessageTooLarge {
+                size: line_size,
+                max_size,
+            });
+        }
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        String::from_utf8(line)
+            .map(Some)
+            .map_err(|_| TransportError::InvalidUtf8 { size: line_size })
+    }
+}
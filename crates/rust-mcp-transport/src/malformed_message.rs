@@ -0,0 +1,44 @@
+use rust_mcp_schema::RequestId;
+
+/// A line received from the peer that could not be deserialized into a message, as passed to a
+/// `MalformedMessageHandler`.
+#[derive(Debug)]
+pub struct MalformedMessage {
+    /// The line as read from the transport, without its line ending
+    pub raw_line: String,
+    /// Why the line could not be deserialized
+    pub serde_error: serde_json::Error,
+    /// The id of the request, if the line is a JSON object with a `method` and an `id`.
+    /// The peer was then answered with a parse error, so that the request does not hang.
+    pub request_id: Option<RequestId>,
+}
+
+/// Receives the lines received from the peer that could not be deserialized into a message,
+/// e.g. to log them or count them.
+///
+/// A handler is configured with `TransportOptions::malformed_message_handler`, malformed messages
+/// are reported on stderr otherwise. Either way, the malformed line is skipped and the transport
+/// keeps reading the next messages. The handler is called synchronously by the transport, so it
+/// should return quickly.
+///
+/// # Example
+/// ```ignore
+/// let transport = StdioTransport::new(TransportOptions {
+///     malformed_message_handler: Some(Arc::new(|message: &MalformedMessage| {
+///         tracing::warn!(raw = %message.raw_line, "malformed message: {}", message.serde_error);
+///     })),
+///     ..Default::default()
+/// })?;
+/// ```
+pub trait MalformedMessageHandler: Send + Sync + 'static {
+    fn handle_malformed_message(&self, message: &MalformedMessage);
+}
+
+impl<F> MalformedMessageHandler for F
+where
+    F: Fn(&MalformedMessage) + Send + Sync + 'static,
+{
+    fn handle_malformed_message(&self, message: &MalformedMessage) {
+        self(message)
+    }
+}
@@ -1,22 +1,24 @@
 use crate::{
-    error::{GenericSendError, TransportError},
-    message_dispatcher::MessageDispatcher,
-    IoStream,
+    envelope::{string_response_id, EnvelopeValidation},
+    error::{TransportError, TransportResult},
+    line_reader::LineReader,
+    malformed_message::{MalformedMessage, MalformedMessageHandler},
+    message_dispatcher::{MessageDispatcher, SharedWriter, StickyRequests},
+    message_tracer::{MessageDirection, MessageTracer, TraceableMessage, TracedMessage},
+    pending_requests::{PendingRequests, SessionId},
+    IoStream, TransportOptions,
 };
 use futures::Stream;
-use rust_mcp_schema::{schema_utils::RPCMessage, RequestId, RpcError};
-use std::{
-    collections::HashMap,
-    pin::Pin,
-    sync::{atomic::AtomicI64, Arc},
-};
+use rust_mcp_schema::{schema_utils::RPCMessage, RequestId, RpcError, JSONRPC_VERSION};
+use std::{collections::HashMap, pin::Pin, sync::Arc, time::SystemTime};
 use tokio::{
-    io::{AsyncBufReadExt, BufReader},
-    sync::{broadcast::Sender, oneshot, Mutex},
+    io::{AsyncWriteExt, BufReader},
+    sync::{mpsc::Sender, Mutex},
 };
 use tokio::{sync::watch::Receiver, task::JoinHandle};
 
-const CHANNEL_CAPACITY: usize = 36;
+/// Reads the messages received from the readable stream, one per line.
+type MessageReader = LineReader<BufReader<Pin<Box<dyn tokio::io::AsyncRead + Send + Sync>>>>;
 
 pub struct MCPStream {}
 
@@ -33,116 +35,1308 @@ impl MCPStream {
         readable: Pin<Box<dyn tokio::io::AsyncRead + Send + Sync>>,
         writable: Mutex<Pin<Box<dyn tokio::io::AsyncWrite + Send + Sync>>>,
         error_io: IoStream,
-        timeout_msec: u64,
+        options: &TransportOptions,
+        shutdown_rx: Receiver<bool>,
+    ) -> (
+        Pin<Box<dyn Stream<Item = R> + Send>>,
+        MessageDispatcher<R>,
+        IoStream,
+    )
+    where
+        R: RPCMessage
+            + TraceableMessage
+            + Clone
+            + Send
+            + Sync
+            + serde::de::DeserializeOwned
+            + 'static,
+    {
+        Self::create_for_session(
+            readable,
+            writable,
+            error_io,
+            options,
+            shutdown_rx,
+            None,
+            Arc::new(PendingRequests::new()),
+        )
+    }
+
+    /// Creates the stream of a session of a transport serving multiple sessions, like `create()`.
+    ///
+    /// The requests sent by the returned dispatcher are registered in the partition of `session_id`
+    /// of `pending_requests`, which is shared with the other sessions of the transport. Responses
+    /// read from `readable` are only delivered to requests of that session, and the pending requests
+    /// of the session are dropped once `readable` ends or the stream is shut down.
+    pub fn create_for_session<R>(
+        readable: Pin<Box<dyn tokio::io::AsyncRead + Send + Sync>>,
+        writable: Mutex<Pin<Box<dyn tokio::io::AsyncWrite + Send + Sync>>>,
+        error_io: IoStream,
+        options: &TransportOptions,
         shutdown_rx: Receiver<bool>,
+        session_id: Option<SessionId>,
+        pending_requests: Arc<PendingRequests<R>>,
     ) -> (
         Pin<Box<dyn Stream<Item = R> + Send>>,
         MessageDispatcher<R>,
         IoStream,
     )
     where
-        R: RPCMessage + Clone + Send + Sync + serde::de::DeserializeOwned + 'static,
+        R: RPCMessage
+            + TraceableMessage
+            + Clone
+            + Send
+            + Sync
+            + serde::de::DeserializeOwned
+            + 'static,
     {
-        let (tx, rx) = tokio::sync::broadcast::channel::<R>(CHANNEL_CAPACITY);
-        let pending_requests = Arc::new(Mutex::new(HashMap::new()));
+        // Messages received while the consumer is busy, e.g. during the initialization, are queued
+        // up to `max_queued_messages`, after which the reader waits for the consumer to catch up.
+        let (tx, rx) = tokio::sync::mpsc::channel::<R>(options.max_queued_messages.max(1));
+        let sticky_requests: StickyRequests<R> = Arc::new(Mutex::new(HashMap::new()));
+
+        let message_id_counter = pending_requests.message_id_counter();
+        let mut sender = MessageDispatcher::new(
+            pending_requests.clone(),
+            writable,
+            message_id_counter,
+            options.timeout,
+        )
+        .with_sticky_requests(sticky_requests.clone())
+        .with_max_message_size(options.max_message_size)
+        .with_max_pending_requests(options.max_pending_requests)
+        .with_request_id_strategy(options.request_id_strategy.clone())
+        .with_message_tracer(options.message_tracer.clone());
+        if let Some(session_id) = session_id.clone() {
+            sender = sender.with_session(session_id);
+        }
 
         #[allow(clippy::let_underscore_future)]
-        let _ = Self::spawn_reader(readable, tx, pending_requests.clone(), shutdown_rx);
+        let _ = Self::spawn_reader(
+            LineReader::new(BufReader::new(readable), options.max_message_size),
+            tx,
+            session_id,
+            pending_requests,
+            sticky_requests,
+            MessageParser {
+                envelope_validation: options.envelope_validation,
+                message_tracer: options.message_tracer.clone(),
+                malformed_message_handler: options.malformed_message_handler.clone(),
+                writable: sender.shared_writer(),
+            },
+            shutdown_rx,
+        );
 
         let stream = {
             Box::pin(futures::stream::unfold(rx, |mut rx| async move {
-                match rx.recv().await {
-                    Ok(msg) => Some((msg, rx)),
-                    Err(_) => None,
-                }
+                rx.recv().await.map(|msg| (msg, rx))
             }))
         };
 
-        let sender = MessageDispatcher::new(
-            pending_requests,
-            writable,
-            Arc::new(AtomicI64::new(0)),
-            timeout_msec,
-        );
-
         (stream, sender, error_io)
     }
 
     /// Creates a new task that continuously reads from the readable stream.
     /// The received data is deserialized into a JsonrpcMessage. If the deserialization is successful,
-    /// the object is transmitted. If the object is a response or error corresponding to a pending request,
+    /// the object is transmitted, otherwise the line is reported and skipped. If the object is a response or error corresponding to a pending request,
     /// the associated pending request will ber removed from pending_requests.
+    /// Responses and errors corresponding to a sticky request are all delivered to its response stream.
+    /// A message that can not be read, deserialized or delivered is reported and skipped, the reader
+    /// only ends with the readable stream, on a read error, or once the stream is shut down.
+    /// The pending requests of the session are dropped once the reader ends.
     fn spawn_reader<R>(
-        readable: Pin<Box<dyn tokio::io::AsyncRead + Send + Sync>>,
+        lines_stream: MessageReader,
         tx: Sender<R>,
-        pending_requests: Arc<Mutex<HashMap<RequestId, oneshot::Sender<R>>>>,
-        mut shutdown_rx: Receiver<bool>,
+        session_id: Option<SessionId>,
+        pending_requests: Arc<PendingRequests<R>>,
+        sticky_requests: StickyRequests<R>,
+        message_parser: MessageParser,
+        shutdown_rx: Receiver<bool>,
     ) -> JoinHandle<Result<(), TransportError>>
     where
-        R: RPCMessage + Clone + Send + Sync + serde::de::DeserializeOwned + 'static,
+        R: RPCMessage
+            + TraceableMessage
+            + Clone
+            + Send
+            + Sync
+            + serde::de::DeserializeOwned
+            + 'static,
     {
         tokio::spawn(async move {
-            let mut lines_stream = BufReader::new(readable).lines();
-
-            loop {
-                tokio::select! {
-                    _ = shutdown_rx.changed() =>{
-                        if *shutdown_rx.borrow() {
-                            break;
-                        }
+            let result = Self::read_messages(
+                lines_stream,
+                tx,
+                session_id.as_deref(),
+                &pending_requests,
+                sticky_requests,
+                message_parser,
+                shutdown_rx,
+            )
+            .await;
+            // the requests of the session can no longer receive their response
+            pending_requests.end_session(session_id.as_deref());
+            result
+        })
+    }
+
+    /// Reads the messages of the readable stream until it ends or the stream is shut down.
+    async fn read_messages<R>(
+        mut lines_stream: MessageReader,
+        tx: Sender<R>,
+        session_id: Option<&str>,
+        pending_requests: &PendingRequests<R>,
+        sticky_requests: StickyRequests<R>,
+        message_parser: MessageParser,
+        mut shutdown_rx: Receiver<bool>,
+    ) -> Result<(), TransportError>
+    where
+        R: RPCMessage
+            + TraceableMessage
+            + Clone
+            + Send
+            + Sync
+            + serde::de::DeserializeOwned
+            + 'static,
+    {
+        // set once the stream of the received messages is dropped
+        let mut stream_dropped = false;
+        let sent_requests = SentRequests {
+            session_id,
+            pending_requests,
+            sticky_requests: &sticky_requests,
+        };
+        loop {
+            tokio::select! {
+                _ = shutdown_rx.changed() =>{
+                    if *shutdown_rx.borrow() {
+                        break;
                     }
+                }
 
-                    line = lines_stream.next_line() =>{
-                        match line {
-                            Ok(Some(line)) => {
-                                            // deserialize and send it to the stream
-                                            let message: R = serde_json::from_str(&line).map_err(|_| {
-                                                crate::error::TransportError::JsonrpcError(
-                                                    RpcError::parse_error(),
-                                                )
-                                            })?;
-
-                                            if message.is_response() || message.is_error() {
-                                                if let Some(request_id) = &message.request_id() {
-                                                    let mut pending_requests = pending_requests.lock().await;
-
-                                                    if let Some(tx_response) = pending_requests.remove(request_id) {
-                                                        tx_response.send(message).map_err(|_| {
-                                                            crate::error::TransportError::JsonrpcError(
-                                                                RpcError::internal_error(),
-                                                            )
-                                                        })?;
-                                                    } else if message.is_error() {
-                                                        //An error that is unrelated to a request.
-                                                        tx.send(message).map_err(GenericSendError::new)?;
-                                                    } else {
-                                                        eprintln!(
-                                                            "Error: Received response does not correspond to any request. {:?}",
-                                                            &message.is_response()
-                                                        );
+                line = lines_stream.next_line() =>{
+                    match line {
+                        Ok(Some(line)) => {
+                                        if line.trim().is_empty() {
+                                            continue;
+                                        }
+                                        // deserialize and send it to the stream.
+                                        // Unknown requests and notifications are deserialized into their Custom variants,
+                                        // a message that can not be deserialized at all is reported and skipped,
+                                        // so that a peer implementing a newer revision of the protocol does not end the stream.
+                                        let Some(message) = message_parser.parse::<R>(&line, &sent_requests).await else {
+                                            continue;
+                                        };
+
+                                        if message.is_response() || message.is_error() {
+                                            if let Some(request_id) = message.request_id().cloned() {
+                                                let mut sticky_requests = sticky_requests.lock().await;
+
+                                                if let Some(tx_response) = pending_requests.take(session_id, &request_id) {
+                                                    // the send awaiting the response was cancelled in the meantime
+                                                    if tx_response.send(message).is_err() {
+                                                        eprintln!("Error: {}", TransportError::UnexpectedResponse { request_id });
+                                                    }
+                                                } else if let Some(tx_response) = sticky_requests.get(&request_id) {
+                                                    // an error ends the stream, the request is also forgotten once its stream is dropped
+                                                    let is_error = message.is_error();
+                                                    if tx_response.send(message).is_err() || is_error {
+                                                        sticky_requests.remove(&request_id);
                                                     }
+                                                } else if message.is_error() {
+                                                    //An error that is unrelated to a request.
+                                                    // the sticky requests are not locked while waiting for room in the queue
+                                                    drop(sticky_requests);
+                                                    Self::forward_message(&tx, message, &mut stream_dropped).await;
+                                                } else {
+                                                    // a duplicate or late response, e.g. to a request that timed out
+                                                    eprintln!("Error: {}", TransportError::UnexpectedResponse { request_id });
                                                 }
-                                            } else {
-                                                tx.send(message).map_err(GenericSendError::new)?;
                                             }
+                                        } else {
+                                            Self::forward_message(&tx, message, &mut stream_dropped).await;
                                         }
-                                        Ok(None) => {
-                                            // EOF reached, exit loop
-                                            break;
-                                        }
-                                        Err(e) => {
-                                            // Handle error in reading from readable_std
-                                            return Err(TransportError::ProcessError(format!(
-                                                "Error reading from readable_std: {}",
-                                                e
-                                            )));
-                                        }
-                        }
+                                    }
+                                    Ok(None) => {
+                                        // EOF reached, exit loop
+                                        break;
+                                    }
+                                    Err(error @ (TransportError::MessageTooLarge { .. } | TransportError::InvalidUtf8 { .. })) => {
+                                        // the message was discarded, the next ones can still be read
+                                        eprintln!("Error: Received message will be ignored. {}", error);
+                                    }
+                                    Err(e) => {
+                                        // Handle error in reading from readable_std
+                                        return Err(TransportError::ProcessError(format!(
+                                            "Error reading from readable_std: {}",
+                                            e
+                                        )));
+                                    }
                     }
                 }
             }
+        }
+
+        Ok::<(), TransportError>(())
+    }
+
+    /// Passes a received message to the stream of the received messages, waiting for room in
+    /// its queue if it is full.
+    ///
+    /// Once the stream is dropped, the received messages are reported once and ignored, but
+    /// the reader keeps delivering the responses to the pending requests.
+    async fn forward_message<R>(tx: &Sender<R>, message: R, stream_dropped: &mut bool) {
+        if tx.send(message).await.is_err() && !*stream_dropped {
+            *stream_dropped = true;
+            eprintln!("Error: The stream of the received messages was dropped, the received messages other than responses will be ignored.");
+        }
+    }
+}
+
+/// The requests sent by a session awaiting their response, the responses received being matched with them.
+struct SentRequests<'a, R> {
+    session_id: Option<&'a str>,
+    pending_requests: &'a PendingRequests<R>,
+    sticky_requests: &'a StickyRequests<R>,
+}
+
+impl<R> SentRequests<'_, R> {
+    /// Returns `true` if a request with the given id awaits its response
+    async fn contains(&self, request_id: &RequestId) -> bool {
+        self.pending_requests
+            .session_of(request_id)
+            .is_some_and(|session_id| session_id.as_deref() == self.session_id)
+            || self.sticky_requests.lock().await.contains_key(request_id)
+    }
+}
+
+/// Deserializes the received messages, passing them to the message tracer, if any.
+struct MessageParser {
+    envelope_validation: EnvelopeValidation,
+    message_tracer: Option<Arc<dyn MessageTracer>>,
+    malformed_message_handler: Option<Arc<dyn MalformedMessageHandler>>,
+    // answers the malformed requests
+    writable: SharedWriter,
+}
+
+impl MessageParser {
+    /// Deserializes a received line into a message, reporting it if it can not be deserialized,
+    /// and answering it with a parse error if it is a request, so that the peer does not wait for
+    /// a response until its request times out.
+    async fn parse<R>(&self, line: &str, sent_requests: &SentRequests<'_, R>) -> Option<R>
+    where
+        R: TraceableMessage + serde::de::DeserializeOwned,
+    {
+        let timestamp = SystemTime::now();
+        let result = self.parse_message::<R>(line, sent_requests).await;
+        if let Some(message_tracer) = &self.message_tracer {
+            message_tracer.trace(&TracedMessage {
+                direction: MessageDirection::Inbound,
+                raw: line,
+                message: result.as_ref().ok().map(|message| message.as_parsed()),
+                timestamp,
+            });
+        }
+        let serde_error = match result {
+            Ok(message) => return Some(message),
+            Err(serde_error) => serde_error,
+        };
+
+        let malformed_message = MalformedMessage {
+            raw_line: line.to_string(),
+            serde_error,
+            request_id: malformed_request_id(line),
+        };
+        if let Some(request_id) = &malformed_message.request_id {
+            let error = RpcError::parse_error()
+                .with_message(format!("Parse error: {}", malformed_message.serde_error));
+            if let Err(error) = self.answer_error(request_id, error).await {
+                eprintln!("Error: Failed to answer a malformed request. {}", error);
+            }
+        }
+        match &self.malformed_message_handler {
+            Some(handler) => handler.handle_malformed_message(&malformed_message),
+            None => eprintln!(
+                "Error: Received message could not be deserialized and will be ignored. {}",
+                malformed_message.serde_error
+            ),
+        }
+        None
+    }
 
-            Ok::<(), TransportError>(())
+    /// Answers a received request with an error, written directly to the transport.
+    async fn answer_error(&self, request_id: &RequestId, error: RpcError) -> TransportResult<()> {
+        let message = serde_json::json!({
+            "jsonrpc": JSONRPC_VERSION,
+            "id": request_id,
+            "error": error,
         })
+        .to_string();
+        if let Some(message_tracer) = &self.message_tracer {
+            message_tracer.trace(&TracedMessage {
+                direction: MessageDirection::Outbound,
+                raw: &message,
+                message: None,
+                timestamp: SystemTime::now(),
+            });
+        }
+        let mut writable = self.writable.lock().await;
+        writable.write_all(message.as_bytes()).await?;
+        writable.write_all(b"\n").await?;
+        writable.flush().await?;
+        Ok(())
+    }
+
+    /// Deserializes a received line into a message, after converting the deviations from the
+    /// JSON-RPC envelope tolerated by `envelope_validation` into their canonical form.
+    async fn parse_message<R>(
+        &self,
+        line: &str,
+        sent_requests: &SentRequests<'_, R>,
+    ) -> serde_json::Result<R>
+    where
+        R: serde::de::DeserializeOwned,
+    {
+        if self.envelope_validation.is_strict() {
+            return serde_json::from_str(line);
+        }
+        let mut value: serde_json::Value = serde_json::from_str(line)?;
+        // the string id of a response is only converted if it answers a request with an integer id
+        let integer_id_pending = match value.as_object().and_then(string_response_id) {
+            Some((string_id, integer_id)) => {
+                let (string_id, integer_id) = (
+                    RequestId::String(string_id.to_string()),
+                    RequestId::Integer(integer_id),
+                );
+                !sent_requests.contains(&string_id).await
+                    && sent_requests.contains(&integer_id).await
+            }
+            None => false,
+        };
+        let diagnostics = self
+            .envelope_validation
+            .normalize(&mut value, integer_id_pending);
+        if !diagnostics.is_empty() {
+            eprintln!(
+                "Warning: Received message is not a valid JSON-RPC 2.0 message and was normalized ({}).",
+                diagnostics.join(", ")
+            );
+        }
+        serde_json::from_value(value)
+    }
+}
+
+// Returns the id of a malformed line, if it is a JSON object with a `method` and an `id`: a request
+fn malformed_request_id(line: &str) -> Option<RequestId> {
+    let serde_json::Value::Object(mut message) = serde_json::from_str(line).ok()? else {
+        return None;
+    };
+    if !message
+        .get("method")
+        .is_some_and(serde_json::Value::is_string)
+    {
+        return None;
+    }
+    serde_json::from_value(message.remove("id")?).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{McpDispatch, ParsedMessage};
+    use futures::StreamExt;
+    use rust_mcp_schema::schema_utils::{
+        ClientMessage, MessageFromClient, NotificationFromServer, RequestFromClient,
+        RequestFromServer, ResultFromServer, ServerMessage,
+    };
+    use rust_mcp_schema::{
+        ClientRequest, ListToolsRequest, RequestId, ServerNotification, ServerResult,
+    };
+    use std::time::Duration;
+    use tokio::io::{AsyncBufReadExt, AsyncWriteExt, DuplexStream};
+
+    const TIMEOUT_MSEC: u64 = 5_000;
+
+    type ServerMessageStream = Pin<Box<dyn Stream<Item = ServerMessage> + Send>>;
+
+    // Creates a client side MCPStream, returning it along with the server end of the connection
+    fn create_client_stream() -> (
+        ServerMessageStream,
+        MessageDispatcher<ServerMessage>,
+        DuplexStream,
+        tokio::sync::watch::Sender<bool>,
+    ) {
+        create_client_stream_with(TransportOptions::default())
+    }
+
+    fn create_client_stream_with(
+        options: TransportOptions,
+    ) -> (
+        ServerMessageStream,
+        MessageDispatcher<ServerMessage>,
+        DuplexStream,
+        tokio::sync::watch::Sender<bool>,
+    ) {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let (readable, writable) = tokio::io::split(client_io);
+        let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+        let (stream, sender, _) = MCPStream::create::<ServerMessage>(
+            Box::pin(readable),
+            Mutex::new(Box::pin(writable)),
+            IoStream::Writable(Box::pin(tokio::io::sink())),
+            &TransportOptions {
+                timeout: TIMEOUT_MSEC,
+                ..options
+            },
+            shutdown_rx,
+        );
+        (stream, sender, server_io, shutdown_tx)
+    }
+
+    // Creates a client side MCPStream limiting the size of the messages to `max_message_size` bytes
+    fn create_limited_client_stream(
+        max_message_size: usize,
+    ) -> (
+        ServerMessageStream,
+        MessageDispatcher<ServerMessage>,
+        DuplexStream,
+        tokio::sync::watch::Sender<bool>,
+    ) {
+        create_client_stream_with(TransportOptions {
+            max_message_size: Some(max_message_size),
+            ..Default::default()
+        })
+    }
+
+    // Creates the client side MCPStream of a session, sharing `pending_requests` with other sessions
+    fn create_session_stream(
+        session_id: &str,
+        pending_requests: Arc<PendingRequests<ServerMessage>>,
+    ) -> (
+        ServerMessageStream,
+        MessageDispatcher<ServerMessage>,
+        DuplexStream,
+        tokio::sync::watch::Sender<bool>,
+    ) {
+        let (client_io, server_io) = tokio::io::duplex(64 * 1024);
+        let (readable, writable) = tokio::io::split(client_io);
+        let (shutdown_tx, shutdown_rx) = tokio::sync::watch::channel(false);
+        let (stream, sender, _) = MCPStream::create_for_session::<ServerMessage>(
+            Box::pin(readable),
+            Mutex::new(Box::pin(writable)),
+            IoStream::Writable(Box::pin(tokio::io::sink())),
+            &TransportOptions {
+                timeout: TIMEOUT_MSEC,
+                ..Default::default()
+            },
+            shutdown_rx,
+            Some(session_id.to_string()),
+            pending_requests,
+        );
+        (stream, sender, server_io, shutdown_tx)
+    }
+
+    fn list_tools_request() -> MessageFromClient {
+        MessageFromClient::RequestFromClient(RequestFromClient::ClientRequest(
+            ClientRequest::ListToolsRequest(ListToolsRequest::new(None)),
+        ))
+    }
+
+    async fn next_message(stream: &mut ServerMessageStream) -> ServerMessage {
+        tokio::time::timeout(Duration::from_millis(TIMEOUT_MSEC), stream.next())
+            .await
+            .expect("timed out waiting for a message")
+            .expect("stream ended unexpectedly")
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kinds_are_deserialized_as_custom() {
+        let (mut stream, _sender, mut server_io, _shutdown_tx) = create_client_stream();
+
+        // messages from a hypothetical future revision of the protocol
+        let payloads = [
+            r#"{"jsonrpc":"2.0","method":"notifications/elicitation/complete","params":{"elicitationId":"e1"}}"#,
+            r#"{"jsonrpc":"2.0","id":1,"method":"elicitation/create","params":{"message":"Name?","requestedSchema":{"type":"object"}}}"#,
+            r#"{"jsonrpc":"2.0","method":"notifications/message","params":{"level":"trace","data":"unknown level"}}"#,
+        ];
+        for payload in payloads {
+            server_io
+                .write_all(format!("{}\n", payload).as_bytes())
+                .await
+                .unwrap();
+        }
+
+        let ServerMessage::Notification(notification) = next_message(&mut stream).await else {
+            panic!("expected a notification");
+        };
+        assert_eq!(notification.method, "notifications/elicitation/complete");
+        assert!(matches!(
+            notification.notification,
+            NotificationFromServer::CustomNotification(_)
+        ));
+
+        let ServerMessage::Request(request) = next_message(&mut stream).await else {
+            panic!("expected a request");
+        };
+        assert_eq!(request.method, "elicitation/create");
+        let RequestFromServer::CustomRequest(value) = request.request else {
+            panic!("expected a custom request");
+        };
+        assert_eq!(value["params"]["message"], "Name?");
+
+        // a known kind with an unknown enum value falls back to the custom variant
+        let ServerMessage::Notification(notification) = next_message(&mut stream).await else {
+            panic!("expected a notification");
+        };
+        assert!(matches!(
+            notification.notification,
+            NotificationFromServer::CustomNotification(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_unknown_fields_are_ignored() {
+        let (mut stream, _sender, mut server_io, _shutdown_tx) = create_client_stream();
+
+        let payload = r#"{"jsonrpc":"2.0","method":"notifications/progress","params":{"progressToken":"t1","progress":2,"total":4,"message":"half way"}}"#;
+        server_io
+            .write_all(format!("{}\n", payload).as_bytes())
+            .await
+            .unwrap();
+
+        let ServerMessage::Notification(notification) = next_message(&mut stream).await else {
+            panic!("expected a notification");
+        };
+        let NotificationFromServer::ServerNotification(ServerNotification::ProgressNotification(
+            progress,
+        )) = notification.notification
+        else {
+            panic!("expected a progress notification");
+        };
+        assert_eq!(progress.params.progress, 2.0);
+        assert_eq!(progress.params.total, Some(4.0));
+    }
+
+    #[tokio::test]
+    async fn test_messages_received_before_consumption_are_kept() {
+        let (mut stream, _sender, mut server_io, _shutdown_tx) = create_client_stream();
+
+        // a burst of log messages, such as those sent by servers right after they start
+        for index in 0..100 {
+            let payload = format!(
+                r#"{{"jsonrpc":"2.0","method":"notifications/message","params":{{"level":"info","data":"log {}"}}}}"#,
+                index
+            );
+            server_io
+                .write_all(format!("{}\n", payload).as_bytes())
+                .await
+                .unwrap();
+        }
+        // let the reader process the whole burst before the stream is consumed
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        for index in 0..100 {
+            let ServerMessage::Notification(notification) = next_message(&mut stream).await else {
+                panic!("expected a notification");
+            };
+            let NotificationFromServer::ServerNotification(
+                ServerNotification::LoggingMessageNotification(notification),
+            ) = notification.notification
+            else {
+                panic!("expected a logging message notification");
+            };
+            assert_eq!(notification.params.data, format!("log {}", index));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flooding_peers_are_throttled_by_the_queue() {
+        let (mut stream, _sender, mut server_io, _shutdown_tx) =
+            create_client_stream_with(TransportOptions {
+                max_queued_messages: 8,
+                ..Default::default()
+            });
+
+        // far more than the queue and the buffer of the connection can hold
+        let flood: String = (0..2_000)
+            .map(|index| {
+                format!(
+                    r#"{{"jsonrpc":"2.0","method":"notifications/message","params":{{"level":"info","data":"log {}"}}}}"#,
+                    index
+                ) + "\n"
+            })
+            .collect();
+
+        // the reader stops reading once the queue is full, so the peer can not write the whole flood
+        let writing = tokio::spawn(async move {
+            server_io.write_all(flood.as_bytes()).await.unwrap();
+            server_io
+        });
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(!writing.is_finished());
+
+        // no message is lost once the stream is consumed
+        for index in 0..2_000 {
+            let ServerMessage::Notification(notification) = next_message(&mut stream).await else {
+                panic!("expected a notification");
+            };
+            let NotificationFromServer::ServerNotification(
+                ServerNotification::LoggingMessageNotification(notification),
+            ) = notification.notification
+            else {
+                panic!("expected a logging message notification");
+            };
+            assert_eq!(notification.params.data, format!("log {}", index));
+        }
+        writing.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_invalid_messages_do_not_end_the_stream() {
+        let (mut stream, _sender, mut server_io, _shutdown_tx) = create_client_stream();
+
+        let payloads: [&[u8]; 5] = [
+            b"not json at all",
+            b"",
+            b"\xff\xfe not UTF-8",
+            br#"[{"jsonrpc":"2.0","method":"notifications/batched"}]"#,
+            br#"{"jsonrpc":"2.0","method":"notifications/tools/list_changed"}"#,
+        ];
+        for payload in payloads {
+            server_io.write_all(payload).await.unwrap();
+            server_io.write_all(b"\n").await.unwrap();
+        }
+
+        let ServerMessage::Notification(notification) = next_message(&mut stream).await else {
+            panic!("expected a notification");
+        };
+        assert!(matches!(
+            notification.notification,
+            NotificationFromServer::ServerNotification(
+                ServerNotification::ToolListChangedNotification(_)
+            )
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_requests_are_answered_with_a_parse_error() {
+        let malformed_messages = Arc::new(std::sync::Mutex::new(vec![]));
+        let handler = {
+            let malformed_messages = malformed_messages.clone();
+            move |message: &MalformedMessage| {
+                malformed_messages
+                    .lock()
+                    .unwrap()
+                    .push((message.raw_line.clone(), message.request_id.clone()));
+            }
+        };
+        let (mut stream, _sender, server_io, _shutdown_tx) =
+            create_client_stream_with(TransportOptions {
+                malformed_message_handler: Some(Arc::new(handler)),
+                ..Default::default()
+            });
+        let (server_readable, mut server_writable) = tokio::io::split(server_io);
+
+        // both a request and a response
+        let malformed_request = r#"{"jsonrpc":"2.0","id":7,"method":"roots/list","result":{}}"#;
+        let payloads = [
+            "not json at all",
+            malformed_request,
+            r#"{"jsonrpc":"2.0","method":"notifications/tools/list_changed"}"#,
+        ];
+        for payload in payloads {
+            server_writable
+                .write_all(format!("{}\n", payload).as_bytes())
+                .await
+                .unwrap();
+        }
+
+        let mut lines = BufReader::new(server_readable).lines();
+        let reply = tokio::time::timeout(Duration::from_secs(1), lines.next_line())
+            .await
+            .expect("expected a parse error")
+            .unwrap()
+            .unwrap();
+        let reply: serde_json::Value = serde_json::from_str(&reply).unwrap();
+        assert_eq!(reply["id"], 7);
+        assert_eq!(reply["error"]["code"], -32700);
+
+        assert!(matches!(
+            next_message(&mut stream).await,
+            ServerMessage::Notification(_)
+        ));
+        let malformed_messages = malformed_messages.lock().unwrap();
+        assert_eq!(malformed_messages.len(), 2);
+        assert_eq!(malformed_messages[0], ("not json at all".to_string(), None));
+        assert_eq!(
+            malformed_messages[1],
+            (malformed_request.to_string(), Some(RequestId::Integer(7)))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_response_with_unknown_fields() {
+        let (_stream, sender, server_io, _shutdown_tx) = create_client_stream();
+        let (server_readable, mut server_writable) = tokio::io::split(server_io);
+
+        let peer = tokio::spawn(async move {
+            let mut lines = BufReader::new(server_readable).lines();
+            let request = lines.next_line().await.unwrap().unwrap();
+            let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {
+                    "tools": [{
+                        "name": "weather",
+                        "title": "Weather",
+                        "inputSchema": {"type": "object"},
+                        "outputSchema": {"type": "object"},
+                        "annotations": {"readOnlyHint": true}
+                    }],
+                    "nextCursor": "2"
+                }
+            });
+            server_writable
+                .write_all(format!("{}\n", response).as_bytes())
+                .await
+                .unwrap();
+        });
+
+        let request = MessageFromClient::RequestFromClient(RequestFromClient::ClientRequest(
+            ClientRequest::ListToolsRequest(ListToolsRequest::new(None)),
+        ));
+        let response = sender.send(request, None).await.unwrap().unwrap();
+        peer.await.unwrap();
+
+        let ServerMessage::Response(response) = response else {
+            panic!("expected a response");
+        };
+        let ResultFromServer::ServerResult(ServerResult::ListToolsResult(result)) = response.result
+        else {
+            panic!("expected a list tools result");
+        };
+        assert_eq!(result.tools.len(), 1);
+        assert_eq!(result.tools[0].name, "weather");
+        assert_eq!(result.next_cursor.as_deref(), Some("2"));
+    }
+
+    #[tokio::test]
+    async fn test_lenient_envelope_validation() {
+        let (mut stream, sender, server_io, _shutdown_tx) =
+            create_client_stream_with(TransportOptions {
+                envelope_validation: EnvelopeValidation::lenient(),
+                ..Default::default()
+            });
+        let (server_readable, mut server_writable) = tokio::io::split(server_io);
+
+        let peer = tokio::spawn(async move {
+            let mut lines = BufReader::new(server_readable).lines();
+            let request = lines.next_line().await.unwrap().unwrap();
+            let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+            // no jsonrpc version, null params and a response id in its string form
+            let payloads = [
+                r#"{"method":"notifications/tools/list_changed","params":null}"#.to_string(),
+                format!(r#"{{"id":"{}","result":{{"tools":[]}}}}"#, request["id"]),
+            ];
+            for payload in payloads {
+                server_writable
+                    .write_all(format!("{}\n", payload).as_bytes())
+                    .await
+                    .unwrap();
+            }
+        });
+
+        let request = MessageFromClient::RequestFromClient(RequestFromClient::ClientRequest(
+            ClientRequest::ListToolsRequest(ListToolsRequest::new(None)),
+        ));
+        let response = sender.send(request, None).await.unwrap().unwrap();
+        peer.await.unwrap();
+
+        let ServerMessage::Notification(notification) = next_message(&mut stream).await else {
+            panic!("expected a notification");
+        };
+        assert!(matches!(
+            notification.notification,
+            NotificationFromServer::ServerNotification(
+                ServerNotification::ToolListChangedNotification(_)
+            )
+        ));
+        assert!(matches!(response, ServerMessage::Response(_)));
+    }
+
+    #[tokio::test]
+    async fn test_deduplicated_requests_share_a_response() {
+        let (_stream, sender, server_io, _shutdown_tx) = create_client_stream();
+        let (server_readable, mut server_writable) = tokio::io::split(server_io);
+        let sender = Arc::new(sender);
+
+        let peer = tokio::spawn(async move {
+            let mut lines = BufReader::new(server_readable).lines();
+            let request = lines.next_line().await.unwrap().unwrap();
+            let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+            // let the duplicate send happen while the request is pending
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {"tools": []}
+            });
+            server_writable
+                .write_all(format!("{}\n", response).as_bytes())
+                .await
+                .unwrap();
+            // the duplicate must not have been written to the transport
+            let next_line =
+                tokio::time::timeout(Duration::from_millis(100), lines.next_line()).await;
+            assert!(next_line.is_err());
+        });
+
+        let sends = (0..2).map(|_| {
+            let sender = Arc::clone(&sender);
+            tokio::spawn(async move {
+                let request =
+                    MessageFromClient::RequestFromClient(RequestFromClient::ClientRequest(
+                        ClientRequest::ListToolsRequest(ListToolsRequest::new(None)),
+                    ));
+                sender.send_deduplicated(request, "list-tools").await
+            })
+        });
+        for send in sends.collect::<Vec<_>>() {
+            let response = send.await.unwrap().unwrap();
+            assert!(matches!(response, Some(ServerMessage::Response(_))));
+        }
+        peer.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sticky_request_receives_all_responses() {
+        let (_stream, sender, server_io, _shutdown_tx) = create_client_stream();
+        let (server_readable, mut server_writable) = tokio::io::split(server_io);
+
+        let peer = tokio::spawn(async move {
+            let mut lines = BufReader::new(server_readable).lines();
+            let request = lines.next_line().await.unwrap().unwrap();
+            let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+            assert_eq!(request["method"], "tools/list");
+            // incremental results, followed by an error ending the stream
+            for page in ["first", "second"] {
+                let response = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"],
+                    "result": {"tools": [{"name": page, "inputSchema": {"type": "object"}}]}
+                });
+                server_writable
+                    .write_all(format!("{}\n", response).as_bytes())
+                    .await
+                    .unwrap();
+            }
+            let error = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "error": {"code": -32603, "message": "done"}
+            });
+            server_writable
+                .write_all(format!("{}\n", error).as_bytes())
+                .await
+                .unwrap();
+        });
+
+        let request = MessageFromClient::RequestFromClient(RequestFromClient::ClientRequest(
+            ClientRequest::ListToolsRequest(ListToolsRequest::new(None)),
+        ));
+        let (request_id, responses) = sender.send_sticky_request(request).await.unwrap();
+        let responses: Vec<ServerMessage> =
+            tokio::time::timeout(Duration::from_millis(TIMEOUT_MSEC), responses.collect())
+                .await
+                .expect("timed out waiting for the responses");
+        peer.await.unwrap();
+
+        assert_eq!(responses.len(), 3);
+        let tool_names: Vec<_> = responses[..2]
+            .iter()
+            .map(|message| {
+                assert_eq!(message.request_id(), Some(&request_id));
+                let ServerMessage::Response(response) = message else {
+                    panic!("expected a response");
+                };
+                let ResultFromServer::ServerResult(ServerResult::ListToolsResult(result)) =
+                    &response.result
+                else {
+                    panic!("expected a list tools result");
+                };
+                result.tools[0].name.clone()
+            })
+            .collect();
+        assert_eq!(tool_names, vec!["first", "second"]);
+        assert!(matches!(responses[2], ServerMessage::Error(_)));
+    }
+
+    #[tokio::test]
+    async fn test_sticky_request_rejects_notifications() {
+        let (_stream, sender, _server_io, _shutdown_tx) = create_client_stream();
+        let notification = MessageFromClient::NotificationFromClient(
+            rust_mcp_schema::schema_utils::NotificationFromClient::CustomNotification(
+                serde_json::json!({"method": "notifications/custom"}),
+            ),
+        );
+        assert!(sender.send_sticky_request(notification).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_responses_are_routed_to_the_session_of_their_request() {
+        let pending_requests = Arc::new(PendingRequests::new());
+        let (_stream_a, sender_a, server_io_a, _shutdown_tx_a) =
+            create_session_stream("a", pending_requests.clone());
+        let (_stream_b, _sender_b, mut server_io_b, _shutdown_tx_b) =
+            create_session_stream("b", pending_requests.clone());
+        let (server_readable_a, mut server_writable_a) = tokio::io::split(server_io_a);
+
+        let send = tokio::spawn(async move { sender_a.send(list_tools_request(), None).await });
+
+        let mut lines = BufReader::new(server_readable_a).lines();
+        let request: serde_json::Value =
+            serde_json::from_str(&lines.next_line().await.unwrap().unwrap()).unwrap();
+        let request_id: RequestId = serde_json::from_value(request["id"].clone()).unwrap();
+        assert_eq!(
+            pending_requests.session_of(&request_id),
+            Some(Some("a".to_string()))
+        );
+
+        let response = format!(
+            "{}\n",
+            serde_json::json!({"jsonrpc": "2.0", "id": request["id"], "result": {"tools": []}})
+        );
+        // a response received from another session is not delivered
+        server_io_b.write_all(response.as_bytes()).await.unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(!send.is_finished());
+        assert_eq!(pending_requests.len(), 1);
+
+        server_writable_a
+            .write_all(response.as_bytes())
+            .await
+            .unwrap();
+        let response = send.await.unwrap().unwrap();
+        assert!(matches!(response, Some(ServerMessage::Response(_))));
+        assert!(pending_requests.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_pending_requests_fail_when_their_session_ends() {
+        let pending_requests = Arc::new(PendingRequests::new());
+        let (_stream_a, sender_a, server_io_a, _shutdown_tx_a) =
+            create_session_stream("a", pending_requests.clone());
+        let (_stream_b, sender_b, server_io_b, _shutdown_tx_b) =
+            create_session_stream("b", pending_requests.clone());
+        let (server_readable_a, server_writable_a) = tokio::io::split(server_io_a);
+        let (server_readable_b, _server_writable_b) = tokio::io::split(server_io_b);
+
+        let send_a = tokio::spawn(async move { sender_a.send(list_tools_request(), None).await });
+        let send_b = tokio::spawn(async move { sender_b.send(list_tools_request(), None).await });
+        let mut lines_a = BufReader::new(server_readable_a).lines();
+        let mut lines_b = BufReader::new(server_readable_b).lines();
+        lines_a.next_line().await.unwrap().unwrap();
+        lines_b.next_line().await.unwrap().unwrap();
+        assert_eq!(pending_requests.len(), 2);
+
+        // the session `a` ends, without waiting for the timeout of its request
+        drop(server_writable_a);
+        drop(lines_a);
+        let error = tokio::time::timeout(Duration::from_millis(TIMEOUT_MSEC / 2), send_a)
+            .await
+            .expect("the request should fail before its timeout")
+            .unwrap()
+            .unwrap_err();
+        assert!(matches!(
+            error,
+            TransportError::SdkError(error)
+                if error.code == i64::from(rust_mcp_schema::schema_utils::SdkErrorCodes::CONNECTION_CLOSED)
+        ));
+
+        // the request of the session `b` is still pending
+        assert_eq!(pending_requests.len(), 1);
+        assert!(!send_b.is_finished());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_messages_are_discarded() {
+        let (mut stream, _sender, mut server_io, _shutdown_tx) = create_limited_client_stream(256);
+
+        // a message far larger than the buffer of the reader, written in several chunks
+        let oversized = format!(
+            r#"{{"jsonrpc":"2.0","method":"notifications/message","params":{{"level":"info","data":"{}"}}}}"#,
+            "x".repeat(200 * 1024)
+        );
+        let writer = tokio::spawn(async move {
+            server_io
+                .write_all(format!("{}\n", oversized).as_bytes())
+                .await
+                .unwrap();
+            server_io
+                .write_all(
+                    b"{\"jsonrpc\":\"2.0\",\"method\":\"notifications/tools/list_changed\"}\n",
+                )
+                .await
+                .unwrap();
+            server_io
+        });
+
+        // the oversized message is skipped, the next one is still received
+        let ServerMessage::Notification(notification) = next_message(&mut stream).await else {
+            panic!("expected a notification");
+        };
+        assert!(matches!(
+            notification.notification,
+            NotificationFromServer::ServerNotification(
+                ServerNotification::ToolListChangedNotification(_)
+            )
+        ));
+        writer.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_sending_oversized_messages_fails() {
+        let (_stream, sender, server_io, _shutdown_tx) = create_limited_client_stream(256);
+
+        let request = MessageFromClient::RequestFromClient(RequestFromClient::ClientRequest(
+            ClientRequest::ListToolsRequest(ListToolsRequest::new(Some(
+                rust_mcp_schema::ListToolsRequestParams {
+                    cursor: Some("x".repeat(1024)),
+                },
+            ))),
+        ));
+        let error = sender.send(request, None).await.unwrap_err();
+        assert!(matches!(
+            error,
+            TransportError::MessageTooLarge { size, max_size: 256 } if size > 1024
+        ));
+
+        // nothing was written, the first line received is the next request
+        let send = tokio::spawn(async move { sender.send(list_tools_request(), None).await });
+        let mut lines = BufReader::new(server_io).lines();
+        let line = lines.next_line().await.unwrap().unwrap();
+        assert!(line.contains(r#""method":"tools/list""#) && !line.contains("cursor"));
+        send.abort();
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_requests_are_forgotten() {
+        let pending_requests = Arc::new(PendingRequests::new());
+        let (mut stream, sender, server_io, _shutdown_tx) =
+            create_session_stream("a", pending_requests.clone());
+        let (server_readable, mut server_writable) = tokio::io::split(server_io);
+
+        let send = tokio::spawn(async move { sender.send(list_tools_request(), None).await });
+        let mut lines = BufReader::new(server_readable).lines();
+        let request = lines.next_line().await.unwrap().unwrap();
+        let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+        assert_eq!(pending_requests.len(), 1);
+
+        // the send is cancelled before the response is received
+        send.abort();
+        let _ = send.await;
+        assert!(pending_requests.is_empty());
+
+        // the late response is discarded, and does not end the stream
+        let response = serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": {"tools": []}
+        });
+        server_writable
+            .write_all(format!("{}\n", response).as_bytes())
+            .await
+            .unwrap();
+        server_writable
+            .write_all(b"{\"jsonrpc\":\"2.0\",\"method\":\"notifications/tools/list_changed\"}\n")
+            .await
+            .unwrap();
+        assert!(matches!(
+            next_message(&mut stream).await,
+            ServerMessage::Notification(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_pending_requests_are_capped() {
+        let (_stream, sender, server_io, _shutdown_tx) =
+            create_client_stream_with(TransportOptions {
+                max_pending_requests: Some(1),
+                ..Default::default()
+            });
+        let sender = Arc::new(sender);
+
+        let first_sender = Arc::clone(&sender);
+        let first_send =
+            tokio::spawn(async move { first_sender.send(list_tools_request(), None).await });
+        let mut lines = BufReader::new(server_io).lines();
+        lines.next_line().await.unwrap().unwrap();
+
+        let error = sender.send(list_tools_request(), None).await.unwrap_err();
+        assert!(matches!(
+            error,
+            TransportError::TooManyPendingRequests {
+                max_pending_requests: 1
+            }
+        ));
+        first_send.abort();
+    }
+
+    #[tokio::test]
+    async fn test_responses_are_delivered_once_the_stream_is_dropped() {
+        let (stream, sender, server_io, _shutdown_tx) = create_client_stream();
+        drop(stream);
+        let (server_readable, mut server_writable) = tokio::io::split(server_io);
+
+        let peer = tokio::spawn(async move {
+            let mut lines = BufReader::new(server_readable).lines();
+            let request = lines.next_line().await.unwrap().unwrap();
+            let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+            // a notification that can no longer be delivered, followed by the response
+            let notification = r#"{"jsonrpc":"2.0","method":"notifications/tools/list_changed"}"#;
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {"tools": []}
+            });
+            server_writable
+                .write_all(format!("{}\n{}\n", notification, response).as_bytes())
+                .await
+                .unwrap();
+        });
+
+        let response = sender.send(list_tools_request(), None).await.unwrap();
+        assert!(matches!(response, Some(ServerMessage::Response(_))));
+        peer.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_responses_to_string_request_ids() {
+        let (_stream, sender, server_io, _shutdown_tx) =
+            create_client_stream_with(TransportOptions {
+                request_id_strategy: crate::RequestIdStrategy::PrefixedCounter(
+                    "client-".to_string(),
+                ),
+                ..Default::default()
+            });
+        let (server_readable, mut server_writable) = tokio::io::split(server_io);
+
+        let peer = tokio::spawn(async move {
+            let mut lines = BufReader::new(server_readable).lines();
+            let request = lines.next_line().await.unwrap().unwrap();
+            let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+            assert_eq!(request["id"], "client-0");
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {"tools": []}
+            });
+            server_writable
+                .write_all(format!("{}\n", response).as_bytes())
+                .await
+                .unwrap();
+        });
+
+        let response = sender.send(list_tools_request(), None).await.unwrap();
+        assert!(matches!(response, Some(ServerMessage::Response(_))));
+        peer.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_numeric_string_request_ids_with_lenient_envelope_validation() {
+        // the ids of the requests are the string form of integers
+        let (_stream, sender, server_io, _shutdown_tx) =
+            create_client_stream_with(TransportOptions {
+                envelope_validation: EnvelopeValidation::lenient(),
+                request_id_strategy: crate::RequestIdStrategy::PrefixedCounter(String::new()),
+                ..Default::default()
+            });
+        let (server_readable, mut server_writable) = tokio::io::split(server_io);
+
+        let peer = tokio::spawn(async move {
+            let mut lines = BufReader::new(server_readable).lines();
+            let request = lines.next_line().await.unwrap().unwrap();
+            let request: serde_json::Value = serde_json::from_str(&request).unwrap();
+            assert_eq!(request["id"], "0");
+            let response = serde_json::json!({
+                "jsonrpc": "2.0",
+                "id": request["id"],
+                "result": {"tools": []}
+            });
+            server_writable
+                .write_all(format!("{}\n", response).as_bytes())
+                .await
+                .unwrap();
+        });
+
+        // the id of the response is not converted to the integer `0`, which is not pending
+        let response = sender.send(list_tools_request(), None).await.unwrap();
+        let Some(ServerMessage::Response(response)) = response else {
+            panic!("expected a response");
+        };
+        assert_eq!(response.id, RequestId::String("0".to_string()));
+        peer.await.unwrap();
+    }
+
+    // Records the direction, raw form and kind of the traced messages
+    #[derive(Default)]
+    struct RecordMessages(std::sync::Mutex<Vec<(MessageDirection, String, &'static str)>>);
+
+    impl MessageTracer for RecordMessages {
+        fn trace(&self, message: &TracedMessage<'_>) {
+            let kind = match message.message {
+                Some(ParsedMessage::Client(ClientMessage::Request(_))) => "client request",
+                Some(ParsedMessage::Server(ServerMessage::Response(_))) => "server response",
+                Some(_) => "other",
+                None => "invalid",
+            };
+            self.0
+                .lock()
+                .unwrap()
+                .push((message.direction, message.raw.to_string(), kind));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_messages_are_traced() {
+        let tracer = Arc::new(RecordMessages::default());
+        let (_stream, sender, server_io, _shutdown_tx) =
+            create_client_stream_with(TransportOptions {
+                message_tracer: Some(tracer.clone()),
+                ..Default::default()
+            });
+        let (server_readable, mut server_writable) = tokio::io::split(server_io);
+
+        let response = r#"{"jsonrpc":"2.0","id":0,"result":{"tools":[]}}"#;
+        let peer = tokio::spawn(async move {
+            let mut lines = BufReader::new(server_readable).lines();
+            let request = lines.next_line().await.unwrap().unwrap();
+            server_writable
+                .write_all(format!("not json\n{}\n", response).as_bytes())
+                .await
+                .unwrap();
+            request
+        });
+
+        sender
+            .send(list_tools_request(), None)
+            .await
+            .unwrap()
+            .unwrap();
+        let request = peer.await.unwrap();
+
+        let traced = tracer.0.lock().unwrap().clone();
+        assert_eq!(
+            traced,
+            vec![
+                (MessageDirection::Outbound, request, "client request"),
+                (MessageDirection::Inbound, "not json".to_string(), "invalid"),
+                (
+                    MessageDirection::Inbound,
+                    response.to_string(),
+                    "server response"
+                ),
+            ]
+        );
     }
 }
@@ -1,21 +1,42 @@
 use async_trait::async_trait;
+use futures::Stream;
 use rust_mcp_schema::schema_utils::{
-    ClientMessage, FromMessage, MCPMessage, MessageFromClient, MessageFromServer, ServerMessage,
+    ClientMessage, FromMessage, MCPMessage, MessageFromClient, MessageFromServer, SdkError,
+    SdkErrorCodes, ServerMessage,
 };
 use rust_mcp_schema::{RequestId, RpcError};
+use std::collections::hash_map::Entry;
 use std::collections::HashMap;
+use std::future::Future;
 use std::pin::Pin;
 use std::sync::atomic::AtomicI64;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, SystemTime};
 use tokio::io::AsyncWriteExt;
-use tokio::sync::oneshot;
 use tokio::sync::Mutex;
+use tokio::sync::{mpsc, oneshot};
 
-use crate::error::TransportResult;
+use crate::error::{TransportError, TransportResult};
+use crate::message_tracer::{MessageDirection, MessageTracer, TraceableMessage, TracedMessage};
+use crate::pending_requests::{PendingRequests, SessionId};
+use crate::request_id::RequestIdStrategy;
 use crate::utils::await_timeout;
 use crate::McpDispatch;
 
+/// A stream of the responses received for a request sent with `send_sticky_request()`.
+pub type ResponseStream<R> = Pin<Box<dyn Stream<Item = R> + Send>>;
+
+/// The writer of the messages sent to the peer, shared by the dispatcher and the reader of the transport.
+pub(crate) type SharedWriter = Arc<Mutex<Pin<Box<dyn tokio::io::AsyncWrite + Send + Sync>>>>;
+
+/// Requests that accept multiple responses, mapped to the channel feeding their `ResponseStream`.
+pub type StickyRequests<R> = Arc<Mutex<HashMap<RequestId, mpsc::UnboundedSender<R>>>>;
+
+/// Requests sent with an idempotency token that are awaiting their response, mapped to the
+/// duplicate sends waiting for the same response.
+type DeduplicatedRequests<R> =
+    std::sync::Mutex<HashMap<String, Vec<oneshot::Sender<TransportResult<Option<R>>>>>>;
+
 /// Provides a dispatcher for sending MCP messages and handling responses.
 ///
 /// `MessageDispatcher` facilitates MCP communication by managing message sending, request tracking,
@@ -24,17 +45,24 @@ use crate::McpDispatch;
 /// (e.g., stdin/stdout) to serialize and send messages, and it tracks pending requests with
 /// a configurable timeout mechanism for asynchronous responses.
 pub struct MessageDispatcher<R> {
-    pending_requests: Arc<Mutex<HashMap<RequestId, oneshot::Sender<R>>>>,
-    writable_std: Mutex<Pin<Box<dyn tokio::io::AsyncWrite + Send + Sync>>>,
+    pending_requests: Arc<PendingRequests<R>>,
+    session_id: Option<SessionId>,
+    sticky_requests: StickyRequests<R>,
+    deduplicated_requests: DeduplicatedRequests<R>,
+    writable_std: SharedWriter,
     message_id_counter: Arc<AtomicI64>,
     timeout_msec: u64,
+    max_message_size: Option<usize>,
+    max_pending_requests: Option<usize>,
+    request_id_strategy: RequestIdStrategy,
+    message_tracer: Option<Arc<dyn MessageTracer>>,
 }
 
 impl<R> MessageDispatcher<R> {
     /// Creates a new `MessageDispatcher` instance with the given configuration.
     ///
     /// # Arguments
-    /// * `pending_requests` - A thread-safe map for storing pending request IDs and their response channels.
+    /// * `pending_requests` - The requests awaiting their response, possibly shared with other sessions.
     /// * `writable_std` - A mutex-protected, pinned writer (e.g., stdout) for sending serialized messages.
     /// * `message_id_counter` - An atomic counter for generating unique request IDs.
     /// * `timeout_msec` - The timeout duration in milliseconds for awaiting responses.
@@ -42,16 +70,158 @@ impl<R> MessageDispatcher<R> {
     /// # Returns
     /// A new `MessageDispatcher` instance configured for MCP message handling.
     pub fn new(
-        pending_requests: Arc<Mutex<HashMap<RequestId, oneshot::Sender<R>>>>,
+        pending_requests: Arc<PendingRequests<R>>,
         writable_std: Mutex<Pin<Box<dyn tokio::io::AsyncWrite + Send + Sync>>>,
         message_id_counter: Arc<AtomicI64>,
         timeout_msec: u64,
     ) -> Self {
         Self {
             pending_requests,
-            writable_std,
+            session_id: None,
+            sticky_requests: Arc::new(Mutex::new(HashMap::new())),
+            deduplicated_requests: std::sync::Mutex::new(HashMap::new()),
+            writable_std: Arc::new(writable_std),
             message_id_counter,
             timeout_msec,
+            max_message_size: None,
+            max_pending_requests: None,
+            request_id_strategy: RequestIdStrategy::default(),
+            message_tracer: None,
+        }
+    }
+
+    /// Shares the given map of sticky requests with the dispatcher, so that the reader of the
+    /// transport can deliver the responses of requests sent with `send_sticky_request()`.
+    pub fn with_sticky_requests(mut self, sticky_requests: StickyRequests<R>) -> Self {
+        self.sticky_requests = sticky_requests;
+        self
+    }
+
+    /// Sets the session the dispatcher sends messages to, its requests are registered in the
+    /// partition of that session of the pending requests, see `PendingRequests`.
+    pub fn with_session(mut self, session_id: impl Into<SessionId>) -> Self {
+        self.session_id = Some(session_id.into());
+        self
+    }
+
+    /// Sets the maximum size in bytes of the messages sent by the dispatcher, unlimited by default.
+    /// Sending a larger message fails with a `TransportError::MessageTooLarge` error.
+    pub fn with_max_message_size(mut self, max_message_size: Option<usize>) -> Self {
+        self.max_message_size = max_message_size;
+        self
+    }
+
+    /// Sets the maximum number of requests of the dispatcher awaiting their response, unlimited by
+    /// default. Sending another request fails with a `TransportError::TooManyPendingRequests` error.
+    pub fn with_max_pending_requests(mut self, max_pending_requests: Option<usize>) -> Self {
+        self.max_pending_requests = max_pending_requests;
+        self
+    }
+
+    /// Sets how the ids of the requests sent by the dispatcher are generated, see `RequestIdStrategy`.
+    pub fn with_request_id_strategy(mut self, request_id_strategy: RequestIdStrategy) -> Self {
+        self.request_id_strategy = request_id_strategy;
+        self
+    }
+
+    /// Sets the tracer receiving the messages sent by the dispatcher, see `MessageTracer`.
+    pub fn with_message_tracer(mut self, message_tracer: Option<Arc<dyn MessageTracer>>) -> Self {
+        self.message_tracer = message_tracer;
+        self
+    }
+
+    /// Returns the session the dispatcher sends messages to, `None` for single-session transports.
+    pub fn session_id(&self) -> Option<&str> {
+        self.session_id.as_deref()
+    }
+
+    /// Returns the writer of the messages sent by the dispatcher.
+    pub(crate) fn shared_writer(&self) -> SharedWriter {
+        Arc::clone(&self.writable_std)
+    }
+
+    /// Registers a request awaiting its response, returning the channel the response is received from.
+    ///
+    /// # Errors
+    /// Returns a `TransportError::TooManyPendingRequests` error if the maximum number of pending
+    /// requests is reached.
+    fn register_pending_request(
+        &self,
+        request_id: &RequestId,
+    ) -> TransportResult<PendingResponse<'_, R>> {
+        if let Some(max_pending_requests) = self.max_pending_requests {
+            if self.pending_requests.session_len(self.session_id()) >= max_pending_requests {
+                return Err(TransportError::TooManyPendingRequests {
+                    max_pending_requests,
+                });
+            }
+        }
+        let (tx_response, rx_response) = oneshot::channel::<R>();
+        self.pending_requests
+            .insert(self.session_id.clone(), request_id.clone(), tx_response);
+        Ok(PendingResponse {
+            pending_requests: &self.pending_requests,
+            request_id: request_id.clone(),
+            rx_response,
+        })
+    }
+
+    /// Serializes a message and writes it to the transport, followed by a new line.
+    ///
+    /// Nothing is written if the message exceeds the maximum message size.
+    /// The message is passed to the message tracer, if any, before it is written.
+    async fn write_message<M: serde::Serialize + TraceableMessage>(
+        &self,
+        writable_std: &mut Pin<Box<dyn tokio::io::AsyncWrite + Send + Sync>>,
+        message: &M,
+    ) -> TransportResult<()> {
+        let message_str = serde_json::to_string(message)
+            .map_err(|_| crate::error::TransportError::JsonrpcError(RpcError::parse_error()))?;
+
+        if let Some(max_size) = self.max_message_size {
+            if message_str.len() > max_size {
+                return Err(TransportError::MessageTooLarge {
+                    size: message_str.len(),
+                    max_size,
+                });
+            }
+        }
+
+        if let Some(message_tracer) = &self.message_tracer {
+            message_tracer.trace(&TracedMessage {
+                direction: MessageDirection::Outbound,
+                raw: &message_str,
+                message: Some(message.as_parsed()),
+                timestamp: SystemTime::now(),
+            });
+        }
+
+        writable_std.write_all(message_str.as_bytes()).await?;
+        writable_std.write_all(b"\n").await?; // new line
+        writable_std.flush().await?;
+        Ok(())
+    }
+
+    /// Awaits the response of a request for `timeout`, forgetting the request if it times out.
+    ///
+    /// The request fails with a connection closed error if it is dropped before its response is
+    /// received, e.g. when its session ends.
+    async fn await_response(
+        &self,
+        mut pending_response: PendingResponse<'_, R>,
+        timeout: Duration,
+    ) -> TransportResult<Option<R>> {
+        let response = async {
+            (&mut pending_response.rx_response)
+                .await
+                .map_err(|_| TransportError::SdkError(SdkError::connection_closed()))
+        };
+        match await_timeout(response, timeout).await {
+            Ok(response) => Ok(Some(response)),
+            Err(error) => Err(with_request_id(
+                error,
+                Some(pending_response.request_id.clone()),
+            )),
         }
     }
 
@@ -75,10 +245,10 @@ impl<R> MessageDispatcher<R> {
         if message.is_request() {
             // request_id should be None for requests
             assert!(request_id.is_none());
-            Some(RequestId::Integer(
-                self.message_id_counter
-                    .fetch_add(1, std::sync::atomic::Ordering::Relaxed),
-            ))
+            Some(
+                self.request_id_strategy
+                    .next_request_id(&self.message_id_counter),
+            )
         } else if !message.is_notification() {
             // `request_id` must not be `None` for errors, notifications and responses
             assert!(request_id.is_some());
@@ -87,30 +257,211 @@ impl<R> MessageDispatcher<R> {
             None
         }
     }
-}
 
-#[async_trait]
-impl McpDispatch<ServerMessage, MessageFromClient> for MessageDispatcher<ServerMessage> {
-    /// Sends a message from the client to the server and awaits a response if applicable.
+    /// Registers a new sticky request and writes it to the transport.
     ///
-    /// Serializes the `MessageFromClient` to JSON, writes it to the transport, and waits for a
-    /// `ServerMessage` response if the message is a request. Notifications and responses return
-    /// `Ok(None)`.
+    /// Unlike regular requests, every response or error received with the id of a sticky request
+    /// is delivered to the returned stream. The stream ends after an error is received, and the
+    /// request is forgotten once the stream is dropped. No timeout applies to sticky requests.
+    async fn send_sticky_message<M: serde::Serialize + TraceableMessage>(
+        &self,
+        build_message: impl FnOnce(RequestId) -> Result<M, RpcError>,
+    ) -> TransportResult<(RequestId, ResponseStream<R>)>
+    where
+        R: Send + 'static,
+    {
+        let mut writable_std = self.writable_std.lock().await;
+
+        let request_id = self
+            .request_id_strategy
+            .next_request_id(&self.message_id_counter);
+        let mpc_message = build_message(request_id.clone())?;
+
+        let (tx_response, rx_response) = mpsc::unbounded_channel::<R>();
+        self.sticky_requests
+            .lock()
+            .await
+            .insert(request_id.clone(), tx_response);
+
+        //serialize the message and write it to the writable_std
+        if let Err(error) = self.write_message(&mut writable_std, &mpc_message).await {
+            self.sticky_requests.lock().await.remove(&request_id);
+            return Err(error);
+        }
+
+        let stream = Box::pin(futures::stream::unfold(rx_response, |mut rx| async move {
+            rx.recv().await.map(|msg| (msg, rx))
+        }));
+        Ok((request_id, stream))
+    }
+
+    /// Runs `send` unless a request with the same idempotency token is already awaiting its response,
+    /// in which case that response is awaited instead, and no new request is written to the transport.
+    async fn send_deduplicated_message<F>(
+        &self,
+        idempotency_token: String,
+        send: F,
+    ) -> TransportResult<Option<R>>
+    where
+        F: Future<Output = TransportResult<Option<R>>>,
+        R: Clone,
+    {
+        let duplicate_of = {
+            let mut deduplicated_requests = self.deduplicated_requests.lock().unwrap();
+            match deduplicated_requests.entry(idempotency_token.clone()) {
+                Entry::Occupied(mut entry) => {
+                    let (tx_response, rx_response) = oneshot::channel();
+                    entry.get_mut().push(tx_response);
+                    Some(rx_response)
+                }
+                Entry::Vacant(entry) => {
+                    entry.insert(vec![]);
+                    None
+                }
+            }
+        };
+
+        if let Some(rx_response) = duplicate_of {
+            return await_timeout(rx_response, Duration::from_millis(self.timeout_msec)).await?;
+        }
+
+        // the token is released even if the send is cancelled, the duplicates then fail
+        // as their channel is dropped
+        let pending = PendingDeduplicatedRequest {
+            deduplicated_requests: &self.deduplicated_requests,
+            idempotency_token,
+        };
+        let result = send.await;
+        for tx_response in pending.release() {
+            let _ = tx_response.send(duplicate_result(&result));
+        }
+        result
+    }
+}
+
+/// A request awaiting its response, forgotten once dropped: after its response is received, once it
+/// timed out, or when the send is cancelled, so that the pending requests do not grow with requests
+/// that will never be awaited. A late response to a forgotten request is reported as unexpected.
+struct PendingResponse<'a, R> {
+    pending_requests: &'a PendingRequests<R>,
+    request_id: RequestId,
+    rx_response: oneshot::Receiver<R>,
+}
+
+impl<R> Drop for PendingResponse<'_, R> {
+    fn drop(&mut self) {
+        self.pending_requests.remove(&self.request_id);
+    }
+}
+
+/// Releases the idempotency token of a request once it completed, or was cancelled.
+struct PendingDeduplicatedRequest<'a, R> {
+    deduplicated_requests: &'a DeduplicatedRequests<R>,
+    idempotency_token: String,
+}
+
+impl<R> PendingDeduplicatedRequest<'_, R> {
+    /// Releases the idempotency token, returning the channels of the duplicate sends.
+    fn release(&self) -> Vec<oneshot::Sender<TransportResult<Option<R>>>> {
+        self.deduplicated_requests
+            .lock()
+            .unwrap()
+            .remove(&self.idempotency_token)
+            .unwrap_or_default()
+    }
+}
+
+impl<R> Drop for PendingDeduplicatedRequest<'_, R> {
+    fn drop(&mut self) {
+        self.release();
+    }
+}
+
+/// Adds the id of the request that timed out to the data of a request timeout error,
+/// as `requestId` next to `timeout`.
+fn with_request_id(error: TransportError, request_id: Option<RequestId>) -> TransportError {
+    match (error, request_id) {
+        (TransportError::SdkError(mut error), Some(request_id))
+            if error.code == i64::from(SdkErrorCodes::REQUEST_TIMEOUT) =>
+        {
+            if let (Some(serde_json::Value::Object(data)), Ok(request_id)) =
+                (error.data.as_mut(), serde_json::to_value(request_id))
+            {
+                data.insert("requestId".to_string(), request_id);
+            }
+            TransportError::SdkError(error)
+        }
+        (error, _) => error,
+    }
+}
+
+/// Copies the result of a request for its duplicates. Errors that can not be cloned
+/// are converted into a `TransportError::FromString` with the same message.
+fn duplicate_result<R: Clone>(result: &TransportResult<Option<R>>) -> TransportResult<Option<R>> {
+    match result {
+        Ok(response) => Ok(response.clone()),
+        Err(TransportError::JsonrpcError(error)) => Err(error.clone().into()),
+        Err(TransportError::SdkError(error)) => Err(SdkError {
+            code: error.code,
+            data: error.data.clone(),
+            message: error.message.clone(),
+        }
+        .into()),
+        Err(error) => Err(TransportError::FromString(error.to_string())),
+    }
+}
+
+impl MessageDispatcher<ServerMessage> {
+    /// Sends a request to the server, delivering all the responses received for it to a stream.
     ///
-    /// # Arguments
-    /// * `message` - The client message to send.
-    /// * `request_id` - An optional request ID (used for responses/errors, None for requests).
+    /// This is an opt-in mode for servers that stream incremental results as multiple responses
+    /// sharing the id of the request. Regular requests sent with `send()` resolve with the first response,
+    /// and any further response with the same id is discarded.
     ///
     /// # Returns
-    /// A `TransportResult` containing `Some(ServerMessage)` for requests with a response,
-    /// or `None` for notifications/responses, or an error if the operation fails.
+    /// The id of the request along with the stream of its responses.
+    pub async fn send_sticky_request(
+        &self,
+        message: MessageFromClient,
+    ) -> TransportResult<(RequestId, ResponseStream<ServerMessage>)> {
+        if !message.is_request() {
+            return Err(RpcError::invalid_request()
+                .with_message("Only requests can be sent as sticky requests.".to_string())
+                .into());
+        }
+        self.send_sticky_message(|request_id| {
+            ClientMessage::from_message(message, Some(request_id))
+        })
+        .await
+    }
+
+    /// Sends a request to the server unless a request sent with the same idempotency token is
+    /// still awaiting its response, in which case that response is returned instead of sending
+    /// the request again.
     ///
-    /// # Errors
-    /// Returns a `TransportError` if serialization, writing, or timeout occurs.
-    async fn send(
+    /// This allows retried sends of the same logical request to share a single request on the wire.
+    /// The token is released once the response is received, later sends with it are sent again.
+    pub async fn send_deduplicated(
+        &self,
+        message: MessageFromClient,
+        idempotency_token: impl Into<String>,
+    ) -> TransportResult<Option<ServerMessage>> {
+        if !message.is_request() {
+            return Err(RpcError::invalid_request()
+                .with_message("Only requests can be deduplicated.".to_string())
+                .into());
+        }
+        self.send_deduplicated_message(idempotency_token.into(), self.send(message, None))
+            .await
+    }
+
+    /// Sends a message to the server like `send()`, awaiting the response of a request for `timeout`
+    /// instead of the timeout of the transport.
+    pub async fn send_with_timeout(
         &self,
         message: MessageFromClient,
         request_id: Option<RequestId>,
+        timeout: Duration,
     ) -> TransportResult<Option<ServerMessage>> {
         let mut writable_std = self.writable_std.lock().await;
 
@@ -118,44 +469,144 @@ impl McpDispatch<ServerMessage, MessageFromClient> for MessageDispatcher<ServerM
         // a new requestId will be returned for Requests and Notification
         let outgoing_request_id = self.request_id_for_message(&message, request_id);
 
-        let rx_response: Option<tokio::sync::oneshot::Receiver<ServerMessage>> = {
-            // Store the sender in the pending requests map
-            if message.is_request() {
-                if let Some(request_id) = &outgoing_request_id {
-                    let (tx_response, rx_response) = oneshot::channel::<ServerMessage>();
-                    let mut pending_requests = self.pending_requests.lock().await;
-                    // store request id in the hashmap while waiting for a matching response
-                    pending_requests.insert(request_id.clone(), tx_response);
-                    Some(rx_response)
-                } else {
-                    None
-                }
-            } else {
-                None
+        // store the request id in the pending requests while waiting for a matching response
+        let rx_response = match &outgoing_request_id {
+            Some(request_id) if message.is_request() => {
+                Some(self.register_pending_request(request_id)?)
             }
+            _ => None,
         };
 
-        let mpc_message: ClientMessage = ClientMessage::from_message(message, outgoing_request_id)?;
+        let mpc_message: ClientMessage =
+            ClientMessage::from_message(message, outgoing_request_id.clone())?;
 
         //serialize the message and write it to the writable_std
-        let message_str = serde_json::to_string(&mpc_message)
-            .map_err(|_| crate::error::TransportError::JsonrpcError(RpcError::parse_error()))?;
+        // the request is forgotten with `rx_response` if it was not sent, it will not receive a response
+        self.write_message(&mut writable_std, &mpc_message).await?;
+        // other messages, e.g. the cancellation of this request, can be sent while awaiting its response
+        drop(writable_std);
 
-        writable_std.write_all(message_str.as_bytes()).await?;
-        writable_std.write_all(b"\n").await?; // new line
-        writable_std.flush().await?;
+        match rx_response {
+            Some(rx_response) => self.await_response(rx_response, timeout).await,
+            None => Ok(None),
+        }
+    }
+}
 
-        if let Some(rx) = rx_response {
-            match await_timeout(rx, Duration::from_millis(self.timeout_msec)).await {
-                Ok(response) => Ok(Some(response)),
-                Err(error) => Err(error),
+impl MessageDispatcher<ClientMessage> {
+    /// Sends a request to the client, delivering all the responses received for it to a stream.
+    ///
+    /// This is an opt-in mode for clients that stream incremental results as multiple responses
+    /// sharing the id of the request. Regular requests sent with `send()` resolve with the first response,
+    /// and any further response with the same id is discarded.
+    ///
+    /// # Returns
+    /// The id of the request along with the stream of its responses.
+    pub async fn send_sticky_request(
+        &self,
+        message: MessageFromServer,
+    ) -> TransportResult<(RequestId, ResponseStream<ClientMessage>)> {
+        if !message.is_request() {
+            return Err(RpcError::invalid_request()
+                .with_message("Only requests can be sent as sticky requests.".to_string())
+                .into());
+        }
+        self.send_sticky_message(|request_id| {
+            ServerMessage::from_message(message, Some(request_id))
+        })
+        .await
+    }
+
+    /// Sends a request to the client unless a request sent with the same idempotency token is
+    /// still awaiting its response, in which case that response is returned instead of sending
+    /// the request again.
+    ///
+    /// This allows retried sends of the same logical request to share a single request on the wire.
+    /// The token is released once the response is received, later sends with it are sent again.
+    pub async fn send_deduplicated(
+        &self,
+        message: MessageFromServer,
+        idempotency_token: impl Into<String>,
+    ) -> TransportResult<Option<ClientMessage>> {
+        if !message.is_request() {
+            return Err(RpcError::invalid_request()
+                .with_message("Only requests can be deduplicated.".to_string())
+                .into());
+        }
+        self.send_deduplicated_message(idempotency_token.into(), self.send(message, None))
+            .await
+    }
+
+    /// Sends a message to the client like `send()`, awaiting the response of a request for `timeout`
+    /// instead of the timeout of the transport.
+    pub async fn send_with_timeout(
+        &self,
+        message: MessageFromServer,
+        request_id: Option<RequestId>,
+        timeout: Duration,
+    ) -> TransportResult<Option<ClientMessage>> {
+        let mut writable_std = self.writable_std.lock().await;
+
+        // returns the request_id to be used to construct the message
+        // a new requestId will be returned for Requests and Notification
+        let outgoing_request_id = self.request_id_for_message(&message, request_id);
+
+        // store the request id in the pending requests while waiting for a matching response
+        let rx_response = match &outgoing_request_id {
+            Some(request_id) if message.is_request() => {
+                Some(self.register_pending_request(request_id)?)
             }
-        } else {
-            Ok(None)
+            _ => None,
+        };
+
+        let mpc_message: ServerMessage =
+            ServerMessage::from_message(message, outgoing_request_id.clone())?;
+
+        //serialize the message and write it to the writable_std
+        // the request is forgotten with `rx_response` if it was not sent, it will not receive a response
+        self.write_message(&mut writable_std, &mpc_message).await?;
+        // other messages, e.g. the cancellation of this request, can be sent while awaiting its response
+        drop(writable_std);
+
+        match rx_response {
+            Some(rx_response) => self.await_response(rx_response, timeout).await,
+            None => Ok(None),
         }
     }
 }
 
+#[async_trait]
+impl McpDispatch<ServerMessage, MessageFromClient> for MessageDispatcher<ServerMessage> {
+    /// Sends a message from the client to the server and awaits a response if applicable.
+    ///
+    /// Serializes the `MessageFromClient` to JSON, writes it to the transport, and waits for a
+    /// `ServerMessage` response if the message is a request. Notifications and responses return
+    /// `Ok(None)`.
+    ///
+    /// # Arguments
+    /// * `message` - The client message to send.
+    /// * `request_id` - An optional request ID (used for responses/errors, None for requests).
+    ///
+    /// # Returns
+    /// A `TransportResult` containing `Some(ServerMessage)` for requests with a response,
+    /// or `None` for notifications/responses, or an error if the operation fails.
+    ///
+    /// # Errors
+    /// Returns a `TransportError` if serialization, writing, or timeout occurs.
+    async fn send(
+        &self,
+        message: MessageFromClient,
+        request_id: Option<RequestId>,
+    ) -> TransportResult<Option<ServerMessage>> {
+        self.send_with_timeout(
+            message,
+            request_id,
+            Duration::from_millis(self.timeout_msec),
+        )
+        .await
+    }
+}
+
 #[async_trait]
 impl McpDispatch<ClientMessage, MessageFromServer> for MessageDispatcher<ClientMessage> {
     /// Sends a message from the server to the client and awaits a response if applicable.
@@ -179,46 +630,11 @@ impl McpDispatch<ClientMessage, MessageFromServer> for MessageDispatcher<ClientM
         message: MessageFromServer,
         request_id: Option<RequestId>,
     ) -> TransportResult<Option<ClientMessage>> {
-        let mut writable_std = self.writable_std.lock().await;
-
-        // returns the request_id to be used to construct the message
-        // a new requestId will be returned for Requests and Notification
-        let outgoing_request_id = self.request_id_for_message(&message, request_id);
-
-        let rx_response: Option<tokio::sync::oneshot::Receiver<ClientMessage>> = {
-            // Store the sender in the pending requests map
-            if message.is_request() {
-                if let Some(request_id) = &outgoing_request_id {
-                    let (tx_response, rx_response) = oneshot::channel::<ClientMessage>();
-                    let mut pending_requests = self.pending_requests.lock().await;
-                    // store request id in the hashmap while waiting for a matching response
-                    pending_requests.insert(request_id.clone(), tx_response);
-                    Some(rx_response)
-                } else {
-                    None
-                }
-            } else {
-                None
-            }
-        };
-
-        let mpc_message: ServerMessage = ServerMessage::from_message(message, outgoing_request_id)?;
-
-        //serialize the message and write it to the writable_std
-        let message_str = serde_json::to_string(&mpc_message)
-            .map_err(|_| crate::error::TransportError::JsonrpcError(RpcError::parse_error()))?;
-
-        writable_std.write_all(message_str.as_bytes()).await?;
-        writable_std.write_all(b"\n").await?; // new line
-        writable_std.flush().await?;
-
-        if let Some(rx) = rx_response {
-            match await_timeout(rx, Duration::from_millis(self.timeout_msec)).await {
-                Ok(response) => Ok(Some(response)),
-                Err(error) => Err(error),
-            }
-        } else {
-            Ok(None)
-        }
+        self.send_with_timeout(
+            message,
+            request_id,
+            Duration::from_millis(self.timeout_msec),
+        )
+        .await
     }
 }
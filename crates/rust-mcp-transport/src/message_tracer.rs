@@ -0,0 +1,84 @@
+use std::time::SystemTime;
+
+use rust_mcp_schema::schema_utils::{ClientMessage, ServerMessage};
+
+/// The direction of a traced message, relative to the local end of the transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageDirection {
+    /// The message was received from the peer
+    Inbound,
+    /// The message was sent to the peer
+    Outbound,
+}
+
+/// A message parsed by the transport, sent by either a client or a server.
+#[derive(Debug, Clone, Copy)]
+pub enum ParsedMessage<'a> {
+    Client(&'a ClientMessage),
+    Server(&'a ServerMessage),
+}
+
+/// A message going through the transport, as passed to a `MessageTracer`.
+#[derive(Debug, Clone, Copy)]
+pub struct TracedMessage<'a> {
+    /// Whether the message was received or sent
+    pub direction: MessageDirection,
+    /// The message as read from, or written to, the transport, without its line ending
+    pub raw: &'a str,
+    /// The parsed message, or `None` for a received message that could not be deserialized, and for
+    /// the parse error answering it if it is a request
+    pub message: Option<ParsedMessage<'a>>,
+    /// When the message was read from, or written to, the transport
+    pub timestamp: SystemTime,
+}
+
+/// A wire tap receiving every JSON-RPC message received or sent by a transport, in both its raw
+/// and parsed form, e.g. to debug interoperability issues or to build a protocol inspector.
+///
+/// A tracer is configured with `TransportOptions::message_tracer`. It is called synchronously by the
+/// transport, received messages before they are processed and sent messages before they are
+/// written, so it should return quickly.
+///
+/// Received messages that could not be deserialized are also traced, messages discarded for
+/// exceeding the maximum message size are not.
+///
+/// # Example
+/// ```ignore
+/// struct PrintMessages;
+///
+/// impl MessageTracer for PrintMessages {
+///     fn trace(&self, message: &TracedMessage<'_>) {
+///         let arrow = match message.direction {
+///             MessageDirection::Inbound => "<<",
+///             MessageDirection::Outbound => ">>",
+///         };
+///         eprintln!("{} {}", arrow, message.raw);
+///     }
+/// }
+///
+/// let transport = StdioTransport::new(TransportOptions {
+///     message_tracer: Some(Arc::new(PrintMessages)),
+///     ..Default::default()
+/// })?;
+/// ```
+pub trait MessageTracer: Send + Sync + 'static {
+    fn trace(&self, message: &TracedMessage<'_>);
+}
+
+/// A message that can be passed to a `MessageTracer`, implemented for `ClientMessage` and `ServerMessage`.
+pub trait TraceableMessage {
+    /// Returns the message as a `ParsedMessage`
+    fn as_parsed(&self) -> ParsedMessage<'_>;
+}
+
+impl TraceableMessage for ClientMessage {
+    fn as_parsed(&self) -> ParsedMessage<'_> {
+        ParsedMessage::Client(self)
+    }
+}
+
+impl TraceableMessage for ServerMessage {
+    fn as_parsed(&self) -> ParsedMessage<'_> {
+        ParsedMessage::Server(self)
+    }
+}
@@ -0,0 +1,174 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::AtomicI64;
+use std::sync::Arc;
+
+use rust_mcp_schema::RequestId;
+use tokio::sync::oneshot;
+
+/// Identifies a session of a transport serving multiple sessions, such as a multiplexer
+/// or an HTTP transport with one session per client.
+pub type SessionId = String;
+
+/// Requests awaiting their response, partitioned by session.
+///
+/// Single-session transports, such as `StdioTransport`, register their requests without a session.
+/// Transports serving multiple sessions share a `PendingRequests` between the dispatchers of their
+/// sessions, created with `MCPStream::create_for_session()`, so that:
+/// - a response is routed to the session that sent the request, and a response received from
+///   another session is not delivered to it
+/// - the pending requests of a session are dropped when it ends, failing with a connection closed
+///   error instead of waiting for their timeout
+///
+/// The dispatchers sharing a `PendingRequests` also share its request id counter, so that request ids are
+/// unique across sessions, and `session_of()` can route a response to its session from its id alone.
+pub struct PendingRequests<R> {
+    inner: std::sync::Mutex<PendingRequestsInner<R>>,
+    message_id_counter: Arc<AtomicI64>,
+}
+
+struct PendingRequestsInner<R> {
+    requests: HashMap<RequestId, PendingRequest<R>>,
+    sessions: HashMap<Option<SessionId>, HashSet<RequestId>>,
+}
+
+struct PendingRequest<R> {
+    session_id: Option<SessionId>,
+    tx_response: oneshot::Sender<R>,
+}
+
+impl<R> Default for PendingRequests<R> {
+    fn default() -> Self {
+        Self {
+            inner: std::sync::Mutex::new(PendingRequestsInner {
+                requests: HashMap::new(),
+                sessions: HashMap::new(),
+            }),
+            message_id_counter: Arc::new(AtomicI64::new(0)),
+        }
+    }
+}
+
+impl<R> PendingRequests<R> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the counter generating the ids of the requests registered in this map.
+    pub fn message_id_counter(&self) -> Arc<AtomicI64> {
+        self.message_id_counter.clone()
+    }
+
+    /// Registers a request sent to the given session, `None` for single-session transports.
+    pub fn insert(
+        &self,
+        session_id: Option<SessionId>,
+        request_id: RequestId,
+        tx_response: oneshot::Sender<R>,
+    ) {
+        let mut inner = self.inner.lock().unwrap();
+        inner
+            .sessions
+            .entry(session_id.clone())
+            .or_default()
+            .insert(request_id.clone());
+        // a request replaced with the same id is dropped, its sender fails
+        if let Some(replaced) = inner.requests.insert(
+            request_id.clone(),
+            PendingRequest {
+                session_id: session_id.clone(),
+                tx_response,
+            },
+        ) {
+            if replaced.session_id != session_id {
+                inner.forget(&replaced.session_id, &request_id);
+            }
+        }
+    }
+
+    /// Returns the session a pending request was sent to, `Some(None)` for requests sent without a
+    /// session, or `None` if no such request is pending.
+    pub fn session_of(&self, request_id: &RequestId) -> Option<Option<SessionId>> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .requests
+            .get(request_id)
+            .map(|pending_request| pending_request.session_id.clone())
+    }
+
+    /// Removes a pending request of the given session, returning the channel its response is sent to.
+    ///
+    /// Returns `None` if no such request is pending, or if it was sent to another session.
+    pub fn take(
+        &self,
+        session_id: Option<&str>,
+        request_id: &RequestId,
+    ) -> Option<oneshot::Sender<R>> {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.requests.get(request_id)?.session_id.as_deref() != session_id {
+            return None;
+        }
+        let pending_request = inner.requests.remove(request_id)?;
+        inner.forget(&pending_request.session_id, request_id);
+        Some(pending_request.tx_response)
+    }
+
+    /// Removes a pending request regardless of its session, e.g. once it timed out.
+    ///
+    /// Returns `true` if the request was pending.
+    pub fn remove(&self, request_id: &RequestId) -> bool {
+        let mut inner = self.inner.lock().unwrap();
+        match inner.requests.remove(request_id) {
+            Some(pending_request) => {
+                inner.forget(&pending_request.session_id, request_id);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Drops the pending requests of a session that ended, `None` for requests sent without a session.
+    /// Their senders fail with a connection closed error.
+    ///
+    /// Returns the number of requests that were pending.
+    pub fn end_session(&self, session_id: Option<&str>) -> usize {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(request_ids) = inner.sessions.remove(&session_id.map(str::to_string)) else {
+            return 0;
+        };
+        for request_id in request_ids.iter() {
+            inner.requests.remove(request_id);
+        }
+        request_ids.len()
+    }
+
+    /// Returns the number of pending requests of a session, `None` for requests sent without a session.
+    pub fn session_len(&self, session_id: Option<&str>) -> usize {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .sessions
+            .get(&session_id.map(str::to_string))
+            .map_or(0, HashSet::len)
+    }
+
+    /// Returns the number of pending requests, across all sessions.
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().requests.len()
+    }
+
+    /// Returns `true` if no request is pending.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<R> PendingRequestsInner<R> {
+    // removes a request from the partition of its session, and the partition once it is empty
+    fn forget(&mut self, session_id: &Option<SessionId>, request_id: &RequestId) {
+        if let Some(request_ids) = self.sessions.get_mut(session_id) {
+            request_ids.remove(request_id);
+            if request_ids.is_empty() {
+                self.sessions.remove(session_id);
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicI64, Ordering};
+
+use rust_mcp_schema::RequestId;
+
+/// How the ids of the requests sent by a transport are generated.
+///
+/// Both parties of a connection usually count their request ids from 0, so that their ids collide in
+/// logs, and some peers mis-correlate them. Prefixed counters and UUIDs keep the ids of each party
+/// distinct.
+///
+/// The counter of the ids is shared by the sessions of a transport serving multiple sessions, see
+/// `PendingRequests`, so that ids are unique across sessions with every strategy.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum RequestIdStrategy {
+    /// Integers counting from 0: `0`, `1`, `2`... (default)
+    #[default]
+    Counter,
+    /// Strings made of the prefix followed by a counter, e.g. `"client-0"`, `"client-1"`...
+    PrefixedCounter(String),
+    /// Random version 4 UUIDs, e.g. `"5f0c6b1e-3d2a-4c8e-9b71-0a4e2f6d8c13"`
+    Uuid,
+}
+
+impl RequestIdStrategy {
+    /// Returns the id of the next request, given the counter of the requests sent.
+    pub(crate) fn next_request_id(&self, counter: &AtomicI64) -> RequestId {
+        let count = counter.fetch_add(1, Ordering::Relaxed);
+        match self {
+            RequestIdStrategy::Counter => RequestId::Integer(count),
+            RequestIdStrategy::PrefixedCounter(prefix) => {
+                RequestId::String(format!("{}{}", prefix, count))
+            }
+            RequestIdStrategy::Uuid => RequestId::String(random_uuid(count)),
+        }
+    }
+}
+
+// Returns a random version 4 UUID, seeded by the randomly keyed hashers of the standard library
+fn random_uuid(salt: i64) -> String {
+    let random = |salt: u64| {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(salt);
+        hasher.finish()
+    };
+    let high = random(salt as u64);
+    let low = random(!(salt as u64));
+    // version 4, variant 1
+    let high = (high & 0xffff_ffff_ffff_0fff) | 0x0000_0000_0000_4000;
+    let low = (low & 0x3fff_ffff_ffff_ffff) | 0x8000_0000_0000_0000;
+    format!(
+        "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+        high >> 32,
+        (high >> 16) & 0xffff,
+        high & 0xffff,
+        low >> 48,
+        low & 0xffff_ffff_ffff
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generates_ids_with_each_strategy() {
+        let counter = AtomicI64::new(0);
+        assert!(matches!(
+            RequestIdStrategy::Counter.next_request_id(&counter),
+            RequestId::Integer(0)
+        ));
+        let prefixed = RequestIdStrategy::PrefixedCounter("client-".to_string());
+        assert!(
+            matches!(prefixed.next_request_id(&counter), RequestId::String(id) if id == "client-1")
+        );
+
+        let RequestId::String(first) = RequestIdStrategy::Uuid.next_request_id(&counter) else {
+            panic!("expected a string id");
+        };
+        let RequestId::String(second) = RequestIdStrategy::Uuid.next_request_id(&counter) else {
+            panic!("expected a string id");
+        };
+        assert_ne!(first, second);
+        let groups: Vec<_> = first.split('-').map(str::len).collect();
+        assert_eq!(groups, [8, 4, 4, 4, 12]);
+        assert_eq!(&first[14..15], "4");
+        assert!(matches!(&first[19..20], "8" | "9" | "a" | "b"));
+    }
+}
@@ -0,0 +1,226 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use tokio::process::Command;
+
+/// How the stderr output of the MCP server launched by a `StdioTransport` is handled.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum StderrMode {
+    /// Piped to the client, which passes each line to its handler's `handle_process_error` (default)
+    #[default]
+    Pipe,
+    /// Inherited from the current process, written to its stderr
+    Inherit,
+    /// Discarded
+    Discard,
+    /// Appended to the given file, created if it does not exist
+    File(PathBuf),
+}
+
+impl StderrMode {
+    fn to_stdio(&self) -> std::io::Result<Stdio> {
+        Ok(match self {
+            StderrMode::Pipe => Stdio::piped(),
+            StderrMode::Inherit => Stdio::inherit(),
+            StderrMode::Discard => Stdio::null(),
+            StderrMode::File(path) => std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)?
+                .into(),
+        })
+    }
+}
+
+/// Scheduling priority of a launched MCP server process.
+///
+/// On Unix, priorities map to nice values (19, 10, 0, -5 and -10), raising the priority above
+/// `Normal` usually requires elevated privileges. On Windows, they map to priority classes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessPriority {
+    Idle,
+    BelowNormal,
+    Normal,
+    AboveNormal,
+    High,
+}
+
+impl ProcessPriority {
+    #[cfg(unix)]
+    fn nice_value(&self) -> i32 {
+        match self {
+            ProcessPriority::Idle => 19,
+            ProcessPriority::BelowNormal => 10,
+            ProcessPriority::Normal => 0,
+            ProcessPriority::AboveNormal => -5,
+            ProcessPriority::High => -10,
+        }
+    }
+
+    // https://learn.microsoft.com/en-us/windows/win32/procthread/process-creation-flags
+    #[cfg(windows)]
+    fn priority_class(&self) -> u32 {
+        match self {
+            ProcessPriority::Idle => 0x00000040,
+            ProcessPriority::BelowNormal => 0x00004000,
+            ProcessPriority::Normal => 0x00000020,
+            ProcessPriority::AboveNormal => 0x00008000,
+            ProcessPriority::High => 0x00000080,
+        }
+    }
+}
+
+/// Configuration of the MCP server process launched by a `StdioTransport`, used to sandbox it.
+///
+/// # Example
+/// ```ignore
+/// let launch_options = ServerLaunchOptions {
+///     // only pass the variables the server needs
+///     clear_env: true,
+///     env: Some(HashMap::from([("PATH".to_string(), "/usr/bin".to_string())])),
+///     current_dir: Some("/srv/mcp".into()),
+///     priority: Some(ProcessPriority::BelowNormal),
+///     #[cfg(unix)]
+///     uid: Some(65534),
+///     ..Default::default()
+/// };
+/// let transport = StdioTransport::create_with_launch_options(
+///     "your-mcp-server",
+///     vec![],
+///     launch_options,
+///     TransportOptions::default(),
+/// )?;
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ServerLaunchOptions {
+    /// Environment variables set for the process
+    pub env: Option<HashMap<String, String>>,
+    /// Whether the environment of the current process is not inherited, only `env` is then set
+    pub clear_env: bool,
+    /// The working directory of the process, the one of the current process by default
+    pub current_dir: Option<PathBuf>,
+    /// How the stderr output of the process is handled
+    pub stderr_mode: StderrMode,
+    /// The scheduling priority of the process, inherited by default
+    pub priority: Option<ProcessPriority>,
+    /// The user id the process runs as, requires the privileges to change it
+    #[cfg(unix)]
+    pub uid: Option<u32>,
+    /// The group id the process runs as, requires the privileges to change it
+    #[cfg(unix)]
+    pub gid: Option<u32>,
+    /// Whether the command is passed directly to `CreateProcess` on Windows, rather than wrapped
+    /// with `cmd.exe /c`. Launches `.exe` servers without a shell, but `.cmd` and `.bat` scripts
+    /// such as `npx` then need to be launched with their extension. Has no effect on other platforms.
+    pub direct_launch: bool,
+}
+
+impl ServerLaunchOptions {
+    /// Applies the options to the command launching the MCP server.
+    pub(crate) fn apply(&self, command: &mut Command) -> std::io::Result<()> {
+        if self.clear_env {
+            command.env_clear();
+        }
+        if let Some(env) = self.env.as_ref() {
+            command.envs(env);
+        }
+        if let Some(current_dir) = self.current_dir.as_ref() {
+            command.current_dir(current_dir);
+        }
+        command.stderr(self.stderr_mode.to_stdio()?);
+
+        #[cfg(unix)]
+        {
+            if let Some(gid) = self.gid {
+                command.gid(gid);
+            }
+            if let Some(uid) = self.uid {
+                command.uid(uid);
+            }
+            if let Some(priority) = self.priority {
+                let nice_value = priority.nice_value();
+                // SAFETY: only calls `setpriority`, which is async-signal-safe
+                unsafe {
+                    command.pre_exec(move || {
+                        if libc::setpriority(libc::PRIO_PROCESS, 0, nice_value) != 0 {
+                            return Err(std::io::Error::last_os_error());
+                        }
+                        Ok(())
+                    });
+                }
+            }
+        }
+
+        #[cfg(windows)]
+        {
+            // CREATE_NO_WINDOW, combined with the priority class if any
+            let mut creation_flags = 0x08000000;
+            if let Some(priority) = self.priority {
+                creation_flags |= priority.priority_class();
+            }
+            command.creation_flags(creation_flags);
+        }
+
+        Ok(())
+    }
+
+    /// Returns `true` if the stderr output of the process is piped to the client.
+    pub(crate) fn pipes_stderr(&self) -> bool {
+        self.stderr_mode == StderrMode::Pipe
+    }
+}
+
+/// Builds the arguments of `cmd.exe` running the given command, passed as is to `CreateProcess`.
+///
+/// `/s` makes `cmd.exe` strip the outer quotes and run the rest verbatim. Arguments are quoted the way
+/// `CommandLineToArgvW` parses them, and the metacharacters of `cmd.exe` are escaped with `^`, so that
+/// paths with spaces and arguments with quotes or special characters reach the server unchanged.
+#[cfg(windows)]
+pub(crate) fn shell_command_line(program: &str, args: &[String]) -> String {
+    let mut command_line = escape_shell_metacharacters(program);
+    for arg in args {
+        command_line.push(' ');
+        command_line.push_str(&escape_shell_metacharacters(&quote_argument(arg)));
+    }
+    format!("/d /s /c \"{command_line}\"")
+}
+
+// quotes an argument, escaping its quotes and the backslashes preceding them
+#[cfg(windows)]
+fn quote_argument(arg: &str) -> String {
+    let mut quoted = String::with_capacity(arg.len() + 2);
+    quoted.push('"');
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        if c == '\\' {
+            backslashes += 1;
+            continue;
+        }
+        let escaped_backslashes = if c == '"' {
+            backslashes * 2 + 1
+        } else {
+            backslashes
+        };
+        quoted.push_str(&"\\".repeat(escaped_backslashes));
+        quoted.push(c);
+        backslashes = 0;
+    }
+    // backslashes preceding the closing quote are escaped too
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(windows)]
+fn escape_shell_metacharacters(value: &str) -> String {
+    const SHELL_METACHARACTERS: &str = "()[]%!^\"`<>&|;, *?";
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if SHELL_METACHARACTERS.contains(c) {
+            escaped.push('^');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
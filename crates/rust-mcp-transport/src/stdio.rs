@@ -3,15 +3,28 @@ use futures::Stream;
 use rust_mcp_schema::schema_utils::{MCPMessage, RPCMessage};
 use std::collections::HashMap;
 use std::pin::Pin;
-use tokio::process::{Child, Command};
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::io::AsyncWrite;
+use tokio::process::{Child, ChildStdin, Command};
 use tokio::sync::watch::Sender;
 use tokio::sync::{watch, Mutex};
+use tokio::time::Instant;
 
 use crate::error::{GenericWatchSendError, TransportError, TransportResult};
 use crate::mcp_stream::MCPStream;
 use crate::message_dispatcher::MessageDispatcher;
+use crate::message_tracer::TraceableMessage;
+#[cfg(windows)]
+use crate::server_launch_options::shell_command_line;
+use crate::server_launch_options::{ServerLaunchOptions, StderrMode};
+use crate::stdio_builder::StdioTransportBuilder;
 use crate::transport::Transport;
-use crate::{IoStream, McpDispatch, TransportOptions};
+use crate::{IoStream, McpDispatch, ProcessStatus, TransportOptions};
+
+/// Interval at which the launched MCP server is checked for its exit during the shutdown
+const EXIT_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 /// Implements a standard I/O transport for MCP communication.
 ///
@@ -23,8 +36,10 @@ use crate::{IoStream, McpDispatch, TransportOptions};
 pub struct StdioTransport {
     command: Option<String>,
     args: Option<Vec<String>>,
-    env: Option<HashMap<String, String>>,
+    launch_options: ServerLaunchOptions,
     process: Mutex<Option<Child>>,
+    // The stdin of the launched MCP server, closed first when the transport shuts down
+    process_stdin: Arc<std::sync::Mutex<Option<ChildStdin>>>,
     options: TransportOptions,
     shutdown_tx: tokio::sync::RwLock<Option<Sender<bool>>>,
     is_shut_down: Mutex<bool>,
@@ -48,8 +63,9 @@ impl StdioTransport {
             // when transport is used for MCP Server, we do not need a command
             args: None,
             command: None,
-            env: None,
+            launch_options: ServerLaunchOptions::default(),
             process: Mutex::new(None),
+            process_stdin: Arc::new(std::sync::Mutex::new(None)),
             options,
             shutdown_tx: tokio::sync::RwLock::new(None),
             is_shut_down: Mutex::new(false),
@@ -70,24 +86,89 @@ impl StdioTransport {
     /// # Returns
     /// A `TransportResult` containing the initialized `StdioTransport` instance, ready to launch
     /// the MCP server on `start`.
+    ///
+    /// See `StdioTransport::builder()` to pass the arguments and environment variables without
+    /// converting them to `String`.
     pub fn create_with_server_launch<C: Into<String>>(
         command: C,
         args: Vec<String>,
         env: Option<HashMap<String, String>>,
         options: TransportOptions,
+    ) -> TransportResult<Self> {
+        Self::builder(command)
+            .args(args)
+            .envs(env.into_iter().flatten())
+            .options(options)
+            .build()
+    }
+
+    /// Returns a builder of a `StdioTransport` launching the MCP server with the given command,
+    /// configuring its arguments, environment, launch options and transport options.
+    pub fn builder(command: impl Into<String>) -> StdioTransportBuilder {
+        StdioTransportBuilder::new(command)
+    }
+
+    /// Creates a new `StdioTransport` instance with a subprocess for MCP Client use, launched with
+    /// the given `ServerLaunchOptions`.
+    ///
+    /// # Arguments
+    /// * `command` - The command to execute (e.g., "rust-mcp-filesystem").
+    /// * `args` - Arguments to pass to the command. (e.g., "~/Documents").
+    /// * `launch_options` - How the subprocess is launched: environment, working directory, priority, user...
+    /// * `options` - Configuration options for the transport, including timeout settings.
+    ///
+    /// # Returns
+    /// A `TransportResult` containing the initialized `StdioTransport` instance, ready to launch
+    /// the MCP server on `start`.
+    pub fn create_with_launch_options<C: Into<String>>(
+        command: C,
+        args: Vec<String>,
+        launch_options: ServerLaunchOptions,
+        options: TransportOptions,
     ) -> TransportResult<Self> {
         Ok(Self {
-            // when transport is used for MCP Server, we do not need a command
             args: Some(args),
             command: Some(command.into()),
-            env,
+            launch_options,
             process: Mutex::new(None),
+            process_stdin: Arc::new(std::sync::Mutex::new(None)),
             options,
             shutdown_tx: tokio::sync::RwLock::new(None),
             is_shut_down: Mutex::new(false),
         })
     }
 
+    /// Sets how the stderr output of the launched MCP server is handled, `StderrMode::Pipe` by default.
+    ///
+    /// Chatty servers can be silenced with `StderrMode::Discard`, or have their output kept aside
+    /// with `StderrMode::File`. This has no effect when the transport is used for an MCP Server.
+    pub fn with_stderr_mode(mut self, stderr_mode: StderrMode) -> Self {
+        self.launch_options.stderr_mode = stderr_mode;
+        self
+    }
+
+    /// Returns the OS process id of the launched MCP server, `None` when the transport is used for an
+    /// MCP Server, before `start`, or once the process has exited and its status was retrieved.
+    pub async fn process_id(&self) -> Option<u32> {
+        self.process.lock().await.as_ref()?.id()
+    }
+
+    /// Sends a signal to the launched MCP server, e.g. `libc::SIGTERM` or `libc::SIGHUP`.
+    ///
+    /// The server is launched in its own process group, the signal is sent to the whole group, so
+    /// that the processes started by the server, such as the node process of `npx`, receive it too.
+    ///
+    /// # Errors
+    /// Returns a `TransportError::ProcessError` if no server process is running, or the OS error if
+    /// the signal could not be sent.
+    #[cfg(unix)]
+    pub async fn send_signal(&self, signal: i32) -> TransportResult<()> {
+        let process_id = self.process_id().await.ok_or_else(|| {
+            TransportError::ProcessError("The MCP server process is not running".to_string())
+        })?;
+        signal_process_group(process_id, signal).map_err(TransportError::StdioError)
+    }
+
     /// Sets the subprocess handle for the transport.
     async fn set_process(&self, value: Child) -> TransportResult<()> {
         let mut process = self.process.lock().await;
@@ -95,34 +176,53 @@ impl StdioTransport {
         Ok(())
     }
 
-    /// Retrieves the command and arguments for launching the subprocess.
-    ///
-    /// Adjusts the command based on the platform: on Windows, wraps it with `cmd.exe /c`.
+    // Waits for `timeout` for the launched MCP server to exit, returning `true` if it did. The process
+    // is polled, and only locked while its status is checked.
+    async fn wait_for_exit(&self, timeout: Duration) -> TransportResult<bool> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            let exited = match self.process.lock().await.as_mut() {
+                Some(p) => p.try_wait()?.is_some(),
+                None => true,
+            };
+            if exited {
+                return Ok(true);
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                return Ok(false);
+            }
+            tokio::time::sleep(EXIT_POLL_INTERVAL.min(deadline - now)).await;
+        }
+    }
+
+    /// Creates the command launching the subprocess.
     ///
-    /// # Returns
-    /// A tuple of the command string and its arguments.
-    fn launch_commands(&self) -> (String, Vec<std::string::String>) {
+    /// On Windows, the command is wrapped with `cmd.exe /c`, unless `direct_launch` is set in the
+    /// launch options.
+    fn launch_command(&self) -> Command {
+        let program = self.command.clone().unwrap_or_default();
+        let args = self.args.clone().unwrap_or_default();
+
         #[cfg(windows)]
         {
-            let command = "cmd.exe".to_string();
-            let mut command_args = vec!["/c".to_string(), self.command.clone().unwrap_or_default()];
-            command_args.extend(self.args.clone().unwrap_or_default());
-            (command, command_args)
+            if !self.launch_options.direct_launch {
+                let mut command = Command::new("cmd.exe");
+                command.raw_arg(shell_command_line(&program, &args));
+                return command;
+            }
         }
 
-        #[cfg(unix)]
-        {
-            let command = self.command.clone().unwrap_or_default();
-            let command_args = self.args.clone().unwrap_or_default();
-            (command, command_args)
-        }
+        let mut command = Command::new(program);
+        command.args(args);
+        command
     }
 }
 
 #[async_trait]
 impl<R, S> Transport<R, S> for StdioTransport
 where
-    R: RPCMessage + Clone + Send + Sync + serde::de::DeserializeOwned + 'static,
+    R: RPCMessage + TraceableMessage + Clone + Send + Sync + serde::de::DeserializeOwned + 'static,
     S: MCPMessage + Clone + Send + Sync + serde::Serialize + 'static,
 {
     /// Starts the transport, initializing streams and the message dispatcher.
@@ -154,19 +254,15 @@ where
         *lock = Some(shutdown_tx);
 
         if self.command.is_some() {
-            let (command_name, command_args) = self.launch_commands();
-
-            let mut command = Command::new(command_name);
+            let mut command = self.launch_command();
             command
-                .envs(self.env.as_ref().unwrap_or(&HashMap::new()))
-                .args(&command_args)
                 .stdout(std::process::Stdio::piped())
                 .stdin(std::process::Stdio::piped())
-                .stderr(std::process::Stdio::piped())
                 .kill_on_drop(true);
 
-            #[cfg(windows)]
-            command.creation_flags(0x08000000); // https://learn.microsoft.com/en-us/windows/win32/procthread/process-creation-flags
+            self.launch_options
+                .apply(&mut command)
+                .map_err(TransportError::StdioError)?;
 
             #[cfg(unix)]
             command.process_group(0);
@@ -183,18 +279,28 @@ where
                 .take()
                 .ok_or_else(|| TransportError::FromString("Unable to retrieve stdout.".into()))?;
 
-            let stderr = process
-                .stderr
-                .take()
-                .ok_or_else(|| TransportError::FromString("Unable to retrieve stderr.".into()))?;
+            // stderr is only available when piped, the error stream is empty otherwise
+            let stderr: Pin<Box<dyn tokio::io::AsyncRead + Send + Sync>> =
+                if self.launch_options.pipes_stderr() {
+                    Box::pin(process.stderr.take().ok_or_else(|| {
+                        TransportError::FromString("Unable to retrieve stderr.".into())
+                    })?)
+                } else {
+                    Box::pin(tokio::io::empty())
+                };
 
             self.set_process(process).await.unwrap();
+            if let Ok(mut process_stdin) = self.process_stdin.lock() {
+                *process_stdin = Some(stdin);
+            }
 
             let (stream, sender, error_stream) = MCPStream::create(
                 Box::pin(stdout),
-                Mutex::new(Box::pin(stdin)),
-                IoStream::Readable(Box::pin(stderr)),
-                self.options.timeout,
+                Mutex::new(Box::pin(ProcessInput {
+                    stdin: Arc::clone(&self.process_stdin),
+                })),
+                IoStream::Readable(stderr),
+                &self.options,
                 shutdown_rx,
             );
 
@@ -204,7 +310,7 @@ where
                 Box::pin(tokio::io::stdin()),
                 Mutex::new(Box::pin(tokio::io::stdout())),
                 IoStream::Writable(Box::pin(tokio::io::stderr())),
-                self.options.timeout,
+                &self.options,
                 shutdown_rx,
             );
 
@@ -218,9 +324,30 @@ where
         *result
     }
 
+    /// Returns the status of the launched MCP server process, `None` when used for an MCP Server.
+    ///
+    /// The exit status is only available once the process has exited, which usually follows
+    /// the end of its stdout stream closely.
+    async fn process_status(&self) -> Option<ProcessStatus> {
+        let mut process = self.process.lock().await;
+        match process.as_mut()?.try_wait() {
+            Ok(Some(status)) => Some(ProcessStatus::Exited(status)),
+            Ok(None) => Some(ProcessStatus::Running),
+            // the status can not be retrieved, the process is gone
+            Err(_) => None,
+        }
+    }
+
     // Shuts down the transport, terminating any subprocess and signaling closure.
     ///
-    /// Sends a shutdown signal via the watch channel and kills the subprocess if present.
+    /// Sends a shutdown signal via the watch channel and stops the subprocess if present, following
+    /// the shutdown sequence recommended by the MCP specification: its stdin is closed first, then it
+    /// is sent `SIGTERM` if it does not exit within `shutdown_grace_period`, and it is killed if it
+    /// does not exit within `terminate_timeout` either, see `TransportOptions`. On Unix, the signals
+    /// are sent to the process group of the server, so that the processes it started are stopped too.
+    ///
+    /// The process is not locked while it is given time to exit, `process_id()` and
+    /// `process_status()` remain available during the shutdown.
     ///
     /// # Returns
     /// A `TransportResult` indicating success or failure.
@@ -230,16 +357,196 @@ where
     async fn shut_down(&self) -> TransportResult<()> {
         let lock = self.shutdown_tx.write().await;
         if let Some(tx) = lock.as_ref() {
-            tx.send(true).map_err(GenericWatchSendError::new)?;
+            // flag the transport as shut down first, so that the end of the streams is not mistaken for a crash
             let mut lock = self.is_shut_down.lock().await;
-            *lock = true
+            let already_shut_down = std::mem::replace(&mut *lock, true);
+            drop(lock);
+            // shutting down again is a no-op, the streams are already closed
+            if !already_shut_down {
+                tx.send(true).map_err(GenericWatchSendError::new)?;
+            }
+        }
+
+        #[cfg_attr(not(unix), allow(unused_variables))]
+        let process_id = match self.process.lock().await.as_mut() {
+            Some(p) => match p.try_wait()? {
+                // the process already exited, e.g. the transport was shut down before
+                Some(_) => return Ok(()),
+                None => p.id(),
+            },
+            None => return Ok(()),
+        };
+
+        // closing its stdin asks the server to exit
+        if let Ok(mut process_stdin) = self.process_stdin.lock() {
+            process_stdin.take();
+        }
+        if self
+            .wait_for_exit(self.options.shutdown_grace_period)
+            .await?
+        {
+            return Ok(());
+        }
+
+        #[cfg(unix)]
+        if let Some(process_id) = process_id {
+            if signal_process_group(process_id, libc::SIGTERM).is_ok()
+                && self.wait_for_exit(self.options.terminate_timeout).await?
+            {
+                return Ok(());
+            }
         }
 
         let mut process = self.process.lock().await;
         if let Some(p) = process.as_mut() {
-            p.kill().await?;
+            if p.try_wait()?.is_some() {
+                return Ok(());
+            }
+            // the process has not been reaped while the lock is held, its process group still exists
+            #[cfg(unix)]
+            let group_killed = process_id
+                .is_some_and(|process_id| signal_process_group(process_id, libc::SIGKILL).is_ok());
+            #[cfg(not(unix))]
+            let group_killed = false;
+            if !group_killed {
+                p.kill().await?;
+            }
             p.wait().await?;
         }
         Ok(())
     }
 }
+
+// Writes to the stdin of the launched MCP server, until it is closed by the transport
+struct ProcessInput {
+    stdin: Arc<std::sync::Mutex<Option<ChildStdin>>>,
+}
+
+impl ProcessInput {
+    fn with_stdin<T>(
+        &self,
+        write: impl FnOnce(Pin<&mut ChildStdin>) -> Poll<std::io::Result<T>>,
+    ) -> Poll<std::io::Result<T>> {
+        let mut stdin = match self.stdin.lock() {
+            Ok(stdin) => stdin,
+            Err(_) => return Poll::Ready(Err(std::io::ErrorKind::BrokenPipe.into())),
+        };
+        match stdin.as_mut() {
+            Some(stdin) => write(Pin::new(stdin)),
+            None => Poll::Ready(Err(std::io::ErrorKind::BrokenPipe.into())),
+        }
+    }
+}
+
+impl AsyncWrite for ProcessInput {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        self.with_stdin(|stdin| stdin.poll_write(cx, buf))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.with_stdin(|stdin| stdin.poll_flush(cx))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        self.with_stdin(|stdin| stdin.poll_shutdown(cx))
+    }
+}
+
+// Sends a signal to the process group led by the given process
+#[cfg(unix)]
+fn signal_process_group(process_id: u32, signal: i32) -> std::io::Result<()> {
+    // SAFETY: `kill` only sends a signal, a negative pid targets the process group of the process
+    if unsafe { libc::kill(-(process_id as libc::pid_t), signal) } != 0 {
+        return Err(std::io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_mcp_schema::schema_utils::{MessageFromClient, ServerMessage};
+
+    // Returns `true` once a process has exited, including processes that were not reaped yet
+    #[cfg(target_os = "linux")]
+    fn has_exited(process_id: u32) -> bool {
+        match std::fs::read_to_string(format!("/proc/{}/stat", process_id)) {
+            // the state follows the command name, e.g. `42 (sleep) Z ...`
+            Ok(stat) => stat
+                .rsplit_once(") ")
+                .is_some_and(|(_, state)| state.starts_with('Z')),
+            Err(_) => true,
+        }
+    }
+
+    #[cfg(target_os = "linux")]
+    #[tokio::test]
+    async fn test_shut_down_kills_servers_ignoring_stdin_eof_and_sigterm() {
+        // the server and the process it starts ignore the end of their stdin and SIGTERM
+        let pid_file = std::env::temp_dir().join(format!(
+            "rust-mcp-transport-shut-down-{}",
+            std::process::id()
+        ));
+        let script = format!(
+            "trap '' TERM; sleep 30 & echo $! > {}; wait",
+            pid_file.display()
+        );
+        let transport = Arc::new(
+            StdioTransport::create_with_server_launch(
+                "sh",
+                vec!["-c".into(), script],
+                None,
+                TransportOptions {
+                    shutdown_grace_period: Duration::from_millis(200),
+                    terminate_timeout: Duration::from_millis(200),
+                    ..Default::default()
+                },
+            )
+            .unwrap(),
+        );
+        let _streams = Transport::<ServerMessage, MessageFromClient>::start(transport.as_ref())
+            .await
+            .unwrap();
+        let server_id = transport.process_id().await.unwrap();
+        let child_id = loop {
+            if let Some(child_id) = std::fs::read_to_string(&pid_file)
+                .ok()
+                .and_then(|content| content.trim().parse::<u32>().ok())
+            {
+                break child_id;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        };
+        let _ = std::fs::remove_file(&pid_file);
+
+        let shut_down = tokio::spawn({
+            let transport = Arc::clone(&transport);
+            async move {
+                Transport::<ServerMessage, MessageFromClient>::shut_down(transport.as_ref()).await
+            }
+        });
+
+        // the process is not locked while it is given time to exit
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let process_id = tokio::time::timeout(Duration::from_millis(50), transport.process_id());
+        assert_eq!(process_id.await.unwrap(), Some(server_id));
+
+        shut_down.await.unwrap().unwrap();
+        assert!(matches!(
+            Transport::<ServerMessage, MessageFromClient>::process_status(transport.as_ref()).await,
+            Some(ProcessStatus::Exited(_))
+        ));
+        // the process started by the server was killed along with it
+        for _ in 0..100 {
+            if has_exited(child_id) {
+                return;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        panic!("The process started by the server is still running");
+    }
+}
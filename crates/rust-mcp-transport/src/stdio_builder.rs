@@ -0,0 +1,126 @@
+use std::path::PathBuf;
+
+use crate::error::TransportResult;
+use crate::server_launch_options::{ProcessPriority, ServerLaunchOptions, StderrMode};
+use crate::{StdioTransport, TransportOptions};
+
+/// Builds a `StdioTransport` launching an MCP server, created with `StdioTransport::builder()`.
+///
+/// Arguments and environment variables are accepted as any iterator of values convertible to
+/// `String`, so that string literals can be passed without converting them.
+///
+/// # Example
+/// ```ignore
+/// let transport = StdioTransport::builder("npx")
+///     .args(["-y", "@modelcontextprotocol/server-everything"])
+///     .env("API_KEY", "A1B2C3D4E5F6G7H8I9J0K1L2M3N4O5P6")
+///     .stderr_mode(StderrMode::Discard)
+///     .options(TransportOptions {
+///         timeout: 60_000,
+///         ..Default::default()
+///     })
+///     .build()?;
+/// ```
+pub struct StdioTransportBuilder {
+    command: String,
+    args: Vec<String>,
+    launch_options: ServerLaunchOptions,
+    options: TransportOptions,
+}
+
+impl StdioTransportBuilder {
+    pub fn new(command: impl Into<String>) -> Self {
+        Self {
+            command: command.into(),
+            args: vec![],
+            launch_options: ServerLaunchOptions::default(),
+            options: TransportOptions::default(),
+        }
+    }
+
+    /// Adds an argument passed to the command.
+    pub fn arg(mut self, arg: impl Into<String>) -> Self {
+        self.args.push(arg.into());
+        self
+    }
+
+    /// Adds arguments passed to the command.
+    pub fn args<I>(mut self, args: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        self.args.extend(args.into_iter().map(Into::into));
+        self
+    }
+
+    /// Sets an environment variable of the launched server.
+    pub fn env(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.envs([(key, value)])
+    }
+
+    /// Sets environment variables of the launched server.
+    pub fn envs<I, K, V>(mut self, vars: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.launch_options
+            .env
+            .get_or_insert_with(Default::default)
+            .extend(
+                vars.into_iter()
+                    .map(|(key, value)| (key.into(), value.into())),
+            );
+        self
+    }
+
+    /// Does not pass the environment of the current process to the launched server, only the
+    /// variables set with `env()` and `envs()`.
+    pub fn env_clear(mut self) -> Self {
+        self.launch_options.clear_env = true;
+        self
+    }
+
+    /// Sets the working directory of the launched server.
+    pub fn current_dir(mut self, current_dir: impl Into<PathBuf>) -> Self {
+        self.launch_options.current_dir = Some(current_dir.into());
+        self
+    }
+
+    /// Sets how the stderr output of the launched server is handled, `StderrMode::Pipe` by default.
+    pub fn stderr_mode(mut self, stderr_mode: StderrMode) -> Self {
+        self.launch_options.stderr_mode = stderr_mode;
+        self
+    }
+
+    /// Sets the scheduling priority of the launched server.
+    pub fn priority(mut self, priority: ProcessPriority) -> Self {
+        self.launch_options.priority = Some(priority);
+        self
+    }
+
+    /// Sets all the launch options at once, replacing the environment, working directory, stderr
+    /// mode and priority set previously.
+    pub fn launch_options(mut self, launch_options: ServerLaunchOptions) -> Self {
+        self.launch_options = launch_options;
+        self
+    }
+
+    /// Sets the configuration options of the transport, including timeout settings.
+    pub fn options(mut self, options: TransportOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Creates the transport, ready to launch the MCP server on `start`.
+    pub fn build(self) -> TransportResult<StdioTransport> {
+        StdioTransport::create_with_launch_options(
+            self.command,
+            self.args,
+            self.launch_options,
+            self.options,
+        )
+    }
+}
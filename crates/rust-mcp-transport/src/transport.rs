@@ -1,15 +1,30 @@
 use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
 
 use async_trait::async_trait;
 use rust_mcp_schema::{schema_utils::MCPMessage, RequestId};
 
 use futures::Stream;
 
-use crate::{error::TransportResult, message_dispatcher::MessageDispatcher};
+use crate::{
+    envelope::EnvelopeValidation, error::TransportResult,
+    malformed_message::MalformedMessageHandler, message_dispatcher::MessageDispatcher,
+    message_tracer::MessageTracer, request_id::RequestIdStrategy,
+};
 
 /// Default Timeout in milliseconds
 const DEFAULT_TIMEOUT_MSEC: u64 = 60_000;
 
+/// Default time given to a launched MCP server to exit once its stdin is closed
+pub const DEFAULT_SHUTDOWN_GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// Default time given to a launched MCP server to exit once it is sent `SIGTERM`
+pub const DEFAULT_TERMINATE_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Default number of received messages awaiting their consumption, see `TransportOptions::max_queued_messages`
+pub const DEFAULT_MAX_QUEUED_MESSAGES: usize = 1024;
+
 /// Enum representing a stream that can either be readable or writable.
 /// This allows the reuse of the same traits for both MCP Server and MCP Client,
 /// where the data direction is reversed.
@@ -23,6 +38,15 @@ pub enum IoStream {
     Writable(Pin<Box<dyn tokio::io::AsyncWrite + Send + Sync>>),
 }
 
+/// Status of the MCP server process launched by a transport.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessStatus {
+    /// The process is still running
+    Running,
+    /// The process exited with the given status
+    Exited(std::process::ExitStatus),
+}
+
 /// Configuration for the transport layer
 pub struct TransportOptions {
     /// The timeout in milliseconds for requests.
@@ -30,11 +54,58 @@ pub struct TransportOptions {
     /// This value defines the maximum amount of time to wait for a response before
     /// considering the request as timed out.
     pub timeout: u64,
+    /// How strictly the JSON-RPC envelope of received messages is validated, strict by default.
+    pub envelope_validation: EnvelopeValidation,
+    /// The maximum size in bytes of a message, unlimited by default.
+    ///
+    /// Received messages exceeding it are discarded without being buffered, so that a misbehaving peer
+    /// can not exhaust the memory of the process with a single gigantic message. Sending a message
+    /// exceeding it fails with a `TransportError::MessageTooLarge` error.
+    pub max_message_size: Option<usize>,
+    /// A wire tap receiving every message received or sent by the transport, none by default.
+    pub message_tracer: Option<Arc<dyn MessageTracer>>,
+    /// Receives the lines received from the peer that could not be deserialized into a message,
+    /// which are reported on stderr by default, see `MalformedMessageHandler`.
+    pub malformed_message_handler: Option<Arc<dyn MalformedMessageHandler>>,
+    /// The maximum number of requests sent that can await their response at the same time,
+    /// unlimited by default. Sending another request fails with a
+    /// `TransportError::TooManyPendingRequests` error, without sending it.
+    pub max_pending_requests: Option<usize>,
+    /// How the ids of the requests sent are generated, integers counting from 0 by default.
+    pub request_id_strategy: RequestIdStrategy,
+    /// How long the MCP server launched by a `StdioTransport` is given to exit once its stdin is
+    /// closed when the transport shuts down, before it is sent `SIGTERM`, e.g. to flush its state.
+    ///
+    /// Defaults to `DEFAULT_SHUTDOWN_GRACE_PERIOD`.
+    pub shutdown_grace_period: Duration,
+    /// How long the launched MCP server is given to exit once it is sent `SIGTERM`, before it is
+    /// killed. On Windows, where it can not be asked to terminate, it is killed right away instead.
+    ///
+    /// Defaults to `DEFAULT_TERMINATE_TIMEOUT`.
+    pub terminate_timeout: Duration,
+    /// The maximum number of received requests, notifications and errors awaiting their consumption
+    /// from the stream of the received messages.
+    ///
+    /// Once it is reached, the transport stops reading from the peer until a message is consumed,
+    /// so that a peer flooding the transport can not exhaust the memory of the process. Responses are
+    /// delivered to their pending requests without being queued, but are not read either in the meantime.
+    ///
+    /// Defaults to `DEFAULT_MAX_QUEUED_MESSAGES`.
+    pub max_queued_messages: usize,
 }
 impl Default for TransportOptions {
     fn default() -> Self {
         Self {
             timeout: DEFAULT_TIMEOUT_MSEC,
+            envelope_validation: EnvelopeValidation::default(),
+            max_message_size: None,
+            message_tracer: None,
+            malformed_message_handler: None,
+            max_pending_requests: None,
+            request_id_strategy: RequestIdStrategy::default(),
+            shutdown_grace_period: DEFAULT_SHUTDOWN_GRACE_PERIOD,
+            terminate_timeout: DEFAULT_TERMINATE_TIMEOUT,
+            max_queued_messages: DEFAULT_MAX_QUEUED_MESSAGES,
         }
     }
 }
@@ -115,4 +186,10 @@ where
         MessageDispatcher<R>: McpDispatch<R, S>;
     async fn shut_down(&self) -> TransportResult<()>;
     async fn is_shut_down(&self) -> bool;
+
+    /// Returns the status of the MCP server process launched by the transport,
+    /// or `None` if the transport did not launch a process.
+    async fn process_status(&self) -> Option<ProcessStatus> {
+        None
+    }
 }
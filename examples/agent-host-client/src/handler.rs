@@ -0,0 +1,71 @@
+use async_trait::async_trait;
+use colored::Colorize;
+use rust_mcp_schema::{LoggingMessageNotification, ProgressNotification, RpcError};
+use rust_mcp_sdk::{
+    mcp_client::{ClientHandler, Progress},
+    McpClient,
+};
+
+/// Width of the progress bar rendered for progress notifications
+const PROGRESS_BAR_WIDTH: usize = 30;
+
+// Handler used for every server connected to the host.
+// Each connection gets its own instance, so messages can be attributed to the server that sent them.
+// Sampling requests are not handled here, but by the sampling handler registered for the connection (see `src/llm.rs`).
+pub struct AgentHostHandler {
+    pub server_name: String,
+}
+
+// To check out a list of all the methods in the trait that you can override, take a look at
+// https://github.com/rust-mcp-stack/rust-mcp-sdk/blob/main/crates/rust-mcp-sdk/src/mcp_handlers/mcp_client_handler.rs
+
+#[async_trait]
+impl ClientHandler for AgentHostHandler {
+    /// Renders progress notifications as a progress bar.
+    async fn handle_progress_notification(
+        &self,
+        notification: ProgressNotification,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        let progress = Progress::from(notification.params);
+        let line = match progress.percent() {
+            Some(percent) => {
+                let filled = (percent / 100.0 * PROGRESS_BAR_WIDTH as f64).round() as usize;
+                format!(
+                    "[{}{}] {:>3.0}%",
+                    "#".repeat(filled),
+                    " ".repeat(PROGRESS_BAR_WIDTH - filled),
+                    percent
+                )
+            }
+            None => format!("{} step(s) completed", progress.current),
+        };
+        println!("[{}] {}", self.server_name, line.cyan());
+        Ok(())
+    }
+
+    async fn handle_logging_message_notification(
+        &self,
+        notification: LoggingMessageNotification,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        println!(
+            "{}",
+            format!(
+                "[{}] log ({:?}): {}",
+                self.server_name, notification.params.level, notification.params.data
+            )
+            .bright_black()
+        );
+        Ok(())
+    }
+
+    async fn handle_process_error(
+        &self,
+        _error_message: String,
+        _runtime: &dyn McpClient,
+    ) -> std::result::Result<(), RpcError> {
+        // servers write their diagnostics to stderr, keep the output of the host readable
+        Ok(())
+    }
+}
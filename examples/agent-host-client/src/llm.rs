@@ -0,0 +1,83 @@
+//! A stand-in for a local language model, used to answer sampling requests from servers.
+
+use std::sync::Arc;
+
+use colored::Colorize;
+use rust_mcp_schema::{
+    CreateMessageRequestParams, CreateMessageResult, CreateMessageResultContent, Role,
+    SamplingMessageContent, TextContent,
+};
+use rust_mcp_sdk::mcp_client::{sampling_fn, SamplingHandler};
+
+pub struct LocalLlmStub {
+    pub model_name: String,
+}
+
+impl LocalLlmStub {
+    pub fn new<T: Into<String>>(model_name: T) -> Self {
+        Self {
+            model_name: model_name.into(),
+        }
+    }
+
+    /// Returns a sampling handler forwarding the sampling requests of `server_name` to the model.
+    pub fn sampling_handler<T: Into<String>>(
+        self: &Arc<Self>,
+        server_name: T,
+    ) -> impl SamplingHandler {
+        let llm = Arc::clone(self);
+        let server_name = server_name.into();
+        sampling_fn(move |params| {
+            let llm = Arc::clone(&llm);
+            println!(
+                "{}",
+                format!(
+                    "[{}] sampling request received ({} message(s), max {} tokens)",
+                    server_name,
+                    params.messages.len(),
+                    params.max_tokens
+                )
+                .magenta()
+            );
+            async move { Ok(llm.complete(&params).await) }
+        })
+    }
+
+    /// Produces a canned completion for the last user message of a sampling request.
+    ///
+    /// A real host would forward the messages, system prompt and model preferences to an
+    /// actual model here, after asking the user to approve the request.
+    pub async fn complete(&self, params: &CreateMessageRequestParams) -> CreateMessageResult {
+        let prompt = params
+            .messages
+            .iter()
+            .rev()
+            .find(|message| message.role == Role::User)
+            .and_then(|message| match &message.content {
+                SamplingMessageContent::TextContent(text_content) => {
+                    Some(text_content.text.as_str())
+                }
+                SamplingMessageContent::ImageContent(_) => None,
+            })
+            .unwrap_or("<no text prompt>");
+
+        let mut answer = format!("[{}] You asked: \"{}\".", self.model_name, prompt);
+        // honour the token budget of the request, counting one token per word
+        let max_words = params.max_tokens.max(1) as usize;
+        if answer.split_whitespace().count() > max_words {
+            answer = answer
+                .split_whitespace()
+                .take(max_words)
+                .collect::<Vec<_>>()
+                .join(" ");
+        }
+
+        CreateMessageResult {
+            content: CreateMessageResultContent::TextContent(TextContent::new(answer, None)),
+            meta: None,
+            model: self.model_name.clone(),
+            role: Role::Assistant,
+            stop_reason: Some("endTurn".to_string()),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+mod handler;
+mod llm;
+mod pool;
+mod supervisor;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use colored::Colorize;
+use handler::AgentHostHandler;
+use llm::LocalLlmStub;
+use pool::ServerPool;
+use rust_mcp_schema::{
+    CallToolRequestParams, CallToolResult, CancelledNotification, CancelledNotificationParams,
+    InitializeRequestParams, ProgressToken, ReadResourceRequestParams,
+};
+use rust_mcp_sdk::error::{McpSdkError, SdkResult};
+use rust_mcp_sdk::mcp_client::{
+    CallToolRequestParamsExt, CallToolResultExt, ClientRuntime, ResourceDispatcher,
+};
+use rust_mcp_sdk::request_context::RequestOptions;
+use rust_mcp_sdk::McpClient;
+use serde_json::json;
+use supervisor::{RestartPolicy, ServerSpec, SupervisorEvent};
+
+const EVERYTHING_SERVER: &str = "@modelcontextprotocol/server-everything";
+/// How long the host is willing to wait for the long running operation before giving up on it
+const LONG_OPERATION_DEADLINE: Duration = Duration::from_secs(3);
+/// Number of times a server is restarted before giving up on it
+const MAX_RESTARTS: u32 = 3;
+/// Delay before the first restart of a server, doubled for each following one
+const RESTART_BACKOFF: Duration = Duration::from_millis(500);
+
+#[tokio::main]
+async fn main() -> SdkResult<()> {
+    // Step1 : Define client details and capabilities.
+    // The host advertises sampling support, so servers are allowed to send `sampling/createMessage` requests.
+    let client_details: InitializeRequestParams = ClientRuntime::info("agent-host-client", "0.1.0")
+        .with_sampling()
+        .build();
+
+    // Step2 : Create the model used to answer sampling requests, shared by all connections
+    let llm = Arc::new(LocalLlmStub::new("local-llm-stub"));
+
+    // Step3 : Connect to two servers through the pool, which supervises their processes:
+    // - @modelcontextprotocol/server-everything (needs node.js and npm to be installed)
+    // - the sqlite-mcp-server example of this repository, launched with cargo
+    let pool = ServerPool::new(client_details);
+    let events = tokio::spawn(print_events(pool.events()));
+
+    for (name, spec) in [
+        (
+            "everything",
+            // restarted if it crashes or stops responding
+            ServerSpec::new("npx", vec!["-y".into(), EVERYTHING_SERVER.into()])
+                .with_restart_policy(RestartPolicy::OnFailure {
+                    max_restarts: MAX_RESTARTS,
+                    backoff: RESTART_BACKOFF,
+                }),
+        ),
+        (
+            "sqlite",
+            ServerSpec::new(
+                "cargo",
+                vec![
+                    "run".into(),
+                    "-q".into(),
+                    "-p".into(),
+                    "sqlite-mcp-server".into(),
+                ],
+            )
+            // restarted whenever it exits
+            .with_restart_policy(RestartPolicy::Always {
+                max_restarts: MAX_RESTARTS,
+                backoff: RESTART_BACKOFF,
+            }),
+        ),
+    ] {
+        let llm = Arc::clone(&llm);
+        pool.connect(
+            name,
+            spec,
+            move || AgentHostHandler {
+                server_name: name.into(),
+            },
+            move || llm.sampling_handler(name),
+        )
+        .await?;
+    }
+
+    // Step4 : Display the tools offered by all servers of the pool
+    print_header("Tools");
+    for (server_name, tool) in pool.list_all_tools().await? {
+        println!(
+            "{} {}",
+            format!("[{}]", server_name).yellow(),
+            tool.name.cyan()
+        );
+    }
+
+    // Step5 : Display the resources of the sqlite server.
+    // They are listed a few at a time by the server, list_all_resources() follows the cursors until the last page.
+    print_header("Resources");
+    if let Some(client) = pool.get("sqlite") {
+        let resources = client.list_all_resources().await?;
+        for resource in &resources {
+            println!("{} {}", resource.name.cyan(), resource.uri);
+        }
+
+        // Read the first resource, and present its contents depending on their mime type
+        if let Some(resource) = resources.first() {
+            let result = client
+                .read_resource(ReadResourceRequestParams {
+                    uri: resource.uri.clone(),
+                })
+                .await?;
+            for presented in resource_presenter().dispatch(&result)? {
+                println!("{}", presented);
+            }
+        }
+    }
+
+    // Step6 : Call a tool that makes the server sample the host's model.
+    // The `sampling/createMessage` request is handled by the sampling handler of the connection, which forwards it to the stub.
+    print_header("Sampling");
+    if let Some(client) = pool.client_for_tool("sampleLLM").await? {
+        let result = client
+            .call_tool(CallToolRequestParams::with_args(
+                "sampleLLM",
+                &json!({"prompt": "What is the Model Context Protocol?", "maxTokens": 50}),
+            )?)
+            .await?;
+        print_result(&result);
+    }
+
+    // Step7 : Call a tool on the second server, selected by tool name
+    print_header("Query");
+    if let Some(client) = pool.client_for_tool("query").await? {
+        let result = client
+            .call_tool(CallToolRequestParams::with_args(
+                "query",
+                &json!({"sql": "SELECT title, year FROM books ORDER BY year", "limit": 3}),
+            )?)
+            .await?;
+        print_result(&result);
+    }
+
+    // Step8 : Start a long running operation, rendering its progress notifications,
+    // and cancel it if it does not complete before the deadline.
+    // The progress token asks the server for progress notifications, rendered by the handler of the connection.
+    print_header("Cancellation");
+    if let Some(client) = pool.client_for_tool("longRunningOperation").await? {
+        let client = client.scoped().with_timeout(LONG_OPERATION_DEADLINE);
+        let options = RequestOptions::new()
+            .with_progress_token(ProgressToken::String("long-running-operation".into()));
+        let call = client.call_tool_with_options(
+            CallToolRequestParams::with_args(
+                "longRunningOperation",
+                &json!({"duration": 10, "steps": 5}),
+            )?,
+            options,
+        );
+        match call.await {
+            Ok(result) => print_result(&result),
+            // let the server know that the result is no longer awaited, so that it stops the operation
+            Err(McpSdkError::RequestTimeout {
+                id: Some(request_id),
+                ..
+            }) => {
+                client
+                    .send_notification(
+                        CancelledNotification::new(CancelledNotificationParams {
+                            reason: Some("The deadline of the host has passed".into()),
+                            request_id,
+                        })
+                        .into(),
+                    )
+                    .await?;
+                println!(
+                    "{}",
+                    format!(
+                        "Operation did not complete within {:?}, it has been cancelled.",
+                        LONG_OPERATION_DEADLINE
+                    )
+                    .red()
+                );
+            }
+            Err(error) => return Err(error),
+        }
+    }
+
+    // Step9 : Display the consolidated status of the servers
+    print_header("Status");
+    let status = pool.status();
+    println!("health: {:?}", status.health);
+    for (server_name, server_status) in &status.servers {
+        println!(
+            "{} {:?}, {} restart(s), up for {:.1?}",
+            format!("[{}]", server_name).yellow(),
+            server_status.state,
+            server_status.restarts,
+            server_status.started_at.elapsed()
+        );
+        if let Some(last_exit) = &server_status.last_exit {
+            println!("  last exit: {}", last_exit);
+        }
+    }
+
+    pool.shut_down().await?;
+    events.abort();
+    Ok(())
+}
+
+// Prints the events of the supervised servers, such as their exits and restarts
+async fn print_events(mut events: tokio::sync::broadcast::Receiver<SupervisorEvent>) {
+    while let Ok(event) = events.recv().await {
+        println!("{}", format!("[supervisor] {}", event).bright_black());
+    }
+}
+
+// Presents the contents of resources as text, depending on their mime type
+fn resource_presenter() -> ResourceDispatcher<String> {
+    ResourceDispatcher::new()
+        .on_json(|contents| match contents.json() {
+            Ok(value) => serde_json::to_string_pretty(&value).unwrap_or_default(),
+            Err(error) => format!("invalid JSON: {}", error.message),
+        })
+        .on_text(|contents| contents.text().unwrap_or_default().to_string())
+        .on_image(|contents| {
+            format!(
+                "[image: {}, {} bytes]",
+                contents.mime_type,
+                contents.bytes().len()
+            )
+        })
+        .fallback(|contents| format!("[{}: {} bytes]", contents.mime_type, contents.bytes().len()))
+}
+
+fn print_header(title: &str) {
+    println!("\n{}", format!("=== {} ===", title).bold());
+}
+
+fn print_result(result: &CallToolResult) {
+    println!("{}", result.render_text().green());
+}
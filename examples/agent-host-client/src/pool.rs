@@ -0,0 +1,106 @@
+//! A minimal pool of MCP clients, one for each server the host is connected to.
+//! Server processes are supervised, and restarted according to their restart policy.
+
+use std::sync::Arc;
+
+use rust_mcp_schema::{InitializeRequestParams, Tool};
+use rust_mcp_sdk::error::SdkResult;
+use rust_mcp_sdk::mcp_client::{client_runtime, ClientHandler, ClientRuntime, SamplingHandler};
+use rust_mcp_sdk::McpClient;
+use tokio::sync::broadcast;
+
+use crate::supervisor::{Launcher, PoolStatus, ServerSpec, Supervisor, SupervisorEvent};
+
+pub struct ServerPool {
+    client_details: InitializeRequestParams,
+    supervisor: Arc<Supervisor>,
+}
+
+impl ServerPool {
+    pub fn new(client_details: InitializeRequestParams) -> Self {
+        Self {
+            client_details,
+            supervisor: Supervisor::new(),
+        }
+    }
+
+    /// Launches a server as described by `spec`, initializes it, and adds it to the pool under `name`.
+    /// Each process of the server gets a new handler from `handler`, and a new sampling handler
+    /// fulfilling its sampling requests from `sampling_handler`.
+    pub async fn connect<N, H, S>(
+        &self,
+        name: N,
+        spec: ServerSpec,
+        handler: impl Fn() -> H + Send + Sync + 'static,
+        sampling_handler: impl Fn() -> S + Send + Sync + 'static,
+    ) -> SdkResult<Arc<ClientRuntime>>
+    where
+        N: Into<String>,
+        H: ClientHandler,
+        S: SamplingHandler,
+    {
+        let client_details = self.client_details.clone();
+        let restart_policy = spec.restart_policy;
+        let launcher: Launcher = Arc::new(move || {
+            let client_details = client_details.clone();
+            let transport = spec.transport();
+            let handler = handler();
+            let sampling_handler = sampling_handler();
+            Box::pin(async move {
+                let client = client_runtime::create_client(client_details, transport?, handler);
+                // register the sampling handler before starting, servers may send sampling requests right after initialization
+                client.set_sampling_handler(sampling_handler);
+                client.clone().start().await?;
+                Ok(client)
+            })
+        });
+        self.supervisor
+            .supervise(name.into(), restart_policy, launcher)
+            .await
+    }
+
+    /// Returns the client connected to a server, if it is running.
+    pub fn get(&self, name: &str) -> Option<Arc<ClientRuntime>> {
+        self.supervisor.client(name)
+    }
+
+    /// Returns the tools of every running server in the pool, along with the name of the server providing them.
+    pub async fn list_all_tools(&self) -> SdkResult<Vec<(String, Tool)>> {
+        let mut all_tools = vec![];
+        for name in self.supervisor.server_names() {
+            let Some(client) = self.get(&name) else {
+                continue;
+            };
+            if !client.server_has_tools().unwrap_or(false) {
+                continue;
+            }
+            let tools = client.list_all_tools().await?;
+            all_tools.extend(tools.into_iter().map(|tool| (name.clone(), tool)));
+        }
+        Ok(all_tools)
+    }
+
+    /// Returns the client connected to the first server providing a tool with the given name.
+    pub async fn client_for_tool(&self, tool_name: &str) -> SdkResult<Option<Arc<ClientRuntime>>> {
+        Ok(self
+            .list_all_tools()
+            .await?
+            .into_iter()
+            .find(|(_, tool)| tool.name == tool_name)
+            .and_then(|(server_name, _)| self.get(&server_name)))
+    }
+
+    /// Returns the status of every server of the pool, and their aggregated health.
+    pub fn status(&self) -> PoolStatus {
+        self.supervisor.status()
+    }
+
+    /// Returns a stream of the events of the servers: starts, exits, restarts...
+    pub fn events(&self) -> broadcast::Receiver<SupervisorEvent> {
+        self.supervisor.subscribe()
+    }
+
+    pub async fn shut_down(&self) -> SdkResult<()> {
+        self.supervisor.shut_down().await
+    }
+}
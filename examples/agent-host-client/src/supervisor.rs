@@ -0,0 +1,477 @@
+//! A small process manager for the servers of the pool: it watches each server process,
+//! restarts it according to its restart policy, and reports the health of all servers.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use rust_mcp_sdk::error::SdkResult;
+use rust_mcp_sdk::mcp_client::ClientRuntime;
+use rust_mcp_sdk::McpClient;
+use rust_mcp_transport::{ProcessStatus, ServerLaunchOptions, StdioTransport, TransportOptions};
+use tokio::sync::broadcast;
+use tokio::task::JoinHandle;
+
+/// How often the supervisor checks the process and the responsiveness of each server
+const SUPERVISION_INTERVAL: Duration = Duration::from_secs(1);
+/// How long a server has to answer a health check ping
+const PING_TIMEOUT: Duration = Duration::from_secs(5);
+/// Number of consecutive failed pings after which an unresponsive server is restarted
+const MAX_FAILED_PINGS: u32 = 3;
+/// Upper bound of the delay between two restarts
+const MAX_RESTART_BACKOFF: Duration = Duration::from_secs(30);
+/// Number of events kept for subscribers lagging behind
+const EVENT_CAPACITY: usize = 64;
+
+/// When a server is restarted after its process exited.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestartPolicy {
+    /// The server is never restarted
+    Never,
+    /// The server is restarted when it exits with a failure or stops responding
+    OnFailure {
+        max_restarts: u32,
+        backoff: Duration,
+    },
+    /// The server is restarted whenever it exits or stops responding
+    Always {
+        max_restarts: u32,
+        backoff: Duration,
+    },
+}
+
+impl RestartPolicy {
+    // Returns the delay before the given restart attempt, or `None` if the server must not be restarted
+    fn restart_delay(&self, attempt: u32, failed: bool) -> Option<Duration> {
+        let (max_restarts, backoff) = match *self {
+            RestartPolicy::Never => return None,
+            RestartPolicy::OnFailure { .. } if !failed => return None,
+            RestartPolicy::OnFailure {
+                max_restarts,
+                backoff,
+            }
+            | RestartPolicy::Always {
+                max_restarts,
+                backoff,
+            } => (max_restarts, backoff),
+        };
+        (attempt <= max_restarts).then(|| {
+            backoff
+                .saturating_mul(2u32.saturating_pow(attempt - 1))
+                .min(MAX_RESTART_BACKOFF)
+        })
+    }
+}
+
+/// How to launch a server, and restart it.
+#[derive(Debug, Clone)]
+pub struct ServerSpec {
+    pub command: String,
+    pub args: Vec<String>,
+    pub launch_options: ServerLaunchOptions,
+    pub restart_policy: RestartPolicy,
+}
+
+impl ServerSpec {
+    pub fn new<C: Into<String>>(command: C, args: Vec<String>) -> Self {
+        Self {
+            command: command.into(),
+            args,
+            launch_options: ServerLaunchOptions::default(),
+            restart_policy: RestartPolicy::Never,
+        }
+    }
+
+    pub fn with_restart_policy(mut self, restart_policy: RestartPolicy) -> Self {
+        self.restart_policy = restart_policy;
+        self
+    }
+
+    /// Creates a transport launching a new process of the server.
+    pub fn transport(&self) -> SdkResult<StdioTransport> {
+        Ok(StdioTransport::create_with_launch_options(
+            self.command.clone(),
+            self.args.clone(),
+            self.launch_options.clone(),
+            TransportOptions::default(),
+        )?)
+    }
+}
+
+/// The state of a supervised server.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ServerState {
+    /// The server is running and answers health checks
+    Running,
+    /// The server is running but did not answer the last health checks
+    Unresponsive { failed_pings: u32 },
+    /// The server is about to be restarted
+    Restarting { attempt: u32 },
+    /// The server exited, or could not be restarted, and is not restarted anymore
+    Exited { reason: String },
+    /// The server was shut down by the host
+    Stopped,
+}
+
+/// The aggregated health of the servers of the pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Health {
+    /// All servers are running and responsive
+    Healthy,
+    /// Some servers are unresponsive, restarting or down
+    Degraded,
+    /// No server is running
+    Down,
+}
+
+/// The status of a supervised server.
+#[derive(Debug, Clone)]
+pub struct ServerStatus {
+    pub state: ServerState,
+    /// Number of times the server was restarted
+    pub restarts: u32,
+    /// When the current process of the server was started
+    pub started_at: Instant,
+    /// Why the previous process of the server ended, if it was restarted
+    pub last_exit: Option<String>,
+}
+
+/// A consolidated snapshot of the status of all supervised servers.
+#[derive(Debug, Clone)]
+pub struct PoolStatus {
+    pub servers: BTreeMap<String, ServerStatus>,
+    pub health: Health,
+}
+
+/// An event reported by the supervisor, see `Supervisor::subscribe()`.
+#[derive(Debug, Clone)]
+pub enum SupervisorEvent {
+    Started {
+        server: String,
+        restarts: u32,
+    },
+    Unresponsive {
+        server: String,
+        failed_pings: u32,
+    },
+    Recovered {
+        server: String,
+    },
+    Exited {
+        server: String,
+        reason: String,
+    },
+    Restarting {
+        server: String,
+        attempt: u32,
+        delay: Duration,
+    },
+    GaveUp {
+        server: String,
+        reason: String,
+    },
+    Stopped {
+        server: String,
+    },
+}
+
+impl fmt::Display for SupervisorEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SupervisorEvent::Started { server, restarts } => {
+                write!(f, "[{}] started ({} restart(s))", server, restarts)
+            }
+            SupervisorEvent::Unresponsive {
+                server,
+                failed_pings,
+            } => write!(
+                f,
+                "[{}] unresponsive ({} failed ping(s))",
+                server, failed_pings
+            ),
+            SupervisorEvent::Recovered { server } => write!(f, "[{}] responsive again", server),
+            SupervisorEvent::Exited { server, reason } => {
+                write!(f, "[{}] exited: {}", server, reason)
+            }
+            SupervisorEvent::Restarting {
+                server,
+                attempt,
+                delay,
+            } => write!(
+                f,
+                "[{}] restarting in {:?} (attempt {})",
+                server, delay, attempt
+            ),
+            SupervisorEvent::GaveUp { server, reason } => {
+                write!(f, "[{}] not restarted anymore: {}", server, reason)
+            }
+            SupervisorEvent::Stopped { server } => write!(f, "[{}] stopped", server),
+        }
+    }
+}
+
+/// Launches a new client connected to a new process of a server, and starts it.
+pub type Launcher = Arc<
+    dyn Fn() -> Pin<Box<dyn Future<Output = SdkResult<Arc<ClientRuntime>>> + Send>> + Send + Sync,
+>;
+
+struct SupervisedServer {
+    client: Arc<ClientRuntime>,
+    status: ServerStatus,
+    task: Option<JoinHandle<()>>,
+}
+
+/// Supervises the servers of the pool, one task per server.
+pub struct Supervisor {
+    servers: Mutex<BTreeMap<String, SupervisedServer>>,
+    events: broadcast::Sender<SupervisorEvent>,
+}
+
+impl Supervisor {
+    pub fn new() -> Arc<Self> {
+        Arc::new(Self {
+            servers: Mutex::new(BTreeMap::new()),
+            events: broadcast::channel(EVENT_CAPACITY).0,
+        })
+    }
+
+    /// Launches a server with `launcher` and supervises it, restarting it with `launcher`
+    /// according to `restart_policy`.
+    pub async fn supervise(
+        self: &Arc<Self>,
+        name: String,
+        restart_policy: RestartPolicy,
+        launcher: Launcher,
+    ) -> SdkResult<Arc<ClientRuntime>> {
+        let client = launcher().await?;
+        let status = ServerStatus {
+            state: ServerState::Running,
+            restarts: 0,
+            started_at: Instant::now(),
+            last_exit: None,
+        };
+        let task = tokio::spawn(Arc::clone(self).watch(name.clone(), restart_policy, launcher));
+        self.update(|servers| {
+            servers.insert(
+                name.clone(),
+                SupervisedServer {
+                    client: Arc::clone(&client),
+                    status,
+                    task: Some(task),
+                },
+            );
+        });
+        self.emit(SupervisorEvent::Started {
+            server: name,
+            restarts: 0,
+        });
+        Ok(client)
+    }
+
+    /// Returns the client connected to the current process of a server, if it is running.
+    pub fn client(&self, name: &str) -> Option<Arc<ClientRuntime>> {
+        let servers = self.servers.lock().ok()?;
+        let server = servers.get(name)?;
+        matches!(
+            server.status.state,
+            ServerState::Running | ServerState::Unresponsive { .. }
+        )
+        .then(|| Arc::clone(&server.client))
+    }
+
+    /// Returns the names of the supervised servers.
+    pub fn server_names(&self) -> Vec<String> {
+        self.servers
+            .lock()
+            .map(|servers| servers.keys().cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Returns a snapshot of the status of all servers, and their aggregated health.
+    pub fn status(&self) -> PoolStatus {
+        let servers: BTreeMap<String, ServerStatus> = self
+            .servers
+            .lock()
+            .map(|servers| {
+                servers
+                    .iter()
+                    .map(|(name, server)| (name.clone(), server.status.clone()))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let running = servers
+            .values()
+            .filter(|status| status.state == ServerState::Running)
+            .count();
+        let health = match running {
+            0 => Health::Down,
+            running if running == servers.len() => Health::Healthy,
+            _ => Health::Degraded,
+        };
+        PoolStatus { servers, health }
+    }
+
+    /// Returns a stream of the events of all servers, starting with the next one.
+    pub fn subscribe(&self) -> broadcast::Receiver<SupervisorEvent> {
+        self.events.subscribe()
+    }
+
+    /// Stops supervising the servers and shuts them down.
+    pub async fn shut_down(&self) -> SdkResult<()> {
+        // the clients of servers that exited or are restarting are already disconnected
+        let clients: Vec<(String, Arc<ClientRuntime>)> = match self.servers.lock() {
+            Ok(mut servers) => servers
+                .iter_mut()
+                .filter_map(|(name, server)| {
+                    if let Some(task) = server.task.take() {
+                        task.abort();
+                    }
+                    let state = std::mem::replace(&mut server.status.state, ServerState::Stopped);
+                    matches!(
+                        state,
+                        ServerState::Running | ServerState::Unresponsive { .. }
+                    )
+                    .then(|| (name.clone(), Arc::clone(&server.client)))
+                })
+                .collect(),
+            Err(_) => vec![],
+        };
+        for (name, client) in clients {
+            client.shut_down().await?;
+            self.emit(SupervisorEvent::Stopped { server: name });
+        }
+        Ok(())
+    }
+
+    // Watches a server until it exits for good, restarting it according to `restart_policy`
+    async fn watch(
+        self: Arc<Self>,
+        name: String,
+        restart_policy: RestartPolicy,
+        launcher: Launcher,
+    ) {
+        let mut failed_pings = 0;
+        let mut restarts = 0;
+        loop {
+            tokio::time::sleep(SUPERVISION_INTERVAL).await;
+            let Some(client) = self.current_client(&name) else {
+                return;
+            };
+
+            // a process exiting successfully is not a failure, one that stops responding is
+            let (reason, failed) = match client.server_process_status().await {
+                Some(ProcessStatus::Exited(status)) => {
+                    (format!("process exited with {}", status), !status.success())
+                }
+                _ if client.is_shut_down().await => ("connection closed".to_string(), true),
+                _ => match tokio::time::timeout(PING_TIMEOUT, client.ping()).await {
+                    Ok(Ok(_)) => {
+                        if failed_pings > 0 {
+                            failed_pings = 0;
+                            self.set_state(&name, ServerState::Running);
+                            self.emit(SupervisorEvent::Recovered {
+                                server: name.clone(),
+                            });
+                        }
+                        continue;
+                    }
+                    _ => {
+                        failed_pings += 1;
+                        self.set_state(&name, ServerState::Unresponsive { failed_pings });
+                        self.emit(SupervisorEvent::Unresponsive {
+                            server: name.clone(),
+                            failed_pings,
+                        });
+                        if failed_pings < MAX_FAILED_PINGS {
+                            continue;
+                        }
+                        let _ = client.shut_down().await;
+                        (format!("no answer to {} health checks", failed_pings), true)
+                    }
+                },
+            };
+            failed_pings = 0;
+            self.emit(SupervisorEvent::Exited {
+                server: name.clone(),
+                reason: reason.clone(),
+            });
+
+            // relaunch the server until it starts, or the restart policy gives up
+            let mut reason = reason;
+            let client = loop {
+                let attempt = restarts + 1;
+                let Some(delay) = restart_policy.restart_delay(attempt, failed) else {
+                    self.set_state(
+                        &name,
+                        ServerState::Exited {
+                            reason: reason.clone(),
+                        },
+                    );
+                    self.emit(SupervisorEvent::GaveUp {
+                        server: name.clone(),
+                        reason,
+                    });
+                    return;
+                };
+                self.set_state(&name, ServerState::Restarting { attempt });
+                self.emit(SupervisorEvent::Restarting {
+                    server: name.clone(),
+                    attempt,
+                    delay,
+                });
+                tokio::time::sleep(delay).await;
+                restarts = attempt;
+                match launcher().await {
+                    Ok(client) => break client,
+                    Err(error) => reason = format!("restart failed: {}", error),
+                }
+            };
+
+            self.update(|servers| {
+                if let Some(server) = servers.get_mut(&name) {
+                    server.client = client;
+                    server.status = ServerStatus {
+                        state: ServerState::Running,
+                        restarts,
+                        started_at: Instant::now(),
+                        last_exit: Some(reason),
+                    };
+                }
+            });
+            self.emit(SupervisorEvent::Started {
+                server: name.clone(),
+                restarts,
+            });
+        }
+    }
+
+    // Returns the client of a server, whatever its state, `None` once it is stopped
+    fn current_client(&self, name: &str) -> Option<Arc<ClientRuntime>> {
+        let servers = self.servers.lock().ok()?;
+        let server = servers.get(name)?;
+        (server.status.state != ServerState::Stopped).then(|| Arc::clone(&server.client))
+    }
+
+    fn set_state(&self, name: &str, state: ServerState) {
+        self.update(|servers| {
+            if let Some(server) = servers.get_mut(name) {
+                server.status.state = state;
+            }
+        });
+    }
+
+    fn update(&self, update: impl FnOnce(&mut BTreeMap<String, SupervisedServer>)) {
+        if let Ok(mut servers) = self.servers.lock() {
+            update(&mut servers);
+        }
+    }
+
+    fn emit(&self, event: SupervisorEvent) {
+        // there may be no subscriber
+        let _ = self.events.send(event);
+    }
+}
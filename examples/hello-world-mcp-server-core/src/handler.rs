@@ -2,7 +2,7 @@ use async_trait::async_trait;
 
 use rust_mcp_schema::{
     schema_utils::{CallToolError, NotificationFromClient, RequestFromClient, ResultFromServer},
-    ClientRequest, ListToolsResult, RpcError,
+    CallToolResult, ClientRequest, ListToolsResult, RpcError,
 };
 use rust_mcp_sdk::{mcp_server::ServerHandlerCore, McpServer};
 
@@ -40,22 +40,23 @@ impl ServerHandlerCore for MyServerHandler {
                 ClientRequest::CallToolRequest(request) => {
                     let tool_name = request.tool_name().to_string();
 
-                    // Attempt to convert request parameters into GreetingTools enum
-                    let tool_params = GreetingTools::try_from(request.params)
-                        .map_err(|_| CallToolError::unknown_tool(tool_name.clone()))?;
+                    // Attempt to convert request parameters into GreetingTools enum,
+                    // unknown tools are reported as a JSON-RPC error
+                    let tool_params = GreetingTools::try_from(request.params).map_err(|_| {
+                        RpcError::invalid_params().with_message(
+                            CallToolError::unknown_tool(tool_name.clone()).to_string(),
+                        )
+                    })?;
 
-                    // Match the tool variant and execute its corresponding logic
+                    // Match the tool variant and execute its corresponding logic,
+                    // errors of the tool are reported as a tool result with `is_error`
                     let result = match tool_params {
-                        GreetingTools::SayHelloTool(say_hello_tool) => {
-                            say_hello_tool.call_tool().map_err(|err| {
-                                RpcError::internal_error().with_message(err.to_string())
-                            })?
-                        }
-                        GreetingTools::SayGoodbyeTool(say_goodbye_tool) => {
-                            say_goodbye_tool.call_tool().map_err(|err| {
-                                RpcError::internal_error().with_message(err.to_string())
-                            })?
-                        }
+                        GreetingTools::SayHelloTool(say_hello_tool) => say_hello_tool
+                            .call_tool()
+                            .unwrap_or_else(CallToolResult::with_error),
+                        GreetingTools::SayGoodbyeTool(say_goodbye_tool) => say_goodbye_tool
+                            .call_tool()
+                            .unwrap_or_else(CallToolResult::with_error),
                     };
                     Ok(result.into())
                 }
@@ -39,10 +39,7 @@ impl ServerHandler for MyServerHandler {
         let tool_params: GreetingTools =
             GreetingTools::try_from(request.params).map_err(CallToolError::new)?;
 
-        // Match the tool variant and execute its corresponding logic
-        match tool_params {
-            GreetingTools::SayHelloTool(say_hello_tool) => say_hello_tool.call_tool(),
-            GreetingTools::SayGoodbyeTool(say_goodbye_tool) => say_goodbye_tool.call_tool(),
-        }
+        // Execute the logic of the tool variant
+        tool_params.call(runtime)
     }
 }
@@ -1,6 +1,6 @@
 use rust_mcp_macros::{mcp_tool, JsonSchema};
 use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
-use rust_mcp_sdk::tool_box;
+use rust_mcp_sdk::{tool_box, McpServer, McpTool};
 
 //****************//
 //  SayHelloTool  //
@@ -15,8 +15,8 @@ pub struct SayHelloTool {
     name: String,
 }
 
-impl SayHelloTool {
-    pub fn call_tool(&self) -> Result<CallToolResult, CallToolError> {
+impl McpTool for SayHelloTool {
+    fn call_tool(&self, _runtime: &dyn McpServer) -> Result<CallToolResult, CallToolError> {
         let hello_message = format!("Hello, {}!", self.name);
         Ok(CallToolResult::text_content(hello_message, None))
     }
@@ -34,8 +34,8 @@ pub struct SayGoodbyeTool {
     /// The name of the person to say goodbye to.
     name: String,
 }
-impl SayGoodbyeTool {
-    pub fn call_tool(&self) -> Result<CallToolResult, CallToolError> {
+impl McpTool for SayGoodbyeTool {
+    fn call_tool(&self, _runtime: &dyn McpServer) -> Result<CallToolResult, CallToolError> {
         let hello_message = format!("Goodbye, {}!", self.name);
         Ok(CallToolResult::text_content(hello_message, None))
     }
@@ -44,5 +44,6 @@ impl SayGoodbyeTool {
 //******************//
 //  GreetingTools  //
 //******************//
-// Generates an enum names GreetingTools, with SayHelloTool and SayGoodbyeTool variants
-tool_box!(GreetingTools, [SayHelloTool, SayGoodbyeTool]);
+// Generates an enum names GreetingTools, with SayHelloTool and SayGoodbyeTool variants,
+// and a `call()` method executing the tool variant
+tool_box!(GreetingTools, [SayHelloTool, SayGoodbyeTool], call);
@@ -0,0 +1,8 @@
+use async_trait::async_trait;
+use rust_mcp_sdk::mcp_client::ClientHandler;
+
+// The load test only sends requests to the server, so default implementations of the handler are sufficient.
+pub struct LoadTestHandler;
+
+#[async_trait]
+impl ClientHandler for LoadTestHandler {}
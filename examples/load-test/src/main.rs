@@ -0,0 +1,111 @@
+mod handler;
+mod options;
+mod stats;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Instant;
+
+use colored::Colorize;
+use handler::LoadTestHandler;
+use options::{Operation, Options, USAGE};
+use rust_mcp_schema::{CallToolRequestParams, InitializeRequestParams};
+use rust_mcp_sdk::error::SdkResult;
+use rust_mcp_sdk::mcp_client::{client_runtime, ClientRuntime};
+use rust_mcp_sdk::McpClient;
+use rust_mcp_transport::StdioTransport;
+use stats::Sample;
+
+#[tokio::main]
+async fn main() -> SdkResult<()> {
+    // Step1 : Parse the command line options
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(Some(options)) => Arc::new(options),
+        Ok(None) => {
+            println!("{}", USAGE);
+            return Ok(());
+        }
+        Err(error) => {
+            eprintln!("{}\n\n{}", error.red(), USAGE);
+            std::process::exit(2);
+        }
+    };
+
+    // Step2 : Define client details and capabilities
+    let client_details: InitializeRequestParams = ClientRuntime::info("load-test", "0.1.0").build();
+
+    // Step3 : Launch the target server and connect to it
+    let transport = StdioTransport::builder(&options.command)
+        .args(&options.args)
+        .build()?;
+    let client = client_runtime::create_client(client_details, transport, LoadTestHandler);
+    client.clone().start().await?;
+
+    if let Some(server_version) = client.server_version() {
+        println!(
+            "Connected to {} v{}, sending {} requests with a concurrency of {}",
+            server_version.name.bold(),
+            server_version.version,
+            options.requests,
+            options.concurrency
+        );
+    }
+
+    // Step4 : Send the requests from `concurrency` workers, each worker sending its next request
+    // as soon as the previous one completed
+    let next_index = Arc::new(AtomicUsize::new(0));
+    let started = Instant::now();
+
+    let workers: Vec<_> = (0..options.concurrency)
+        .map(|_| {
+            let client = Arc::clone(&client);
+            let options = Arc::clone(&options);
+            let next_index = Arc::clone(&next_index);
+            tokio::spawn(async move {
+                let mut samples = vec![];
+                loop {
+                    let index = next_index.fetch_add(1, Ordering::Relaxed);
+                    if index >= options.requests {
+                        break;
+                    }
+                    let operation = options.operation_for(index);
+                    let request_started = Instant::now();
+                    let success = send_request(&client, operation, &options).await;
+                    samples.push(Sample {
+                        operation,
+                        latency: request_started.elapsed(),
+                        success,
+                    });
+                }
+                samples
+            })
+        })
+        .collect();
+
+    let mut samples = vec![];
+    for worker in workers {
+        samples.extend(worker.await.unwrap_or_default());
+    }
+    let elapsed = started.elapsed();
+
+    // Step5 : Report throughput and latency percentiles
+    stats::print_report(&samples, elapsed);
+
+    client.shut_down().await?;
+    Ok(())
+}
+
+// Sends a single request of the given kind, returns true if it succeeded
+async fn send_request(client: &ClientRuntime, operation: Operation, options: &Options) -> bool {
+    match operation {
+        Operation::Ping => client.ping().await.is_ok(),
+        Operation::ListTools => client.list_tools(None).await.is_ok(),
+        Operation::CallTool => client
+            .call_tool(CallToolRequestParams {
+                name: options.tool.clone(),
+                arguments: Some(options.tool_arguments.clone()),
+            })
+            .await
+            .is_ok_and(|result| !result.is_error.unwrap_or(false)),
+    }
+}
@@ -0,0 +1,163 @@
+use std::fmt::Display;
+
+use serde_json::{Map, Value};
+
+pub const USAGE: &str = "\
+Usage: load-test [OPTIONS] [-- <SERVER_COMMAND> [ARGS]...]
+
+Options:
+  --concurrency <N>     Number of requests in flight at the same time [default: 8]
+  --requests <N>        Total number of requests to send [default: 1000]
+  --mix <MIX>           Weighted request mix [default: ping=1,list_tools=1,call_tool=2]
+  --tool <NAME>         Tool invoked by call_tool requests [default: say_hello]
+  --arguments <JSON>    Arguments of the invoked tool [default: {\"name\":\"load-test\"}]
+  -h, --help            Print this help
+
+The server is launched with SERVER_COMMAND, by default the hello-world-mcp-server example
+of this repository: cargo run -q -p hello-world-mcp-server";
+
+/// Kind of request sent to the server
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Operation {
+    Ping,
+    ListTools,
+    CallTool,
+}
+
+impl Operation {
+    fn parse(name: &str) -> Result<Self, String> {
+        match name {
+            "ping" => Ok(Operation::Ping),
+            "list_tools" => Ok(Operation::ListTools),
+            "call_tool" => Ok(Operation::CallTool),
+            _ => Err(format!(
+                "Unknown operation '{}', expected one of: ping, list_tools, call_tool",
+                name
+            )),
+        }
+    }
+}
+
+impl Display for Operation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Operation::Ping => "ping",
+            Operation::ListTools => "list_tools",
+            Operation::CallTool => "call_tool",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+pub struct Options {
+    pub concurrency: usize,
+    pub requests: usize,
+    /// Operations with their relative weight in the request mix
+    pub mix: Vec<(Operation, usize)>,
+    pub tool: String,
+    pub tool_arguments: Map<String, Value>,
+    pub command: String,
+    pub args: Vec<String>,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            concurrency: 8,
+            requests: 1000,
+            mix: vec![
+                (Operation::Ping, 1),
+                (Operation::ListTools, 1),
+                (Operation::CallTool, 2),
+            ],
+            tool: "say_hello".to_string(),
+            tool_arguments: Map::from_iter([("name".to_string(), Value::from("load-test"))]),
+            command: "cargo".to_string(),
+            args: vec![
+                "run".into(),
+                "-q".into(),
+                "-p".into(),
+                "hello-world-mcp-server".into(),
+            ],
+        }
+    }
+}
+
+impl Options {
+    /// Parses command line arguments, returns `Ok(None)` if help was requested
+    pub fn parse(mut args: impl Iterator<Item = String>) -> Result<Option<Self>, String> {
+        let mut options = Options::default();
+
+        while let Some(arg) = args.next() {
+            let mut value_of = |name: &str| {
+                args.next()
+                    .ok_or_else(|| format!("Missing value for '{}'", name))
+            };
+            match arg.as_str() {
+                "-h" | "--help" => return Ok(None),
+                "--concurrency" => options.concurrency = parse_count(&arg, &value_of(&arg)?)?,
+                "--requests" => options.requests = parse_count(&arg, &value_of(&arg)?)?,
+                "--mix" => options.mix = parse_mix(&value_of(&arg)?)?,
+                "--tool" => options.tool = value_of(&arg)?,
+                "--arguments" => {
+                    options.tool_arguments = serde_json::from_str(&value_of(&arg)?)
+                        .map_err(|err| format!("Invalid value for '--arguments': {}", err))?
+                }
+                "--" => {
+                    options.command = args
+                        .next()
+                        .ok_or_else(|| "Missing server command after '--'".to_string())?;
+                    options.args = args.collect();
+                    break;
+                }
+                _ => return Err(format!("Unexpected argument '{}'", arg)),
+            }
+        }
+
+        Ok(Some(options))
+    }
+
+    /// Returns the operation of the request at `index`, distributing the operations according to their weight
+    pub fn operation_for(&self, index: usize) -> Operation {
+        let total_weight: usize = self.mix.iter().map(|(_, weight)| weight).sum();
+        let mut slot = index % total_weight;
+        for (operation, weight) in &self.mix {
+            if slot < *weight {
+                return *operation;
+            }
+            slot -= weight;
+        }
+        unreachable!("the slot is always lower than the total weight")
+    }
+}
+
+fn parse_count(name: &str, value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(count) if count > 0 => Ok(count),
+        _ => Err(format!(
+            "Invalid value for '{}': expected a positive integer, got '{}'",
+            name, value
+        )),
+    }
+}
+
+// Parses a request mix such as `ping=1,call_tool=3`
+fn parse_mix(value: &str) -> Result<Vec<(Operation, usize)>, String> {
+    let mix = value
+        .split(',')
+        .map(|entry| {
+            let (name, weight) = entry.split_once('=').unwrap_or((entry, "1"));
+            let weight = weight
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid weight '{}' for '{}'", weight, name))?;
+            Ok((Operation::parse(name.trim())?, weight))
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+
+    if mix.iter().all(|(_, weight)| *weight == 0) {
+        return Err(
+            "The request mix must contain at least one operation with a non-zero weight".into(),
+        );
+    }
+    Ok(mix)
+}
@@ -0,0 +1,92 @@
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use colored::Colorize;
+
+use crate::options::Operation;
+
+/// Outcome of a single request
+pub struct Sample {
+    pub operation: Operation,
+    pub latency: Duration,
+    pub success: bool,
+}
+
+/// Latency and error statistics of a group of requests
+struct Summary {
+    count: usize,
+    errors: usize,
+    // sorted in ascending order
+    latencies: Vec<Duration>,
+}
+
+impl Summary {
+    fn new(samples: &[&Sample]) -> Self {
+        let mut latencies: Vec<Duration> = samples.iter().map(|sample| sample.latency).collect();
+        latencies.sort();
+        Self {
+            count: samples.len(),
+            errors: samples.iter().filter(|sample| !sample.success).count(),
+            latencies,
+        }
+    }
+
+    // Nearest-rank percentile, `percentile` being in the 0..=100 range
+    fn percentile(&self, percentile: usize) -> Duration {
+        if self.latencies.is_empty() {
+            return Duration::ZERO;
+        }
+        let rank = (percentile * self.latencies.len()).div_ceil(100).max(1);
+        self.latencies[rank - 1]
+    }
+
+    fn row(&self, label: &str) -> String {
+        format!(
+            "{:<12} {:>8} {:>7} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            label,
+            self.count,
+            self.errors,
+            format_latency(self.percentile(0)),
+            format_latency(self.percentile(50)),
+            format_latency(self.percentile(90)),
+            format_latency(self.percentile(99)),
+            format_latency(self.percentile(100)),
+        )
+    }
+}
+
+fn format_latency(latency: Duration) -> String {
+    format!("{:.2}ms", latency.as_secs_f64() * 1000.0)
+}
+
+/// Prints throughput and latency percentiles, per operation and for all requests
+pub fn print_report(samples: &[Sample], elapsed: Duration) {
+    let mut by_operation: BTreeMap<Operation, Vec<&Sample>> = BTreeMap::new();
+    for sample in samples {
+        by_operation
+            .entry(sample.operation)
+            .or_default()
+            .push(sample);
+    }
+
+    println!(
+        "\n{} {} requests in {:.2}s, {:.1} requests/s\n",
+        "Completed".bold(),
+        samples.len(),
+        elapsed.as_secs_f64(),
+        samples.len() as f64 / elapsed.as_secs_f64()
+    );
+    println!(
+        "{}",
+        format!(
+            "{:<12} {:>8} {:>7} {:>10} {:>10} {:>10} {:>10} {:>10}",
+            "operation", "requests", "errors", "min", "p50", "p90", "p99", "max"
+        )
+        .bold()
+    );
+    for (operation, samples) in &by_operation {
+        println!("{}", Summary::new(samples).row(&operation.to_string()));
+    }
+    let all: Vec<&Sample> = samples.iter().collect();
+    println!("{}", Summary::new(&all).row("all").bold());
+}
@@ -4,12 +4,13 @@ mod inquiry_utils;
 use handler::MyClientHandler;
 
 use inquiry_utils::InquiryUtils;
-use rust_mcp_schema::{
-    ClientCapabilities, Implementation, InitializeRequestParams, JSONRPC_VERSION,
-};
+use rust_mcp_schema::InitializeRequestParams;
 use rust_mcp_sdk::McpClient;
-use rust_mcp_sdk::{error::SdkResult, mcp_client::client_runtime_core};
-use rust_mcp_transport::{StdioTransport, TransportOptions};
+use rust_mcp_sdk::{
+    error::SdkResult,
+    mcp_client::{client_runtime_core, ClientRuntime},
+};
+use rust_mcp_transport::StdioTransport;
 use std::sync::Arc;
 
 const MCP_SERVER_TO_LAUNCH: &str = "@modelcontextprotocol/server-everything";
@@ -17,23 +18,14 @@ const MCP_SERVER_TO_LAUNCH: &str = "@modelcontextprotocol/server-everything";
 #[tokio::main]
 async fn main() -> SdkResult<()> {
     // Step1 : Define client details and capabilities
-    let client_details: InitializeRequestParams = InitializeRequestParams {
-        capabilities: ClientCapabilities::default(),
-        client_info: Implementation {
-            name: "simple-rust-mcp-client-core".into(),
-            version: "0.1.0".into(),
-        },
-        protocol_version: JSONRPC_VERSION.into(),
-    };
+    let client_details: InitializeRequestParams =
+        ClientRuntime::info("simple-rust-mcp-client-core", "0.1.0").build();
 
     // Step2 : Create a transport, with options to launch/connect to a MCP Server
     // In this example we launch @modelcontextprotocol/server-everything (needs node.js and npm to be installed)
-    let transport = StdioTransport::create_with_server_launch(
-        "npx",
-        vec!["-y".into(), MCP_SERVER_TO_LAUNCH.into()],
-        None,
-        TransportOptions::default(),
-    )?;
+    let transport = StdioTransport::builder("npx")
+        .args(["-y", MCP_SERVER_TO_LAUNCH])
+        .build()?;
 
     // STEP 3: instantiate our custom handler for handling MCP messages
     let handler = MyClientHandler {};
@@ -48,10 +40,8 @@ async fn main() -> SdkResult<()> {
     // The following demonstrates how to use client methods to retrieve server information,
     // and print them in the terminal, set the log level, invoke a tool, and more.
 
-    // Create a struct with utility functions for demonstration purpose, to utilize different client methods and display the information.
-    let utils = InquiryUtils {
-        client: Arc::clone(&client),
-    };
+    // Inspect the server with `rust_mcp_sdk::diagnostics`, and create a struct with utility functions for demonstration purpose, to display the information.
+    let utils = InquiryUtils::inspect(Arc::clone(&client)).await;
     // Display server information (name and version)
     utils.print_server_info();
 
@@ -59,19 +49,22 @@ async fn main() -> SdkResult<()> {
     utils.print_server_capabilities();
 
     // Display the list of tools available on the server
-    utils.print_tool_list().await?;
+    utils.print_tool_list();
 
     // Display the list of prompts available on the server
-    utils.print_prompts_list().await?;
+    utils.print_prompts_list();
 
     // Display the list of resources available on the server
-    utils.print_resource_list().await?;
+    utils.print_resource_list();
 
     // Display the list of resource templates available on the server
-    utils.print_resource_templates().await?;
+    utils.print_resource_templates();
+
+    // Display the requests that failed while inspecting the server
+    utils.print_inspection_errors();
 
     // Call add tool, and print the result
-    utils.call_add_tool(100, 25).await?;
+    utils.call_add_tool(100, 25).await;
 
     // Set the log level
     utils
@@ -1,23 +1,28 @@
 //! This module contains utility functions for querying and displaying server capabilities.
+//! The server is inspected with `rust_mcp_sdk::diagnostics`, this module displays the reports.
 
 use colored::Colorize;
-use rust_mcp_schema::CallToolRequestParams;
-use rust_mcp_sdk::McpClient;
-use rust_mcp_sdk::{error::SdkResult, mcp_client::ClientRuntime};
+use rust_mcp_sdk::diagnostics::{self, ServerInspection};
+use rust_mcp_sdk::mcp_client::ClientRuntime;
 use serde_json::json;
-use std::io::Write;
 use std::sync::Arc;
 use std::time::Duration;
-use tokio::time::sleep;
 
 const GREY_COLOR: (u8, u8, u8) = (90, 90, 90);
 const HEADER_SIZE: usize = 31;
 
 pub struct InquiryUtils {
     pub client: Arc<ClientRuntime>,
+    pub inspection: ServerInspection,
 }
 
 impl InquiryUtils {
+    /// Inspects the server the client is connected to.
+    pub async fn inspect(client: Arc<ClientRuntime>) -> Self {
+        let inspection = diagnostics::inspect_server(client.as_ref()).await;
+        Self { client, inspection }
+    }
+
     fn print_header(&self, title: &str) {
         let pad = ((HEADER_SIZE as f32 / 2.0) + (title.len() as f32 / 2.0)).floor() as usize;
         println!("\n{}", "=".repeat(HEADER_SIZE).custom_color(GREY_COLOR));
@@ -33,7 +38,7 @@ impl InquiryUtils {
 
     pub fn print_server_info(&self) {
         self.print_header("Server info");
-        let server_version = self.client.server_version().unwrap();
+        let server_version = self.inspection.server_info.as_ref().unwrap();
         println!("{} {}", "Server name:".bold(), server_version.name.cyan());
         println!(
             "{} {}",
@@ -44,12 +49,13 @@ impl InquiryUtils {
 
     pub fn print_server_capabilities(&self) {
         self.print_header("Capabilities");
+        let capabilities = &self.inspection.capabilities;
         let capability_vec = [
-            ("tools", self.client.server_has_tools()),
-            ("prompts", self.client.server_has_prompts()),
-            ("resources", self.client.server_has_resources()),
-            ("logging", self.client.server_supports_logging()),
-            ("experimental", self.client.server_has_experimental()),
+            ("tools", capabilities.tools),
+            ("prompts", capabilities.prompts),
+            ("resources", capabilities.resources),
+            ("logging", capabilities.logging),
+            ("experimental", capabilities.experimental),
         ];
 
         capability_vec.iter().for_each(|(tool_name, opt)| {
@@ -63,16 +69,15 @@ impl InquiryUtils {
         });
     }
 
-    pub async fn print_tool_list(&self) -> SdkResult<()> {
+    pub fn print_tool_list(&self) {
         // Return if the MCP server does not support tools
-        if !self.client.server_has_tools().unwrap_or(false) {
-            return Ok(());
+        if !self.inspection.capabilities.tools.unwrap_or(false) {
+            return;
         }
 
-        let tools = self.client.list_tools(None).await?;
         self.print_header("Tools");
         self.print_list(
-            tools
+            self.inspection
                 .tools
                 .iter()
                 .map(|item| {
@@ -83,21 +88,17 @@ impl InquiryUtils {
                 })
                 .collect(),
         );
-
-        Ok(())
     }
 
-    pub async fn print_prompts_list(&self) -> SdkResult<()> {
+    pub fn print_prompts_list(&self) {
         // Return if the MCP server does not support prompts
-        if !self.client.server_has_prompts().unwrap_or(false) {
-            return Ok(());
+        if !self.inspection.capabilities.prompts.unwrap_or(false) {
+            return;
         }
 
-        let prompts = self.client.list_prompts(None).await?;
-
         self.print_header("Prompts");
         self.print_list(
-            prompts
+            self.inspection
                 .prompts
                 .iter()
                 .map(|item| {
@@ -108,21 +109,18 @@ impl InquiryUtils {
                 })
                 .collect(),
         );
-        Ok(())
     }
 
-    pub async fn print_resource_list(&self) -> SdkResult<()> {
+    pub fn print_resource_list(&self) {
         // Return if the MCP server does not support resources
-        if !self.client.server_has_resources().unwrap_or(false) {
-            return Ok(());
+        if !self.inspection.capabilities.resources.unwrap_or(false) {
+            return;
         }
 
-        let resources = self.client.list_resources(None).await?;
-
         self.print_header("Resources");
 
         self.print_list(
-            resources
+            self.inspection
                 .resources
                 .iter()
                 .map(|item| {
@@ -137,22 +135,18 @@ impl InquiryUtils {
                 })
                 .collect(),
         );
-
-        Ok(())
     }
 
-    pub async fn print_resource_templates(&self) -> SdkResult<()> {
+    pub fn print_resource_templates(&self) {
         // Return if the MCP server does not support resources
-        if !self.client.server_has_resources().unwrap_or(false) {
-            return Ok(());
+        if !self.inspection.capabilities.resources.unwrap_or(false) {
+            return;
         }
 
-        let templates = self.client.list_resource_templates(None).await?;
-
         self.print_header("Resource Templates");
 
         self.print_list(
-            templates
+            self.inspection
                 .resource_templates
                 .iter()
                 .map(|item| {
@@ -163,10 +157,25 @@ impl InquiryUtils {
                 })
                 .collect(),
         );
-        Ok(())
     }
 
-    pub async fn call_add_tool(&self, a: i64, b: i64) -> SdkResult<()> {
+    /// Displays the requests that failed during the inspection, if any.
+    pub fn print_inspection_errors(&self) {
+        if self.inspection.errors.is_empty() {
+            return;
+        }
+
+        self.print_header("Errors");
+        self.print_list(
+            self.inspection
+                .errors
+                .iter()
+                .map(|error| (error.method.clone(), error.message.clone()))
+                .collect(),
+        );
+    }
+
+    pub async fn call_add_tool(&self, a: i64, b: i64) {
         // Invoke the "add" tool with 100 and 25 as arguments, and display the result
         println!(
             "{}",
@@ -183,40 +192,34 @@ impl InquiryUtils {
         .clone();
 
         // invoke the tool
-        let result = self
-            .client
-            .call_tool(CallToolRequestParams {
-                name: "add".to_string(),
-                arguments: Some(params),
-            })
-            .await?;
+        let report = diagnostics::call_tool(self.client.as_ref(), "add", Some(params)).await;
 
         // Retrieve the result content and print it to the stdout
-        let result_content = result.content.first().unwrap().as_text_content()?;
-        println!("{}", result_content.text.green());
-
-        Ok(())
+        match (&report.result, &report.error) {
+            (Some(result), _) => {
+                let result_content = result.content.first().unwrap().as_text_content().unwrap();
+                println!("{}", result_content.text.green());
+            }
+            (None, Some(error)) => println!("{}", error.bright_red()),
+            (None, None) => {}
+        }
     }
 
-    pub async fn ping_n_times(&self, n: i32) {
-        let max_pings = n;
-        println!();
-        for ping_index in 1..=max_pings {
-            print!("Ping the server ({} out of {})...", ping_index, max_pings);
-            std::io::stdout().flush().unwrap();
-            let ping_result = self.client.ping().await;
-            print!(
-                "\rPing the server ({} out of {}) : {}",
-                ping_index,
-                max_pings,
-                if ping_result.is_ok() {
-                    "success".bright_green()
+    pub async fn ping_n_times(&self, n: usize) {
+        println!("\nPinging the server {} times...", n);
+        let report =
+            diagnostics::ping_server(self.client.as_ref(), n, Duration::from_secs(2)).await;
+        for (index, attempt) in report.attempts.iter().enumerate() {
+            println!(
+                "Ping the server ({} out of {}) : {}",
+                index + 1,
+                n,
+                if attempt.error.is_none() {
+                    format!("success ({:.2}ms)", attempt.duration_ms).bright_green()
                 } else {
                     "failed".bright_red()
                 }
             );
-            println!();
-            sleep(Duration::from_secs(2)).await;
         }
     }
 }
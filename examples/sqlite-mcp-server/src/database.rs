@@ -0,0 +1,149 @@
+//! A small wrapper around a SQLite connection, exposing the few operations the server needs.
+
+use rusqlite::{hooks::Action, types::ValueRef, Connection, OpenFlags};
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+
+/// Sample data used when the server is started without a database file.
+const SEED_SQL: &str = r#"
+CREATE TABLE authors (id INTEGER PRIMARY KEY, name TEXT NOT NULL, country TEXT);
+CREATE TABLE books (id INTEGER PRIMARY KEY, title TEXT NOT NULL, author_id INTEGER REFERENCES authors(id), year INTEGER);
+CREATE TABLE reviews (id INTEGER PRIMARY KEY, book_id INTEGER REFERENCES books(id), rating INTEGER, comment TEXT);
+
+INSERT INTO authors (name, country) VALUES ('Ursula K. Le Guin', 'USA'), ('Stanislaw Lem', 'Poland'), ('Octavia E. Butler', 'USA');
+INSERT INTO books (title, author_id, year) VALUES
+    ('The Left Hand of Darkness', 1, 1969),
+    ('The Dispossessed', 1, 1974),
+    ('Solaris', 2, 1961),
+    ('Kindred', 3, 1979);
+INSERT INTO reviews (book_id, rating, comment) VALUES (1, 5, 'A classic.'), (3, 4, 'Haunting.');
+"#;
+
+pub type DbResult<T> = std::result::Result<T, rusqlite::Error>;
+
+pub struct Database {
+    connection: Mutex<Connection>,
+    // names of the tables modified since the last call to `take_changed_tables()`
+    changed_tables: Arc<Mutex<HashSet<String>>>,
+}
+
+impl Database {
+    /// Opens the database at `path`, or an in-memory database seeded with sample data if `path` is None.
+    pub fn open(path: Option<&str>) -> DbResult<Self> {
+        let connection = match path {
+            Some(path) => Connection::open_with_flags(
+                path,
+                OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_CREATE,
+            )?,
+            None => {
+                let connection = Connection::open_in_memory()?;
+                connection.execute_batch(SEED_SQL)?;
+                connection
+            }
+        };
+
+        // Keep track of every table touched by INSERT, UPDATE or DELETE statements,
+        // so that subscribed clients can be notified once a statement completes.
+        let changed_tables: Arc<Mutex<HashSet<String>>> = Arc::new(Mutex::new(HashSet::new()));
+        let tables = Arc::clone(&changed_tables);
+        connection.update_hook(Some(
+            move |_action: Action, _db_name: &str, table: &str, _row_id: i64| {
+                if let Ok(mut tables) = tables.lock() {
+                    tables.insert(table.to_string());
+                }
+            },
+        ));
+
+        Ok(Self {
+            connection: Mutex::new(connection),
+            changed_tables,
+        })
+    }
+
+    /// Returns the names of all user tables, sorted alphabetically.
+    pub fn table_names(&self) -> DbResult<Vec<String>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(
+            "SELECT name FROM sqlite_master WHERE type = 'table' AND name NOT LIKE 'sqlite_%' ORDER BY name",
+        )?;
+        let names = statement
+            .query_map([], |row| row.get::<_, String>(0))?
+            .collect::<DbResult<Vec<_>>>()?;
+        Ok(names)
+    }
+
+    /// Returns the `CREATE TABLE` statement of the given table.
+    pub fn table_schema(&self, table: &str) -> DbResult<String> {
+        let connection = self.connection.lock().unwrap();
+        connection.query_row(
+            "SELECT sql FROM sqlite_master WHERE type = 'table' AND name = ?1",
+            [table],
+            |row| row.get(0),
+        )
+    }
+
+    /// Runs a read-only statement and returns the resulting rows as JSON objects.
+    ///
+    /// Statements that could modify the database are rejected.
+    pub fn query(&self, sql: &str, params: &[String], limit: usize) -> DbResult<Vec<Value>> {
+        let connection = self.connection.lock().unwrap();
+        let mut statement = connection.prepare(sql)?;
+        if !statement.readonly() {
+            return Err(rusqlite::Error::InvalidQuery);
+        }
+
+        let column_names: Vec<String> = statement
+            .column_names()
+            .into_iter()
+            .map(String::from)
+            .collect();
+
+        let mut rows = statement.query(rusqlite::params_from_iter(params.iter()))?;
+        let mut result = vec![];
+        while let Some(row) = rows.next()? {
+            if result.len() >= limit {
+                break;
+            }
+            let mut object = Map::new();
+            for (index, column) in column_names.iter().enumerate() {
+                object.insert(column.to_string(), to_json_value(row.get_ref(index)?));
+            }
+            result.push(Value::Object(object));
+        }
+        Ok(result)
+    }
+
+    /// Returns up to `limit` rows of the given table.
+    pub fn table_rows(&self, table: &str, limit: usize) -> DbResult<Vec<Value>> {
+        // make sure we only ever interpolate an existing table name into the statement
+        if !self.table_names()?.iter().any(|name| name == table) {
+            return Err(rusqlite::Error::QueryReturnedNoRows);
+        }
+        self.query(&format!("SELECT * FROM \"{}\"", table), &[], limit)
+    }
+
+    /// Executes a statement that modifies the database, returning the number of affected rows.
+    pub fn execute(&self, sql: &str, params: &[String]) -> DbResult<usize> {
+        let connection = self.connection.lock().unwrap();
+        connection.execute(sql, rusqlite::params_from_iter(params.iter()))
+    }
+
+    /// Returns and clears the set of tables modified since the previous call.
+    pub fn take_changed_tables(&self) -> Vec<String> {
+        let mut tables = self.changed_tables.lock().unwrap();
+        let mut changed: Vec<String> = tables.drain().collect();
+        changed.sort();
+        changed
+    }
+}
+
+fn to_json_value(value: ValueRef) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(number) => Value::from(number),
+        ValueRef::Real(number) => Value::from(number),
+        ValueRef::Text(text) => Value::String(String::from_utf8_lossy(text).to_string()),
+        ValueRef::Blob(blob) => Value::String(format!("<{} bytes>", blob.len())),
+    }
+}
@@ -0,0 +1,192 @@
+use async_trait::async_trait;
+use rust_mcp_schema::{
+    schema_utils::CallToolError, CallToolRequest, CallToolResult, ListResourceTemplatesRequest,
+    ListResourceTemplatesResult, ListResourcesRequest, ListResourcesResult, ListToolsRequest,
+    ListToolsResult, ReadResourceRequest, ReadResourceResult, Resource, ResourceTemplate, RpcError,
+    TextResourceContents,
+};
+use rust_mcp_sdk::{
+    mcp_server::{ListOrder, Paginator, ServerHandler, UriTemplate},
+    McpServer,
+};
+
+use crate::database::Database;
+use crate::tools::SqliteTools;
+
+/// Number of resources returned per page of `resources/list`
+const RESOURCES_PAGE_SIZE: usize = 2;
+/// Maximum number of rows included when a table resource is read
+const MAX_RESOURCE_ROWS: usize = 100;
+const TABLE_URI_PREFIX: &str = "sqlite://tables/";
+const TABLE_URI_TEMPLATE: &str = "sqlite://tables/{table}";
+
+// Custom Handler to handle MCP Messages
+pub struct SqliteServerHandler {
+    database: Database,
+}
+
+impl SqliteServerHandler {
+    pub fn new(database: Database) -> Self {
+        Self { database }
+    }
+
+    fn table_uri(table: &str) -> String {
+        format!("{}{}", TABLE_URI_PREFIX, table)
+    }
+
+    pub fn table_from_uri(uri: &str) -> Result<String, RpcError> {
+        UriTemplate::new(TABLE_URI_TEMPLATE)
+            .matches(uri)
+            .and_then(|mut params| params.remove("table"))
+            .ok_or_else(|| {
+                RpcError::invalid_params().with_message(format!("Unknown resource uri: {}", uri))
+            })
+    }
+
+    /// Notifies the client about tables modified by the last statement, if it has subscribed to them,
+    /// and about changes to the resource list when tables were created or dropped.
+    async fn notify_changes(&self, tables_before: Vec<String>, runtime: &dyn McpServer) {
+        for table in self.database.take_changed_tables() {
+            let _ = runtime
+                .notify_resource_updated(&Self::table_uri(&table))
+                .await;
+        }
+
+        if self.database.table_names().unwrap_or_default() != tables_before {
+            let _ = runtime.send_resource_list_changed(None).await;
+        }
+    }
+}
+
+// To check out a list of all the methods in the trait that you can override, take a look at
+// https://github.com/rust-mcp-stack/rust-mcp-sdk/blob/main/crates/rust-mcp-sdk/src/mcp_handlers/mcp_server_handler.rs
+
+#[async_trait]
+#[allow(unused)]
+impl ServerHandler for SqliteServerHandler {
+    // Handle ListToolsRequest, return list of available tools as ListToolsResult
+    async fn handle_list_tools_request(
+        &self,
+        request: ListToolsRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<ListToolsResult, RpcError> {
+        Ok(ListToolsResult {
+            meta: None,
+            next_cursor: None,
+            tools: SqliteTools::tools(),
+        })
+    }
+
+    /// Handles incoming CallToolRequest and processes it using the appropriate tool.
+    async fn handle_call_tool_request(
+        &self,
+        request: CallToolRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<CallToolResult, CallToolError> {
+        // Attempt to convert request parameters into SqliteTools enum
+        let tool_params: SqliteTools =
+            SqliteTools::try_from(request.params).map_err(CallToolError::new)?;
+
+        // Match the tool variant and execute its corresponding logic
+        match tool_params {
+            SqliteTools::ListTablesTool(list_tables_tool) => {
+                list_tables_tool.call_tool(&self.database)
+            }
+            SqliteTools::QueryTool(query_tool) => query_tool.call_tool(&self.database),
+            SqliteTools::ExecuteTool(execute_tool) => {
+                let tables_before = self.database.table_names().unwrap_or_default();
+                // CallToolError is not Send, convert it to a result before awaiting notifications
+                let result: CallToolResult = execute_tool
+                    .call_tool(&self.database)
+                    .unwrap_or_else(CallToolResult::from);
+                self.notify_changes(tables_before, runtime).await;
+                Ok(result)
+            }
+        }
+    }
+
+    /// Lists every table as a resource, a few at a time to demonstrate pagination.
+    async fn handle_list_resources_request(
+        &self,
+        request: ListResourcesRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<ListResourcesResult, RpcError> {
+        let resources = self
+            .database
+            .table_names()
+            .map_err(|err| RpcError::internal_error().with_message(err.to_string()))?
+            .iter()
+            .map(|table| Resource {
+                annotations: None,
+                description: Some(format!("Rows of the '{}' table.", table)),
+                mime_type: Some("application/json".to_string()),
+                name: table.to_string(),
+                size: None,
+                uri: Self::table_uri(table),
+            })
+            .collect();
+
+        let cursor = request.params.and_then(|params| params.cursor);
+        let page = Paginator::new(RESOURCES_PAGE_SIZE)
+            .with_order(ListOrder::Name)
+            .paginate(resources, cursor.as_deref())?;
+        Ok(ListResourcesResult {
+            meta: None,
+            next_cursor: page.next_cursor,
+            resources: page.items,
+        })
+    }
+
+    async fn handle_list_resource_templates_request(
+        &self,
+        request: ListResourceTemplatesRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<ListResourceTemplatesResult, RpcError> {
+        Ok(ListResourceTemplatesResult {
+            meta: None,
+            next_cursor: None,
+            resource_templates: vec![ResourceTemplate {
+                annotations: None,
+                description: Some("Rows of a table in the database.".to_string()),
+                mime_type: Some("application/json".to_string()),
+                name: "table".to_string(),
+                uri_template: TABLE_URI_TEMPLATE.to_string(),
+            }],
+        })
+    }
+
+    /// Returns the schema and the first rows of a table.
+    async fn handle_read_resource_request(
+        &self,
+        request: ReadResourceRequest,
+        runtime: &dyn McpServer,
+    ) -> std::result::Result<ReadResourceResult, RpcError> {
+        let uri = request.params.uri;
+        let table = Self::table_from_uri(&uri)?;
+
+        let rows = self
+            .database
+            .table_rows(&table, MAX_RESOURCE_ROWS)
+            .map_err(|err| {
+                RpcError::invalid_params()
+                    .with_message(format!("Unable to read '{}': {}", table, err))
+            })?;
+        let schema = self.database.table_schema(&table).unwrap_or_default();
+
+        let text = serde_json::to_string_pretty(&serde_json::json!({
+            "schema": schema,
+            "rows": rows,
+        }))
+        .map_err(|err| RpcError::internal_error().with_message(err.to_string()))?;
+
+        Ok(ReadResourceResult {
+            contents: vec![TextResourceContents {
+                mime_type: Some("application/json".to_string()),
+                text,
+                uri,
+            }
+            .into()],
+            meta: None,
+        })
+    }
+}
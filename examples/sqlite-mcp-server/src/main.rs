@@ -0,0 +1,68 @@
+mod database;
+mod handler;
+mod tools;
+
+use database::Database;
+use handler::SqliteServerHandler;
+use rust_mcp_schema::{
+    Implementation, InitializeResult, RpcError, ServerCapabilities, ServerCapabilitiesResources,
+    ServerCapabilitiesTools, LATEST_PROTOCOL_VERSION,
+};
+
+use rust_mcp_sdk::{
+    error::SdkResult,
+    mcp_server::{server_runtime, ServerRuntime, SubscriptionManager},
+    McpServer,
+};
+
+use rust_mcp_transport::{StdioTransport, TransportOptions};
+
+#[tokio::main]
+async fn main() -> SdkResult<()> {
+    // STEP 1: Open the database, the path to an existing SQLite file can be passed as the first argument.
+    // Without it, an in-memory database with some sample data is used.
+    let db_path = std::env::args().nth(1);
+    let database = Database::open(db_path.as_deref())
+        .map_err(|err| RpcError::internal_error().with_message(err.to_string()))?;
+
+    // STEP 2: Define server details and capabilities
+    let server_details = InitializeResult {
+        // server name and version
+        server_info: Implementation {
+            name: "SQLite MCP Server".to_string(),
+            version: "0.1.0".to_string(),
+        },
+        capabilities: ServerCapabilities {
+            // indicates that server support mcp tools
+            tools: Some(ServerCapabilitiesTools { list_changed: None }),
+            // tables are exposed as resources, clients can subscribe to changes
+            resources: Some(ServerCapabilitiesResources {
+                list_changed: Some(true),
+                subscribe: Some(true),
+            }),
+            ..Default::default() // Using default values for other fields
+        },
+        meta: None,
+        instructions: Some(
+            "Each table of the database is available as a resource. Use the 'query' tool for read-only queries and the 'execute' tool to modify data.".to_string(),
+        ),
+        protocol_version: LATEST_PROTOCOL_VERSION.to_string(),
+    };
+
+    // STEP 3: create a std transport with default options
+    let transport = StdioTransport::new(TransportOptions::default())?;
+
+    // STEP 4: instantiate our custom handler for handling MCP messages
+    let handler = SqliteServerHandler::new(database);
+
+    // STEP 5: create a MCP server
+    // subscriptions to the tables are tracked by the server, handlers only notify the updated tables
+    let server: ServerRuntime = server_runtime::create_server(server_details, transport, handler)
+        .with_subscription_manager(
+            SubscriptionManager::new()
+                .with_validator(|uri| SqliteServerHandler::table_from_uri(uri).map(|_| ())),
+        );
+
+    // STEP 6: Start the server
+    server.start().await
+}
@@ -0,0 +1,88 @@
+use rust_mcp_macros::{mcp_tool, JsonSchema};
+use rust_mcp_schema::{schema_utils::CallToolError, CallToolResult};
+use rust_mcp_sdk::tool_box;
+
+use crate::database::Database;
+
+/// Maximum number of rows returned by a single query, unless a smaller limit is requested.
+const MAX_QUERY_ROWS: usize = 100;
+
+//*******************//
+//  ListTablesTool   //
+//*******************//
+#[mcp_tool(
+    name = "list_tables",
+    description = "Lists the names of all tables available in the database."
+)]
+#[derive(Debug, ::serde::Deserialize, ::serde::Serialize, JsonSchema)]
+pub struct ListTablesTool {}
+
+impl ListTablesTool {
+    pub fn call_tool(&self, database: &Database) -> Result<CallToolResult, CallToolError> {
+        let tables = database.table_names().map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(tables.join("\n"), None))
+    }
+}
+
+//**************//
+//  QueryTool   //
+//**************//
+#[mcp_tool(
+    name = "query",
+    description = "Runs a read-only SQL query (e.g. SELECT) against the database and returns the rows as JSON."
+)]
+#[derive(Debug, ::serde::Deserialize, ::serde::Serialize, JsonSchema)]
+pub struct QueryTool {
+    /// The SQL statement to run. Statements that modify the database are rejected.
+    sql: String,
+    /// Values bound to the positional parameters (?1, ?2, ...) of the statement.
+    params: Option<Vec<String>>,
+    /// Maximum number of rows to return (at most 100).
+    limit: Option<u32>,
+}
+
+impl QueryTool {
+    pub fn call_tool(&self, database: &Database) -> Result<CallToolResult, CallToolError> {
+        let limit = self
+            .limit
+            .map_or(MAX_QUERY_ROWS, |limit| (limit as usize).min(MAX_QUERY_ROWS));
+        let rows = database
+            .query(&self.sql, self.params.as_deref().unwrap_or_default(), limit)
+            .map_err(CallToolError::new)?;
+        let content = serde_json::to_string_pretty(&rows).map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(content, None))
+    }
+}
+
+//****************//
+//  ExecuteTool   //
+//****************//
+#[mcp_tool(
+    name = "execute",
+    description = "Executes a SQL statement that modifies the database (INSERT, UPDATE, DELETE, CREATE ...) and returns the number of affected rows."
+)]
+#[derive(Debug, ::serde::Deserialize, ::serde::Serialize, JsonSchema)]
+pub struct ExecuteTool {
+    /// The SQL statement to execute.
+    sql: String,
+    /// Values bound to the positional parameters (?1, ?2, ...) of the statement.
+    params: Option<Vec<String>>,
+}
+
+impl ExecuteTool {
+    pub fn call_tool(&self, database: &Database) -> Result<CallToolResult, CallToolError> {
+        let affected_rows = database
+            .execute(&self.sql, self.params.as_deref().unwrap_or_default())
+            .map_err(CallToolError::new)?;
+        Ok(CallToolResult::text_content(
+            format!("{} row(s) affected.", affected_rows),
+            None,
+        ))
+    }
+}
+
+//***************//
+//  SqliteTools  //
+//***************//
+// Generates an enum names SqliteTools, with ListTablesTool, QueryTool and ExecuteTool variants
+tool_box!(SqliteTools, [ListTablesTool, QueryTool, ExecuteTool]);